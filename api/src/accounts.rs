@@ -6,17 +6,19 @@ use crate::{
     accept_type::AcceptType,
     context::{api_spawn_blocking, Context},
     failpoint::fail_point_poem,
-    page::determine_limit,
+    page::{determine_limit, Page},
     response::{
-        account_not_found, resource_not_found, struct_field_not_found, BadRequestError,
-        BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404, InternalError,
+        account_not_found, api_forbidden, resource_not_found, struct_field_not_found,
+        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
+        InternalError,
     },
     ApiTags,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    AccountData, Address, AptosErrorCode, AsConverter, AssetType, LedgerInfo, MoveModuleBytecode,
-    MoveModuleId, MoveResource, MoveStructTag, StateKeyWrapper, U64,
+    AccountData, Address, AptosErrorCode, AsConverter, AssetType, FungibleAssetActivity,
+    LedgerInfo, MoveModuleBytecode, MoveModuleId, MoveResource, MoveStructTag, StateKeyWrapper,
+    U64,
 };
 use aptos_sdk::types::{get_paired_fa_metadata_address, get_paired_fa_primary_store_address};
 use aptos_types::{
@@ -165,6 +167,66 @@ impl AccountsApi {
         .await
     }
 
+    /// Get account balance activities
+    ///
+    /// Retrieves the deposits into and withdrawals from the coin store and fungible stores of an
+    /// account for a given asset type, in the order they happened. Balance changes of a coin are
+    /// included with those of the fungible asset paired with it.
+    ///
+    /// The balance changes of a transaction are always returned on the same page, so a page can
+    /// hold more than `limit` balance changes.
+    ///
+    /// This requires the internal indexer with `enable_fungible_asset_activities` to be enabled,
+    /// otherwise the server responds with a 403.
+    #[oai(
+        path = "/accounts/:address/balance/:asset_type/activities",
+        method = "get",
+        operation_id = "get_account_balance_activities",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_balance_activities(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        asset_type: Path<AssetType>,
+        /// Ledger version to start list of balance changes
+        ///
+        /// If not provided, defaults to showing the latest balance changes
+        start: Query<Option<U64>>,
+        /// Max number of balance changes to retrieve
+        ///
+        /// If not provided, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<FungibleAssetActivity>> {
+        fail_point_poem("endpoint_get_account_balance_activities")?;
+        self.context
+            .check_api_output_enabled("Get account balance activities", &accept_type)?;
+        if !self
+            .context
+            .node_config
+            .indexer_db_config
+            .enable_fungible_asset_activities()
+        {
+            return Err(api_forbidden(
+                "Get account balance activities",
+                "The internal indexer for fungible asset activities is not enabled on this node.",
+            ));
+        }
+
+        let page = Page::new(
+            start.0.map(|v| v.0),
+            limit.0,
+            self.context.max_events_page_size(),
+        );
+        let context = self.context.clone();
+        api_spawn_blocking(move || {
+            let account = Account::new(context, address.0, None, None, None)?;
+            account.balance_activities(asset_type.0, page, &accept_type)
+        })
+        .await
+    }
+
     /// Get account modules
     ///
     /// Retrieves all account modules' bytecode for a given account at a specific ledger version.
@@ -399,6 +461,42 @@ impl Account {
         }
     }
 
+    pub fn balance_activities(
+        &self,
+        asset_type: AssetType,
+        page: Page,
+        accept_type: &AcceptType,
+    ) -> BasicResultWith404<Vec<FungibleAssetActivity>> {
+        let metadata_address = match asset_type {
+            AssetType::Coin(move_struct_tag) => get_paired_fa_metadata_address(&move_struct_tag),
+            AssetType::FungibleAsset(fa_metadata_address) => fa_metadata_address.into(),
+        };
+        let activities = self.context.get_fungible_asset_activities(
+            self.address.into(),
+            metadata_address,
+            page.start_option(),
+            page.limit(&self.latest_ledger_info)?,
+            self.ledger_version,
+            &self.latest_ledger_info,
+        )?;
+
+        match accept_type {
+            AcceptType::Json => BasicResponse::try_from_json((
+                activities
+                    .into_iter()
+                    .map(FungibleAssetActivity::from)
+                    .collect::<Vec<_>>(),
+                &self.latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+            AcceptType::Bcs => BasicResponse::try_from_bcs((
+                activities,
+                &self.latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+        }
+    }
+
     pub fn get_account_resource(&self) -> Result<Vec<u8>, BasicErrorWith404> {
         let state_key =
             StateKey::resource_typed::<AccountResource>(self.address.inner()).map_err(|e| {
@@ -34,7 +34,7 @@ use aptos_types::{
     chain_id::ChainId,
     contract_event::{ContractEvent, ContractEventV1, EventWithVersion},
     event::EventKey,
-    indexer::indexer_db_reader::IndexerReader,
+    indexer::{fungible_asset_activity::FungibleAssetActivity, indexer_db_reader::IndexerReader},
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{GasSchedule, GasScheduleV2, OnChainConfig, OnChainExecutionConfig},
     state_store::{
@@ -81,6 +81,7 @@ pub struct Context {
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
+    pub transaction_stream_active_connections: Arc<AtomicUsize>,
 }
 
 impl std::fmt::Debug for Context {
@@ -133,6 +134,7 @@ impl Context {
             simulate_txn_stats,
             indexer_reader,
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
+            transaction_stream_active_connections: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -148,6 +150,10 @@ impl Context {
         self.node_config.api.max_account_resources_page_size
     }
 
+    pub fn max_account_resource_history_page_size(&self) -> u16 {
+        self.node_config.api.max_account_resource_history_page_size
+    }
+
     pub fn max_account_modules_page_size(&self) -> u16 {
         self.node_config.api.max_account_modules_page_size
     }
@@ -206,6 +212,10 @@ impl Context {
         self.node_config.api.max_submit_transaction_batch_size
     }
 
+    pub fn max_view_function_batch_size(&self) -> usize {
+        self.node_config.api.max_view_function_batch_size
+    }
+
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
@@ -893,6 +903,90 @@ impl Context {
             .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
     }
 
+    pub fn get_account_participant_transactions<E: InternalError>(
+        &self,
+        address: AccountAddress,
+        start_version: Option<u64>,
+        limit: u16,
+        ledger_version: u64,
+        ledger_info: &LedgerInfo,
+    ) -> Result<Vec<TransactionOnChainData>, E> {
+        let txns = self
+            .indexer_reader
+            .as_ref()
+            .ok_or_else(|| anyhow!("Indexer reader is None"))
+            .and_then(|indexer_reader| {
+                indexer_reader.get_account_participant_transactions(
+                    address,
+                    start_version,
+                    limit as u64,
+                    true,
+                    ledger_version,
+                )
+            })
+            .context("Failed to retrieve account participant transactions")
+            .map_err(|err| {
+                E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+            })?;
+        txns.into_inner()
+            .into_iter()
+            .map(|t| -> Result<TransactionOnChainData> {
+                let txn = self.convert_into_transaction_on_chain_data(t)?;
+                Ok(self.maybe_translate_v2_to_v1_events(txn))
+            })
+            .collect::<Result<Vec<_>>>()
+            .context("Failed to parse account participant transactions")
+            .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
+    }
+
+    pub fn get_fungible_asset_activities<E: InternalError>(
+        &self,
+        owner: AccountAddress,
+        metadata_address: AccountAddress,
+        start_version: Option<u64>,
+        limit: u16,
+        ledger_version: u64,
+        ledger_info: &LedgerInfo,
+    ) -> Result<Vec<FungibleAssetActivity>, E> {
+        self.indexer_reader
+            .as_ref()
+            .ok_or_else(|| anyhow!("Indexer reader is None"))
+            .and_then(|indexer_reader| {
+                indexer_reader.get_fungible_asset_activities(
+                    owner,
+                    metadata_address,
+                    start_version,
+                    limit as u64,
+                    ledger_version,
+                )
+            })
+            .context("Failed to retrieve fungible asset activities")
+            .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
+    }
+
+    pub fn get_state_value_history<E: InternalError>(
+        &self,
+        state_key: &StateKey,
+        start_version: Option<u64>,
+        limit: u16,
+        ledger_version: u64,
+        ledger_info: &LedgerInfo,
+    ) -> Result<Vec<(Version, Option<StateValue>)>, E> {
+        self.indexer_reader
+            .as_ref()
+            .ok_or_else(|| anyhow!("Indexer reader is None"))
+            .and_then(|indexer_reader| {
+                indexer_reader.get_state_value_history(
+                    state_key,
+                    start_version,
+                    limit as u64,
+                    ledger_version,
+                )
+            })
+            .context("Failed to retrieve state value history")
+            .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
+    }
+
     pub fn get_transaction_by_hash(
         &self,
         hash: HashValue,
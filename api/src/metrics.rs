@@ -105,3 +105,20 @@ pub static WAIT_TRANSACTION_POLL_TIME: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static TRANSACTION_STREAM_GAUGE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_api_transaction_stream_active_connections",
+        "Number of active transaction stream connections"
+    )
+    .unwrap()
+});
+
+pub static TRANSACTION_STREAM_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_transaction_stream_messages",
+        "Number of messages pushed to transaction stream clients, grouped by message type",
+        &["message_type"]
+    )
+    .unwrap()
+});
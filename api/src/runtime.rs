@@ -15,6 +15,7 @@ use crate::{
     set_failpoints,
     spec::{spec_endpoint_json, spec_endpoint_yaml},
     state::StateApi,
+    stream::StreamApi,
     transactions::TransactionsApi,
     view_function::ViewFunctionApi,
 };
@@ -117,6 +118,7 @@ pub fn get_api_service(
         EventsApi,
         IndexApi,
         StateApi,
+        StreamApi,
         TransactionsApi,
         ViewFunctionApi,
     ),
@@ -142,6 +144,9 @@ pub fn get_api_service(
         StateApi {
             context: context.clone(),
         },
+        StreamApi {
+            context: context.clone(),
+        },
         TransactionsApi {
             context: context.clone(),
         },
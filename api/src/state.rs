@@ -5,6 +5,7 @@ use crate::{
     accept_type::AcceptType,
     context::api_spawn_blocking,
     failpoint::fail_point_poem,
+    page::Page,
     response::{
         api_forbidden, build_not_found, module_not_found, resource_not_found, table_item_not_found,
         BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
@@ -16,16 +17,18 @@ use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
     verify_module_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper,
     MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, RawStateValueRequest,
-    RawTableItemRequest, TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
+    RawTableItemRequest, TableItemRequest, VerifyInput, VerifyInputWithRecursion,
+    VersionedMoveResource, U64,
 };
 use aptos_types::state_store::{state_key::StateKey, table::TableHandle, TStateView};
+use bytes::Bytes;
 use move_core_types::language_storage::StructTag;
 use poem_openapi::{
     param::{Path, Query},
     payload::Json,
     OpenApi,
 };
-use std::{convert::TryInto, sync::Arc};
+use std::{collections::BTreeMap, convert::TryInto, sync::Arc};
 
 /// API for retrieving individual state
 #[derive(Clone)]
@@ -83,6 +86,71 @@ impl StateApi {
         .await
     }
 
+    /// Get account resource history
+    ///
+    /// Retrieves the versions at which a resource of a given account was written, in ascending
+    /// order, together with the resource after each of these transactions. The resource is null
+    /// for transactions that deleted it. For a resource that is a member of a resource group, every
+    /// transaction that wrote the group is included, even if it didn't change that resource.
+    ///
+    /// This requires the internal indexer with `enable_state_value_history` to be enabled,
+    /// otherwise the server responds with a 403. Versions that have been pruned are not returned.
+    #[oai(
+        path = "/accounts/:address/resource/:resource_type/history",
+        method = "get",
+        operation_id = "get_account_resource_history",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_resource_history(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        /// Name of struct to retrieve e.g. `0x1::account::Account`
+        resource_type: Path<MoveStructTag>,
+        /// Ledger version to start list of versions
+        ///
+        /// If not provided, defaults to showing the latest versions
+        start: Query<Option<U64>>,
+        /// Max number of versions to retrieve
+        ///
+        /// If not provided, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<VersionedMoveResource>> {
+        resource_type
+            .0
+            .verify(0)
+            .context("'resource_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_account_resource_history")?;
+        self.context
+            .check_api_output_enabled("Get account resource history", &accept_type)?;
+        if !self
+            .context
+            .node_config
+            .indexer_db_config
+            .enable_state_value_history()
+        {
+            return Err(api_forbidden(
+                "Get account resource history",
+                "The internal indexer for state value history is not enabled on this node.",
+            ));
+        }
+
+        let page = Page::new(
+            start.0.map(|v| v.0),
+            limit.0,
+            self.context.max_account_resource_history_page_size(),
+        );
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.resource_history(&accept_type, address.0, resource_type.0, page)
+        })
+        .await
+    }
+
     /// Get account module
     ///
     /// Retrieves an individual module from a given account and at a specific ledger version. If the
@@ -326,6 +394,110 @@ impl StateApi {
         }
     }
 
+    /// Read the versions at which a resource was written, and its value after each of them
+    ///
+    /// JSON: Convert to VersionedMoveResource
+    /// BCS: Pairs of versions and the resource left encoded, if it exists
+    fn resource_history(
+        &self,
+        accept_type: &AcceptType,
+        address: Address,
+        resource_type: MoveStructTag,
+        page: Page,
+    ) -> BasicResultWith404<Vec<VersionedMoveResource>> {
+        let tag: StructTag = (&resource_type)
+            .try_into()
+            .context("Failed to parse given resource type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+
+        let (ledger_info, ledger_version, state_view) = self.context.state_view(None)?;
+        let converter =
+            state_view.as_converter(self.context.db.clone(), self.context.indexer_reader.clone());
+
+        // Members of resource groups are stored, and indexed, as part of their group.
+        let group_tag = converter.find_resource_group(&tag);
+        let state_key = match &group_tag {
+            Some(group_tag) => Ok(StateKey::resource_group(&address.into(), group_tag)),
+            None => StateKey::resource(&address.into(), &tag),
+        }
+        .context("Failed to build the state key of the resource")
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            )
+        })?;
+
+        let history = self
+            .context
+            .get_state_value_history(
+                &state_key,
+                page.start_option(),
+                page.limit(&ledger_info)?,
+                ledger_version,
+                &ledger_info,
+            )?
+            .into_iter()
+            .map(|(version, value)| {
+                let bytes = match (value, &group_tag) {
+                    (None, _) => None,
+                    (Some(value), None) => Some(value.bytes().clone()),
+                    (Some(value), Some(_)) => {
+                        let group: BTreeMap<StructTag, Bytes> = bcs::from_bytes(value.bytes())?;
+                        group.get(&tag).cloned()
+                    },
+                };
+                Ok((version, bytes))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context("Failed to read resource data retrieved from DB")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+
+        match accept_type {
+            AcceptType::Json => {
+                let resources = history
+                    .into_iter()
+                    .map(|(version, bytes)| {
+                        let resource = bytes
+                            .map(|bytes| converter.try_into_resource(&tag, &bytes))
+                            .transpose()?;
+                        Ok(VersionedMoveResource {
+                            version: version.into(),
+                            resource,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .context("Failed to deserialize resource data retrieved from DB")
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AptosErrorCode::InternalError,
+                            &ledger_info,
+                        )
+                    })?;
+
+                BasicResponse::try_from_json((resources, &ledger_info, BasicResponseStatus::Ok))
+            },
+            AcceptType::Bcs => BasicResponse::try_from_bcs((
+                history
+                    .into_iter()
+                    .map(|(version, bytes)| (version, bytes.map(|bytes| bytes.to_vec())))
+                    .collect::<Vec<_>>(),
+                &ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+        }
+    }
+
     /// Retrieve the module
     ///
     /// JSON: Parse ABI and bytecode
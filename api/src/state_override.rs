@@ -0,0 +1,270 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::Context;
+use anyhow::{anyhow, ensure, Context as AnyhowContext, Result};
+use aptos_api_types::{AsConverter, StateOverrides};
+use aptos_types::{
+    account_config::{
+        primary_apt_store, AccountResource, CoinStoreResource, ConcurrentFungibleBalanceResource,
+        FungibleStoreResource, ObjectGroupResource,
+    },
+    state_store::{
+        errors::StateViewError, state_key::StateKey, state_storage_usage::StateStorageUsage,
+        state_value::StateValue, StateView, StateViewId, TStateView,
+    },
+    AptosCoinType,
+};
+use bytes::Bytes;
+use move_binary_format::CompiledModule;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{StructTag, TypeTag},
+    move_resource::MoveStructType,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// A state view that applies the state overrides of a simulation request
+/// on top of a base state view. The overrides are never persisted.
+pub struct StateOverrideView<'a, S> {
+    base_view: &'a S,
+    overrides: HashMap<StateKey, StateValue>,
+}
+
+impl<'a, S: StateView> StateOverrideView<'a, S> {
+    pub fn new(
+        base_view: &'a S,
+        context: &Context,
+        state_overrides: &StateOverrides,
+    ) -> Result<Self> {
+        let mut view = Self {
+            base_view,
+            overrides: HashMap::new(),
+        };
+
+        // Apply the module overrides first, so that the resource
+        // overrides can use the overridden struct definitions.
+        for account in &state_overrides.accounts {
+            let address: AccountAddress = account.address.into();
+            for module in &account.modules {
+                let compiled_module = CompiledModule::deserialize(&module.0)
+                    .context("Failed to deserialize module override")?;
+                let module_id = compiled_module.self_id();
+                ensure!(
+                    module_id.address() == &address,
+                    "Module {} can't be published under account {}",
+                    module_id,
+                    address
+                );
+                view.set_state_value(StateKey::module_id(&module_id), module.0.clone().into())?;
+            }
+        }
+
+        // Convert the resource overrides into BCS, and find their resource groups
+        let mut resources = vec![];
+        {
+            let converter = view.as_converter(context.db.clone(), context.indexer_reader.clone());
+            for account in &state_overrides.accounts {
+                for resource in &account.resources {
+                    let tag: StructTag = (&resource.typ).try_into()?;
+                    let value = converter
+                        .try_into_vm_value(
+                            &TypeTag::Struct(Box::new(tag.clone())),
+                            serde_json::to_value(&resource.data)?,
+                        )
+                        .with_context(|| format!("Invalid resource override for {}", tag))?;
+                    let bytes = value
+                        .simple_serialize()
+                        .ok_or_else(|| anyhow!("Failed to serialize resource override {}", tag))?;
+                    let group = converter.find_resource_group(&tag);
+                    resources.push((account.address.into(), tag, group, bytes));
+                }
+            }
+        }
+        for (address, tag, group, bytes) in resources {
+            view.set_resource_bytes(address, &tag, group.as_ref(), bytes)?;
+        }
+
+        // Apply the sequence number and balance overrides last, as they
+        // modify the (possibly overridden) account resources.
+        for account in &state_overrides.accounts {
+            let address: AccountAddress = account.address.into();
+            if let Some(sequence_number) = account.sequence_number {
+                view.override_sequence_number(address, sequence_number.0)?;
+            }
+            if let Some(balance) = account.balance {
+                view.override_balance(address, balance.0)?;
+            }
+        }
+
+        Ok(view)
+    }
+
+    /// Overrides the sequence number of an existing account
+    fn override_sequence_number(
+        &mut self,
+        address: AccountAddress,
+        sequence_number: u64,
+    ) -> Result<()> {
+        let tag = AccountResource::struct_tag();
+        let mut account_resource: AccountResource =
+            self.get_resource(address, &tag, None)?.ok_or_else(|| {
+                anyhow!(
+                    "Account {} does not exist, so its sequence number can't be overridden",
+                    address
+                )
+            })?;
+        account_resource.set_sequence_number(sequence_number);
+        self.set_resource(address, &tag, None, &account_resource)
+    }
+
+    /// Overrides the APT balance of an account. If the account has a primary fungible
+    /// store for APT, the whole balance is held there (and any coins are cleared).
+    /// Otherwise, the balance is held in the coin store.
+    fn override_balance(&mut self, address: AccountAddress, balance: u64) -> Result<()> {
+        let coin_store_tag = CoinStoreResource::<AptosCoinType>::struct_tag();
+        let coin_store: Option<CoinStoreResource<AptosCoinType>> =
+            self.get_resource(address, &coin_store_tag, None)?;
+
+        let store_address = primary_apt_store(address);
+        let object_group_tag = ObjectGroupResource::struct_tag();
+        let fungible_store_tag = FungibleStoreResource::struct_tag();
+        let fungible_store: Option<FungibleStoreResource> =
+            self.get_resource(store_address, &fungible_store_tag, Some(&object_group_tag))?;
+
+        ensure!(
+            coin_store.is_some() || fungible_store.is_some(),
+            "Account {} has no APT store, so its balance can't be overridden",
+            address
+        );
+
+        let mut coin_balance = balance;
+        if let Some(mut fungible_store) = fungible_store {
+            // Stores with a concurrent balance hold the balance in a separate resource
+            let concurrent_balance_tag = ConcurrentFungibleBalanceResource::struct_tag();
+            let concurrent_balance: Option<ConcurrentFungibleBalanceResource> = self.get_resource(
+                store_address,
+                &concurrent_balance_tag,
+                Some(&object_group_tag),
+            )?;
+            if concurrent_balance.is_some() {
+                self.set_resource(
+                    store_address,
+                    &concurrent_balance_tag,
+                    Some(&object_group_tag),
+                    &ConcurrentFungibleBalanceResource::new(balance),
+                )?;
+                fungible_store.balance = 0;
+            } else {
+                fungible_store.balance = balance;
+            }
+            self.set_resource(
+                store_address,
+                &fungible_store_tag,
+                Some(&object_group_tag),
+                &fungible_store,
+            )?;
+            coin_balance = 0;
+        }
+        if let Some(mut coin_store) = coin_store {
+            coin_store.set_coin(coin_balance);
+            self.set_resource(address, &coin_store_tag, None, &coin_store)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a resource (from the overrides, or the base view)
+    fn get_resource<T: DeserializeOwned>(
+        &self,
+        address: AccountAddress,
+        tag: &StructTag,
+        group: Option<&StructTag>,
+    ) -> Result<Option<T>> {
+        let bytes = match group {
+            Some(group) => self
+                .get_resource_group(address, group)?
+                .and_then(|mut resources| resources.remove(tag)),
+            None => self.get_state_value_bytes(&StateKey::resource(&address, tag)?)?,
+        };
+        bytes
+            .map(|bytes| bcs::from_bytes(&bytes))
+            .transpose()
+            .with_context(|| format!("Failed to deserialize resource {}", tag))
+    }
+
+    fn set_resource<T: Serialize>(
+        &mut self,
+        address: AccountAddress,
+        tag: &StructTag,
+        group: Option<&StructTag>,
+        resource: &T,
+    ) -> Result<()> {
+        self.set_resource_bytes(address, tag, group, bcs::to_bytes(resource)?)
+    }
+
+    /// Overrides a resource, updating its resource group if it belongs to one
+    fn set_resource_bytes(
+        &mut self,
+        address: AccountAddress,
+        tag: &StructTag,
+        group: Option<&StructTag>,
+        bytes: Vec<u8>,
+    ) -> Result<()> {
+        match group {
+            Some(group) => {
+                let mut resources = self.get_resource_group(address, group)?.unwrap_or_default();
+                resources.insert(tag.clone(), bytes.into());
+                self.set_state_value(
+                    StateKey::resource_group(&address, group),
+                    bcs::to_bytes(&resources)?.into(),
+                )
+            },
+            None => self.set_state_value(StateKey::resource(&address, tag)?, bytes.into()),
+        }
+    }
+
+    fn get_resource_group(
+        &self,
+        address: AccountAddress,
+        group: &StructTag,
+    ) -> Result<Option<BTreeMap<StructTag, Bytes>>> {
+        self.get_state_value_bytes(&StateKey::resource_group(&address, group))?
+            .map(|bytes| bcs::from_bytes(&bytes))
+            .transpose()
+            .with_context(|| format!("Failed to deserialize resource group {}", group))
+    }
+
+    /// Overrides the bytes of a state value, keeping the metadata of any existing value
+    fn set_state_value(&mut self, state_key: StateKey, bytes: Bytes) -> Result<()> {
+        let state_value = match self.get_state_value(&state_key)? {
+            Some(mut state_value) => {
+                state_value.set_bytes(bytes);
+                state_value
+            },
+            None => StateValue::new_legacy(bytes),
+        };
+        self.overrides.insert(state_key, state_value);
+        Ok(())
+    }
+}
+
+impl<'a, S: StateView> TStateView for StateOverrideView<'a, S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base_view.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>, StateViewError> {
+        match self.overrides.get(state_key) {
+            Some(state_value) => Ok(Some(state_value.clone())),
+            None => self.base_view.get_state_value(state_key),
+        }
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage, StateViewError> {
+        self.base_view.get_usage()
+    }
+}
@@ -0,0 +1,641 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    accept_type::AcceptType,
+    context::{api_spawn_blocking, Context},
+    failpoint::fail_point_poem,
+    metrics,
+    response::{
+        api_disabled, version_pruned, AptosErrorResponse, BadRequestError, BasicError,
+        BasicErrorWith404, InternalError, ServiceUnavailableError,
+    },
+    ApiTags,
+};
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    Address, AptosError, AptosErrorCode, AsConverter, EntryFunctionId, HexEncodedBytes, LedgerInfo,
+    MoveStructTag, Transaction, TransactionOnChainData, VerifyInput, VerifyInputWithRecursion,
+    VersionedEvent, U64,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithVersion},
+    transaction::{EntryFunction, MultisigTransactionPayload, TransactionPayload},
+};
+use futures::stream::{self, BoxStream, StreamExt};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use poem_openapi::{param::Query, payload::EventStream, Enum, Object, OpenApi, Union};
+use std::{
+    collections::VecDeque,
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
+
+/// A committed transaction pushed to a stream client
+#[derive(Clone, Debug, Object)]
+pub struct StreamedTransaction {
+    pub version: U64,
+    pub transaction: Transaction,
+}
+
+/// A committed transaction pushed to a stream client, as hex encoded BCS of
+/// the on-chain transaction data
+#[derive(Clone, Debug, Object)]
+pub struct StreamedBcsTransaction {
+    pub version: U64,
+    pub bytes: HexEncodedBytes,
+}
+
+/// A single message of the transaction stream
+///
+/// If an error occurs while reading or rendering the ledger, an `error`
+/// message is pushed and the stream is closed. Clients can resume from the
+/// version following the last message they received.
+#[derive(Clone, Debug, Union)]
+#[oai(one_of, discriminator_name = "type", rename_all = "snake_case")]
+pub enum TransactionStreamMessage {
+    Transaction(StreamedTransaction),
+    BcsTransaction(StreamedBcsTransaction),
+    Event(VersionedEvent),
+    Error(AptosError),
+}
+
+impl TransactionStreamMessage {
+    fn label(&self) -> &'static str {
+        match self {
+            TransactionStreamMessage::Transaction(_) => "transaction",
+            TransactionStreamMessage::BcsTransaction(_) => "bcs_transaction",
+            TransactionStreamMessage::Event(_) => "event",
+            TransactionStreamMessage::Error(_) => "error",
+        }
+    }
+}
+
+/// What is pushed for each transaction matching the stream filters
+#[derive(Clone, Copy, Debug, Enum, Eq, PartialEq)]
+#[oai(rename_all = "snake_case")]
+pub enum TransactionStreamContent {
+    /// Push the matching transactions only
+    Transactions,
+    /// Push the events of the matching transactions only
+    Events,
+    /// Push the matching transactions followed by their events
+    All,
+}
+
+impl TransactionStreamContent {
+    fn includes_transactions(&self) -> bool {
+        matches!(self, Self::Transactions | Self::All)
+    }
+
+    fn includes_events(&self) -> bool {
+        matches!(self, Self::Events | Self::All)
+    }
+}
+
+/// Filters applied to committed transactions before they are pushed to a
+/// stream client. All of the set filters must match.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TransactionStreamFilter {
+    sender: Option<AccountAddress>,
+    entry_function: Option<EntryFunctionId>,
+    event_type: Option<TypeTag>,
+}
+
+impl TransactionStreamFilter {
+    pub fn new(
+        sender: Option<AccountAddress>,
+        entry_function: Option<EntryFunctionId>,
+        event_type: Option<StructTag>,
+    ) -> Self {
+        Self {
+            sender,
+            entry_function,
+            event_type: event_type.map(|tag| TypeTag::Struct(Box::new(tag))),
+        }
+    }
+
+    /// Returns true if the transaction and at least one of its events match
+    /// the filter (the event is only checked if an event type is set).
+    pub fn matches(&self, txn: &TransactionOnChainData) -> bool {
+        self.matches_transaction(&txn.transaction)
+            && (self.event_type.is_none()
+                || txn.events.iter().any(|event| self.matches_event(event)))
+    }
+
+    /// Returns true if the event matches the event type filter, if any
+    pub fn matches_event(&self, event: &ContractEvent) -> bool {
+        self.event_type
+            .as_ref()
+            .map_or(true, |event_type| event.type_tag() == event_type)
+    }
+
+    fn matches_transaction(&self, txn: &aptos_types::transaction::Transaction) -> bool {
+        if self.sender.is_none() && self.entry_function.is_none() {
+            return true;
+        }
+        let signed_txn = match txn.try_as_signed_user_txn() {
+            Some(signed_txn) => signed_txn,
+            None => return false,
+        };
+        if let Some(sender) = &self.sender {
+            if signed_txn.sender() != *sender {
+                return false;
+            }
+        }
+        if let Some(entry_function_id) = &self.entry_function {
+            let entry_function = match signed_txn.payload() {
+                TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+                TransactionPayload::Multisig(multisig) => match &multisig.transaction_payload {
+                    Some(MultisigTransactionPayload::EntryFunction(entry_function)) => {
+                        Some(entry_function)
+                    },
+                    None => None,
+                },
+                _ => None,
+            };
+            return entry_function.map_or(false, |entry_function| {
+                entry_function_matches(entry_function_id, entry_function)
+            });
+        }
+        true
+    }
+}
+
+fn entry_function_matches(id: &EntryFunctionId, entry_function: &EntryFunction) -> bool {
+    entry_function.module().address() == id.module.address.inner()
+        && entry_function.module().name() == id.module.name.as_ident_str()
+        && entry_function.function() == id.name.as_ident_str()
+}
+
+/// Decrements the active stream connection count when the stream is dropped,
+/// i.e. when the client disconnects
+struct ActiveStreamGuard {
+    context: Arc<Context>,
+}
+
+impl ActiveStreamGuard {
+    /// Registers a new stream, or returns None if too many streams are open
+    fn try_new(context: Arc<Context>) -> Option<Self> {
+        let max_active_connections = context
+            .node_config
+            .api
+            .transaction_stream_max_active_connections;
+        if context
+            .transaction_stream_active_connections
+            .fetch_add(1, Ordering::Relaxed)
+            >= max_active_connections
+        {
+            context
+                .transaction_stream_active_connections
+                .fetch_sub(1, Ordering::Relaxed);
+            return None;
+        }
+        metrics::TRANSACTION_STREAM_GAUGE.inc();
+        Some(Self { context })
+    }
+}
+
+impl Drop for ActiveStreamGuard {
+    fn drop(&mut self) {
+        metrics::TRANSACTION_STREAM_GAUGE.dec();
+        self.context
+            .transaction_stream_active_connections
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// State of a single transaction stream, driven by the client polling the
+/// stream
+struct TransactionStreamState {
+    context: Arc<Context>,
+    accept_type: AcceptType,
+    content: TransactionStreamContent,
+    filter: Arc<TransactionStreamFilter>,
+    next_version: u64,
+    pending: VecDeque<TransactionStreamMessage>,
+    done: bool,
+    _guard: ActiveStreamGuard,
+}
+
+impl TransactionStreamState {
+    /// Reads the next page of committed transactions into `pending`. Returns
+    /// false if there are no new transactions yet.
+    async fn fetch_next_page(&mut self) -> Result<bool, BasicError> {
+        let context = self.context.clone();
+        let accept_type = self.accept_type.clone();
+        let content = self.content;
+        let filter = self.filter.clone();
+        let start_version = self.next_version;
+
+        let page = api_spawn_blocking(move || {
+            next_page(&context, &accept_type, content, &filter, start_version)
+        })
+        .await?;
+
+        Ok(match page {
+            Some((next_version, messages)) => {
+                self.next_version = next_version;
+                self.pending.extend(messages);
+                true
+            },
+            None => false,
+        })
+    }
+}
+
+/// Reads and renders the committed transactions starting at `start_version`.
+/// Returns the version to continue from along with the messages to push, or
+/// None if `start_version` has not been committed yet.
+fn next_page(
+    context: &Context,
+    accept_type: &AcceptType,
+    content: TransactionStreamContent,
+    filter: &TransactionStreamFilter,
+    start_version: u64,
+) -> Result<Option<(u64, Vec<TransactionStreamMessage>)>, BasicError> {
+    let latest_ledger_info = context.get_latest_ledger_info::<BasicError>()?;
+    let ledger_version = latest_ledger_info.version();
+    if start_version > ledger_version {
+        return Ok(None);
+    }
+
+    let data = context
+        .get_transactions(
+            start_version,
+            context.max_transactions_page_size(),
+            ledger_version,
+        )
+        .context("Failed to read raw transactions from storage")
+        .map_err(|err| {
+            BasicError::internal_with_code(err, AptosErrorCode::InternalError, &latest_ledger_info)
+        })?;
+    let next_version = start_version + data.len() as u64;
+    let data: Vec<_> = data.into_iter().filter(|txn| filter.matches(txn)).collect();
+
+    let messages = match accept_type {
+        AcceptType::Json => render_json(context, &latest_ledger_info, content, filter, data)?,
+        AcceptType::Bcs => render_bcs(&latest_ledger_info, content, filter, data)?,
+    };
+    Ok(Some((next_version, messages)))
+}
+
+fn render_json(
+    context: &Context,
+    ledger_info: &LedgerInfo,
+    content: TransactionStreamContent,
+    filter: &TransactionStreamFilter,
+    data: Vec<TransactionOnChainData>,
+) -> Result<Vec<TransactionStreamMessage>, BasicError> {
+    let mut messages = vec![];
+    if data.is_empty() {
+        return Ok(messages);
+    }
+
+    let events: Vec<Vec<EventWithVersion>> = data
+        .iter()
+        .map(|txn| {
+            txn.events
+                .iter()
+                .filter(|event| filter.matches_event(event))
+                .map(|event| EventWithVersion::new(txn.version, event.clone()))
+                .collect()
+        })
+        .collect();
+    let versions: Vec<u64> = data.iter().map(|txn| txn.version).collect();
+    let transactions = if content.includes_transactions() {
+        Some(context.render_transactions_non_sequential::<BasicError>(ledger_info, data)?)
+    } else {
+        None
+    };
+
+    let state_view = context.latest_state_view_poem::<BasicError>(ledger_info)?;
+    let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+    let mut transactions = transactions.map(|txns| txns.into_iter());
+    for (version, events) in versions.into_iter().zip(events) {
+        if let Some(transaction) = transactions.as_mut().and_then(|txns| txns.next()) {
+            messages.push(TransactionStreamMessage::Transaction(StreamedTransaction {
+                version: version.into(),
+                transaction,
+            }));
+        }
+        if content.includes_events() {
+            let events = converter
+                .try_into_versioned_events(&events)
+                .context("Failed to convert events from storage into response")
+                .map_err(|err| {
+                    BasicError::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+                })?;
+            messages.extend(events.into_iter().map(TransactionStreamMessage::Event));
+        }
+    }
+    Ok(messages)
+}
+
+fn render_bcs(
+    ledger_info: &LedgerInfo,
+    content: TransactionStreamContent,
+    filter: &TransactionStreamFilter,
+    data: Vec<TransactionOnChainData>,
+) -> Result<Vec<TransactionStreamMessage>, BasicError> {
+    let mut messages = vec![];
+    for txn in data {
+        if content.includes_events() && !content.includes_transactions() {
+            // Events have no standalone BCS representation, so we push the
+            // whole transaction if any of its events are requested.
+            if !txn.events.iter().any(|event| filter.matches_event(event)) {
+                continue;
+            }
+        }
+        let bytes = bcs::to_bytes(&txn)
+            .context("Failed to serialize transaction data into BCS")
+            .map_err(|err| {
+                BasicError::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+            })?;
+        messages.push(TransactionStreamMessage::BcsTransaction(
+            StreamedBcsTransaction {
+                version: txn.version.into(),
+                bytes: bytes.into(),
+            },
+        ));
+    }
+    Ok(messages)
+}
+
+/// API for streaming committed transactions and events
+#[derive(Clone)]
+pub struct StreamApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl StreamApi {
+    /// Stream transactions
+    ///
+    /// Pushes committed transactions and their events to the client as
+    /// server-sent events, as they are committed. Each message is a JSON
+    /// encoded `TransactionStreamMessage`. If the BCS accept type is given,
+    /// transactions are pushed as hex encoded BCS instead.
+    ///
+    /// The stream can be resumed by passing the version following the last
+    /// received message as `start`. If the start version has been pruned, a
+    /// 410 will be returned.
+    ///
+    /// The stream is disabled by default. If it is not enabled on the node, a 403
+    /// is returned.
+    #[oai(
+        path = "/transactions/stream",
+        method = "get",
+        operation_id = "stream_transactions",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn stream_transactions(
+        &self,
+        accept_type: AcceptType,
+        /// Ledger version to start streaming from
+        ///
+        /// If not provided, defaults to the next transaction committed
+        start: Query<Option<U64>>,
+        /// What to push for each matching transaction
+        ///
+        /// If not provided, defaults to transactions only
+        content: Query<Option<TransactionStreamContent>>,
+        /// Only push transactions sent by this account
+        sender: Query<Option<Address>>,
+        /// Only push transactions calling this entry function, e.g. `0x1::coin::transfer`
+        entry_function: Query<Option<EntryFunctionId>>,
+        /// Only push transactions emitting events of this type, e.g. `0x1::coin::CoinDeposit`
+        ///
+        /// If events are pushed, only the events of this type are included
+        event_type: Query<Option<MoveStructTag>>,
+    ) -> poem::Result<EventStream<BoxStream<'static, TransactionStreamMessage>>, BasicErrorWith404>
+    {
+        fail_point_poem("endpoint_stream_transactions")?;
+        if !self.context.node_config.api.transaction_stream_enabled {
+            return Err(api_disabled("Stream transactions"));
+        }
+        self.context
+            .check_api_output_enabled("Stream transactions", &accept_type)?;
+
+        if let Some(entry_function) = &entry_function.0 {
+            entry_function
+                .verify()
+                .context("'entry_function' invalid")
+                .map_err(|err| {
+                    BasicErrorWith404::bad_request_with_code_no_info(
+                        err,
+                        AptosErrorCode::InvalidInput,
+                    )
+                })?;
+        }
+        let event_type = event_type
+            .0
+            .map(|event_type| {
+                event_type.verify(0)?;
+                StructTag::try_from(&event_type)
+            })
+            .transpose()
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        let filter = TransactionStreamFilter::new(
+            sender.0.map(|sender| sender.into()),
+            entry_function.0,
+            event_type,
+        );
+
+        // Unlike the paginated APIs, a start version ahead of the ledger is
+        // allowed here, the stream just waits for it to be committed.
+        let context = self.context.clone();
+        let start_version = api_spawn_blocking(move || {
+            let latest_ledger_info = context.get_latest_ledger_info::<BasicErrorWith404>()?;
+            match start.0 {
+                Some(start) if start.0 < latest_ledger_info.oldest_ledger_version.0 => {
+                    Err(version_pruned(start.0, &latest_ledger_info))
+                },
+                Some(start) => Ok(start.0),
+                None => Ok(latest_ledger_info.version() + 1),
+            }
+        })
+        .await?;
+
+        let guard = ActiveStreamGuard::try_new(self.context.clone()).ok_or_else(|| {
+            BasicErrorWith404::service_unavailable_with_code_no_info(
+                "Too many active transaction streams, please retry later",
+                AptosErrorCode::InternalError,
+            )
+        })?;
+
+        let config = &self.context.node_config.api;
+        let poll_interval = Duration::from_millis(config.transaction_stream_poll_interval_ms);
+        let keep_alive = Duration::from_millis(config.transaction_stream_keep_alive_ms);
+        let state = TransactionStreamState {
+            context: self.context.clone(),
+            accept_type,
+            content: content.0.unwrap_or(TransactionStreamContent::Transactions),
+            filter: Arc::new(filter),
+            next_version: start_version,
+            pending: VecDeque::new(),
+            done: false,
+            _guard: guard,
+        };
+
+        let messages = stream::unfold(state, move |mut state| async move {
+            loop {
+                if let Some(message) = state.pending.pop_front() {
+                    metrics::TRANSACTION_STREAM_MESSAGES
+                        .with_label_values(&[message.label()])
+                        .inc();
+                    return Some((message, state));
+                }
+                if state.done {
+                    return None;
+                }
+                match state.fetch_next_page().await {
+                    Ok(true) => {},
+                    Ok(false) => tokio::time::sleep(poll_interval).await,
+                    Err(mut err) => {
+                        state.done = true;
+                        state
+                            .pending
+                            .push_back(TransactionStreamMessage::Error(err.inner_mut().clone()));
+                    },
+                }
+            }
+        })
+        .boxed();
+
+        Ok(EventStream::new(messages).keep_alive(keep_alive))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransactionStreamFilter;
+    use aptos_api_types::{EntryFunctionId, TransactionOnChainData};
+    use aptos_crypto::{
+        ed25519::{Ed25519PrivateKey, Ed25519Signature},
+        HashValue, PrivateKey, Uniform,
+    };
+    use aptos_types::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        contract_event::ContractEvent,
+        transaction::{
+            EntryFunction, ExecutionStatus, RawTransaction, SignedTransaction, Transaction,
+            TransactionInfo, TransactionPayload,
+        },
+        write_set::WriteSet,
+    };
+    use move_core_types::{
+        ident_str,
+        identifier::Identifier,
+        language_storage::{ModuleId, StructTag, TypeTag},
+    };
+    use std::str::FromStr;
+
+    fn create_transaction(
+        sender: AccountAddress,
+        function: &str,
+        events: Vec<ContractEvent>,
+    ) -> TransactionOnChainData {
+        let entry_function = EntryFunction::new(
+            ModuleId::new(AccountAddress::ONE, ident_str!("coin").to_owned()),
+            Identifier::new(function).unwrap(),
+            vec![],
+            vec![],
+        );
+        let raw_transaction = RawTransaction::new(
+            sender,
+            0,
+            TransactionPayload::EntryFunction(entry_function),
+            0,
+            0,
+            0,
+            ChainId::test(),
+        );
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let signed_transaction = SignedTransaction::new(
+            raw_transaction,
+            private_key.public_key(),
+            Ed25519Signature::dummy_signature(),
+        );
+        TransactionOnChainData {
+            version: 0,
+            transaction: Transaction::UserTransaction(signed_transaction),
+            info: TransactionInfo::new(
+                HashValue::zero(),
+                HashValue::zero(),
+                HashValue::zero(),
+                None,
+                0,
+                ExecutionStatus::Success,
+            ),
+            events,
+            accumulator_root_hash: HashValue::zero(),
+            changes: WriteSet::default(),
+        }
+    }
+
+    fn create_event(type_tag: &str) -> ContractEvent {
+        ContractEvent::new_v2(
+            TypeTag::Struct(Box::new(StructTag::from_str(type_tag).unwrap())),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = TransactionStreamFilter::default();
+        let txn = create_transaction(AccountAddress::random(), "transfer", vec![]);
+        assert!(filter.matches(&txn));
+    }
+
+    #[test]
+    fn test_filter_by_sender_and_entry_function() {
+        let sender = AccountAddress::random();
+        let filter = TransactionStreamFilter::new(
+            Some(sender),
+            Some(EntryFunctionId::from_str("0x1::coin::transfer").unwrap()),
+            None,
+        );
+
+        // Only the transaction sent by the sender to the entry function matches
+        assert!(filter.matches(&create_transaction(sender, "transfer", vec![])));
+        assert!(!filter.matches(&create_transaction(sender, "register", vec![])));
+        assert!(!filter.matches(&create_transaction(
+            AccountAddress::random(),
+            "transfer",
+            vec![]
+        )));
+    }
+
+    #[test]
+    fn test_filter_by_event_type() {
+        let filter = TransactionStreamFilter::new(
+            None,
+            None,
+            Some(StructTag::from_str("0x1::coin::CoinDeposit").unwrap()),
+        );
+
+        // Verify the transaction matches only if it emits the event type
+        let deposit = create_event("0x1::coin::CoinDeposit");
+        let withdraw = create_event("0x1::coin::CoinWithdraw");
+        let sender = AccountAddress::random();
+        assert!(filter.matches(&create_transaction(sender, "transfer", vec![
+            withdraw.clone(),
+            deposit.clone()
+        ])));
+        assert!(
+            !filter.matches(&create_transaction(sender, "transfer", vec![
+                withdraw.clone()
+            ]))
+        );
+
+        // Verify only the matching events are selected
+        assert!(filter.matches_event(&deposit));
+        assert!(!filter.matches_event(&withdraw));
+    }
+}
@@ -241,6 +241,48 @@ async fn test_get_account_balance() {
     assert_eq!(concurrent_fa_balance, fa_balance);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_balance_activities() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    let txn = context.mint_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    context.wait_for_internal_indexer_caught_up().await;
+
+    let address = account.address().to_hex_literal();
+    let coin_activities = context
+        .get(&account_balance_activities(&address, APTOS_COIN_TYPE_STR))
+        .await;
+    let deposit = coin_activities.as_array().unwrap().last().unwrap();
+    assert_eq!(deposit["type"], json!("deposit"));
+    assert_ne!(deposit["amount"], json!("0"));
+
+    // Balance changes of a coin are reported together with its paired fungible asset.
+    let fa_activities = context
+        .get(&account_balance_activities(
+            &address,
+            &AccountAddress::TEN.to_hex_literal(),
+        ))
+        .await;
+    assert_eq!(coin_activities, fa_activities);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_balance_activities_disabled() {
+    let context = new_test_context(current_function_name!());
+    let root_account = context.root_account().await;
+    context
+        .expect_status_code(403)
+        .get(&account_balance_activities(
+            &root_account.address().to_hex_literal(),
+            APTOS_COIN_TYPE_STR,
+        ))
+        .await;
+}
+
 async fn test_get_account_modules_by_ledger_version_with_context(mut context: TestContext) {
     let payload =
         aptos_stdlib::publish_module_source("test_module", "module 0xa550c18::test_module {}");
@@ -491,6 +533,10 @@ fn account_balance(address: &str, coin_type: &str) -> String {
     format!("/accounts/{}/balance/{}", address, coin_type)
 }
 
+fn account_balance_activities(address: &str, coin_type: &str) -> String {
+    format!("{}/activities", account_balance(address, coin_type))
+}
+
 fn account_modules_with_ledger_version(address: &str, ledger_version: i128) -> String {
     format!(
         "{}?ledger_version={}",
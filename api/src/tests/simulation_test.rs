@@ -1,8 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
+use super::{new_test_context, new_test_context_with_config};
 use aptos_api_test_context::{current_function_name, pretty, TestContext};
+use aptos_config::config::NodeConfig;
 use aptos_crypto::ed25519::Ed25519Signature;
 use aptos_types::{
     account_address::AccountAddress,
@@ -22,6 +23,29 @@ async fn simulate_aptos_transfer(
     transfer_amount: u64,
     expected_status: u16,
     assert_gas_used: bool,
+) -> serde_json::Value {
+    simulate_aptos_transfer_at(
+        context,
+        "/v1/transactions/simulate",
+        use_valid_signature,
+        transfer_amount,
+        expected_status,
+        assert_gas_used,
+        |_, _, _| {},
+    )
+    .await
+}
+
+/// Simulates an Aptos transfer by posting it to the given simulation path. The JSON request
+/// can be modified by `customize_request`, which is given the sender and receiver addresses.
+async fn simulate_aptos_transfer_at(
+    context: &mut TestContext,
+    path: &str,
+    use_valid_signature: bool,
+    transfer_amount: u64,
+    expected_status: u16,
+    assert_gas_used: bool,
+    customize_request: impl FnOnce(&mut serde_json::Value, AccountAddress, AccountAddress),
 ) -> serde_json::Value {
     let alice = &mut context.gen_account();
     let bob = &mut context.gen_account();
@@ -38,29 +62,31 @@ async fn simulate_aptos_transfer(
         let signature = use_valid_signature
             .then(|| signature.to_string())
             .unwrap_or(Ed25519Signature::dummy_signature().to_string());
+        let mut request = json!({
+            "sender": txn.sender().to_string(),
+            "sequence_number": txn.sequence_number().to_string(),
+            "max_gas_amount": txn.max_gas_amount().to_string(),
+            "gas_unit_price": txn.gas_unit_price().to_string(),
+            "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+            "payload": {
+                "type": "entry_function_payload",
+                "function": "0x1::aptos_account::transfer",
+                "type_arguments": [],
+                "arguments": [
+                    bob.address().to_standard_string(), transfer_amount.to_string(),
+                ]
+            },
+            "signature": {
+                "type": "ed25519_signature",
+                "public_key": public_key.to_string(),
+                "signature": signature,
+            }
+        });
+        customize_request(&mut request, alice.address(), bob.address());
         let req = warp::test::request()
             .method("POST")
-            .path("/v1/transactions/simulate")
-            .json(&json!({
-                "sender": txn.sender().to_string(),
-                "sequence_number": txn.sequence_number().to_string(),
-                "max_gas_amount": txn.max_gas_amount().to_string(),
-                "gas_unit_price": txn.gas_unit_price().to_string(),
-                "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
-                "payload": {
-                    "type": "entry_function_payload",
-                    "function": "0x1::aptos_account::transfer",
-                    "type_arguments": [],
-                    "arguments": [
-                        bob.address().to_standard_string(), transfer_amount.to_string(),
-                    ]
-                },
-                "signature": {
-                    "type": "ed25519_signature",
-                    "public_key": public_key.to_string(),
-                    "signature": signature,
-                }
-            }));
+            .path(path)
+            .json(&request);
         let resp = context.expect_status_code(expected_status).reply(req).await;
         // Assert the gas used header is present if expected.
         if assert_gas_used {
@@ -81,6 +107,51 @@ async fn simulate_aptos_transfer(
     }
 }
 
+/// Simulates an Aptos transfer (with an invalid signature) using the state overrides built
+/// by `state_overrides` from the sender and receiver addresses
+async fn simulate_aptos_transfer_with_state_overrides(
+    context: &mut TestContext,
+    transfer_amount: u64,
+    expected_status: u16,
+    assert_gas_used: bool,
+    state_overrides: impl FnOnce(AccountAddress, AccountAddress) -> serde_json::Value,
+) -> serde_json::Value {
+    simulate_aptos_transfer_at(
+        context,
+        "/v1/transactions/simulate",
+        false,
+        transfer_amount,
+        expected_status,
+        assert_gas_used,
+        |request, alice, bob| request["state_overrides"] = state_overrides(alice, bob),
+    )
+    .await
+}
+
+/// Simulates an Aptos transfer (with an invalid signature), requesting a call trace
+async fn simulate_aptos_transfer_with_trace(
+    context: &mut TestContext,
+    transfer_amount: u64,
+    expected_status: u16,
+) -> serde_json::Value {
+    simulate_aptos_transfer_at(
+        context,
+        "/v1/transactions/simulate?include_trace=true",
+        false,
+        transfer_amount,
+        expected_status,
+        false,
+        |_, _, _| {},
+    )
+    .await
+}
+
+fn trace_enabled_config() -> NodeConfig {
+    let mut node_config = NodeConfig::default();
+    node_config.api.simulation_trace_enabled = true;
+    node_config
+}
+
 const SMALL_TRANSFER_AMOUNT: u64 = 10;
 const LARGE_TRANSFER_AMOUNT: u64 = 1_000_000_000;
 
@@ -119,6 +190,102 @@ async fn test_bcs_simulate_fee_payer_transaction_without_gas_fee_check() {
     bcs_simulate_fee_payer_transaction_without_gas_fee_check(&mut context).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_balance_override() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = simulate_aptos_transfer_with_state_overrides(
+        &mut context,
+        LARGE_TRANSFER_AMOUNT,
+        200,
+        true,
+        |alice, _| {
+            json!({
+                "accounts": [{
+                    "address": alice.to_standard_string(),
+                    "balance": (10 * LARGE_TRANSFER_AMOUNT).to_string(),
+                }]
+            })
+        },
+    )
+    .await;
+
+    // The overridden balance is large enough for the transfer to succeed
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+    assert!(!resp[0]["changes"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_invalid_state_override() {
+    let mut context = new_test_context(current_function_name!());
+
+    // The receiver doesn't exist, so its sequence number can't be overridden
+    let resp = simulate_aptos_transfer_with_state_overrides(
+        &mut context,
+        SMALL_TRANSFER_AMOUNT,
+        400,
+        false,
+        |_, bob| {
+            json!({
+                "accounts": [{
+                    "address": bob.to_standard_string(),
+                    "sequence_number": "5",
+                }]
+            })
+        },
+    )
+    .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_trace() {
+    let mut context =
+        new_test_context_with_config(current_function_name!(), trace_enabled_config());
+    let resp = simulate_aptos_transfer_with_trace(&mut context, SMALL_TRANSFER_AMOUNT, 200).await;
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    let trace = &resp[0]["trace"];
+    assert_eq!(trace["function"], json!("0x1::aptos_account::transfer"));
+    assert!(trace["gas_used"].as_str().unwrap().parse::<u64>().unwrap() > 0);
+    assert!(!trace["calls"].as_array().unwrap().is_empty());
+    assert_eq!(trace["truncated"], json!(false));
+
+    // The transfer emits events somewhere down the call tree, and every callee is
+    // called with arguments.
+    fn collect_events(trace: &serde_json::Value, events: &mut Vec<serde_json::Value>) {
+        events.extend(trace["events"].as_array().unwrap().iter().cloned());
+        for call in trace["calls"].as_array().unwrap() {
+            assert!(call["arguments"].is_array());
+            collect_events(call, events);
+        }
+    }
+    let mut events = vec![];
+    collect_events(trace, &mut events);
+    assert!(!events.is_empty());
+    assert!(events.iter().all(|event| event["type"].is_string()));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_trace_max_calls() {
+    let mut node_config = trace_enabled_config();
+    node_config.api.simulation_trace_max_calls = 1;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let resp = simulate_aptos_transfer_with_trace(&mut context, SMALL_TRANSFER_AMOUNT, 200).await;
+
+    // Only the entry point fits in the trace, but it still accounts for all gas used
+    let trace = &resp[0]["trace"];
+    assert!(trace["calls"].as_array().unwrap().is_empty());
+    assert_eq!(trace["truncated"], json!(true));
+    assert!(trace["gas_used"].as_str().unwrap().parse::<u64>().unwrap() > 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_trace_disabled() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = simulate_aptos_transfer_with_trace(&mut context, SMALL_TRANSFER_AMOUNT, 403).await;
+    assert_eq!(resp["error_code"], json!("api_disabled"));
+}
+
 // Enable the MODULE_EVENT_MIGRATION feature
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_txn_with_aggregator() {
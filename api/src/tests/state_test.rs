@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
+use super::{new_test_context, new_test_context_with_db_sharding_and_internal_indexer};
 use aptos_api_test_context::{current_function_name, TestContext};
 use aptos_sdk::{transaction_builder::aptos_stdlib::aptos_token_stdlib, types::LocalAccount};
 use aptos_storage_interface::DbReader;
@@ -94,6 +94,70 @@ async fn test_get_account_resource_with_version_too_large() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resource_history() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let mut account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    let txn = context.mint_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    let root_account = context.root_account().await;
+    let txn = context.account_transfer(&mut account, &root_account, 1);
+    context.commit_block(&vec![txn]).await;
+    context.wait_for_internal_indexer_caught_up().await;
+
+    let address = account.address().to_hex_literal();
+    let history = context
+        .get(&get_account_resource_history(
+            &address,
+            "0x1::account::Account",
+        ))
+        .await;
+    let history = history.as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history[0]["resource"]["data"]["sequence_number"],
+        json!("0")
+    );
+    assert_eq!(
+        history[1]["resource"]["data"]["sequence_number"],
+        json!("1")
+    );
+
+    // Without a start version, the latest versions are returned.
+    let latest = context
+        .get(&format!(
+            "{}?limit=1",
+            get_account_resource_history(&address, "0x1::account::Account")
+        ))
+        .await;
+    assert_eq!(latest, json!([history[1]]));
+
+    let start = history[1]["version"].as_str().unwrap();
+    let from_start = context
+        .get(&format!(
+            "{}?start={}",
+            get_account_resource_history(&address, "0x1::account::Account"),
+            start
+        ))
+        .await;
+    assert_eq!(from_start, json!([history[1]]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resource_history_disabled() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(403)
+        .get(&get_account_resource_history(
+            "0xA550C18",
+            "0x1::account::Account",
+        ))
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_module() {
     let mut context = new_test_context(current_function_name!());
@@ -308,6 +372,10 @@ fn get_account_resource_with_version(address: &str, struct_tag: &str, version: u
     )
 }
 
+fn get_account_resource_history(address: &str, struct_tag: &str) -> String {
+    format!("{}/history", get_account_resource(address, struct_tag))
+}
+
 fn get_account_module(address: &str, name: &str) -> String {
     format!("/accounts/{}/module/{}", address, name)
 }
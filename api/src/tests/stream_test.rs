@@ -0,0 +1,76 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use aptos_api_test_context::{current_function_name, ApiSpecificConfig, TestContext};
+use aptos_config::config::NodeConfig;
+use serde_json::Value;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_disabled_by_default() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(403)
+        .get("/transactions/stream")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_with_entry_function_filter() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.transaction_stream_enabled = true;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let root_address = context.root_account().await.address();
+    let account = context.create_account().await;
+
+    // Stream from genesis, and verify the first pushed transaction is the transfer
+    let message = next_stream_message(
+        &context,
+        "/transactions/stream?start=0&entry_function=0x1::aptos_account::transfer",
+    )
+    .await;
+    assert_eq!(message["type"], "transaction");
+    let transaction = &message["transaction"];
+    assert_eq!(transaction["type"], "user_transaction");
+    assert_eq!(transaction["sender"], root_address.to_hex_literal());
+    assert_eq!(
+        transaction["payload"]["function"],
+        "0x1::aptos_account::transfer"
+    );
+    assert_eq!(
+        transaction["payload"]["arguments"][0],
+        account.address().to_hex_literal()
+    );
+}
+
+/// Opens a stream against the API server and returns the first pushed message
+async fn next_stream_message(context: &TestContext, path: &str) -> Value {
+    let ApiSpecificConfig::V1(address) = context.api_specific_config;
+    let url = format!(
+        "http://{}{}{}",
+        address,
+        context.api_specific_config.get_api_base_path(),
+        path
+    );
+    let mut response = reqwest::get(url).await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Read server-sent events until a data line is received
+    let mut buffer = String::new();
+    tokio::time::timeout(Duration::from_secs(30), async move {
+        while let Some(chunk) = response.chunk().await.unwrap() {
+            buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+            if let Some(data) = buffer
+                .lines()
+                .find_map(|line| line.strip_prefix("data:"))
+                .filter(|_| buffer.contains("\n\n"))
+            {
+                return serde_json::from_str(data.trim()).unwrap();
+            }
+        }
+        panic!("The stream closed before pushing a message");
+    })
+    .await
+    .expect("Timed out waiting for a stream message")
+}
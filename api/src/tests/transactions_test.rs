@@ -813,6 +813,42 @@ async fn test_get_account_transactions() {
     test_account_transaction_with_context(shard_context).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_participant_transactions() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    let txn = context.mint_user_account(&account).await;
+    context.commit_block(&vec![txn.clone()]).await;
+    context.wait_for_internal_indexer_caught_up().await;
+
+    // The account did not send the transfer, but received funds from it.
+    let txns = context
+        .get(format!("/accounts/{}/participant_transactions", account.address()).as_str())
+        .await;
+    let txns = txns.as_array().unwrap();
+    assert!(!txns.is_empty());
+    let expected_txn = context
+        .get(&format!(
+            "/transactions/by_hash/{}",
+            txn.committed_hash().to_hex_literal()
+        ))
+        .await;
+    assert_json(txns.last().unwrap().clone(), expected_txn);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_participant_transactions_disabled() {
+    let context = new_test_context(current_function_name!());
+    let address = context.root_account().await.address();
+    context
+        .expect_status_code(403)
+        .get(format!("/accounts/{}/participant_transactions", address).as_str())
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_transactions_filter_transactions_by_start_sequence_number() {
     let mut context = new_test_context(current_function_name!());
@@ -252,3 +252,117 @@ async fn test_view_aggregator() {
         .await;
     context.check_golden_output_no_prune(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/view/batch",
+            json!([
+                build_coin_balance_request(&owner.address()),
+                build_coin_decimals_request(),
+                {
+                    "function":"0x1::aptos_account::assert_account_exists",
+                    "arguments": vec![owner.address().to_string()],
+                    "type_arguments": [],
+                },
+            ]),
+        )
+        .await;
+
+    // Verify the results are returned in the same order as the requests
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["values"], json!(["100000"]));
+    assert_eq!(results[1]["values"], json!([8]));
+
+    // Verify the invalid request failed without failing the batch
+    assert!(results[2].get("values").is_none());
+    assert_eq!(results[2]["error"]["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_blocklist() {
+    let mut node_config = NodeConfig::default();
+
+    // Blocklist the balance function.
+    node_config.api.view_filter = ViewFilter::Blocklist(vec![ViewFunctionId {
+        address: AccountAddress::from_str("0x1").unwrap(),
+        module: "coin".to_string(),
+        function_name: "balance".to_string(),
+    }]);
+
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let owner = &mut context.gen_account();
+
+    // See that only the blocked function is rejected.
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/view/batch",
+            json!([
+                build_coin_balance_request(&owner.address()),
+                build_coin_decimals_request(),
+            ]),
+        )
+        .await;
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["error"]["error_code"], json!("invalid_input"));
+    assert_eq!(results[1]["values"], json!([8]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_too_many_requests() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_view_function_batch_size = 1;
+
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view/batch",
+            json!([build_coin_decimals_request(), build_coin_decimals_request()]),
+        )
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_gas_limit() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_gas_view_function_batch = 1;
+
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let owner = &mut context.gen_account();
+
+    // See that the first view function uses up the gas of the batch, and the rest are rejected
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/view/batch",
+            json!([
+                build_coin_balance_request(&owner.address()),
+                build_coin_decimals_request(),
+            ]),
+        )
+        .await;
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1]["error"]["error_code"], json!("invalid_input"));
+    assert!(results[1]["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("exhausted"));
+    assert_eq!(results[1]["gas_used"], json!("0"));
+}
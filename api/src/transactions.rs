@@ -17,14 +17,16 @@ use crate::{
         BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404,
         ForbiddenError, InsufficientStorageError, InternalError,
     },
+    state_override::StateOverrideView,
     ApiTags,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
-    Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
+    AsConverter, CallTrace, CallTraceEvent, EncodeSubmissionRequest, GasEstimation,
+    GasEstimationBcs, HashValue, HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction,
+    SimulateTransactionRequest, StateOverrides, SubmitTransactionRequest, Transaction,
+    TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
     TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
@@ -101,6 +103,48 @@ impl VerifyInput for SubmitTransactionPost {
     }
 }
 
+// We need a custom type here because we use different types for each of the
+// content types possible for the POST data.
+#[derive(ApiRequest, Debug)]
+pub enum SimulateTransactionPost {
+    #[oai(content_type = "application/json")]
+    Json(Json<SimulateTransactionRequest>),
+
+    // State overrides are only supported for JSON requests.
+    #[oai(content_type = "application/x.aptos.signed_transaction+bcs")]
+    Bcs(Bcs),
+}
+
+impl VerifyInput for SimulateTransactionPost {
+    fn verify(&self) -> anyhow::Result<()> {
+        match self {
+            SimulateTransactionPost::Json(inner) => inner.0.verify(),
+            SimulateTransactionPost::Bcs(_) => Ok(()),
+        }
+    }
+}
+
+impl SimulateTransactionPost {
+    /// Splits the request into the transaction to simulate and the state overrides
+    fn into_parts(self) -> (SubmitTransactionPost, StateOverrides) {
+        match self {
+            SimulateTransactionPost::Json(inner) => {
+                let SimulateTransactionRequest {
+                    submit_transaction_request,
+                    state_overrides,
+                } = inner.0;
+                (
+                    SubmitTransactionPost::Json(Json(submit_transaction_request)),
+                    state_overrides.unwrap_or_default(),
+                )
+            },
+            SimulateTransactionPost::Bcs(data) => {
+                (SubmitTransactionPost::Bcs(data), StateOverrides::default())
+            },
+        }
+    }
+}
+
 // We need a custom type here because we use different types for each of the
 // content types possible for the POST data.
 #[derive(ApiRequest, Debug)]
@@ -343,6 +387,60 @@ impl TransactionsApi {
         api_spawn_blocking(move || api.list_by_account(&accept_type, page, address.0)).await
     }
 
+    /// Get account participant transactions
+    ///
+    /// Retrieves on-chain committed transactions that an account participated in, in the order
+    /// they were committed. Unlike `/accounts/{address}/transactions`, this includes transactions
+    /// not sent by the account, e.g., transactions where the account is the fee payer or a
+    /// secondary signer, or transactions that emitted events to the account or changed the
+    /// balance of one of its fungible stores.
+    ///
+    /// This requires the node to run the internal indexer with participant transactions enabled.
+    /// Transactions that have been pruned are not returned.
+    #[oai(
+        path = "/accounts/:address/participant_transactions",
+        method = "get",
+        operation_id = "get_account_participant_transactions",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_accounts_participant_transactions(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        /// Ledger version to start list of transactions
+        ///
+        /// If not provided, defaults to showing the latest transactions
+        start: Query<Option<U64>>,
+        /// Max number of transactions to retrieve.
+        ///
+        /// If not provided, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        fail_point_poem("endpoint_get_accounts_participant_transactions")?;
+        self.context
+            .check_api_output_enabled("Get account participant transactions", &accept_type)?;
+        if !self
+            .context
+            .node_config
+            .indexer_db_config
+            .enable_participant_transactions()
+        {
+            return Err(api_forbidden(
+                "Get account participant transactions",
+                "The internal indexer for participant transactions is not enabled on this node.",
+            ));
+        }
+        let page = Page::new(
+            start.0.map(|v| v.0),
+            limit.0,
+            self.context.max_transactions_page_size(),
+        );
+        let api = self.clone();
+        api_spawn_blocking(move || api.list_by_account_participant(&accept_type, page, address.0))
+            .await
+    }
+
     /// Submit transaction
     ///
     /// This endpoint accepts transaction submissions in two formats.
@@ -470,6 +568,16 @@ impl TransactionsApi {
     ///
     /// To use this endpoint with BCS, you must submit a SignedTransaction
     /// encoded as BCS. See SignedTransaction in types/src/transaction/mod.rs.
+    ///
+    /// JSON requests may also include `state_overrides`, which override the sequence
+    /// numbers, APT balances, resources and modules of accounts before the transaction is
+    /// simulated. The overrides only apply to the simulation, and the resulting write set
+    /// is returned in the `changes` of the simulated transaction.
+    ///
+    /// If `include_trace` is set and call traces are enabled on the node, JSON responses
+    /// include a `trace` of the function calls made during execution, with type arguments,
+    /// arguments, gas used and emitted events. The number of calls and the size of the
+    /// recorded values are limited by the node configuration.
     #[oai(
         path = "/transactions/simulate",
         method = "post",
@@ -488,7 +596,11 @@ impl TransactionsApi {
         /// If set to true, the transaction will use a higher price than the original
         /// estimate.
         estimate_prioritized_gas_unit_price: Query<Option<bool>>,
-        data: SubmitTransactionPost,
+        /// If set to true, the output will include a call trace of the execution.
+        /// Only supported for JSON output. If call traces are not enabled on the
+        /// node, a 403 is returned.
+        include_trace: Query<Option<bool>>,
+        data: SimulateTransactionPost,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         data.verify()
             .context("Simulated transaction invalid")
@@ -504,11 +616,19 @@ impl TransactionsApi {
         }
         self.context
             .check_api_output_enabled("Simulate transaction", &accept_type)?;
+        let include_trace = include_trace.0.unwrap_or_default();
+        if include_trace && !self.context.node_config.api.simulation_trace_enabled {
+            return Err(api_forbidden(
+                "Simulate transaction with a call trace",
+                "Call traces of simulated transactions are not enabled on this node.",
+            ));
+        }
 
         let api = self.clone();
         let context = self.context.clone();
         api_spawn_blocking(move || {
             let ledger_info = context.get_latest_ledger_info()?;
+            let (data, state_overrides) = data.into_parts();
             let mut signed_transaction = api.get_signed_transaction(&ledger_info, data)?;
 
             // Confirm the simulation filter allows the transaction. We use HashValue::zero()
@@ -563,6 +683,15 @@ impl TransactionsApi {
                             AptosErrorCode::InvalidInput,
                         )
                     })?;
+                let state_view = StateOverrideView::new(&state_view, &context, &state_overrides)
+                    .context("Invalid state overrides")
+                    .map_err(|err| {
+                        SubmitTransactionError::bad_request_with_code(
+                            err,
+                            AptosErrorCode::InvalidInput,
+                            &ledger_info,
+                        )
+                    })?;
                 let output = AptosVM::execute_view_function(
                     &state_view,
                     ModuleId::new(AccountAddress::ONE, ident_str!("coin").into()),
@@ -616,7 +745,13 @@ impl TransactionsApi {
                 );
             }
 
-            api.simulate(&accept_type, ledger_info, signed_transaction)
+            api.simulate(
+                &accept_type,
+                ledger_info,
+                signed_transaction,
+                &state_overrides,
+                include_trace,
+            )
         })
         .await
     }
@@ -1034,6 +1169,34 @@ impl TransactionsApi {
         }
     }
 
+    fn list_by_account_participant(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+        address: Address,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        // When the internal indexer is enabled, this is the latest version it has processed
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let data = self.context.get_account_participant_transactions(
+            address.into(),
+            page.start_option(),
+            page.limit(&ledger_info)?,
+            ledger_info.version(),
+            &ledger_info,
+        )?;
+        match accept_type {
+            AcceptType::Json => BasicResponse::try_from_json((
+                self.context
+                    .render_transactions_non_sequential(&ledger_info, data)?,
+                &ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((data, &ledger_info, BasicResponseStatus::Ok))
+            },
+        }
+    }
+
     /// Parses a single signed transaction
     fn get_signed_transaction(
         &self,
@@ -1252,6 +1415,10 @@ impl TransactionsApi {
                 mempool_status.message,
                 AptosErrorCode::InvalidTransactionUpdate,
             )),
+            MempoolStatusCode::RejectedByFilter => Err(AptosError::new_with_error_code(
+                mempool_status.message,
+                AptosErrorCode::TransactionRejectedByFilter,
+            )),
             MempoolStatusCode::UnknownStatus => Err(AptosError::new_with_error_code(
                 format!("Transaction was rejected with status {}", mempool_status,),
                 AptosErrorCode::InternalError,
@@ -1321,6 +1488,9 @@ impl TransactionsApi {
                         ledger_info,
                     ),
                 ),
+                AptosErrorCode::TransactionRejectedByFilter => Err(
+                    SubmitTransactionError::forbidden_from_aptos_error(error, ledger_info),
+                ),
                 _ => Err(SubmitTransactionError::internal_from_aptos_error(
                     error,
                     ledger_info,
@@ -1377,6 +1547,8 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: LedgerInfo,
         txn: SignedTransaction,
+        state_overrides: &StateOverrides,
+        include_trace: bool,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         // The caller must ensure that the signature is not valid, as otherwise
         // a malicious actor could execute the transaction without their knowledge
@@ -1388,10 +1560,31 @@ impl TransactionsApi {
             ));
         }
 
-        // Simulate transaction
+        // Simulate transaction, with the state overrides applied on top of the latest state
         let state_view = self.context.latest_state_view_poem(&ledger_info)?;
-        let (vm_status, output) =
-            AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
+        let state_view = StateOverrideView::new(&state_view, &self.context, state_overrides)
+            .context("Invalid state overrides")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                )
+            })?;
+        let (vm_status, output, gas_log) = if include_trace {
+            AptosSimulationVM::create_vm_and_simulate_signed_transaction_with_gas_profiler(
+                &txn,
+                &state_view,
+                self.context
+                    .node_config
+                    .api
+                    .simulation_trace_max_value_bytes,
+            )
+        } else {
+            let (vm_status, output) =
+                AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
+            (vm_status, output, None)
+        };
         let version = ledger_info.version();
 
         // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
@@ -1459,6 +1652,10 @@ impl TransactionsApi {
                 let transactions = self
                     .context
                     .render_transactions_non_sequential(&ledger_info, vec![simulated_txn])?;
+                let max_calls = self.context.node_config.api.simulation_trace_max_calls;
+                let trace = gas_log.map(|gas_log| {
+                    convert_call_trace(gas_log.call_trace_with_max_calls(max_calls))
+                });
 
                 // Users can only make requests to simulate UserTransactions, so unpack
                 // the Vec<Transaction> into Vec<UserTransaction>.
@@ -1479,6 +1676,7 @@ impl TransactionsApi {
                                 },
                                 _ => (),
                             }
+                            user_txn.trace = trace.clone();
                             user_transactions.push(user_txn);
                         },
                         _ => {
@@ -1562,6 +1760,27 @@ impl TransactionsApi {
     }
 }
 
+fn convert_call_trace(trace: aptos_gas_profiling::CallTrace) -> CallTrace {
+    CallTrace {
+        function: trace.function,
+        type_arguments: trace.type_arguments,
+        arguments: trace.arguments,
+        return_values: trace.return_values,
+        is_native: trace.is_native,
+        gas_used: U64::from(trace.gas_used.ceil() as u64),
+        events: trace
+            .events
+            .into_iter()
+            .map(|event| CallTraceEvent {
+                typ: event.typ,
+                data: event.data,
+            })
+            .collect(),
+        calls: trace.calls.into_iter().map(convert_call_trace).collect(),
+        truncated: trace.truncated,
+    }
+}
+
 fn override_gas_parameters(
     signed_txn: &SignedTransaction,
     max_gas_amount: Option<u64>,
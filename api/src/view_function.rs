@@ -14,10 +14,11 @@ use crate::{
 };
 use anyhow::Context as anyhowContext;
 use aptos_api_types::{
-    AptosErrorCode, AsConverter, MoveValue, ViewFunction, ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED,
-    U64,
+    AptosError, AptosErrorCode, AsConverter, MoveValue, ViewFunction, ViewFunctionBatchOutput,
+    ViewFunctionBatchResult, ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_bcs_utils::serialize_uleb128;
+use aptos_storage_interface::state_store::state_view::db_state_view::DbStateView;
 use aptos_vm::AptosVM;
 use itertools::Itertools;
 use move_core_types::language_storage::TypeTag;
@@ -39,6 +40,31 @@ pub enum ViewFunctionRequest {
     Bcs(Bcs),
 }
 
+#[derive(ApiRequest, Debug)]
+pub enum ViewFunctionBatchRequest {
+    #[oai(content_type = "application/json")]
+    Json(Json<Vec<ViewRequest>>),
+
+    #[oai(content_type = "application/x.aptos.view_function_batch+bcs")]
+    Bcs(Bcs),
+}
+
+/// A deserialized batch view request. JSON requests are only converted to view
+/// functions once the state view is available.
+enum ParsedViewFunctionBatch {
+    Json(Vec<ViewRequest>),
+    Bcs(Vec<ViewFunction>),
+}
+
+impl ParsedViewFunctionBatch {
+    fn len(&self) -> usize {
+        match self {
+            ParsedViewFunctionBatch::Json(view_requests) => view_requests.len(),
+            ParsedViewFunctionBatch::Bcs(view_functions) => view_functions.len(),
+        }
+    }
+}
+
 #[OpenApi]
 impl ViewFunctionApi {
     /// Execute view function of a module
@@ -71,6 +97,45 @@ impl ViewFunctionApi {
         api_spawn_blocking(move || view_request(context, accept_type, request, ledger_version))
             .await
     }
+
+    /// Execute a batch of view functions
+    ///
+    /// Execute multiple Move view functions against the same ledger version, and return their
+    /// results in the same order as the requests. If a single view function fails, its error
+    /// is returned in place of its return values, and the rest of the batch is unaffected.
+    ///
+    /// The maximum number of view functions in a batch, and the total gas they may use, are
+    /// configured by the node. For BCS responses, each result is a BCS encoded
+    /// `ViewFunctionBatchOutput`.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window.
+    /// If the requested ledger version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/view/batch",
+        method = "post",
+        operation_id = "view_batch",
+        tag = "ApiTags::View"
+    )]
+    async fn view_function_batch(
+        &self,
+        accept_type: AcceptType,
+        /// List of view function requests with type and position arguments
+        request: ViewFunctionBatchRequest,
+        /// Ledger version to get state of account
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<ViewFunctionBatchResult>> {
+        fail_point_poem("endpoint_view_function_batch")?;
+        self.context
+            .check_api_output_enabled("View function batch", &accept_type)?;
+
+        let context = self.context.clone();
+        api_spawn_blocking(move || {
+            view_batch_request(context, accept_type, request, ledger_version)
+        })
+        .await
+    }
 }
 
 fn view_request(
@@ -118,19 +183,9 @@ fn view_request(
     };
 
     // Reject the request if it's not allowed by the filter.
-    if !context.node_config.api.view_filter.allows(
-        view_function.module.address(),
-        view_function.module.name().as_str(),
-        view_function.function.as_str(),
-    ) {
-        return Err(BasicErrorWith404::forbidden_with_code_no_info(
-            format!(
-                "Function {}::{} is not allowed",
-                view_function.module, view_function.function
-            ),
-            AptosErrorCode::InvalidInput,
-        ));
-    }
+    check_view_function_allowed(&context, &view_function).map_err(|err| {
+        BasicErrorWith404::forbidden_with_code_no_info(err, AptosErrorCode::InvalidInput)
+    })?;
 
     let output = AptosVM::execute_view_function(
         &state_view,
@@ -166,14 +221,7 @@ fn view_request(
             BasicResponse::try_from_encoded((ret, &ledger_info, BasicResponseStatus::Ok))
         },
         AcceptType::Json => {
-            let return_types = state_view
-                .as_converter(context.db.clone(), context.indexer_reader.clone())
-                .function_return_types(&view_function)
-                .and_then(|tys| {
-                    tys.iter()
-                        .map(TypeTag::try_from)
-                        .collect::<anyhow::Result<Vec<_>>>()
-                })
+            let move_vals = convert_return_values(&context, &state_view, &view_function, values)
                 .map_err(|err| {
                     BasicErrorWith404::bad_request_with_code(
                         err,
@@ -182,29 +230,268 @@ fn view_request(
                     )
                 })?;
 
-            let move_vals = values
-                .into_iter()
-                .zip(return_types.into_iter())
-                .map(|(v, ty)| {
-                    state_view
-                        .as_converter(context.db.clone(), context.indexer_reader.clone())
-                        .try_into_move_value(&ty, &v)
-                })
-                .collect::<anyhow::Result<Vec<_>>>()
+            BasicResponse::try_from_json((move_vals, &ledger_info, BasicResponseStatus::Ok))
+        },
+    };
+    context.view_function_stats().increment(
+        FunctionStats::function_to_key(&view_function.module, &view_function.function),
+        output.gas_used,
+    );
+    result.map(|r| r.with_gas_used(Some(output.gas_used)))
+}
+
+fn view_batch_request(
+    context: Arc<Context>,
+    accept_type: AcceptType,
+    request: ViewFunctionBatchRequest,
+    ledger_version: Query<Option<U64>>,
+) -> BasicResultWith404<Vec<ViewFunctionBatchResult>> {
+    // Retrieve the current state of the chain. All view functions are executed against it.
+    let (ledger_info, requested_version) = context
+        .get_latest_ledger_info_and_verify_lookup_version(ledger_version.map(|inner| inner.0))?;
+
+    // Reject oversized batches before doing any other work
+    let check_batch_size = |batch_size: usize| {
+        let max_batch_size = context.max_view_function_batch_size();
+        if batch_size > max_batch_size {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "Requested too many view functions: {}, while limit is {}",
+                    batch_size, max_batch_size,
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+        Ok(())
+    };
+    let view_functions = match request {
+        ViewFunctionBatchRequest::Json(data) => ParsedViewFunctionBatch::Json(data.0),
+        ViewFunctionBatchRequest::Bcs(data) => ParsedViewFunctionBatch::Bcs(
+            bcs::from_bytes_with_limit(data.0.as_slice(), MAX_RECURSIVE_TYPES_ALLOWED as usize)
+                .context("Failed to deserialize input into a batch of ViewFunctions")
                 .map_err(|err| {
                     BasicErrorWith404::bad_request_with_code(
                         err,
-                        AptosErrorCode::InternalError,
+                        AptosErrorCode::InvalidInput,
                         &ledger_info,
                     )
-                })?;
+                })?,
+        ),
+    };
+    check_batch_size(view_functions.len())?;
 
-            BasicResponse::try_from_json((move_vals, &ledger_info, BasicResponseStatus::Ok))
+    let state_view = context
+        .state_view_at_version(requested_version)
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AptosErrorCode::InternalError,
+                &ledger_info,
+            )
+        })?;
+
+    // Parse the view functions. Invalid JSON requests are reported per item.
+    let view_functions: Vec<Result<ViewFunction, AptosError>> = match view_functions {
+        ParsedViewFunctionBatch::Json(view_requests) => {
+            let converter =
+                state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+            view_requests
+                .into_iter()
+                .map(|view_request| {
+                    converter
+                        .convert_view_function(view_request)
+                        .map_err(|err| {
+                            AptosError::new_with_error_code(err, AptosErrorCode::InvalidInput)
+                        })
+                })
+                .collect()
+        },
+        ParsedViewFunctionBatch::Bcs(view_functions) => {
+            view_functions.into_iter().map(Ok).collect()
         },
     };
+
+    // Execute each of the view functions, sharing the gas limit of the batch
+    let mut remaining_gas = context.node_config.api.max_gas_view_function_batch;
+    let outputs: Vec<_> = view_functions
+        .into_iter()
+        .map(|view_function| {
+            view_function.map(|view_function| {
+                let max_gas_amount = context
+                    .node_config
+                    .api
+                    .max_gas_view_function
+                    .min(remaining_gas);
+                let (values, gas_used) = execute_batched_view_function(
+                    &context,
+                    &state_view,
+                    &view_function,
+                    max_gas_amount,
+                );
+                remaining_gas = remaining_gas.saturating_sub(gas_used);
+                (view_function, values, gas_used)
+            })
+        })
+        .collect();
+    let total_gas_used: u64 = outputs
+        .iter()
+        .map(|output| match output {
+            Ok((_, _, gas_used)) => *gas_used,
+            Err(_) => 0,
+        })
+        .sum();
+
+    let result = match accept_type {
+        AcceptType::Bcs => {
+            let outputs: Vec<_> = outputs
+                .into_iter()
+                .map(|output| match output {
+                    Ok((_, Ok(values), gas_used)) => {
+                        ViewFunctionBatchOutput::Success { values, gas_used }
+                    },
+                    Ok((_, Err(error), gas_used)) => {
+                        ViewFunctionBatchOutput::Failure { error, gas_used }
+                    },
+                    Err(error) => ViewFunctionBatchOutput::Failure { error, gas_used: 0 },
+                })
+                .collect();
+            let bytes = bcs::to_bytes(&outputs).map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+
+            BasicResponse::try_from_encoded((bytes, &ledger_info, BasicResponseStatus::Ok))
+        },
+        AcceptType::Json => {
+            let results: Vec<_> = outputs
+                .into_iter()
+                .map(|output| {
+                    let (values, gas_used) = match output {
+                        Ok((view_function, values, gas_used)) => {
+                            let values = values.and_then(|values| {
+                                convert_return_values(&context, &state_view, &view_function, values)
+                                    .map_err(|err| {
+                                        AptosError::new_with_error_code(
+                                            err,
+                                            AptosErrorCode::InternalError,
+                                        )
+                                    })
+                            });
+                            (values, gas_used)
+                        },
+                        Err(error) => (Err(error), 0),
+                    };
+                    match values {
+                        Ok(values) => ViewFunctionBatchResult {
+                            values: Some(values),
+                            error: None,
+                            gas_used: gas_used.into(),
+                        },
+                        Err(error) => ViewFunctionBatchResult {
+                            values: None,
+                            error: Some(error),
+                            gas_used: gas_used.into(),
+                        },
+                    }
+                })
+                .collect();
+
+            BasicResponse::try_from_json((results, &ledger_info, BasicResponseStatus::Ok))
+        },
+    };
+    result.map(|r| r.with_gas_used(Some(total_gas_used)))
+}
+
+/// Executes a single view function of a batch, returning the BCS encoded return values
+/// (or the error) and the gas used
+fn execute_batched_view_function(
+    context: &Context,
+    state_view: &DbStateView,
+    view_function: &ViewFunction,
+    max_gas_amount: u64,
+) -> (Result<Vec<Vec<u8>>, AptosError>, u64) {
+    // Reject the view function if it's not allowed by the filter.
+    if let Err(err) = check_view_function_allowed(context, view_function) {
+        return (
+            Err(AptosError::new_with_error_code(
+                err,
+                AptosErrorCode::InvalidInput,
+            )),
+            0,
+        );
+    }
+
+    // Reject the view function if the batch has already used all of its gas
+    if max_gas_amount == 0 {
+        return (
+            Err(AptosError::new_with_error_code(
+                "The gas limit of the view function batch has been exhausted",
+                AptosErrorCode::InvalidInput,
+            )),
+            0,
+        );
+    }
+
+    let output = AptosVM::execute_view_function(
+        state_view,
+        view_function.module.clone(),
+        view_function.function.clone(),
+        view_function.ty_args.clone(),
+        view_function.args.clone(),
+        max_gas_amount,
+    );
     context.view_function_stats().increment(
         FunctionStats::function_to_key(&view_function.module, &view_function.function),
         output.gas_used,
     );
-    result.map(|r| r.with_gas_used(Some(output.gas_used)))
+    let values = output
+        .values
+        .map_err(|err| AptosError::new_with_error_code(err, AptosErrorCode::InvalidInput));
+    (values, output.gas_used)
+}
+
+/// Checks that the view function is allowed by the view filter
+fn check_view_function_allowed(
+    context: &Context,
+    view_function: &ViewFunction,
+) -> anyhow::Result<()> {
+    if !context.node_config.api.view_filter.allows(
+        view_function.module.address(),
+        view_function.module.name().as_str(),
+        view_function.function.as_str(),
+    ) {
+        anyhow::bail!(
+            "Function {}::{} is not allowed",
+            view_function.module,
+            view_function.function
+        );
+    }
+    Ok(())
+}
+
+/// Converts the BCS encoded return values of a view function into Move values
+fn convert_return_values(
+    context: &Context,
+    state_view: &DbStateView,
+    view_function: &ViewFunction,
+    values: Vec<Vec<u8>>,
+) -> anyhow::Result<Vec<MoveValue>> {
+    let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+    let return_types = converter
+        .function_return_types(view_function)
+        .and_then(|tys| {
+            tys.iter()
+                .map(TypeTag::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()
+        })?;
+
+    values
+        .into_iter()
+        .zip(return_types)
+        .map(|(v, ty)| converter.try_into_move_value(&ty, &v))
+        .collect()
 }
@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{Address, HexEncodedBytes, MoveStructTag, U64};
-use aptos_types::account_config::AccountResource;
-use poem_openapi::Object;
+use aptos_types::{account_config::AccountResource, indexer::fungible_asset_activity};
+use poem_openapi::{Enum, Object};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use std::{fmt::Debug, str::FromStr};
 
@@ -65,3 +65,52 @@ impl<'de> Deserialize<'de> for AssetType {
         data.parse().map_err(D::Error::custom)
     }
 }
+
+/// Type of a fungible asset balance change
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum FungibleAssetActivityType {
+    /// The balance of the store increased
+    Deposit,
+    /// The balance of the store decreased
+    Withdraw,
+}
+
+impl From<fungible_asset_activity::FungibleAssetActivityType> for FungibleAssetActivityType {
+    fn from(activity_type: fungible_asset_activity::FungibleAssetActivityType) -> Self {
+        match activity_type {
+            fungible_asset_activity::FungibleAssetActivityType::Deposit => Self::Deposit,
+            fungible_asset_activity::FungibleAssetActivityType::Withdraw => Self::Withdraw,
+        }
+    }
+}
+
+/// A balance change of a coin or fungible asset held by an account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct FungibleAssetActivity {
+    /// Version of the transaction that changed the balance
+    pub version: U64,
+    /// Index of the deposit or withdraw event in the transaction
+    pub event_index: U64,
+    #[serde(rename = "type")]
+    #[oai(rename = "type")]
+    pub typ: FungibleAssetActivityType,
+    /// Address of the store whose balance changed
+    ///
+    /// For coin stores, this is the address of the account.
+    pub store: Address,
+    pub amount: U64,
+}
+
+impl From<fungible_asset_activity::FungibleAssetActivity> for FungibleAssetActivity {
+    fn from(activity: fungible_asset_activity::FungibleAssetActivity) -> Self {
+        Self {
+            version: activity.version.into(),
+            event_index: activity.event_index.into(),
+            typ: activity.activity_type.into(),
+            store: activity.store.into(),
+            amount: activity.amount.into(),
+        }
+    }
+}
@@ -96,6 +96,8 @@ pub enum AptosErrorCode {
     SequenceNumberTooOld = 402,
     /// The submitted transaction failed VM checks.
     VmError = 403,
+    /// The submitted transaction was rejected by the node's transaction filter.
+    TransactionRejectedByFilter = 404,
 
     /// Health check failed.
     HealthCheckFailed = 500,
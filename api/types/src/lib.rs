@@ -18,12 +18,13 @@ mod ledger_info;
 pub mod mime_types;
 mod move_types;
 mod state;
+mod state_override;
 mod table;
 pub mod transaction;
 mod view;
 mod wrappers;
 
-pub use account::{AccountData, AssetType};
+pub use account::{AccountData, AssetType, FungibleAssetActivity, FungibleAssetActivityType};
 pub use address::Address;
 pub use block::{BcsBlock, Block};
 pub use bytecode::Bytecode;
@@ -38,26 +39,28 @@ pub use move_types::{
     HexEncodedBytes, MoveAbility, MoveFunction, MoveFunctionGenericTypeParam,
     MoveFunctionVisibility, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
     MoveScriptBytecode, MoveStruct, MoveStructField, MoveStructTag, MoveType, MoveValue,
-    ResourceGroup, MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
+    ResourceGroup, VersionedMoveResource, MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
 };
 use serde::{Deserialize, Deserializer};
 pub use state::RawStateValueRequest;
+pub use state_override::{AccountStateOverride, ResourceOverride, StateOverrides};
 use std::str::FromStr;
 pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
-    AbstractionSignature, AccountSignature, BlockMetadataTransaction, DeleteModule, DeleteResource,
-    DeleteTableItem, DirectWriteSet, Ed25519Signature, EncodeSubmissionRequest,
-    EntryFunctionPayload, Event, FeePayerSignature, GasEstimation, GasEstimationBcs,
-    GenesisPayload, GenesisTransaction, MultiAgentSignature, MultiEd25519Signature,
-    MultiKeySignature, MultisigPayload, MultisigTransactionPayload, NoAccountSignature,
-    PendingTransaction, PublicKey, ScriptPayload, ScriptWriteSet, Signature, SingleKeySignature,
-    SubmitTransactionRequest, Transaction, TransactionData, TransactionId, TransactionInfo,
-    TransactionOnChainData, TransactionPayload, TransactionSignature, TransactionSigningMessage,
-    TransactionsBatchSingleSubmissionFailure, TransactionsBatchSubmissionResult,
-    UserCreateSigningMessageRequest, UserTransaction, UserTransactionRequest, VersionedEvent,
-    WriteModule, WriteResource, WriteSet, WriteSetChange, WriteSetPayload, WriteTableItem,
+    AbstractionSignature, AccountSignature, BlockMetadataTransaction, CallTrace, CallTraceEvent,
+    DeleteModule, DeleteResource, DeleteTableItem, DirectWriteSet, Ed25519Signature,
+    EncodeSubmissionRequest, EntryFunctionPayload, Event, FeePayerSignature, GasEstimation,
+    GasEstimationBcs, GenesisPayload, GenesisTransaction, MultiAgentSignature,
+    MultiEd25519Signature, MultiKeySignature, MultisigPayload, MultisigTransactionPayload,
+    NoAccountSignature, PendingTransaction, PublicKey, ScriptPayload, ScriptWriteSet, Signature,
+    SimulateTransactionRequest, SingleKeySignature, SubmitTransactionRequest, Transaction,
+    TransactionData, TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
+    TransactionSignature, TransactionSigningMessage, TransactionsBatchSingleSubmissionFailure,
+    TransactionsBatchSubmissionResult, UserCreateSigningMessageRequest, UserTransaction,
+    UserTransactionRequest, VersionedEvent, WriteModule, WriteResource, WriteSet, WriteSetChange,
+    WriteSetPayload, WriteTableItem,
 };
-pub use view::{ViewFunction, ViewRequest};
+pub use view::{ViewFunction, ViewFunctionBatchOutput, ViewFunctionBatchResult, ViewRequest};
 pub use wrappers::{EventGuid, IdentifierWrapper, StateKeyWrapper};
 
 pub fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -42,6 +42,15 @@ pub struct MoveResource {
     pub data: MoveStructValue,
 }
 
+/// A Move resource as written by a transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct VersionedMoveResource {
+    /// Version of the transaction that wrote the resource
+    pub version: U64,
+    /// The resource after the transaction, or null if the transaction deleted it
+    pub resource: Option<MoveResource>,
+}
+
 impl TryFrom<AnnotatedMoveStruct> for MoveResource {
     type Error = anyhow::Error;
 
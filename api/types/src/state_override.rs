@@ -0,0 +1,51 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HexEncodedBytes, MoveStructTag, MoveStructValue, U64};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// State overrides applied on top of the ledger state when simulating a transaction
+///
+/// Overrides are only visible to the simulation, and are never persisted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateOverrides {
+    /// Overrides of the state of individual accounts, applied in order
+    pub accounts: Vec<AccountStateOverride>,
+}
+
+/// Overrides of the state of a single account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct AccountStateOverride {
+    pub address: Address,
+    /// Overrides the sequence number of the account
+    ///
+    /// The account must already exist (or be created by a resource override).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(default, skip_serializing_if = "Option::is_none")]
+    pub sequence_number: Option<U64>,
+    /// Overrides the APT balance of the account, in octas
+    ///
+    /// The account must already have a coin store or a primary fungible store for APT.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U64>,
+    /// Resources to create or replace under the account
+    #[serde(default)]
+    #[oai(default)]
+    pub resources: Vec<ResourceOverride>,
+    /// BCS encoded modules to publish or replace under the account
+    #[serde(default)]
+    #[oai(default)]
+    pub modules: Vec<HexEncodedBytes>,
+}
+
+/// A resource to create or replace under an account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ResourceOverride {
+    #[serde(rename = "type")]
+    #[oai(rename = "type")]
+    pub typ: MoveStructTag,
+    /// The resource value, in the same format as returned by the resource APIs
+    pub data: MoveStructValue,
+}
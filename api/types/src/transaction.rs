@@ -5,7 +5,7 @@
 use crate::{
     Address, AptosError, EntryFunctionId, EventGuid, HashValue, HexEncodedBytes,
     MoveModuleBytecode, MoveModuleId, MoveResource, MoveScriptBytecode, MoveStructTag, MoveType,
-    MoveValue, VerifyInput, VerifyInputWithRecursion, U64,
+    MoveValue, StateOverrides, VerifyInput, VerifyInputWithRecursion, U64,
 };
 use anyhow::{bail, Context as AnyhowContext, Result};
 use aptos_crypto::{
@@ -311,6 +311,7 @@ impl
             request: (txn, payload).into(),
             events,
             timestamp: timestamp.into(),
+            trace: None,
         })
     }
 }
@@ -395,6 +396,40 @@ pub struct UserTransaction {
     /// Events generated by the transaction
     pub events: Vec<Event>,
     pub timestamp: U64,
+    /// Call trace of the transaction's execution. Only returned by simulation when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<CallTrace>,
+}
+
+/// A function call made while executing a transaction, with all calls it made in turn
+#[derive(Clone, Debug, Deserialize, Eq, Object, PartialEq, Serialize)]
+pub struct CallTrace {
+    /// Fully qualified name of the function, or `<script>` for a script
+    pub function: String,
+    pub type_arguments: Vec<String>,
+    /// JSON representation of the arguments, if they were recorded
+    pub arguments: Option<Vec<serde_json::Value>>,
+    /// JSON representation of the return values of a native function, if they were recorded
+    pub return_values: Option<Vec<serde_json::Value>>,
+    pub is_native: bool,
+    /// Execution gas used by the call and all of its callees, rounded up to gas units
+    pub gas_used: U64,
+    /// Events emitted directly by the call
+    pub events: Vec<CallTraceEvent>,
+    pub calls: Vec<CallTrace>,
+    /// Whether some of the calls made by the call were left out of the trace
+    pub truncated: bool,
+}
+
+/// An event emitted by a function call in a call trace
+#[derive(Clone, Debug, Deserialize, Eq, Object, PartialEq, Serialize)]
+pub struct CallTraceEvent {
+    #[serde(rename = "type")]
+    #[oai(rename = "type")]
+    pub typ: String,
+    /// JSON representation of the event, if it was recorded
+    pub data: Option<serde_json::Value>,
 }
 
 /// A state checkpoint transaction
@@ -442,6 +477,27 @@ impl VerifyInput for SubmitTransactionRequest {
     }
 }
 
+/// A request to simulate a transaction
+///
+/// This is a transaction submission request, with optional state overrides that are
+/// applied on top of the ledger state before the transaction is simulated
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SimulateTransactionRequest {
+    #[serde(flatten)]
+    #[oai(flatten)]
+    pub submit_transaction_request: SubmitTransactionRequest,
+    /// State overrides to apply before simulating the transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverrides>,
+}
+
+impl VerifyInput for SimulateTransactionRequest {
+    fn verify(&self) -> anyhow::Result<()> {
+        self.submit_transaction_request.verify()
+    }
+}
+
 /// Batch transaction submission result
 ///
 /// Tells which transactions failed
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{EntryFunctionId, MoveType};
+use crate::{AptosError, EntryFunctionId, MoveType, MoveValue, U64};
 use aptos_types::serde_helper::vec_bytes;
 use move_core_types::{
     identifier::Identifier,
@@ -28,3 +28,27 @@ pub struct ViewFunction {
     #[serde(with = "vec_bytes")]
     pub args: Vec<Vec<u8>>,
 }
+
+/// Result of a single view function in a batch view request
+///
+/// Exactly one of `values` and `error` is set.
+#[derive(Clone, Debug, Serialize, Deserialize, Object)]
+pub struct ViewFunctionBatchResult {
+    /// Return values of the view function, if it executed successfully
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<MoveValue>>,
+    /// Error describing why the view function failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<AptosError>,
+    /// Gas used by the view function
+    pub gas_used: U64,
+}
+
+/// BCS encoded result of a single view function in a batch view request
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ViewFunctionBatchOutput {
+    /// The BCS encoded return values of the view function
+    Success { values: Vec<Vec<u8>>, gas_used: u64 },
+    /// The view function failed
+    Failure { error: AptosError, gas_used: u64 },
+}
@@ -3,7 +3,7 @@
 
 use anyhow::{bail, format_err};
 use aptos_block_executor::txn_provider::{default::DefaultTxnProvider, TxnProvider};
-use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
+use aptos_gas_profiling::{CallTrace, GasProfiler, TransactionGasLog};
 use aptos_rest_client::Client;
 use aptos_types::{
     account_address::AccountAddress,
@@ -119,6 +119,27 @@ impl AptosDebugger {
         &self,
         version: Version,
         txn: SignedTransaction,
+    ) -> anyhow::Result<(VMStatus, VMOutput, TransactionGasLog)> {
+        self.execute_transaction_at_version_with_profiler(version, txn, false)
+    }
+
+    /// Executes the transaction at the given version and returns a call trace of the execution,
+    /// including the arguments and return values of all function calls.
+    pub fn execute_transaction_at_version_with_call_trace(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+    ) -> anyhow::Result<(VMStatus, VMOutput, CallTrace)> {
+        let (status, output, gas_log) =
+            self.execute_transaction_at_version_with_profiler(version, txn, true)?;
+        Ok((status, output, gas_log.call_trace()))
+    }
+
+    fn execute_transaction_at_version_with_profiler(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+        record_values: bool,
     ) -> anyhow::Result<(VMStatus, VMOutput, TransactionGasLog)> {
         let state_view = DebuggerStateView::new(self.debugger.clone(), version);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
@@ -157,7 +178,11 @@ impl AptosDebugger {
                         unreachable!("Module bundle payload has already been checked because before this function is called")
                     },
                 };
-                gas_profiler
+                if record_values {
+                    gas_profiler.with_values(usize::MAX)
+                } else {
+                    gas_profiler
+                }
             },
         )?;
 
@@ -9,6 +9,7 @@ use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, TypeTag},
 };
+use serde_json::Value;
 use smallvec::{smallvec, SmallVec};
 
 /// An event occurred during the execution of a function, along with the
@@ -29,6 +30,11 @@ pub enum ExecutionGasEvent {
         fn_name: Identifier,
         ty_args: Vec<TypeTag>,
         cost: InternalGas,
+        /// Rendered arguments, only recorded if the profiler was created with values enabled.
+        args: Option<Vec<Value>>,
+        /// Rendered return values, only recorded if the profiler was created with values enabled
+        /// and the native function returned successfully.
+        return_values: Option<Vec<Value>>,
     },
     LoadResource {
         addr: AccountAddress,
@@ -54,10 +60,14 @@ pub enum FrameName {
 
 /// A struct containing information about a function call, including the name of the
 /// function and all gas events that happened during the call.
+///
+/// Arguments are only recorded if the profiler was created with values enabled (see
+/// `GasProfiler::with_values`).
 #[derive(Debug, Clone)]
 pub struct CallFrame {
     pub name: FrameName,
     pub events: Vec<ExecutionGasEvent>,
+    pub args: Option<Vec<Value>>,
 }
 
 /// The type of an operation performed on a storage item.
@@ -183,6 +193,7 @@ impl CallFrame {
                 ty_args,
             },
             events: vec![],
+            args: None,
         }
     }
 
@@ -190,6 +201,7 @@ impl CallFrame {
         Self {
             name: FrameName::Script,
             events: vec![],
+            args: None,
         }
     }
 }
@@ -1,9 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::log::{
-    CallFrame, Dependency, EventStorage, EventTransient, ExecutionAndIOCosts, ExecutionGasEvent,
-    FrameName, StorageFees, TransactionGasLog, WriteOpType, WriteStorage, WriteTransient,
+use crate::{
+    log::{
+        CallFrame, Dependency, EventStorage, EventTransient, ExecutionAndIOCosts,
+        ExecutionGasEvent, FrameName, StorageFees, TransactionGasLog, WriteOpType, WriteStorage,
+        WriteTransient,
+    },
+    trace::render_values,
 };
 use aptos_gas_algebra::{Fee, FeePerGasUnit, InternalGas, NumArgs, NumBytes, NumTypeNodes};
 use aptos_gas_meter::{AptosGasMeter, GasAlgebra};
@@ -42,6 +46,10 @@ pub struct GasProfiler<G> {
     events_transient: Vec<EventTransient>,
     write_set_transient: Vec<WriteTransient>,
     storage_fees: Option<StorageFees>,
+
+    // Remaining budget, in bytes of rendered JSON, for recording argument and return values.
+    // Values are only recorded if this is set (see `with_values`).
+    value_bytes_left: Option<usize>,
 }
 
 // TODO: consider switching to a library like https://docs.rs/delegate/latest/delegate/.
@@ -99,6 +107,8 @@ impl<G> GasProfiler<G> {
             events_transient: vec![],
             write_set_transient: vec![],
             storage_fees: None,
+
+            value_bytes_left: None,
         }
     }
 
@@ -119,7 +129,34 @@ impl<G> GasProfiler<G> {
             events_transient: vec![],
             write_set_transient: vec![],
             storage_fees: None,
+
+            value_bytes_left: None,
+        }
+    }
+
+    /// Makes the profiler also record the arguments of every function call and the return
+    /// values of native functions, which are needed to produce call traces. Once the rendered
+    /// values add up to `max_value_bytes`, no more values are recorded.
+    pub fn with_values(mut self, max_value_bytes: usize) -> Self {
+        self.value_bytes_left = Some(max_value_bytes);
+        self
+    }
+
+    /// Renders the values if values are recorded and the rendered values fit in the remaining
+    /// budget. Once a value does not fit, the budget is exhausted.
+    fn render_values_within_budget(
+        &mut self,
+        vals: impl IntoIterator<Item = impl ValueView>,
+    ) -> Option<Vec<serde_json::Value>> {
+        let bytes_left = self.value_bytes_left.as_mut().filter(|left| **left > 0)?;
+        let values = render_values(vals);
+        let num_bytes = values.iter().map(|val| val.to_string().len()).sum();
+        if num_bytes > *bytes_left {
+            *bytes_left = 0;
+            return None;
         }
+        *bytes_left -= num_bytes;
+        Some(values)
     }
 }
 
@@ -320,6 +357,10 @@ where
         amount: InternalGas,
         ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView> + Clone>,
     ) -> PartialVMResult<()> {
+        let return_values = ret_vals
+            .clone()
+            .and_then(|ret_vals| self.render_values_within_budget(ret_vals));
+
         let (cost, res) =
             self.delegate_charge(|base| base.charge_native_function(amount, ret_vals));
 
@@ -351,6 +392,8 @@ where
             fn_name: name,
             ty_args,
             cost,
+            args: cur.args,
+            return_values,
         });
 
         res
@@ -413,15 +456,19 @@ where
         args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
         num_locals: NumArgs,
     ) -> PartialVMResult<()> {
+        let arg_values = self.render_values_within_budget(args.clone());
+
         let (cost, res) =
             self.delegate_charge(|base| base.charge_call(module_id, func_name, args, num_locals));
 
         self.record_bytecode(Opcodes::CALL, cost);
-        self.frames.push(CallFrame::new_function(
+        let mut frame = CallFrame::new_function(
             module_id.clone(),
             Identifier::new(func_name).unwrap(),
             vec![],
-        ));
+        );
+        frame.args = arg_values;
+        self.frames.push(frame);
 
         res
     }
@@ -438,17 +485,20 @@ where
             .clone()
             .map(|ty| ty.to_type_tag())
             .collect::<Vec<_>>();
+        let arg_values = self.render_values_within_budget(args.clone());
 
         let (cost, res) = self.delegate_charge(|base| {
             base.charge_call_generic(module_id, func_name, ty_args, args, num_locals)
         });
 
         self.record_bytecode(Opcodes::CALL_GENERIC, cost);
-        self.frames.push(CallFrame::new_function(
+        let mut frame = CallFrame::new_function(
             module_id.clone(),
             Identifier::new(func_name).unwrap(),
             ty_tags,
-        ));
+        );
+        frame.args = arg_values;
+        self.frames.push(frame);
 
         res
     }
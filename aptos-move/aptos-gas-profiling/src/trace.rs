@@ -0,0 +1,345 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::log::{CallFrame, ExecutionGasEvent, FrameName, TransactionGasLog};
+use aptos_gas_algebra::{GasScalingFactor, InternalGas};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+    u256::U256,
+};
+use move_vm_types::{
+    delayed_values::delayed_field_id::DelayedFieldID,
+    views::{ValueView, ValueVisitor},
+};
+use serde::Serialize;
+use serde_json::Value;
+
+/// A structured trace of a single function call, including the values it was called with, the
+/// gas it consumed and all calls it made in turn.
+#[derive(Debug, Clone, Serialize)]
+pub struct CallTrace {
+    /// Fully qualified name of the function, or `<script>` for the script entry point.
+    pub function: String,
+    pub type_arguments: Vec<String>,
+    /// Arguments of the call. Not set for the entry point, whose arguments are part of the
+    /// transaction payload, or if the values were not recorded.
+    pub arguments: Option<Vec<Value>>,
+    /// Return values of a native call. Not set for Move functions, for calls that did not
+    /// return, or if the values were not recorded.
+    pub return_values: Option<Vec<Value>>,
+    pub is_native: bool,
+    /// Execution gas consumed by the call and all of its callees, in gas units.
+    pub gas_used: f64,
+    /// Events emitted directly by this call.
+    pub events: Vec<TraceEvent>,
+    pub calls: Vec<CallTrace>,
+    /// Set if some of the calls made by this call were left out of the trace, because the
+    /// trace reached its maximum number of calls.
+    pub truncated: bool,
+}
+
+/// An event emitted during execution, as seen by the event store natives.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// The event data, if the values were recorded.
+    pub data: Option<Value>,
+}
+
+impl TransactionGasLog {
+    /// Builds a call trace from the call graph.
+    ///
+    /// Values are only included if the log was produced by a profiler created with
+    /// `GasProfiler::with_values`.
+    pub fn call_trace(&self) -> CallTrace {
+        self.call_trace_with_max_calls(usize::MAX)
+    }
+
+    /// Builds a call trace from the call graph, including at most `max_calls` calls (counting
+    /// the entry point). Calls are added in depth-first order, and calls left out are marked
+    /// by `CallTrace::truncated` on their caller.
+    pub fn call_trace_with_max_calls(&self, max_calls: usize) -> CallTrace {
+        let mut calls_left = max_calls.saturating_sub(1);
+        let (trace, _cost) = CallTrace::from_frame(
+            &self.exec_io.call_graph,
+            self.exec_io.gas_scaling_factor,
+            &mut calls_left,
+        );
+        trace
+    }
+}
+
+impl CallTrace {
+    /// Converts a call frame into a trace, also returning the total cost of the frame.
+    fn from_frame(
+        frame: &CallFrame,
+        scaling_factor: GasScalingFactor,
+        calls_left: &mut usize,
+    ) -> (Self, InternalGas) {
+        let (function, type_arguments) = match &frame.name {
+            FrameName::Script => ("<script>".to_string(), vec![]),
+            FrameName::Function {
+                module_id,
+                name,
+                ty_args,
+            } => render_function(module_id, name, ty_args),
+        };
+
+        let mut cost = InternalGas::zero();
+        let mut events = vec![];
+        let mut calls = vec![];
+        let mut truncated = false;
+        for event in &frame.events {
+            use ExecutionGasEvent::*;
+
+            match event {
+                Loc(_) => (),
+                Bytecode { cost: c, .. } | LoadResource { cost: c, .. } | CreateTy { cost: c } => {
+                    cost += *c
+                },
+                Call(callee) => {
+                    if *calls_left == 0 {
+                        cost += frame_cost(callee);
+                        truncated = true;
+                        continue;
+                    }
+                    *calls_left -= 1;
+                    let (trace, callee_cost) = Self::from_frame(callee, scaling_factor, calls_left);
+                    cost += callee_cost;
+                    calls.push(trace);
+                },
+                CallNative {
+                    module_id,
+                    fn_name,
+                    ty_args,
+                    cost: c,
+                    args,
+                    return_values,
+                } => {
+                    cost += *c;
+                    if let Some(event) = emitted_event(module_id, fn_name, ty_args, args) {
+                        events.push(event);
+                    }
+
+                    if *calls_left == 0 {
+                        truncated = true;
+                        continue;
+                    }
+                    *calls_left -= 1;
+                    let (function, type_arguments) = render_function(module_id, fn_name, ty_args);
+                    calls.push(Self {
+                        function,
+                        type_arguments,
+                        arguments: args.clone(),
+                        return_values: return_values.clone(),
+                        is_native: true,
+                        gas_used: to_gas_units(*c, scaling_factor),
+                        events: vec![],
+                        calls: vec![],
+                        truncated: false,
+                    });
+                },
+            }
+        }
+
+        let trace = Self {
+            function,
+            type_arguments,
+            arguments: frame.args.clone(),
+            return_values: None,
+            is_native: false,
+            gas_used: to_gas_units(cost, scaling_factor),
+            events,
+            calls,
+            truncated,
+        };
+        (trace, cost)
+    }
+}
+
+/// Returns the total execution cost of a call frame and all of its callees.
+fn frame_cost(frame: &CallFrame) -> InternalGas {
+    let mut cost = InternalGas::zero();
+    for event in &frame.events {
+        use ExecutionGasEvent::*;
+
+        match event {
+            Loc(_) => (),
+            Bytecode { cost: c, .. }
+            | LoadResource { cost: c, .. }
+            | CreateTy { cost: c }
+            | CallNative { cost: c, .. } => cost += *c,
+            Call(callee) => cost += frame_cost(callee),
+        }
+    }
+    cost
+}
+
+fn render_function(
+    module_id: &ModuleId,
+    name: &Identifier,
+    ty_args: &[TypeTag],
+) -> (String, Vec<String>) {
+    (
+        format!("{}::{}", module_id.short_str_lossless(), name),
+        ty_args.iter().map(|ty| ty.to_canonical_string()).collect(),
+    )
+}
+
+fn to_gas_units(cost: InternalGas, scaling_factor: GasScalingFactor) -> f64 {
+    u64::from(cost) as f64 / u64::from(scaling_factor) as f64
+}
+
+/// Recognizes calls to the event store natives, whose last argument is the event data.
+fn emitted_event(
+    module_id: &ModuleId,
+    fn_name: &Identifier,
+    ty_args: &[TypeTag],
+    args: &Option<Vec<Value>>,
+) -> Option<TraceEvent> {
+    if module_id.address() != &AccountAddress::ONE
+        || module_id.name().as_str() != "event"
+        || !matches!(
+            fn_name.as_str(),
+            "write_to_event_store" | "write_module_event_to_store"
+        )
+    {
+        return None;
+    }
+
+    Some(TraceEvent {
+        typ: ty_args.first()?.to_canonical_string(),
+        data: args.as_ref().and_then(|args| args.last().cloned()),
+    })
+}
+
+/// Renders Move values into JSON for call traces.
+///
+/// Integers wider than 32 bits are rendered as strings so they survive JSON parsers that use
+/// doubles, `vector<u8>` is rendered as a hex string, structs and vectors are rendered as arrays
+/// and references are rendered as the values they point to.
+pub(crate) fn render_values(vals: impl IntoIterator<Item = impl ValueView>) -> Vec<Value> {
+    vals.into_iter()
+        .map(|val| {
+            let mut renderer = ValueRenderer::default();
+            val.visit(&mut renderer);
+            renderer.finish()
+        })
+        .collect()
+}
+
+struct PartialContainer {
+    remaining: usize,
+    is_ref: bool,
+    elems: Vec<Value>,
+}
+
+#[derive(Default)]
+struct ValueRenderer {
+    stack: Vec<PartialContainer>,
+    result: Option<Value>,
+}
+
+impl ValueRenderer {
+    fn finish(self) -> Value {
+        self.result.unwrap_or(Value::Null)
+    }
+
+    fn push_value(&mut self, mut val: Value) {
+        loop {
+            let Some(container) = self.stack.last_mut() else {
+                self.result = Some(val);
+                return;
+            };
+            container.elems.push(val);
+            container.remaining -= 1;
+            if container.remaining > 0 {
+                return;
+            }
+
+            let mut container = self.stack.pop().expect("container must exist");
+            val = if container.is_ref {
+                container.elems.pop().expect("referenced value must exist")
+            } else {
+                Value::Array(container.elems)
+            };
+        }
+    }
+
+    fn open_container(&mut self, len: usize, is_ref: bool) {
+        if len == 0 {
+            self.push_value(Value::Array(vec![]));
+        } else {
+            self.stack.push(PartialContainer {
+                remaining: len,
+                is_ref,
+                elems: Vec::with_capacity(len),
+            });
+        }
+    }
+}
+
+impl ValueVisitor for ValueRenderer {
+    fn visit_delayed(&mut self, _depth: usize, id: DelayedFieldID) {
+        self.push_value(Value::String(format!("{:?}", id)));
+    }
+
+    fn visit_u8(&mut self, _depth: usize, val: u8) {
+        self.push_value(val.into());
+    }
+
+    fn visit_u16(&mut self, _depth: usize, val: u16) {
+        self.push_value(val.into());
+    }
+
+    fn visit_u32(&mut self, _depth: usize, val: u32) {
+        self.push_value(val.into());
+    }
+
+    fn visit_u64(&mut self, _depth: usize, val: u64) {
+        self.push_value(Value::String(val.to_string()));
+    }
+
+    fn visit_u128(&mut self, _depth: usize, val: u128) {
+        self.push_value(Value::String(val.to_string()));
+    }
+
+    fn visit_u256(&mut self, _depth: usize, val: U256) {
+        self.push_value(Value::String(val.to_string()));
+    }
+
+    fn visit_bool(&mut self, _depth: usize, val: bool) {
+        self.push_value(val.into());
+    }
+
+    fn visit_address(&mut self, _depth: usize, val: AccountAddress) {
+        self.push_value(Value::String(val.to_hex_literal()));
+    }
+
+    fn visit_struct(&mut self, _depth: usize, len: usize) -> bool {
+        self.open_container(len, false);
+        true
+    }
+
+    fn visit_closure(&mut self, _depth: usize, len: usize) -> bool {
+        self.open_container(len, false);
+        true
+    }
+
+    fn visit_vec(&mut self, _depth: usize, len: usize) -> bool {
+        self.open_container(len, false);
+        true
+    }
+
+    fn visit_ref(&mut self, _depth: usize, _is_global: bool) -> bool {
+        self.open_container(1, true);
+        true
+    }
+
+    fn visit_vec_u8(&mut self, _depth: usize, vals: &[u8]) {
+        self.push_value(Value::String(format!("0x{}", hex::encode(vals))));
+    }
+}
@@ -39,6 +39,7 @@ use aptos_framework::{
 };
 use aptos_gas_algebra::{Gas, GasQuantity, NumBytes, Octa};
 use aptos_gas_meter::{AptosGasMeter, GasAlgebra};
+use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
 use aptos_gas_schedule::{
     gas_feature_versions::{RELEASE_V1_10, RELEASE_V1_27},
     AptosGasParameters, VMGasParameters,
@@ -3018,6 +3019,72 @@ impl AptosSimulationVM {
             .expect("Materializing aggregator V1 deltas should never fail");
         (vm_status, txn_output)
     }
+
+    /// Simulates a signed transaction like [`Self::create_vm_and_simulate_signed_transaction`],
+    /// additionally profiling the execution with up to `max_value_bytes` of values recorded, so
+    /// that a call trace can be derived from the returned gas log. The gas log is not available
+    /// if the transaction was discarded before execution.
+    pub fn create_vm_and_simulate_signed_transaction_with_gas_profiler(
+        transaction: &SignedTransaction,
+        state_view: &impl StateView,
+        max_value_bytes: usize,
+    ) -> (VMStatus, TransactionOutput, Option<TransactionGasLog>) {
+        assert_err!(
+            transaction.verify_signature(),
+            "Simulated transaction should not have a valid signature"
+        );
+
+        let env = AptosEnvironment::new(state_view);
+        let mut vm = AptosVM::new(&env, state_view);
+        vm.is_simulation = true;
+
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+
+        let resolver = state_view.as_move_resolver();
+        let code_storage = state_view.as_aptos_code_storage(&env);
+
+        let result = vm.execute_user_transaction_with_modified_gas_meter(
+            &resolver,
+            &code_storage,
+            transaction,
+            &log_context,
+            |gas_meter| {
+                let entry_function = match transaction.payload() {
+                    TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+                    TransactionPayload::Multisig(Multisig {
+                        transaction_payload:
+                            Some(MultisigTransactionPayload::EntryFunction(entry_function)),
+                        ..
+                    }) => Some(entry_function),
+                    _ => None,
+                };
+                let gas_profiler = match entry_function {
+                    Some(entry_function) => GasProfiler::new_function(
+                        gas_meter,
+                        entry_function.module().clone(),
+                        entry_function.function().to_owned(),
+                        entry_function.ty_args().to_vec(),
+                    ),
+                    None => GasProfiler::new_script(gas_meter),
+                };
+                gas_profiler.with_values(max_value_bytes)
+            },
+        );
+        let (vm_status, vm_output, gas_log) = match result {
+            Ok((vm_status, vm_output, gas_profiler)) => {
+                (vm_status, vm_output, Some(gas_profiler.finish()))
+            },
+            Err(vm_status) => {
+                let vm_output = discarded_output(vm_status.status_code());
+                (vm_status, vm_output, None)
+            },
+        };
+
+        let txn_output = vm_output
+            .try_materialize_into_transaction_output(&resolver)
+            .expect("Materializing aggregator V1 deltas should never fail");
+        (vm_status, txn_output, gas_log)
+    }
 }
 
 fn create_account_if_does_not_exist(
@@ -19,6 +19,7 @@ use aptos_mempool::network::MempoolSyncMsg;
 use aptos_network::{
     application::{
         interface::{NetworkClient, NetworkServiceEvents},
+        reputation::PeerReputations,
         storage::PeersAndMetadata,
     },
     protocols::network::{
@@ -146,7 +147,24 @@ pub fn peer_monitoring_network_configuration(node_config: &NodeConfig) -> Networ
 /// Returns the network application config for the storage service client and server
 pub fn storage_service_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
     let direct_send_protocols = vec![]; // The storage service does not use direct send
-    let rpc_protocols = vec![ProtocolId::StorageServiceRpc];
+
+    // Both compression protocols are supported (older peers only support lz4),
+    // but zstd is only preferred when sending requests if it has been enabled.
+    let use_zstd_compression = node_config
+        .state_sync
+        .aptos_data_client
+        .use_zstd_compression;
+    let rpc_protocols = if use_zstd_compression {
+        vec![
+            ProtocolId::StorageServiceRpcZstd,
+            ProtocolId::StorageServiceRpc,
+        ]
+    } else {
+        vec![
+            ProtocolId::StorageServiceRpc,
+            ProtocolId::StorageServiceRpcZstd,
+        ]
+    };
     let max_network_channel_size = node_config
         .state_sync
         .storage_service
@@ -170,7 +188,19 @@ pub fn storage_service_network_configuration(node_config: &NodeConfig) -> Networ
 pub fn consensus_observer_network_configuration(
     node_config: &NodeConfig,
 ) -> NetworkApplicationConfig {
-    let direct_send_protocols = vec![ProtocolId::ConsensusObserver];
+    // Both compression protocols are supported (older peers only support lz4),
+    // but zstd is only preferred when publishing messages if it has been enabled.
+    let direct_send_protocols = if node_config.consensus_observer.use_zstd_compression {
+        vec![
+            ProtocolId::ConsensusObserverZstd,
+            ProtocolId::ConsensusObserver,
+        ]
+    } else {
+        vec![
+            ProtocolId::ConsensusObserver,
+            ProtocolId::ConsensusObserverZstd,
+        ]
+    };
     let rpc_protocols = vec![ProtocolId::ConsensusObserverRpc];
     let max_network_channel_size = node_config.consensus_observer.max_network_channel_size as usize;
 
@@ -238,10 +268,20 @@ fn extract_network_ids(node_config: &NodeConfig) -> Vec<NetworkId> {
         .collect()
 }
 
-/// Creates the global peers and metadata struct
+/// Creates the global peers and metadata struct (including
+/// any peer reputations persisted in the data directory).
 pub fn create_peers_and_metadata(node_config: &NodeConfig) -> Arc<PeersAndMetadata> {
     let network_ids = extract_network_ids(node_config);
-    PeersAndMetadata::new(&network_ids)
+    let peer_reputation_config = node_config.peer_reputation.clone();
+    let reputation_file = node_config
+        .get_data_dir()
+        .join(&peer_reputation_config.reputation_file_name);
+    let peer_reputations = PeerReputations::new(
+        peer_reputation_config,
+        Some(reputation_file),
+        TimeService::real(),
+    );
+    PeersAndMetadata::new_with_peer_reputations(&network_ids, Arc::new(peer_reputations))
 }
 
 /// Sets up all networks and returns the appropriate application network interfaces
@@ -412,6 +452,15 @@ pub fn setup_networks_and_get_interfaces(
         );
     }
 
+    // Periodically persist the peer reputations (on the first network runtime)
+    if let Some(runtime) = network_runtimes.first() {
+        runtime.spawn(
+            peers_and_metadata
+                .get_peer_reputations()
+                .start_persistence_loop(),
+        );
+    }
+
     // Transform all network handles into application interfaces
     let (
         consensus_interfaces,
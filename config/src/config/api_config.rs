@@ -52,8 +52,17 @@ pub struct ApiConfig {
     /// Enables transaction simulation
     #[serde(default = "default_enabled")]
     pub transaction_simulation_enabled: bool,
+    /// Enables call traces of simulated transactions (see `include_trace`)
+    #[serde(default = "default_disabled")]
+    pub simulation_trace_enabled: bool,
+    /// Maximum number of function calls included in the call trace of a simulation
+    pub simulation_trace_max_calls: usize,
+    /// Maximum total size in bytes of the values included in the call trace of a simulation
+    pub simulation_trace_max_value_bytes: usize,
     /// Maximum number of transactions that can be sent with the Batch submit API
     pub max_submit_transaction_batch_size: usize,
+    /// Maximum number of view functions that can be executed with the Batch view API
+    pub max_view_function_batch_size: usize,
     /// Maximum page size for transaction paginated APIs
     pub max_transactions_page_size: u16,
     /// Maximum page size for block transaction APIs
@@ -62,12 +71,16 @@ pub struct ApiConfig {
     pub max_events_page_size: u16,
     /// Maximum page size for resource paginated APIs
     pub max_account_resources_page_size: u16,
+    /// Maximum page size for the account resource history API
+    pub max_account_resource_history_page_size: u16,
     /// Maximum page size for module paginated APIs
     pub max_account_modules_page_size: u16,
     /// Maximum gas unit limit for view functions
     ///
     /// This limits the execution length of a view function to the given gas used.
     pub max_gas_view_function: u64,
+    /// Maximum total gas unit limit for all view functions of a Batch view API request
+    pub max_gas_view_function_batch: u64,
     /// Optional: Maximum number of worker threads for the API.
     ///
     /// If not set, `runtime_worker_multiplier` will multiply times the number of CPU cores on the machine
@@ -92,16 +105,29 @@ pub struct ApiConfig {
     pub wait_by_hash_poll_interval_ms: u64,
     /// The number of active wait_by_hash requests that can be active at any given time.
     pub wait_by_hash_max_active_connections: usize,
+    /// Enables the transaction and event streaming API
+    #[serde(default = "default_disabled")]
+    pub transaction_stream_enabled: bool,
+    /// The interval at which the streaming API will poll the storage for new transactions.
+    pub transaction_stream_poll_interval_ms: u64,
+    /// The interval at which the streaming API will send keep-alive messages to idle clients.
+    pub transaction_stream_keep_alive_ms: u64,
+    /// The number of active streaming connections that can be open at any given time.
+    pub transaction_stream_max_active_connections: usize,
 }
 
 const DEFAULT_ADDRESS: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 8080;
 const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
 pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
+pub const DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE: usize = 20;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
 const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_VIEW_GAS: u64 = 2_000_000; // We keep this value the same as the max number of gas allowed for one single transaction defined in aptos-gas.
+const DEFAULT_MAX_VIEW_BATCH_GAS: u64 = 10_000_000;
+const DEFAULT_SIMULATION_TRACE_MAX_CALLS: usize = 10_000;
+const DEFAULT_SIMULATION_TRACE_MAX_VALUE_BYTES: usize = 1024 * 1024; // 1 MB
 
 fn default_enabled() -> bool {
     true
@@ -128,13 +154,19 @@ impl Default for ApiConfig {
             encode_submission_enabled: default_enabled(),
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
+            simulation_trace_enabled: default_disabled(),
+            simulation_trace_max_calls: DEFAULT_SIMULATION_TRACE_MAX_CALLS,
+            simulation_trace_max_value_bytes: DEFAULT_SIMULATION_TRACE_MAX_VALUE_BYTES,
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
+            max_view_function_batch_size: DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE,
             max_block_transactions_page_size: *MAX_RECEIVING_BLOCK_TXNS as u16,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_account_resources_page_size: DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE,
+            max_account_resource_history_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_account_modules_page_size: DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE,
             max_gas_view_function: DEFAULT_MAX_VIEW_GAS,
+            max_gas_view_function_batch: DEFAULT_MAX_VIEW_BATCH_GAS,
             max_runtime_workers: None,
             runtime_worker_multiplier: 2,
             gas_estimation: GasEstimationConfig::default(),
@@ -145,6 +177,10 @@ impl Default for ApiConfig {
             wait_by_hash_timeout_ms: 1_000,
             wait_by_hash_poll_interval_ms: 20,
             wait_by_hash_max_active_connections: 100,
+            transaction_stream_enabled: default_disabled(),
+            transaction_stream_poll_interval_ms: 100,
+            transaction_stream_keep_alive_ms: 15_000,
+            transaction_stream_max_active_connections: 100,
         }
     }
 }
@@ -196,7 +232,10 @@ impl ConfigSanitizer for ApiConfig {
 
         // We don't support Block ID based simulation filters.
         for rule in api_config.simulation_filter.rules() {
-            if let Matcher::BlockId(_) = rule.matcher() {
+            if rule
+                .matcher()
+                .any(&|matcher| matches!(matcher, Matcher::BlockId(_)))
+            {
                 return Err(Error::ConfigSanitizerFailed(
                     sanitizer_name,
                     "Block ID based simulation filters are not supported!".into(),
@@ -7,7 +7,8 @@ use crate::config::{
     utils::{are_failpoints_enabled, get_config_name},
     AdminServiceConfig, ApiConfig, BaseConfig, ConsensusConfig, DagConsensusConfig, Error,
     ExecutionConfig, IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig, MempoolConfig,
-    NetbenchConfig, NodeConfig, StateSyncConfig, StorageConfig,
+    NetbenchConfig, NodeConfig, PeerReputationConfig, ProtocolQuotaConfig, StateSyncConfig,
+    StorageConfig,
 };
 use aptos_types::chain_id::ChainId;
 use std::collections::HashSet;
@@ -61,6 +62,8 @@ impl ConfigSanitizer for NodeConfig {
         LoggerConfig::sanitize(node_config, node_type, chain_id)?;
         MempoolConfig::sanitize(node_config, node_type, chain_id)?;
         NetbenchConfig::sanitize(node_config, node_type, chain_id)?;
+        PeerReputationConfig::sanitize(node_config, node_type, chain_id)?;
+        ProtocolQuotaConfig::sanitize(node_config, node_type, chain_id)?;
         StateSyncConfig::sanitize(node_config, node_type, chain_id)?;
         StorageConfig::sanitize(node_config, node_type, chain_id)?;
         InternalIndexerDBConfig::sanitize(node_config, node_type, chain_id)?;
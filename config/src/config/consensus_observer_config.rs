@@ -29,6 +29,9 @@ pub struct ConsensusObserverConfig {
     pub max_parallel_serialization_tasks: usize,
     /// Timeout (in milliseconds) for network RPC requests
     pub network_request_timeout_ms: u64,
+    /// Whether to prefer zstd compression (over lz4) when publishing
+    /// to peers that support it
+    pub use_zstd_compression: bool,
 
     /// Interval (in milliseconds) to garbage collect peer state
     pub garbage_collection_interval_ms: u64,
@@ -68,6 +71,7 @@ impl Default for ConsensusObserverConfig {
             max_network_channel_size: 1000,
             max_parallel_serialization_tasks: num_cpus::get(), // Default to the number of CPUs
             network_request_timeout_ms: 5_000,                 // 5 seconds
+            use_zstd_compression: false,                       // lz4 is preferred by default
             garbage_collection_interval_ms: 60_000,            // 60 seconds
             max_num_pending_blocks: 100,                       // 100 blocks
             progress_check_interval_ms: 5_000,                 // 5 seconds
@@ -15,6 +15,14 @@ pub struct InternalIndexerDBConfig {
     pub enable_event_v2_translation: bool,
     pub event_v2_translation_ignores_below_version: u64,
     pub enable_statekeys: bool,
+    /// Index every account touched by a transaction (sender, fee payer, secondary signers, owners
+    /// of emitted event handles and of written fungible stores), not just the sender.
+    pub enable_participant_transactions: bool,
+    /// Index deposits into and withdrawals from the fungible stores and coin stores of accounts.
+    pub enable_fungible_asset_activities: bool,
+    /// Index the versions at which each state key was written, so that the history of a single
+    /// resource can be looked up.
+    pub enable_state_value_history: bool,
     pub batch_size: usize,
 }
 
@@ -34,6 +42,7 @@ impl InternalIndexerDBConfig {
             event_v2_translation_ignores_below_version,
             enable_statekeys,
             batch_size,
+            ..Default::default()
         }
     }
 
@@ -57,8 +66,25 @@ impl InternalIndexerDBConfig {
         self.enable_statekeys
     }
 
+    pub fn enable_participant_transactions(&self) -> bool {
+        self.enable_participant_transactions
+    }
+
+    pub fn enable_fungible_asset_activities(&self) -> bool {
+        self.enable_fungible_asset_activities
+    }
+
+    pub fn enable_state_value_history(&self) -> bool {
+        self.enable_state_value_history
+    }
+
     pub fn is_internal_indexer_db_enabled(&self) -> bool {
-        self.enable_transaction || self.enable_event || self.enable_statekeys
+        self.enable_transaction
+            || self.enable_event
+            || self.enable_statekeys
+            || self.enable_participant_transactions
+            || self.enable_fungible_asset_activities
+            || self.enable_state_value_history
     }
 
     pub fn batch_size(&self) -> usize {
@@ -74,6 +100,9 @@ impl Default for InternalIndexerDBConfig {
             enable_event_v2_translation: false,
             event_v2_translation_ignores_below_version: 0,
             enable_statekeys: false,
+            enable_participant_transactions: false,
+            enable_fungible_asset_activities: false,
+            enable_state_value_history: false,
             batch_size: 10_000,
         }
     }
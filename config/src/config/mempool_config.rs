@@ -3,13 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::{
-    config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
-    node_config_loader::NodeType, Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
+    config_optimizer::ConfigOptimizer,
+    config_sanitizer::ConfigSanitizer,
+    node_config_loader::NodeType,
+    transaction_filter_type::{Filter, Matcher},
+    Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
 };
 use aptos_global_constants::DEFAULT_BUCKETS;
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -37,6 +41,29 @@ impl Default for LoadBalancingThresholdConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolPersistenceConfig {
+    /// Whether or not to persist pending transactions to disk, and restore them on startup.
+    /// Restored transactions are re-validated before being reinserted into the mempool.
+    pub enabled: bool,
+    /// The path of the snapshot file. Relative paths are resolved against the storage directory.
+    pub snapshot_path: PathBuf,
+    /// The interval (in seconds) at which the snapshot is written to disk. A final
+    /// snapshot is also written when the mempool runtime is shut down.
+    pub snapshot_interval_secs: u64,
+}
+
+impl Default for MempoolPersistenceConfig {
+    fn default() -> MempoolPersistenceConfig {
+        MempoolPersistenceConfig {
+            enabled: false,
+            snapshot_path: PathBuf::from("mempool/snapshot.bcs"),
+            snapshot_interval_secs: 60,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
@@ -100,6 +127,14 @@ pub struct MempoolConfig {
     /// up to 10 minutes (shared_mempool_priority_update_interval_secs) to enable the load balancing. If this flag is enabled,
     /// then the PFNs will always do load balancing irrespective of the load.
     pub enable_max_load_balancing_at_any_load: bool,
+    /// Filter applied to transactions before they are admitted into the mempool (both client
+    /// submissions and peer broadcasts). Denied transactions are rejected with
+    /// `MempoolStatusCode::RejectedByFilter`, so they never enter the mempool or get broadcast.
+    ///
+    /// Block timestamp matchers are evaluated against the current wall clock time.
+    pub transaction_filter: Filter,
+    /// Configuration for persisting pending transactions across node restarts
+    pub persistence: MempoolPersistenceConfig,
 }
 
 impl Default for MempoolConfig {
@@ -164,17 +199,44 @@ impl Default for MempoolConfig {
                 },
             ],
             enable_max_load_balancing_at_any_load: false,
+            transaction_filter: Filter::empty(),
+            persistence: MempoolPersistenceConfig::default(),
         }
     }
 }
 
 impl ConfigSanitizer for MempoolConfig {
     fn sanitize(
-        _node_config: &NodeConfig,
+        node_config: &NodeConfig,
         _node_type: NodeType,
         _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
-        Ok(()) // TODO: add reasonable verifications
+        let sanitizer_name = Self::get_sanitizer_name();
+
+        // Transactions are admitted before they are part of any block, so
+        // we don't support Block ID based mempool filters.
+        for rule in node_config.mempool.transaction_filter.rules() {
+            if rule
+                .matcher()
+                .any(&|matcher| matches!(matcher, Matcher::BlockId(_)))
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "Block ID based mempool transaction filters are not supported!".into(),
+                ));
+            }
+        }
+
+        // Verify that the persistence snapshot interval is valid
+        let persistence_config = &node_config.mempool.persistence;
+        if persistence_config.enabled && persistence_config.snapshot_interval_secs == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The mempool persistence snapshot interval must be greater than 0!".into(),
+            ));
+        }
+
+        Ok(()) // TODO: add more reasonable verifications
     }
 }
 
@@ -346,4 +408,58 @@ mod tests {
             local_max_broadcasts_per_peer
         );
     }
+
+    #[test]
+    fn test_sanitize_block_id_transaction_filter() {
+        // Create a node config with a nested block ID matcher in the mempool filter
+        let node_config = NodeConfig {
+            mempool: MempoolConfig {
+                transaction_filter: Filter::empty().add_deny_matcher(Matcher::And(vec![
+                    Matcher::All,
+                    Matcher::Not(Box::new(Matcher::BlockId(Default::default()))),
+                ])),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Sanitize a config with a sender based filter and verify that it succeeds
+        let node_config = NodeConfig {
+            mempool: MempoolConfig {
+                transaction_filter: Filter::empty().add_deny_sender(Default::default()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_persistence_snapshot_interval() {
+        // Create a node config with persistence enabled and a zero snapshot interval
+        let node_config = NodeConfig {
+            mempool: MempoolConfig {
+                persistence: MempoolPersistenceConfig {
+                    enabled: true,
+                    snapshot_interval_secs: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
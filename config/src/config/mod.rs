@@ -31,6 +31,7 @@ mod node_config_loader;
 mod node_startup_config;
 mod override_node_config;
 mod peer_monitoring_config;
+mod peer_reputation_config;
 mod persistable_config;
 mod quorum_store_config;
 mod safety_rules_config;
@@ -63,6 +64,7 @@ pub use node_config::*;
 pub use node_config_loader::{sanitize_node_config, NodeType};
 pub use override_node_config::*;
 pub use peer_monitoring_config::*;
+pub use peer_reputation_config::*;
 pub use persistable_config::*;
 pub use quorum_store_config::*;
 pub use safety_rules_config::*;
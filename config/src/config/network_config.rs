@@ -4,8 +4,10 @@
 
 use crate::{
     config::{
+        config_sanitizer::ConfigSanitizer,
         identity_config::{Identity, IdentityFromStorage},
-        Error, IdentityBlob,
+        node_config_loader::NodeType,
+        Error, IdentityBlob, NodeConfig,
     },
     network_id::NetworkId,
     utils,
@@ -14,7 +16,7 @@ use aptos_crypto::{x25519, Uniform};
 use aptos_secure_storage::{CryptoStorage, KVStorage, Storage};
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_types::{
-    account_address::from_identity_public_key, network_address::NetworkAddress,
+    account_address::from_identity_public_key, chain_id::ChainId, network_address::NetworkAddress,
     transaction::authenticator::AuthenticationKey, PeerId,
 };
 use rand::{
@@ -118,6 +120,10 @@ pub struct NetworkConfig {
     pub inbound_rate_limit_config: Option<RateLimitConfig>,
     /// Outbound rate limiting configuration, if not specified, no rate limiting
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
+    /// Outbound priorities and byte-rate limits of protocols (keyed by protocol name, e.g.,
+    /// "ConsensusRpcBcs"), applied to each peer connection. Protocols that aren't specified
+    /// are sent at the default priority (0), without rate limiting.
+    pub outbound_protocol_quotas: HashMap<String, ProtocolQuotaConfig>,
     /// The maximum size of an inbound or outbound message (it may be divided into multiple frame)
     pub max_message_size: usize,
     /// The maximum number of parallel message deserialization tasks that can run (per application)
@@ -158,6 +164,7 @@ impl NetworkConfig {
             max_inbound_connections: MAX_INBOUND_CONNECTIONS,
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
+            outbound_protocol_quotas: HashMap::new(),
             max_message_size: MAX_MESSAGE_SIZE,
             inbound_rx_buffer_size_bytes: None,
             inbound_tx_buffer_size_bytes: None,
@@ -347,6 +354,7 @@ pub enum DiscoveryMethod {
     Onchain,
     File(FileDiscovery),
     Rest(RestDiscovery),
+    Dns(DnsDiscovery),
     None,
 }
 
@@ -364,6 +372,15 @@ pub struct RestDiscovery {
     pub interval_secs: u64,
 }
 
+/// Discovers seed peers by resolving the SRV records of the given name (e.g.,
+/// `_aptosnet._tcp.seeds.example.com`), and the TXT records of each target.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DnsDiscovery {
+    pub name: String,
+    pub interval_secs: u64,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct RateLimitConfig {
@@ -388,6 +405,120 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// The outbound quota of a protocol on each peer connection. Note: the quota is applied
+/// to the serialized message data (i.e., before framing and encryption).
+/// If the outbound queue of a protocol with a quota is full, its oldest message is dropped.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProtocolQuotaConfig {
+    /// Messages of protocols with a higher priority are always sent first
+    pub priority: u8,
+    /// Maximum number of bytes/s for the protocol, if not specified, no rate limiting
+    pub byte_bucket_rate: Option<usize>,
+    /// Maximum burst of bytes for the protocol, if not specified, the rate is used
+    pub byte_bucket_size: Option<usize>,
+}
+
+/// The names of the protocols that outbound quotas can be configured for. These must match
+/// the names of the `ProtocolId`s in the network framework.
+pub const OUTBOUND_QUOTA_PROTOCOL_NAMES: &[&str] = &[
+    "ConsensusRpcBcs",
+    "ConsensusDirectSendBcs",
+    "MempoolDirectSend",
+    "StateSyncDirectSend",
+    "DiscoveryDirectSend",
+    "HealthCheckerRpc",
+    "ConsensusDirectSendJson",
+    "ConsensusRpcJson",
+    "StorageServiceRpc",
+    "MempoolRpc",
+    "PeerMonitoringServiceRpc",
+    "ConsensusRpcCompressed",
+    "ConsensusDirectSendCompressed",
+    "NetbenchDirectSend",
+    "NetbenchRpc",
+    "DKGDirectSendCompressed",
+    "DKGDirectSendBcs",
+    "DKGDirectSendJson",
+    "DKGRpcCompressed",
+    "DKGRpcBcs",
+    "DKGRpcJson",
+    "JWKConsensusDirectSendCompressed",
+    "JWKConsensusDirectSendBcs",
+    "JWKConsensusDirectSendJson",
+    "JWKConsensusRpcCompressed",
+    "JWKConsensusRpcBcs",
+    "JWKConsensusRpcJson",
+    "ConsensusObserver",
+    "ConsensusObserverRpc",
+    "ConsensusObserverZstd",
+    "StorageServiceRpcZstd",
+];
+
+impl ConfigSanitizer for ProtocolQuotaConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let network_configs = node_config
+            .validator_network
+            .iter()
+            .chain(node_config.full_node_networks.iter());
+
+        for network_config in network_configs {
+            let network_id = network_config.network_id;
+            for (protocol_name, quota_config) in &network_config.outbound_protocol_quotas {
+                // Verify that the protocol exists
+                if !OUTBOUND_QUOTA_PROTOCOL_NAMES.contains(&protocol_name.as_str()) {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!(
+                            "Unknown protocol in the outbound quotas of the {} network: {}!",
+                            network_id, protocol_name
+                        ),
+                    ));
+                }
+
+                // Verify that the rate limit is valid
+                match (quota_config.byte_bucket_rate, quota_config.byte_bucket_size) {
+                    (Some(0), _) => {
+                        return Err(Error::ConfigSanitizerFailed(
+                            sanitizer_name,
+                            format!(
+                                "The outbound byte rate of {} on the {} network must be positive!",
+                                protocol_name, network_id
+                            ),
+                        ));
+                    },
+                    (Some(rate), Some(size)) if size < rate => {
+                        return Err(Error::ConfigSanitizerFailed(
+                            sanitizer_name,
+                            format!(
+                                "The outbound bucket size ({}) of {} on the {} network can't be smaller than the rate ({})!",
+                                size, protocol_name, network_id, rate
+                            ),
+                        ));
+                    },
+                    (None, Some(_)) => {
+                        return Err(Error::ConfigSanitizerFailed(
+                            sanitizer_name,
+                            format!(
+                                "The outbound bucket size of {} on the {} network is set without a rate!",
+                                protocol_name, network_id
+                            ),
+                        ));
+                    },
+                    _ => {},
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
@@ -509,6 +640,54 @@ impl Peer {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sanitize_outbound_protocol_quotas() {
+        // Verify that valid quotas pass sanitization
+        let quotas = HashMap::from([
+            ("ConsensusRpcBcs".to_string(), ProtocolQuotaConfig {
+                priority: 2,
+                ..ProtocolQuotaConfig::default()
+            }),
+            ("StorageServiceRpc".to_string(), ProtocolQuotaConfig {
+                priority: 0,
+                byte_bucket_rate: Some(100),
+                byte_bucket_size: Some(200),
+            }),
+        ]);
+        let node_config = create_node_config_with_quotas(quotas);
+        ProtocolQuotaConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+
+        // Verify that unknown protocols and invalid rate limits are rejected
+        for (protocol_name, byte_bucket_rate, byte_bucket_size) in [
+            ("UnknownRpc", None, None),
+            ("StorageServiceRpc", Some(0), None),
+            ("StorageServiceRpc", Some(200), Some(100)),
+            ("StorageServiceRpc", None, Some(100)),
+        ] {
+            let quotas = HashMap::from([(protocol_name.to_string(), ProtocolQuotaConfig {
+                priority: 0,
+                byte_bucket_rate,
+                byte_bucket_size,
+            })]);
+            let node_config = create_node_config_with_quotas(quotas);
+            let error = ProtocolQuotaConfig::sanitize(&node_config, NodeType::PublicFullnode, None)
+                .unwrap_err();
+            assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+        }
+    }
+
+    fn create_node_config_with_quotas(
+        outbound_protocol_quotas: HashMap<String, ProtocolQuotaConfig>,
+    ) -> NodeConfig {
+        NodeConfig {
+            full_node_networks: vec![NetworkConfig {
+                outbound_protocol_quotas,
+                ..NetworkConfig::network_with_id(NetworkId::Public)
+            }],
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_num_parallel_deserialization_tasks() {
         // Create a default network config and verify the number of deserialization tasks
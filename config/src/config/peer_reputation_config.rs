@@ -0,0 +1,139 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{
+    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
+};
+use aptos_types::chain_id::ChainId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerReputationConfig {
+    pub enable_peer_reputation: bool, // Whether or not peer reputations drive bans and dial priority
+    pub ban_score_threshold: f64, // Public peers are temporarily banned once their score drops to this threshold
+    pub deprioritize_score_threshold: f64, // Peers with a score below this threshold are dialed last
+    pub ban_duration_secs: u64, // The duration (secs) of a peer's first ban (doubled for each repeat ban)
+    pub max_ban_duration_secs: u64, // The max duration (secs) of a single ban
+    pub persist_interval_secs: u64, // The interval (secs) between persisting the reputations to disk
+    pub reputation_file_name: String, // The file (in the data directory) holding the reputations
+    pub max_num_reputations: usize, // The max number of peers with a tracked reputation
+}
+
+impl Default for PeerReputationConfig {
+    fn default() -> Self {
+        Self {
+            enable_peer_reputation: false,
+            ban_score_threshold: 10.0,
+            deprioritize_score_threshold: 25.0,
+            ban_duration_secs: 600,        // 10 minutes
+            max_ban_duration_secs: 86_400, // 1 day
+            persist_interval_secs: 60,     // 1 minute
+            reputation_file_name: "peer_reputations.json".into(),
+            max_num_reputations: 10_000,
+        }
+    }
+}
+
+impl ConfigSanitizer for PeerReputationConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let peer_reputation_config = &node_config.peer_reputation;
+
+        // Verify that peers are deprioritized before they are banned
+        if peer_reputation_config.ban_score_threshold
+            >= peer_reputation_config.deprioritize_score_threshold
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The ban score threshold ({}) must be less than the deprioritize score threshold ({})!",
+                    peer_reputation_config.ban_score_threshold,
+                    peer_reputation_config.deprioritize_score_threshold
+                ),
+            ));
+        }
+
+        // Verify that the ban duration does not exceed the max ban duration
+        if peer_reputation_config.ban_duration_secs > peer_reputation_config.max_ban_duration_secs {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The ban duration ({}) must not exceed the max ban duration ({})!",
+                    peer_reputation_config.ban_duration_secs,
+                    peer_reputation_config.max_ban_duration_secs
+                ),
+            ));
+        }
+
+        // Verify that the reputations are persisted periodically
+        if peer_reputation_config.persist_interval_secs == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The persist interval must be greater than zero!".to_string(),
+            ));
+        }
+
+        // Verify that reputations can be tracked
+        if peer_reputation_config.max_num_reputations == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The max number of reputations must be greater than zero!".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_default_config() {
+        // Verify that the default config passes sanitization
+        let node_config = NodeConfig::default();
+        PeerReputationConfig::sanitize(&node_config, NodeType::Validator, None).unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_invalid_thresholds() {
+        // Create a config with a ban threshold above the deprioritize threshold
+        let node_config = NodeConfig {
+            peer_reputation: PeerReputationConfig {
+                ban_score_threshold: 30.0,
+                deprioritize_score_threshold: 25.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error =
+            PeerReputationConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_invalid_ban_durations() {
+        // Create a config with a ban duration above the max ban duration
+        let node_config = NodeConfig {
+            peer_reputation: PeerReputationConfig {
+                ban_duration_secs: 1000,
+                max_ban_duration_secs: 999,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error = PeerReputationConfig::sanitize(&node_config, NodeType::PublicFullnode, None)
+            .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+}
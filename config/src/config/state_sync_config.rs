@@ -426,6 +426,9 @@ pub struct AptosDataClientConfig {
     pub subscription_response_timeout_ms: u64,
     /// Whether or not to request compression for incoming data
     pub use_compression: bool,
+    /// Whether or not to prefer zstd compression (negotiated by the network
+    /// layer) over lz4 compression for peers that support it
+    pub use_zstd_compression: bool,
 }
 
 impl Default for AptosDataClientConfig {
@@ -448,6 +451,7 @@ impl Default for AptosDataClientConfig {
             response_timeout_ms: 10_000,              // 10 seconds
             subscription_response_timeout_ms: 15_000, // 15 seconds (longer than a regular timeout because of prefetching)
             use_compression: true,
+            use_zstd_compression: false,
         }
     }
 }
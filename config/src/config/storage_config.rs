@@ -205,6 +205,8 @@ pub struct StorageConfig {
     pub ensure_rlimit_nofile: u64,
     /// panic if failed to ensure `ulimit -n`
     pub assert_rlimit_nofile: bool,
+    /// Background verification of the data in the DB.
+    pub consistency_checker_config: ConsistencyCheckerConfig,
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -280,6 +282,29 @@ impl From<EpochSnapshotPrunerConfig> for StateMerklePrunerConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsistencyCheckerConfig {
+    /// Boolean to enable/disable the consistency checker, which keeps sampling ranges of versions
+    /// in the background, recomputing the transaction info, event root and state checkpoint hashes
+    /// and verifying them against the transaction accumulator and the state tree.
+    pub enable: bool,
+    /// Number of consecutive versions checked in each sampled range.
+    pub versions_per_sample: u64,
+    /// Upper limit of the number of versions checked per second, to keep the extra IO low.
+    pub max_versions_per_second: u64,
+}
+
+impl Default for ConsistencyCheckerConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            versions_per_sample: 1_000,
+            max_versions_per_second: 1_000,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, Default)]
 #[serde(default, deny_unknown_fields)]
 pub struct PrunerConfig {
@@ -356,6 +381,7 @@ impl Default for StorageConfig {
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
             ensure_rlimit_nofile: 0,
             assert_rlimit_nofile: false,
+            consistency_checker_config: ConsistencyCheckerConfig::default(),
         }
     }
 }
@@ -579,6 +605,17 @@ impl ConfigSanitizer for StorageConfig {
             ));
         }
 
+        let consistency_checker_config = &config.consistency_checker_config;
+        if consistency_checker_config.enable
+            && (consistency_checker_config.versions_per_sample == 0
+                || consistency_checker_config.max_versions_per_second == 0)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "versions_per_sample and max_versions_per_second of the consistency checker must be positive.".to_string(),
+            ));
+        }
+
         if let Some(db_path_overrides) = config.db_path_overrides.as_ref() {
             if !config.rocksdb_configs.enable_storage_sharding {
                 return Err(Error::ConfigSanitizerFailed(
@@ -4,10 +4,36 @@
 use aptos_crypto::HashValue;
 use aptos_types::{
     account_address::AccountAddress,
-    transaction::{SignedTransaction, TransactionPayload},
+    keyless,
+    transaction::{
+        authenticator::TransactionAuthenticator, EntryFunction, MultisigTransactionPayload,
+        SignedTransaction, TransactionPayload,
+    },
 };
 use serde::{Deserialize, Serialize};
 
+/// The kind of payload carried by a transaction
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PayloadType {
+    Script,
+    EntryFunction,
+    Multisig,
+    /// A call to `0x1::code::publish_package_txn` (directly or through a multisig account)
+    ModulePublish,
+}
+
+/// The kind of authenticator signing a transaction
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AuthenticatorType {
+    Ed25519,
+    MultiEd25519,
+    SingleSender,
+    MultiAgent,
+    FeePayer,
+    /// Any of the signers (sender, secondary signers or fee payer) uses a keyless account
+    Keyless,
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Matcher {
     All,
@@ -18,6 +44,19 @@ pub enum Matcher {
     Sender(AccountAddress),
     ModuleAddress(AccountAddress),
     EntryFunction(AccountAddress, String, String),
+    PayloadType(PayloadType),
+    AuthenticatorType(AuthenticatorType),
+    GasUnitPriceGreaterThan(u64),
+    GasUnitPriceLessThan(u64),
+    MaxGasAmountGreaterThan(u64),
+    MaxGasAmountLessThan(u64),
+    /// Matches if any BCS encoded argument of the entry function (directly or through a
+    /// multisig account) contains the given bytes. The bytes are written as a hex string
+    /// without a `0x` prefix, e.g. `EntryFunctionArgumentContains: "0a0b0c"`.
+    EntryFunctionArgumentContains(#[serde(with = "hex")] Vec<u8>),
+    And(Vec<Matcher>),
+    Or(Vec<Matcher>),
+    Not(Box<Matcher>),
 }
 
 impl Matcher {
@@ -43,8 +82,106 @@ impl Matcher {
                 },
                 _ => false,
             },
+            Matcher::PayloadType(payload_type) => matches_payload_type(*payload_type, txn),
+            Matcher::AuthenticatorType(authenticator_type) => {
+                matches_authenticator_type(*authenticator_type, txn)
+            },
+            Matcher::GasUnitPriceGreaterThan(price) => txn.gas_unit_price() > *price,
+            Matcher::GasUnitPriceLessThan(price) => txn.gas_unit_price() < *price,
+            Matcher::MaxGasAmountGreaterThan(amount) => txn.max_gas_amount() > *amount,
+            Matcher::MaxGasAmountLessThan(amount) => txn.max_gas_amount() < *amount,
+            Matcher::EntryFunctionArgumentContains(pattern) => {
+                executed_entry_function(txn).map_or(false, |entry_function| {
+                    entry_function
+                        .args()
+                        .iter()
+                        .any(|arg| contains_bytes(arg, pattern))
+                })
+            },
+            Matcher::And(matchers) => matchers
+                .iter()
+                .all(|matcher| matcher.matches(block_id, timestamp, txn)),
+            Matcher::Or(matchers) => matchers
+                .iter()
+                .any(|matcher| matcher.matches(block_id, timestamp, txn)),
+            Matcher::Not(matcher) => !matcher.matches(block_id, timestamp, txn),
         }
     }
+
+    /// Returns true if the matcher, or any of its nested matchers, satisfies the given predicate
+    pub fn any(&self, predicate: &impl Fn(&Matcher) -> bool) -> bool {
+        if predicate(self) {
+            return true;
+        }
+        match self {
+            Matcher::And(matchers) | Matcher::Or(matchers) => {
+                matchers.iter().any(|matcher| matcher.any(predicate))
+            },
+            Matcher::Not(matcher) => matcher.any(predicate),
+            _ => false,
+        }
+    }
+}
+
+/// Returns the entry function executed by the transaction, either directly or
+/// through a multisig account (if the payload is not already stored on chain)
+fn executed_entry_function(txn: &SignedTransaction) -> Option<&EntryFunction> {
+    match txn.payload() {
+        TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+        TransactionPayload::Multisig(multisig) => match &multisig.transaction_payload {
+            Some(MultisigTransactionPayload::EntryFunction(entry_function)) => Some(entry_function),
+            None => None,
+        },
+        _ => None,
+    }
+}
+
+fn matches_payload_type(payload_type: PayloadType, txn: &SignedTransaction) -> bool {
+    match payload_type {
+        PayloadType::Script => matches!(txn.payload(), TransactionPayload::Script(_)),
+        PayloadType::EntryFunction => {
+            matches!(txn.payload(), TransactionPayload::EntryFunction(_))
+        },
+        PayloadType::Multisig => matches!(txn.payload(), TransactionPayload::Multisig(_)),
+        PayloadType::ModulePublish => {
+            matches!(txn.payload(), TransactionPayload::ModuleBundle(_))
+                || executed_entry_function(txn).map_or(false, |entry_function| {
+                    *entry_function.module().address() == AccountAddress::ONE
+                        && entry_function.module().name().as_str() == "code"
+                        && entry_function.function().as_str() == "publish_package_txn"
+                })
+        },
+    }
+}
+
+fn matches_authenticator_type(
+    authenticator_type: AuthenticatorType,
+    txn: &SignedTransaction,
+) -> bool {
+    let authenticator = txn.authenticator_ref();
+    match authenticator_type {
+        AuthenticatorType::Ed25519 => {
+            matches!(authenticator, TransactionAuthenticator::Ed25519 { .. })
+        },
+        AuthenticatorType::MultiEd25519 => {
+            matches!(authenticator, TransactionAuthenticator::MultiEd25519 { .. })
+        },
+        AuthenticatorType::SingleSender => {
+            matches!(authenticator, TransactionAuthenticator::SingleSender { .. })
+        },
+        AuthenticatorType::MultiAgent => {
+            matches!(authenticator, TransactionAuthenticator::MultiAgent { .. })
+        },
+        AuthenticatorType::FeePayer => {
+            matches!(authenticator, TransactionAuthenticator::FeePayer { .. })
+        },
+        AuthenticatorType::Keyless => keyless::get_authenticators(txn)
+            .map_or(false, |authenticators| !authenticators.is_empty()),
+    }
+}
+
+fn contains_bytes(bytes: &[u8], pattern: &[u8]) -> bool {
+    pattern.is_empty() || bytes.windows(pattern.len()).any(|window| window == pattern)
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -113,6 +250,20 @@ impl Rule {
 /// This filter allows transactions from the sender with address f8871acf2c827d40e23b71f6ff2b9accef8dbb17709b88bd9eb95e6bb748c25a or
 /// from the module with address 0000000000000000000000000000000000000000000000000000000000000001 or entry functions
 /// test::check and test::new from the module 0000000000000000000000000000000000000000000000000000000000000001. All other transactions are denied.
+///
+/// Matchers can be combined with And, Or and Not. For example:
+///             rules:
+///                 - Deny:
+///                     And:
+///                         - PayloadType: ModulePublish
+///                         - Not:
+///                             Sender: f8871acf2c827d40e23b71f6ff2b9accef8dbb17709b88bd9eb95e6bb748c25a
+///                 - Deny:
+///                     Or:
+///                         - AuthenticatorType: Keyless
+///                         - GasUnitPriceLessThan: 100
+/// This filter denies module publishing from all senders except f8871acf2c827d40e23b71f6ff2b9accef8dbb17709b88bd9eb95e6bb748c25a,
+/// as well as keyless transactions and transactions with a gas unit price below 100. All other transactions are allowed.
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Filter {
     rules: Vec<Rule>,
@@ -197,6 +348,16 @@ impl Filter {
         self
     }
 
+    pub fn add_allow_matcher(mut self, matcher: Matcher) -> Self {
+        self.rules.push(Rule::Allow(matcher));
+        self
+    }
+
+    pub fn add_deny_matcher(mut self, matcher: Matcher) -> Self {
+        self.rules.push(Rule::Deny(matcher));
+        self
+    }
+
     pub fn rules(&self) -> &[Rule] {
         &self.rules
     }
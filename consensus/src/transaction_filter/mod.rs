@@ -33,20 +33,20 @@ impl TransactionFilter {
 #[cfg(test)]
 mod test {
     use crate::transaction_filter::TransactionFilter;
-    use aptos_config::config::transaction_filter_type::Filter;
+    use aptos_config::config::transaction_filter_type::{
+        AuthenticatorType, Filter, Matcher, PayloadType,
+    };
     use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, SigningKey, Uniform};
     use aptos_types::{
         chain_id::ChainId,
         move_utils::MemberId,
-        transaction::{EntryFunction, RawTransaction, SignedTransaction, TransactionPayload},
+        transaction::{
+            EntryFunction, RawTransaction, Script, SignedTransaction, TransactionPayload,
+        },
     };
     use move_core_types::account_address::AccountAddress;
 
     fn create_signed_transaction(function: MemberId) -> SignedTransaction {
-        let private_key = Ed25519PrivateKey::generate_for_testing();
-        let public_key = private_key.public_key();
-        let sender = AccountAddress::random();
-        let sequence_number = 0;
         let MemberId {
             module_id,
             member_id: function_id,
@@ -58,8 +58,27 @@ mod test {
             vec![],
             vec![],
         ));
-        let raw_transaction =
-            RawTransaction::new(sender, sequence_number, payload, 0, 0, 0, ChainId::new(10));
+        create_signed_transaction_with_payload(payload, 0, 0)
+    }
+
+    fn create_signed_transaction_with_payload(
+        payload: TransactionPayload,
+        max_gas_amount: u64,
+        gas_unit_price: u64,
+    ) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        let sender = AccountAddress::random();
+        let sequence_number = 0;
+        let raw_transaction = RawTransaction::new(
+            sender,
+            sequence_number,
+            payload,
+            max_gas_amount,
+            gas_unit_price,
+            0,
+            ChainId::new(10),
+        );
 
         SignedTransaction::new(
             raw_transaction.clone(),
@@ -287,4 +306,160 @@ mod test {
         let filtered_txns = allow_list_filter.filter(block_id, 0, txns.clone());
         assert_eq!(filtered_txns, txns[4..].to_vec());
     }
+
+    #[test]
+    fn test_gas_filter() {
+        let block_id = HashValue::random();
+        let payload = get_transactions()[0].payload().clone();
+        let txns = vec![
+            create_signed_transaction_with_payload(payload.clone(), 1_000, 50),
+            create_signed_transaction_with_payload(payload.clone(), 1_000, 100),
+            create_signed_transaction_with_payload(payload, 2_000_000, 100),
+        ];
+
+        // Deny transactions with a low gas unit price or a high max gas amount
+        let gas_filter = TransactionFilter::new(
+            Filter::empty()
+                .add_deny_matcher(Matcher::GasUnitPriceLessThan(100))
+                .add_deny_matcher(Matcher::MaxGasAmountGreaterThan(1_000_000)),
+        );
+        let filtered_txns = gas_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[1..2].to_vec());
+    }
+
+    #[test]
+    fn test_payload_type_filter() {
+        let block_id = HashValue::random();
+        let entry_function_txn = create_signed_transaction(str::parse("0x1::test::add").unwrap());
+        let publish_txn =
+            create_signed_transaction(str::parse("0x1::code::publish_package_txn").unwrap());
+        let script_txn = create_signed_transaction_with_payload(
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            0,
+            0,
+        );
+        let txns = vec![entry_function_txn, publish_txn, script_txn];
+
+        // Deny scripts and module publishing
+        let payload_type_filter = TransactionFilter::new(
+            Filter::empty()
+                .add_deny_matcher(Matcher::PayloadType(PayloadType::Script))
+                .add_deny_matcher(Matcher::PayloadType(PayloadType::ModulePublish)),
+        );
+        let filtered_txns = payload_type_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[0..1].to_vec());
+
+        // Only allow entry functions
+        let payload_type_filter = TransactionFilter::new(
+            Filter::empty()
+                .add_allow_matcher(Matcher::PayloadType(PayloadType::EntryFunction))
+                .add_deny_all(),
+        );
+        let filtered_txns = payload_type_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[0..2].to_vec());
+    }
+
+    #[test]
+    fn test_authenticator_type_filter() {
+        let txns = get_transactions();
+        let block_id = HashValue::random();
+
+        // All transactions are signed by a single ed25519 key
+        let ed25519_filter = TransactionFilter::new(
+            Filter::empty()
+                .add_deny_matcher(Matcher::AuthenticatorType(AuthenticatorType::Ed25519)),
+        );
+        assert_eq!(ed25519_filter.filter(block_id, 0, txns.clone()), vec![]);
+
+        // No transactions are fee payer or keyless transactions
+        let fee_payer_or_keyless_filter =
+            TransactionFilter::new(Filter::empty().add_deny_matcher(Matcher::Or(vec![
+                Matcher::AuthenticatorType(AuthenticatorType::FeePayer),
+                Matcher::AuthenticatorType(AuthenticatorType::Keyless),
+            ])));
+        assert_eq!(
+            fee_payer_or_keyless_filter.filter(block_id, 0, txns.clone()),
+            txns
+        );
+    }
+
+    #[test]
+    fn test_entry_function_argument_filter() {
+        let block_id = HashValue::random();
+        let blocked_address = AccountAddress::random();
+        let txns: Vec<_> = [AccountAddress::random(), blocked_address]
+            .iter()
+            .map(|address| {
+                let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+                    str::parse("0x1::aptos_account").unwrap(),
+                    str::parse("transfer").unwrap(),
+                    vec![],
+                    vec![
+                        bcs::to_bytes(address).unwrap(),
+                        bcs::to_bytes(&1u64).unwrap(),
+                    ],
+                ));
+                create_signed_transaction_with_payload(payload, 0, 0)
+            })
+            .collect();
+
+        // Deny transfers to the blocked address
+        let argument_filter = TransactionFilter::new(Filter::empty().add_deny_matcher(
+            Matcher::EntryFunctionArgumentContains(blocked_address.to_vec()),
+        ));
+        let filtered_txns = argument_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, txns[0..1].to_vec());
+
+        // The same filter can be configured with a hex encoded pattern
+        let filter = serde_yaml::from_str::<Filter>(&format!(
+            r#"
+            rules:
+                - Deny:
+                    EntryFunctionArgumentContains: "{}"
+            "#,
+            hex::encode(blocked_address)
+        ))
+        .unwrap();
+        let yaml_filter = TransactionFilter::new(filter);
+        assert_eq!(
+            yaml_filter.filter(block_id, 0, txns.clone()),
+            txns[0..1].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_boolean_composition_filter() {
+        let txns = get_transactions();
+        let block_id = HashValue::random();
+        let filter = serde_yaml::from_str::<Filter>(r#"
+            rules:
+                - Deny:
+                    And:
+                        - ModuleAddress: "0000000000000000000000000000000000000000000000000000000000000001"
+                        - Not:
+                            Or:
+                                - EntryFunction:
+                                    - "0000000000000000000000000000000000000000000000000000000000000001"
+                                    - test
+                                    - add
+                                - EntryFunction:
+                                    - "0000000000000000000000000000000000000000000000000000000000000001"
+                                    - test
+                                    - sub
+                - Allow:
+                    Or:
+                        - ModuleAddress: "0000000000000000000000000000000000000000000000000000000000000001"
+                        - ModuleAddress: "0000000000000000000000000000000000000000000000000000000000000002"
+                - Deny: All
+              "#).unwrap();
+
+        // Only 0x1::test::add, 0x1::test::sub and 0x2::test2::mul are allowed
+        let boolean_filter = TransactionFilter::new(filter);
+        let filtered_txns = boolean_filter.filter(block_id, 0, txns.clone());
+        assert_eq!(filtered_txns, vec![
+            txns[0].clone(),
+            txns[3].clone(),
+            txns[4].clone()
+        ]);
+    }
 }
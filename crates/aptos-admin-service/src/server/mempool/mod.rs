@@ -1,13 +1,19 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_crypto::HashValue;
 use aptos_logger::info;
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender};
+use aptos_mempool::{MempoolClientRequest, MempoolClientSender, MempoolEvictionCriteria};
 use aptos_system_utils::utils::{reply_with, reply_with_status};
-use aptos_types::account_address::AccountAddress;
-use futures_channel::oneshot::Canceled;
-use http::{Request, Response, StatusCode};
+use aptos_types::{account_address::AccountAddress, move_utils::MemberId};
+use futures_channel::oneshot::{Canceled, Sender};
+use http::{
+    header::{HeaderValue, CONTENT_TYPE},
+    Request, Response, StatusCode,
+};
 use hyper::Body;
+use serde::Serialize;
+use std::{collections::HashMap, str::FromStr};
 
 pub async fn mempool_handle_parking_lot_address_request(
     _req: Request<Body>,
@@ -37,19 +43,189 @@ pub async fn mempool_handle_parking_lot_address_request(
     }
 }
 
+pub async fn mempool_handle_transactions_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let sender: Option<AccountAddress> = match query_pairs.get("sender") {
+        Some(val) => match AccountAddress::from_str(val) {
+            Ok(val) => Some(val),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => None,
+    };
+
+    match send_mempool_request(
+        mempool_client_sender,
+        "GetTransactionSummaries",
+        |callback| MempoolClientRequest::GetTransactionSummaries(sender, callback),
+    )
+    .await
+    {
+        Ok(summaries) => {
+            info!("Finished getting transaction summaries from mempool.");
+            Ok(reply_with_json(&summaries))
+        },
+        Err(e) => {
+            info!("Failed to get transaction summaries from mempool: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn mempool_handle_transaction_by_hash_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let hash: HashValue = match query_pairs.get("hash") {
+        Some(val) => match HashValue::from_str(val.trim_start_matches("0x")) {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "The hash query parameter is required.",
+            ))
+        },
+    };
+
+    match send_mempool_request(
+        mempool_client_sender,
+        "GetTransactionSummaryByHash",
+        |callback| MempoolClientRequest::GetTransactionSummaryByHash(hash, callback),
+    )
+    .await
+    {
+        Ok(Some(summary)) => {
+            info!("Finished getting transaction summary ({hash}) from mempool.");
+            Ok(reply_with_json(&summary))
+        },
+        Ok(None) => Ok(reply_with_status(
+            StatusCode::NOT_FOUND,
+            format!("Transaction {hash} is not in mempool."),
+        )),
+        Err(e) => {
+            info!("Failed to get transaction summary ({hash}) from mempool: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn mempool_handle_evict_transactions_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let sender: Option<AccountAddress> = match query_pairs.get("sender") {
+        Some(val) => match AccountAddress::from_str(val) {
+            Ok(val) => Some(val),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => None,
+    };
+
+    // The entry function is specified as <address>::<module>::<function>
+    let entry_function: Option<(AccountAddress, String, String)> =
+        match query_pairs.get("entry_function") {
+            Some(val) => match MemberId::from_str(val) {
+                Ok(member_id) => Some((
+                    *member_id.module_id.address(),
+                    member_id.module_id.name().to_string(),
+                    member_id.member_id.to_string(),
+                )),
+                Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+            },
+            None => None,
+        };
+
+    // Avoid accidentally evicting every transaction in mempool
+    let criteria = MempoolEvictionCriteria {
+        sender,
+        entry_function,
+    };
+    if criteria.is_empty() {
+        return Ok(reply_with_status(
+            StatusCode::BAD_REQUEST,
+            "At least one of the sender or entry_function query parameters is required.",
+        ));
+    }
+
+    info!("Evicting transactions from mempool matching: {criteria:?}.");
+
+    match send_mempool_request(mempool_client_sender, "EvictTransactions", |callback| {
+        MempoolClientRequest::EvictTransactions(criteria, callback)
+    })
+    .await
+    {
+        Ok(evicted_txns) => {
+            info!(
+                "Finished evicting {} transactions from mempool.",
+                evicted_txns.len()
+            );
+            Ok(reply_with_json(&evicted_txns))
+        },
+        Err(e) => {
+            info!("Failed to evict transactions from mempool: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
 async fn get_parking_lot_addresses(
     mempool_client_sender: MempoolClientSender,
 ) -> Result<Vec<(AccountAddress, u64)>, Canceled> {
+    send_mempool_request(
+        mempool_client_sender,
+        "GetAddressesFromParkingLot",
+        MempoolClientRequest::GetAddressesFromParkingLot,
+    )
+    .await
+}
+
+/// Sends the request (created using the given callback sender) to mempool and waits for the response
+async fn send_mempool_request<T>(
+    mempool_client_sender: MempoolClientSender,
+    request_name: &str,
+    create_request: impl FnOnce(Sender<T>) -> MempoolClientRequest,
+) -> Result<T, Canceled> {
     let (sender, receiver) = futures_channel::oneshot::channel();
 
     match mempool_client_sender
         .clone()
-        .try_send(MempoolClientRequest::GetAddressesFromParkingLot(sender))
+        .try_send(create_request(sender))
     {
         Ok(_) => receiver.await,
         Err(e) => {
-            info!("Failed to send request for GetAddressesFromParkingLot: {e:?}");
+            info!("Failed to send request for {request_name}: {e:?}");
             Err(Canceled)
         },
     }
 }
+
+fn reply_with_json<T: Serialize>(value: &T) -> Response<Body> {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => reply_with(
+            vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))],
+            json,
+        ),
+        Err(e) => reply_with_status(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
@@ -28,6 +28,7 @@ use tokio::runtime::Runtime;
 
 mod consensus;
 mod mempool;
+mod storage;
 
 #[derive(Default)]
 pub struct Context {
@@ -237,6 +238,76 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/mempool/transactions") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_transactions_request(req, mempool_client_sender).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/mempool/transaction") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_transaction_by_hash_request(req, mempool_client_sender)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/mempool/evict") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_evict_transactions_request(req, mempool_client_sender)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/storage/pruners") => {
+                let aptos_db = context.aptos_db.read().clone();
+                if let Some(aptos_db) = aptos_db {
+                    storage::handle_pruner_status_request(req, aptos_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "AptosDB is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/storage/pruners/pause")
+            | (hyper::Method::POST, "/debug/storage/pruners/resume") => {
+                let paused = req.uri().path().ends_with("/pause");
+                let aptos_db = context.aptos_db.read().clone();
+                if let Some(aptos_db) = aptos_db {
+                    storage::handle_pause_pruner_request(req, aptos_db, paused).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "AptosDB is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/storage/pruners/prune") => {
+                let aptos_db = context.aptos_db.read().clone();
+                if let Some(aptos_db) = aptos_db {
+                    storage::handle_prune_request(req, aptos_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "AptosDB is not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
@@ -0,0 +1,145 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_logger::info;
+use aptos_storage_interface::{pruner::PrunerKind, DbReaderWriter};
+use aptos_system_utils::utils::{reply_with, reply_with_status};
+use aptos_types::transaction::Version;
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+pub async fn handle_pruner_status_request(
+    _req: Request<Body>,
+    aptos_db: Arc<DbReaderWriter>,
+) -> hyper::Result<Response<Body>> {
+    match aptos_db.reader.get_pruner_statuses() {
+        Ok(statuses) => match serde_json::to_string_pretty(&statuses) {
+            Ok(json) => Ok(reply_with(
+                vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))],
+                json,
+            )),
+            Err(e) => Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            )),
+        },
+        Err(e) => {
+            info!("Failed to get pruner statuses: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+/// Pauses or resumes the pruner given by the `pruner` query parameter, or all enabled pruners if
+/// it's not given.
+pub async fn handle_pause_pruner_request(
+    req: Request<Body>,
+    aptos_db: Arc<DbReaderWriter>,
+    paused: bool,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let kinds = match query_pairs.get("pruner") {
+        Some(val) => match PrunerKind::from_str(val) {
+            Ok(kind) => vec![kind],
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => match aptos_db.reader.get_pruner_statuses() {
+            Ok(statuses) => statuses
+                .into_iter()
+                .filter(|status| status.enabled)
+                .map(|status| status.kind)
+                .collect(),
+            Err(e) => {
+                return Ok(reply_with_status(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    e.to_string(),
+                ))
+            },
+        },
+    };
+
+    for kind in &kinds {
+        if let Err(e) = aptos_db.writer.set_pruner_paused(*kind, paused) {
+            info!("Failed to set the {kind} pruner paused to {paused}: {e:?}");
+            return Ok(reply_with_status(StatusCode::BAD_REQUEST, e.to_string()));
+        }
+    }
+
+    let action = if paused { "Paused" } else { "Resumed" };
+    Ok(reply_with(
+        vec![],
+        format!(
+            "{action} pruners: {}.",
+            kinds
+                .iter()
+                .map(PrunerKind::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    ))
+}
+
+/// Has the pruner given by the `pruner` query parameter prune everything before `target_version`,
+/// optionally overriding its batch size with `batch_size`.
+pub async fn handle_prune_request(
+    req: Request<Body>,
+    aptos_db: Arc<DbReaderWriter>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let kind = match query_pairs.get("pruner") {
+        Some(val) => match PrunerKind::from_str(val) {
+            Ok(kind) => kind,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "The pruner query parameter is required.",
+            ))
+        },
+    };
+    let target_version: Version = match query_pairs.get("target_version") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, format!("{err}"))),
+        },
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "The target_version query parameter is required.",
+            ))
+        },
+    };
+    let batch_size: Option<usize> = match query_pairs.get("batch_size") {
+        Some(val) => match val.parse() {
+            Ok(val) => Some(val),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, format!("{err}"))),
+        },
+        None => None,
+    };
+
+    match aptos_db
+        .writer
+        .prune_to_version(kind, target_version, batch_size)
+    {
+        Ok(()) => Ok(reply_with(
+            vec![],
+            format!("The {kind} pruner is pruning to version {target_version}."),
+        )),
+        Err(e) => {
+            info!("Failed to prune {kind} data to version {target_version}: {e:?}");
+            Ok(reply_with_status(StatusCode::BAD_REQUEST, e.to_string()))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests;
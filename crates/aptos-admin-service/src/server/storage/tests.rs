@@ -0,0 +1,219 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_infallible::Mutex;
+use aptos_storage_interface::{
+    pruner::PrunerStatus, AptosDbError, DbReader, DbWriter, Result as DbResult,
+};
+use std::collections::BTreeMap;
+
+const SYNCED_VERSION: Version = 100;
+
+/// Keeps the pruner statuses in memory and applies the admin operations to them.
+struct MockPrunerDb {
+    statuses: Mutex<Vec<PrunerStatus>>,
+}
+
+impl MockPrunerDb {
+    fn new() -> Self {
+        let statuses = PrunerKind::ALL
+            .into_iter()
+            .map(|kind| {
+                // Only the ledger and the state kv pruners are enabled.
+                let enabled = matches!(kind, PrunerKind::Ledger | PrunerKind::StateKv);
+                PrunerStatus {
+                    kind,
+                    enabled,
+                    paused: false,
+                    prune_window: 10,
+                    batch_size: enabled.then_some(5),
+                    min_readable_version: 0,
+                    target_version: 0,
+                    progress: 0,
+                    sub_pruner_progress: BTreeMap::new(),
+                }
+            })
+            .collect();
+        Self {
+            statuses: Mutex::new(statuses),
+        }
+    }
+
+    fn update_status(
+        &self,
+        kind: PrunerKind,
+        update: impl FnOnce(&mut PrunerStatus) -> DbResult<()>,
+    ) -> DbResult<()> {
+        let mut statuses = self.statuses.lock();
+        let status = statuses
+            .iter_mut()
+            .find(|status| status.kind == kind)
+            .unwrap();
+        if !status.enabled {
+            return Err(AptosDbError::Other(format!(
+                "The {kind} pruner is not enabled."
+            )));
+        }
+        update(status)
+    }
+}
+
+impl DbReader for MockPrunerDb {
+    fn get_pruner_statuses(&self) -> DbResult<Vec<PrunerStatus>> {
+        Ok(self.statuses.lock().clone())
+    }
+}
+
+impl DbWriter for MockPrunerDb {
+    fn set_pruner_paused(&self, kind: PrunerKind, paused: bool) -> DbResult<()> {
+        self.update_status(kind, |status| {
+            status.paused = paused;
+            Ok(())
+        })
+    }
+
+    fn prune_to_version(
+        &self,
+        kind: PrunerKind,
+        target_version: Version,
+        batch_size: Option<usize>,
+    ) -> DbResult<()> {
+        if target_version > SYNCED_VERSION {
+            return Err(AptosDbError::Other(format!(
+                "Can't prune the {kind} data to version {target_version}."
+            )));
+        }
+        self.update_status(kind, |status| {
+            if let Some(batch_size) = batch_size {
+                status.batch_size = Some(batch_size);
+            }
+            status.min_readable_version = status.min_readable_version.max(target_version);
+            status.target_version = status.min_readable_version;
+            Ok(())
+        })
+    }
+}
+
+fn post(uri: &str) -> Request<Body> {
+    Request::builder()
+        .method(hyper::Method::POST)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn get_statuses(aptos_db: &Arc<DbReaderWriter>) -> Vec<PrunerStatus> {
+    let response = handle_pruner_status_request(
+        Request::get("/debug/storage/pruners")
+            .body(Body::empty())
+            .unwrap(),
+        aptos_db.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(CONTENT_TYPE).unwrap(),
+        "application/json"
+    );
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+fn find_status(statuses: &[PrunerStatus], kind: PrunerKind) -> &PrunerStatus {
+    statuses.iter().find(|status| status.kind == kind).unwrap()
+}
+
+#[tokio::test]
+async fn test_get_pruner_statuses() {
+    let aptos_db = Arc::new(DbReaderWriter::new(MockPrunerDb::new()));
+
+    let statuses = get_statuses(&aptos_db).await;
+    assert_eq!(
+        statuses
+            .iter()
+            .map(|status| status.kind)
+            .collect::<Vec<_>>(),
+        PrunerKind::ALL.to_vec()
+    );
+    let ledger = find_status(&statuses, PrunerKind::Ledger);
+    assert!(ledger.enabled);
+    assert!(!ledger.paused);
+    assert_eq!(ledger.batch_size, Some(5));
+    assert!(!find_status(&statuses, PrunerKind::StateMerkle).enabled);
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_pruners() {
+    let aptos_db = Arc::new(DbReaderWriter::new(MockPrunerDb::new()));
+
+    // Without the pruner parameter all enabled pruners are paused.
+    let response =
+        handle_pause_pruner_request(post("/debug/storage/pruners/pause"), aptos_db.clone(), true)
+            .await
+            .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let statuses = get_statuses(&aptos_db).await;
+    assert!(find_status(&statuses, PrunerKind::Ledger).paused);
+    assert!(find_status(&statuses, PrunerKind::StateKv).paused);
+    assert!(!find_status(&statuses, PrunerKind::StateMerkle).paused);
+
+    let response = handle_pause_pruner_request(
+        post("/debug/storage/pruners/resume?pruner=state_kv"),
+        aptos_db.clone(),
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let statuses = get_statuses(&aptos_db).await;
+    assert!(find_status(&statuses, PrunerKind::Ledger).paused);
+    assert!(!find_status(&statuses, PrunerKind::StateKv).paused);
+
+    for uri in [
+        "/debug/storage/pruners/pause?pruner=unknown",
+        "/debug/storage/pruners/pause?pruner=state_merkle",
+    ] {
+        let response = handle_pause_pruner_request(post(uri), aptos_db.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_prune_to_version() {
+    let aptos_db = Arc::new(DbReaderWriter::new(MockPrunerDb::new()));
+
+    let response = handle_prune_request(
+        post("/debug/storage/pruners/prune?pruner=ledger&target_version=50&batch_size=20"),
+        aptos_db.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let statuses = get_statuses(&aptos_db).await;
+    let ledger = find_status(&statuses, PrunerKind::Ledger);
+    assert_eq!(ledger.target_version, 50);
+    assert_eq!(ledger.min_readable_version, 50);
+    assert_eq!(ledger.batch_size, Some(20));
+    assert_eq!(
+        find_status(&statuses, PrunerKind::StateKv).target_version,
+        0
+    );
+
+    for uri in [
+        "/debug/storage/pruners/prune?target_version=50",
+        "/debug/storage/pruners/prune?pruner=ledger",
+        "/debug/storage/pruners/prune?pruner=ledger&target_version=abc",
+        "/debug/storage/pruners/prune?pruner=ledger&target_version=50&batch_size=-1",
+        "/debug/storage/pruners/prune?pruner=ledger&target_version=101",
+        "/debug/storage/pruners/prune?pruner=state_merkle&target_version=50",
+    ] {
+        let response = handle_prune_request(post(uri), aptos_db.clone())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#[macro_use]
+extern crate criterion;
+
+use aptos_compression::{
+    client::CompressionClient,
+    codec::{train_dictionary, CompressionCodec, DEFAULT_ZSTD_COMPRESSION_LEVEL},
+    CompressedData,
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{
+        RawTransaction, Script, SignedTransaction, Transaction, TransactionListWithProof,
+        TransactionPayload,
+    },
+};
+use criterion::{BenchmarkId, Criterion, Throughput};
+
+/// The maximum compression size (matches the max network message size)
+const MAX_COMPRESSION_SIZE: usize = 64 * 1024 * 1024; // 64 MiB
+
+/// The maximum size of the trained dictionary
+const MAX_DICTIONARY_SIZE: usize = 64 * 1024; // 64 KiB
+
+/// The number of transactions used to train the dictionary
+const NUM_TRAINING_TRANSACTIONS: u64 = 5_000;
+
+/// Runs all the benchmarks
+fn bench_group(c: &mut Criterion) {
+    // Create the codecs to compare against lz4
+    let dictionary = create_transaction_dictionary();
+    let codecs = [
+        CompressionCodec::Lz4,
+        CompressionCodec::Zstd(1),
+        CompressionCodec::Zstd(DEFAULT_ZSTD_COMPRESSION_LEVEL),
+        CompressionCodec::Zstd(9),
+        CompressionCodec::ZstdWithDictionary(DEFAULT_ZSTD_COMPRESSION_LEVEL, dictionary),
+    ];
+
+    // Create the payloads (i.e., a single transaction and a state sync chunk)
+    let payloads = [
+        (
+            "transaction",
+            bcs::to_bytes(&create_transaction(NUM_TRAINING_TRANSACTIONS)).unwrap(),
+        ),
+        (
+            "transaction_chunk",
+            bcs::to_bytes(&create_transaction_list_with_proof(0, 3000)).unwrap(),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("compression");
+    for (payload_name, payload) in &payloads {
+        group.throughput(Throughput::Bytes(payload.len() as u64));
+        for codec in &codecs {
+            let codec_name = get_codec_name(codec);
+            let compressed_payload = compress(payload.clone(), *codec);
+            println!(
+                "{}/{}: {} bytes -> {} bytes (ratio: {:.2})",
+                payload_name,
+                codec_name,
+                payload.len(),
+                compressed_payload.len(),
+                payload.len() as f64 / compressed_payload.len() as f64
+            );
+
+            group.bench_function(
+                BenchmarkId::new(format!("compress/{}", codec_name), payload_name),
+                |b| b.iter(|| compress(payload.clone(), *codec)),
+            );
+            group.bench_function(
+                BenchmarkId::new(format!("decompress/{}", codec_name), payload_name),
+                |b| b.iter(|| decompress(&compressed_payload, *codec)),
+            );
+        }
+    }
+    group.finish();
+}
+
+/// Compresses the payload using the given codec
+fn compress(payload: Vec<u8>, codec: CompressionCodec) -> CompressedData {
+    aptos_compression::compress_with_codec(
+        payload,
+        codec,
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap()
+}
+
+/// Decompresses the payload using the given codec
+fn decompress(compressed_payload: &CompressedData, codec: CompressionCodec) -> Vec<u8> {
+    aptos_compression::decompress_with_codec(
+        compressed_payload,
+        codec,
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap()
+}
+
+/// Returns a unique name for the codec (including the compression level)
+fn get_codec_name(codec: &CompressionCodec) -> String {
+    match codec {
+        CompressionCodec::Lz4 => codec.get_label().to_string(),
+        CompressionCodec::Zstd(level) | CompressionCodec::ZstdWithDictionary(level, _) => {
+            format!("{}_{}", codec.get_label(), level)
+        },
+    }
+}
+
+/// Trains a dictionary on BCS encoded transactions. The dictionary
+/// is leaked, as codecs require a static dictionary.
+fn create_transaction_dictionary() -> &'static [u8] {
+    let samples: Vec<_> = (0..NUM_TRAINING_TRANSACTIONS)
+        .map(|sequence_number| bcs::to_bytes(&create_transaction(sequence_number)).unwrap())
+        .collect();
+    let dictionary = train_dictionary(&samples, MAX_DICTIONARY_SIZE).unwrap();
+    Box::leak(dictionary.into_boxed_slice())
+}
+
+/// Creates a test transaction list with proof
+fn create_transaction_list_with_proof(
+    start_version: u64,
+    end_version: u64,
+) -> TransactionListWithProof {
+    let mut transaction_list_with_proof = TransactionListWithProof::new_empty();
+    transaction_list_with_proof.first_transaction_version = Some(start_version);
+    transaction_list_with_proof.transactions = (start_version..=end_version)
+        .map(create_transaction)
+        .collect();
+    transaction_list_with_proof
+}
+
+/// Creates a test user transaction
+fn create_transaction(sequence_number: u64) -> Transaction {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let public_key = private_key.public_key();
+
+    let transaction_payload = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+    let raw_transaction = RawTransaction::new(
+        AccountAddress::random(),
+        sequence_number,
+        transaction_payload,
+        0,
+        0,
+        0,
+        ChainId::new(10),
+    );
+    let signed_transaction = SignedTransaction::new(
+        raw_transaction.clone(),
+        public_key,
+        private_key.sign(&raw_transaction).unwrap(),
+    );
+
+    Transaction::UserTransaction(signed_transaction)
+}
+
+criterion_group!(
+    name = compression_benches;
+    config = Criterion::default();
+    targets = bench_group);
+criterion_main!(compression_benches);
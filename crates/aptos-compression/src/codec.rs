@@ -0,0 +1,134 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{CompressedData, Error};
+use lz4::block::CompressionMode;
+
+/// The acceleration parameter to use for FAST compression mode.
+/// This was determined anecdotally.
+const ACCELERATION_PARAMETER: i32 = 1;
+
+/// The default zstd compression level. This is the level used by the zstd
+/// command line tool, and offers a good balance between speed and ratio.
+pub const DEFAULT_ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+/// The number of bytes used to prefix the compressed data with the raw data size
+const SIZE_PREFIX_LENGTH: usize = 4;
+
+/// The codec used to compress and decompress data. Both ends of a
+/// connection must agree on the codec (e.g., by negotiating a protocol
+/// that implies it), as the compressed data does not identify its codec.
+///
+/// All codecs prefix the compressed data with the raw data size (as a
+/// little-endian i32), so the decompressed size can always be checked
+/// before allocating the output buffer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompressionCodec {
+    /// LZ4 in fast mode. See <https://github.com/10xGenomics/lz4-rs>.
+    Lz4,
+    /// Zstd at the given compression level. See <https://github.com/gyscos/zstd-rs>.
+    Zstd(i32),
+    /// Zstd at the given compression level, using the given (pre-trained)
+    /// dictionary. The dictionary must be identical on both ends.
+    ZstdWithDictionary(i32, &'static [u8]),
+}
+
+impl CompressionCodec {
+    /// Returns a summary label for the codec
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::Lz4 => "lz4",
+            Self::Zstd(_) => "zstd",
+            Self::ZstdWithDictionary(_, _) => "zstd_with_dictionary",
+        }
+    }
+
+    /// Compresses the raw data using the codec
+    pub(crate) fn compress(&self, raw_data: &[u8]) -> Result<CompressedData, String> {
+        match self {
+            Self::Lz4 => {
+                let compression_mode = CompressionMode::FAST(ACCELERATION_PARAMETER);
+                lz4::block::compress(raw_data, Some(compression_mode), true)
+                    .map_err(|error| error.to_string())
+            },
+            Self::Zstd(level) => {
+                let compressed_data =
+                    zstd::bulk::compress(raw_data, *level).map_err(|error| error.to_string())?;
+                prefix_with_size(raw_data.len(), compressed_data)
+            },
+            Self::ZstdWithDictionary(level, dictionary) => {
+                let compressed_data = zstd::bulk::Compressor::with_dictionary(*level, dictionary)
+                    .and_then(|mut compressor| compressor.compress(raw_data))
+                    .map_err(|error| error.to_string())?;
+                prefix_with_size(raw_data.len(), compressed_data)
+            },
+        }
+    }
+
+    /// Decompresses the compressed data using the codec. The decompressed
+    /// size must have already been parsed (and verified) from the prefix.
+    pub(crate) fn decompress(
+        &self,
+        compressed_data: &CompressedData,
+        decompressed_size: usize,
+    ) -> Result<Vec<u8>, String> {
+        let raw_data = match self {
+            Self::Lz4 => {
+                let mut raw_data = vec![0u8; decompressed_size];
+                let num_bytes =
+                    lz4::block::decompress_to_buffer(compressed_data, None, &mut raw_data)
+                        .map_err(|error| error.to_string())?;
+                raw_data.truncate(num_bytes);
+                raw_data
+            },
+            Self::Zstd(_) => {
+                zstd::bulk::decompress(&compressed_data[SIZE_PREFIX_LENGTH..], decompressed_size)
+                    .map_err(|error| error.to_string())?
+            },
+            Self::ZstdWithDictionary(_, dictionary) => {
+                zstd::bulk::Decompressor::with_dictionary(dictionary)
+                    .and_then(|mut decompressor| {
+                        decompressor
+                            .decompress(&compressed_data[SIZE_PREFIX_LENGTH..], decompressed_size)
+                    })
+                    .map_err(|error| error.to_string())?
+            },
+        };
+
+        // Ensure the size prefix matches the decompressed data
+        if raw_data.len() != decompressed_size {
+            return Err(format!(
+                "Decompressed size does not match the size prefix: {}, expected: {}",
+                raw_data.len(),
+                decompressed_size
+            ));
+        }
+
+        Ok(raw_data)
+    }
+}
+
+/// Prefixes the compressed data with the raw data size (as a little-endian
+/// i32). This matches the prefix that lz4 prepends to the compressed data.
+fn prefix_with_size(
+    raw_data_size: usize,
+    compressed_data: Vec<u8>,
+) -> Result<CompressedData, String> {
+    let raw_data_size = i32::try_from(raw_data_size).map_err(|error| error.to_string())?;
+    let mut prefixed_data = Vec::with_capacity(SIZE_PREFIX_LENGTH + compressed_data.len());
+    prefixed_data.extend_from_slice(&raw_data_size.to_le_bytes());
+    prefixed_data.extend_from_slice(&compressed_data);
+    Ok(prefixed_data)
+}
+
+/// Trains a zstd dictionary (of at most `max_dictionary_size` bytes) on the
+/// given data samples. Samples should be representative of the data that
+/// will later be compressed (e.g., BCS serialized transactions).
+pub fn train_dictionary<S: AsRef<[u8]>>(
+    samples: &[S],
+    max_dictionary_size: usize,
+) -> Result<Vec<u8>, Error> {
+    zstd::dict::from_samples(samples, max_dictionary_size).map_err(|error| {
+        Error::CompressionError(format!("Failed to train the dictionary: {}", error))
+    })
+}
@@ -3,31 +3,30 @@
 
 use crate::{
     client::CompressionClient,
+    codec::CompressionCodec,
     Error::{CompressionError, DecompressionError},
 };
 use aptos_logger::prelude::*;
-use lz4::block::CompressionMode;
 use std::time::Instant;
 use thiserror::Error;
 
 /// This crate provides a simple library interface for data compression.
 /// It is useful for compressing large data chunks that are
 /// sent across the network (e.g., by state sync and consensus).
-/// Internally, it uses LZ4 in fast mode to compress the data.
+/// By default, it uses LZ4 in fast mode to compress the data.
 /// See <https://github.com/10xGenomics/lz4-rs> for more information.
+/// Zstd (optionally with a pre-trained dictionary) is also supported
+/// via the `*_with_codec` variants (see [`CompressionCodec`]).
 ///
 /// Note: the crate also exposes some basic compression metrics
 /// that can be used to track the cumulative compression ratio
 /// and compression/decompression durations during the runtime.
 pub mod client;
+pub mod codec;
 mod metrics;
 #[cfg(test)]
 mod tests;
 
-/// The acceleration parameter to use for FAST compression mode.
-/// This was determined anecdotally.
-const ACCELERATION_PARAMETER: i32 = 1;
-
 /// A useful wrapper for representing compressed data
 pub type CompressedData = Vec<u8>;
 
@@ -40,11 +39,21 @@ pub enum Error {
     DecompressionError(String),
 }
 
-/// Compresses the raw data stream
+/// Compresses the raw data stream (using LZ4)
 pub fn compress(
     raw_data: Vec<u8>,
     client: CompressionClient,
     max_bytes: usize,
+) -> Result<CompressedData, Error> {
+    compress_with_codec(raw_data, CompressionCodec::Lz4, client, max_bytes)
+}
+
+/// Compresses the raw data stream using the given codec
+pub fn compress_with_codec(
+    raw_data: Vec<u8>,
+    codec: CompressionCodec,
+    client: CompressionClient,
+    max_bytes: usize,
 ) -> Result<CompressedData, Error> {
     // Start the compression timer
     let start_time = Instant::now();
@@ -60,8 +69,7 @@ pub fn compress(
     }
 
     // Compress the data
-    let compression_mode = CompressionMode::FAST(ACCELERATION_PARAMETER);
-    let compressed_data = match lz4::block::compress(&raw_data, Some(compression_mode), true) {
+    let compressed_data = match codec.compress(&raw_data) {
         Ok(compressed_data) => compressed_data,
         Err(error) => {
             let error_string = format!("Failed to compress the data: {}", error);
@@ -88,11 +96,22 @@ pub fn compress(
     Ok(compressed_data)
 }
 
-/// Decompresses the compressed data stream
+/// Decompresses the compressed data stream (using LZ4)
 pub fn decompress(
     compressed_data: &CompressedData,
     client: CompressionClient,
     max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    decompress_with_codec(compressed_data, CompressionCodec::Lz4, client, max_size)
+}
+
+/// Decompresses the compressed data stream using the given codec. The
+/// codec must match the one used to compress the data.
+pub fn decompress_with_codec(
+    compressed_data: &CompressedData,
+    codec: CompressionCodec,
+    client: CompressionClient,
+    max_size: usize,
 ) -> Result<Vec<u8>, Error> {
     // Start the decompression timer
     let start_time = Instant::now();
@@ -105,12 +124,14 @@ pub fn decompress(
             return create_decompression_error(&client, error_string);
         },
     };
-    let mut raw_data = vec![0u8; decompressed_size];
 
     // Decompress the data
-    if let Err(error) = lz4::block::decompress_to_buffer(compressed_data, None, &mut raw_data) {
-        let error_string = format!("Failed to decompress the data: {}", error);
-        return create_decompression_error(&client, error_string);
+    let raw_data = match codec.decompress(compressed_data, decompressed_size) {
+        Ok(raw_data) => raw_data,
+        Err(error) => {
+            let error_string = format!("Failed to decompress the data: {}", error);
+            return create_decompression_error(&client, error_string);
+        },
     };
 
     // Stop the timer and update the metrics
@@ -145,7 +166,7 @@ fn create_decompression_error(
 }
 
 /// Derived from the lz4-rs crate, which prepends the compressed payload
-/// with the original data size as i32.
+/// with the original data size as i32. All other codecs use the same prefix.
 /// See: https://github.com/10XGenomics/lz4-rs/blob/0abc0a52af1f6010f9a57640b1dc8eb8d2d697aa/src/block/mod.rs#L162
 fn get_decompressed_size(
     compressed_data: &CompressedData,
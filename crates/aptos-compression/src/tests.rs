@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::CompressionClient;
+use crate::{
+    codec::{train_dictionary, CompressionCodec, DEFAULT_ZSTD_COMPRESSION_LEVEL},
+    CompressionClient,
+};
 use aptos_crypto::{ed25519::Ed25519PrivateKey, hash::HashValue, PrivateKey, SigningKey, Uniform};
 use aptos_types::{
     account_address::AccountAddress,
@@ -23,6 +26,7 @@ use std::fmt::Debug;
 // Useful test constants
 const MAX_COMPRESSION_SIZE: usize = 64 * 1024 * 1024; // 64 MiBi
 const MIB: usize = 1024 * 1024;
+const MAX_DICTIONARY_SIZE: usize = 16 * 1024; // 16 KiB
 
 #[test]
 fn test_basic_compression() {
@@ -43,6 +47,58 @@ fn test_basic_compression() {
     test_compress_and_decompress(transactions_with_proof);
 }
 
+#[test]
+fn test_dictionary_compression() {
+    // Train a dictionary on BCS encoded transactions
+    let dictionary = create_transaction_dictionary();
+    assert!(!dictionary.is_empty());
+    assert!(dictionary.len() <= MAX_DICTIONARY_SIZE);
+
+    // Compress and decompress transactions with the dictionary
+    let codec = CompressionCodec::ZstdWithDictionary(DEFAULT_ZSTD_COMPRESSION_LEVEL, dictionary);
+    let transactions_with_proof = create_transaction_list_with_proof(1000, 1999, 1999, true);
+    test_compress_and_decompress_with_codec(&transactions_with_proof, codec);
+
+    // Verify that decompression fails without the dictionary
+    let transaction_bytes = bcs::to_bytes(&create_test_transaction(0)).unwrap();
+    let dictionary_compressed_bytes = crate::compress_with_codec(
+        transaction_bytes,
+        codec,
+        CompressionClient::Mempool,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap();
+    let maybe_decompressed_bytes = crate::decompress_with_codec(
+        &dictionary_compressed_bytes,
+        CompressionCodec::Zstd(DEFAULT_ZSTD_COMPRESSION_LEVEL),
+        CompressionClient::Mempool,
+        MAX_COMPRESSION_SIZE,
+    );
+    assert!(maybe_decompressed_bytes.is_err());
+}
+
+#[test]
+fn test_mismatched_codecs() {
+    // Compress the data using lz4
+    let transactions_with_proof = create_transaction_list_with_proof(1000, 1999, 1999, true);
+    let bcs_encoded_bytes = bcs::to_bytes(&transactions_with_proof).unwrap();
+    let compressed_bytes = crate::compress(
+        bcs_encoded_bytes,
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap();
+
+    // Verify that decompression fails using zstd
+    let maybe_decompressed_bytes = crate::decompress_with_codec(
+        &compressed_bytes,
+        CompressionCodec::Zstd(DEFAULT_ZSTD_COMPRESSION_LEVEL),
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    );
+    assert!(maybe_decompressed_bytes.is_err());
+}
+
 #[test]
 fn test_compression_limits() {
     // Create test data
@@ -72,27 +128,78 @@ fn test_compression_limits() {
         too_small_bytes,
     );
     assert!(maybe_decompressed_bytes.is_err());
+
+    // Test the limits for zstd
+    let codec = CompressionCodec::Zstd(DEFAULT_ZSTD_COMPRESSION_LEVEL);
+    let bcs_encoded_bytes = bcs::to_bytes(&transactions_with_proof).unwrap();
+    let maybe_compressed_bytes = crate::compress_with_codec(
+        bcs_encoded_bytes.clone(),
+        codec,
+        CompressionClient::StateSync,
+        too_small_bytes,
+    );
+    assert!(maybe_compressed_bytes.is_err());
+    let compressed_bytes = crate::compress_with_codec(
+        bcs_encoded_bytes,
+        codec,
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap();
+    let maybe_decompressed_bytes = crate::decompress_with_codec(
+        &compressed_bytes,
+        codec,
+        CompressionClient::StateSync,
+        too_small_bytes,
+    );
+    assert!(maybe_decompressed_bytes.is_err());
 }
 
 /// Ensures that the given object can be compressed and decompressed successfully
-/// when BCS encoded.
+/// when BCS encoded (using lz4 and zstd).
 fn test_compress_and_decompress<T: Debug + DeserializeOwned + PartialEq + Serialize>(object: T) {
-    let bcs_encoded_bytes = bcs::to_bytes(&object).unwrap();
-    let compressed_bytes = crate::compress(
+    for codec in [
+        CompressionCodec::Lz4,
+        CompressionCodec::Zstd(DEFAULT_ZSTD_COMPRESSION_LEVEL),
+    ] {
+        test_compress_and_decompress_with_codec(&object, codec);
+    }
+}
+
+/// Ensures that the given object can be compressed and decompressed successfully
+/// when BCS encoded, using the specified codec.
+fn test_compress_and_decompress_with_codec<T: Debug + DeserializeOwned + PartialEq + Serialize>(
+    object: &T,
+    codec: CompressionCodec,
+) {
+    let bcs_encoded_bytes = bcs::to_bytes(object).unwrap();
+    let compressed_bytes = crate::compress_with_codec(
         bcs_encoded_bytes,
+        codec,
         CompressionClient::StateSync,
         MAX_COMPRESSION_SIZE,
     )
     .unwrap();
-    let decompressed_bytes = crate::decompress(
+    let decompressed_bytes = crate::decompress_with_codec(
         &compressed_bytes,
+        codec,
         CompressionClient::StateSync,
         MAX_COMPRESSION_SIZE,
     )
     .unwrap();
     let decoded_object = bcs::from_bytes::<T>(&decompressed_bytes).unwrap();
 
-    assert_eq!(object, decoded_object);
+    assert_eq!(object, &decoded_object);
+}
+
+/// Trains a dictionary on BCS encoded transactions. The dictionary
+/// is leaked, as codecs require a static dictionary.
+fn create_transaction_dictionary() -> &'static [u8] {
+    let samples: Vec<_> = (0..1000)
+        .map(|sequence_number| bcs::to_bytes(&create_test_transaction(sequence_number)).unwrap())
+        .collect();
+    let dictionary = train_dictionary(&samples, MAX_DICTIONARY_SIZE).unwrap();
+    Box::leak(dictionary.into_boxed_slice())
 }
 
 /// Creates a test epoch change proof
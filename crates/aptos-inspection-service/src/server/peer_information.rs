@@ -87,6 +87,10 @@ fn get_peer_information(
     display_state_sync_metadata(&mut peer_information_output, &all_peers, aptos_data_client);
     peer_information_output.push("\n".into());
 
+    // Display the reputation of each peer (including disconnected and banned peers)
+    display_peer_reputations(&mut peer_information_output, peers_and_metadata.deref());
+    peer_information_output.push("\n".into());
+
     // Display detailed peer metadata for each peer
     display_detailed_monitoring_metadata(
         &mut peer_information_output,
@@ -202,6 +206,32 @@ fn display_peer_monitoring_metadata(
     }
 }
 
+/// Displays the reputation of each peer
+fn display_peer_reputations(
+    peer_information_output: &mut Vec<String>,
+    peers_and_metadata: &PeersAndMetadata,
+) {
+    peer_information_output.push("Reputation for each peer:".into());
+
+    // Fetch and sort the peer reputations
+    let peer_reputations = peers_and_metadata.get_peer_reputations();
+    let sorted_reputations: BTreeMap<_, _> =
+        peer_reputations.get_all_reputations().into_iter().collect();
+
+    // Display the reputation of each peer
+    let now_secs = peer_reputations.now_secs();
+    for (peer, reputation) in sorted_reputations {
+        peer_information_output.push(format!(
+            "\t- Peer: {}, score: {}, banned: {}, number of bans: {}, banned until (unix secs): {:?}",
+            peer,
+            reputation.get_score(now_secs),
+            reputation.is_banned(now_secs),
+            reputation.get_num_bans(),
+            reputation.get_banned_until_secs()
+        ));
+    }
+}
+
 /// Displays state sync metadata for each peer
 fn display_state_sync_metadata(
     peer_information_output: &mut Vec<String>,
@@ -178,6 +178,7 @@ async fn test_inspect_peer_information() {
     assert!(response_body_string.contains("Registered networks"));
     assert!(response_body_string.contains("Peers and network IDs"));
     assert!(response_body_string.contains("State sync metadata"));
+    assert!(response_body_string.contains("Reputation for each peer"));
 }
 
 rusty_fork_test! {
@@ -300,6 +300,9 @@ impl From<RestError> for ApiError {
                     ApiError::SequenceNumberTooOld(Some(err.error.message))
                 },
                 AptosErrorCode::VmError => ApiError::VmError(Some(err.error.message)),
+                AptosErrorCode::TransactionRejectedByFilter => {
+                    ApiError::InvalidInput(Some(err.error.message))
+                },
                 AptosErrorCode::HealthCheckFailed => {
                     ApiError::InternalError(Some(err.error.message))
                 },
@@ -146,3 +146,28 @@ pub fn profile_transaction_using_debugger(
 
     Ok((vm_status, vm_output))
 }
+
+pub fn trace_transaction_using_debugger(
+    debugger: &AptosDebugger,
+    version: u64,
+    transaction: SignedTransaction,
+    hash: HashValue,
+) -> CliTypedResult<(VMStatus, VMOutput)> {
+    let (vm_status, vm_output, call_trace) = debugger
+        .execute_transaction_at_version_with_call_trace(version, transaction)
+        .map_err(|err| {
+            CliError::UnexpectedError(format!("failed to trace txn with gas profiler: {}", err))
+        })?;
+
+    let trace = serde_json::to_string_pretty(&call_trace)
+        .map_err(|err| CliError::UnexpectedError(format!("failed to serialize trace: {}", err)))?;
+
+    let dir = Path::new("call-traces");
+    std::fs::create_dir_all(dir).map_err(|err| CliError::IO(dir.display().to_string(), err))?;
+    let path = dir.join(format!("txn-{}.json", hash));
+    std::fs::write(&path, trace).map_err(|err| CliError::IO(path.display().to_string(), err))?;
+
+    println!("Call trace saved to {}.", path.display());
+
+    Ok((vm_status, vm_output))
+}
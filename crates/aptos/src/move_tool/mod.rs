@@ -2174,6 +2174,11 @@ pub struct Replay {
     #[clap(long)]
     pub(crate) profile_gas: bool,
 
+    /// If this option is set, save a JSON call trace of the transaction, including the arguments,
+    /// return values, gas usage and emitted events of every function call.
+    #[clap(long)]
+    pub(crate) trace: bool,
+
     /// If present, skip the comparison against the expected transaction output.
     #[clap(long)]
     pub(crate) skip_comparison: bool,
@@ -2206,9 +2211,15 @@ impl CliCommand<TransactionSummary> for Replay {
     async fn execute(self) -> CliTypedResult<TransactionSummary> {
         use ReplayNetworkSelection::*;
 
-        if self.profile_gas && self.benchmark {
+        if [self.profile_gas, self.benchmark, self.trace]
+            .into_iter()
+            .filter(|enabled| *enabled)
+            .count()
+            > 1
+        {
             return Err(CliError::UnexpectedError(
-                "Cannot perform benchmarking and gas profiling at the same time.".to_string(),
+                "Only one of benchmarking, gas profiling and tracing can be performed at a time."
+                    .to_string(),
             ));
         }
 
@@ -2260,6 +2271,14 @@ impl CliCommand<TransactionSummary> for Replay {
                 txn.clone(),
                 hash,
             )?
+        } else if self.trace {
+            println!("Tracing transaction...");
+            local_simulation::trace_transaction_using_debugger(
+                &debugger,
+                self.txn_id,
+                txn.clone(),
+                hash,
+            )?
         } else if self.benchmark {
             println!("Benchmarking transaction...");
             local_simulation::benchmark_transaction_using_debugger(
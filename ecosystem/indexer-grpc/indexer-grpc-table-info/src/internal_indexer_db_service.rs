@@ -51,8 +51,17 @@ impl InternalIndexerDBService {
                 .expect("Failed to open internal indexer db"),
         );
 
-        let internal_indexer_db_config =
-            InternalIndexerDBConfig::new(true, true, true, 0, true, 10_000);
+        let internal_indexer_db_config = InternalIndexerDBConfig {
+            enable_transaction: true,
+            enable_event: true,
+            enable_event_v2_translation: true,
+            enable_statekeys: true,
+            enable_participant_transactions: true,
+            enable_fungible_asset_activities: true,
+            enable_state_value_history: true,
+            batch_size: 10_000,
+            ..Default::default()
+        };
         Some(InternalIndexerDB::new(arc_db, internal_indexer_db_config))
     }
 
@@ -127,6 +136,51 @@ impl InternalIndexerDBService {
             }
         }
 
+        if node_config
+            .indexer_db_config
+            .enable_participant_transactions()
+        {
+            let participant_start_version = self
+                .db_indexer
+                .indexer_db
+                .get_participant_transaction_version()?
+                .map_or(0, |v| v + 1);
+            if start_version != participant_start_version {
+                panic!(
+                    "Cannot start participant transaction indexer because the progress doesn't match."
+                );
+            }
+        }
+
+        if node_config
+            .indexer_db_config
+            .enable_fungible_asset_activities()
+        {
+            let fungible_asset_activity_start_version = self
+                .db_indexer
+                .indexer_db
+                .get_fungible_asset_activity_version()?
+                .map_or(0, |v| v + 1);
+            if start_version != fungible_asset_activity_start_version {
+                panic!(
+                    "Cannot start fungible asset activity indexer because the progress doesn't match."
+                );
+            }
+        }
+
+        if node_config.indexer_db_config.enable_state_value_history() {
+            let state_value_history_start_version = self
+                .db_indexer
+                .indexer_db
+                .get_state_value_history_version()?
+                .map_or(0, |v| v + 1);
+            if start_version != state_value_history_start_version {
+                panic!(
+                    "Cannot start state value history indexer because the progress doesn't match."
+                );
+            }
+        }
+
         if node_config.indexer_db_config.enable_event() {
             let event_start_version = self
                 .db_indexer
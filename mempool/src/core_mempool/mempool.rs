@@ -14,7 +14,8 @@ use crate::{
     logging::{LogEntry, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        MempoolEvictionCriteria, MempoolSenderBucket, MempoolTransactionSummary,
+        MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
     },
 };
 use aptos_config::config::NodeConfig;
@@ -601,4 +602,44 @@ impl Mempool {
     pub fn get_parking_lot_addresses(&self) -> Vec<(AccountAddress, u64)> {
         self.transactions.get_parking_lot_addresses()
     }
+
+    pub(crate) fn get_all_transactions(&self) -> Vec<(SignedTransaction, bool)> {
+        self.transactions.get_all_transactions()
+    }
+
+    pub fn get_transaction_summaries(
+        &self,
+        sender: Option<&AccountAddress>,
+    ) -> Vec<MempoolTransactionSummary> {
+        self.transactions.get_transaction_summaries(sender)
+    }
+
+    pub fn get_transaction_summary_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Option<MempoolTransactionSummary> {
+        self.transactions.get_transaction_summary_by_hash(hash)
+    }
+
+    /// Evicts all transactions matching the given criteria, and returns the
+    /// sender and sequence number of each evicted transaction.
+    pub fn evict_transactions(
+        &mut self,
+        criteria: &MempoolEvictionCriteria,
+    ) -> Vec<(AccountAddress, u64)> {
+        let evicted_txns = self.transactions.evict_transactions(criteria);
+
+        let mut txns_log = TxnsLog::new();
+        for (sender, sequence_number) in &evicted_txns {
+            txns_log.add(*sender, *sequence_number);
+        }
+        info!(
+            LogSchema::new(LogEntry::AdminEvictedTxns).txns(txns_log),
+            "Evicted {} transactions from mempool matching: {:?}",
+            evicted_txns.len(),
+            criteria
+        );
+
+        evicted_txns
+    }
 }
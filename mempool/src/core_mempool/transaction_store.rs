@@ -9,13 +9,14 @@ use crate::{
             PriorityQueueIter, TTLIndex,
         },
         mempool::Mempool,
-        transaction::{InsertionInfo, MempoolTransaction, TimelineState},
+        transaction::{InsertionInfo, MempoolTransaction, SubmittedBy, TimelineState},
     },
     counters::{self, BROADCAST_BATCHED_LABEL, BROADCAST_READY_LABEL, CONSENSUS_READY_LABEL},
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        MempoolEvictionCriteria, MempoolSenderBucket, MempoolTransactionSummary,
+        MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
     },
 };
 use aptos_config::config::MempoolConfig;
@@ -31,7 +32,7 @@ use std::{
     collections::HashMap,
     mem::size_of,
     ops::Bound,
-    time::{Duration, Instant, SystemTime},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 /// Estimated per-txn overhead of indexes. Needs to be updated if additional indexes are added.
@@ -920,4 +921,143 @@ impl TransactionStore {
     pub(crate) fn get_parking_lot_addresses(&self) -> Vec<(AccountAddress, u64)> {
         self.parking_lot_index.get_addresses()
     }
+
+    /// Returns all transactions (ordered by sender and sequence number), along with
+    /// whether or not each transaction was submitted by a client.
+    pub(crate) fn get_all_transactions(&self) -> Vec<(SignedTransaction, bool)> {
+        let mut transactions: Vec<_> = self
+            .transactions
+            .values()
+            .flat_map(|txns| txns.values())
+            .map(|txn| {
+                let client_submitted = txn.insertion_info.submitted_by == SubmittedBy::Client;
+                (txn.txn.clone(), client_submitted)
+            })
+            .collect();
+        transactions.sort_by_key(|(txn, _)| (txn.sender(), txn.sequence_number()));
+        transactions
+    }
+
+    /// Returns the summaries of all transactions (ordered by sender and sequence number),
+    /// optionally restricted to the transactions of the given sender.
+    pub(crate) fn get_transaction_summaries(
+        &self,
+        sender: Option<&AccountAddress>,
+    ) -> Vec<MempoolTransactionSummary> {
+        let mut summaries: Vec<_> = self
+            .transactions
+            .iter()
+            .filter(|(account, _)| sender.map_or(true, |sender| sender == *account))
+            .flat_map(|(_, txns)| txns.values())
+            .map(|txn| self.get_transaction_summary(txn))
+            .collect();
+        summaries.sort_by_key(|summary| (summary.sender, summary.sequence_number));
+        summaries
+    }
+
+    /// Returns the summary of the transaction with the given committed hash (if it exists)
+    pub(crate) fn get_transaction_summary_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Option<MempoolTransactionSummary> {
+        self.hash_index
+            .get(&hash)
+            .and_then(|(address, sequence_number)| self.get_mempool_txn(address, *sequence_number))
+            .map(|txn| self.get_transaction_summary(txn))
+    }
+
+    fn get_transaction_summary(&self, txn: &MempoolTransaction) -> MempoolTransactionSummary {
+        let sender = txn.get_sender();
+        let sequence_number = txn.sequence_info.transaction_sequence_number;
+        let hash = txn.get_committed_hash();
+        let insertion_time_usecs = txn
+            .insertion_info
+            .insertion_time
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_micros() as u64);
+        MempoolTransactionSummary {
+            sender,
+            sequence_number,
+            hash,
+            gas_unit_price: txn.get_gas_price(),
+            ranking_score: txn.ranking_score,
+            insertion_time_usecs,
+            timeline_state: txn.timeline_state,
+            parked: self
+                .parking_lot_index
+                .contains(&sender, sequence_number, hash),
+            submitted_by: txn.insertion_info.submitted_by_label().to_string(),
+        }
+    }
+
+    /// Evicts all transactions matching the given criteria, and returns the sender and
+    /// sequence number of each evicted transaction. Any remaining transactions that follow
+    /// an evicted transaction (for the same sender) are parked, as they can no longer be
+    /// executed in sequence.
+    pub(crate) fn evict_transactions(
+        &mut self,
+        criteria: &MempoolEvictionCriteria,
+    ) -> Vec<(AccountAddress, u64)> {
+        let addresses: Vec<AccountAddress> = match &criteria.sender {
+            Some(sender) => vec![*sender],
+            None => self.transactions.keys().copied().collect(),
+        };
+
+        let mut evicted_txns = vec![];
+        for address in addresses {
+            if let Some(txns) = self.transactions.get_mut(&address) {
+                let evicted_sequence_numbers: Vec<u64> = txns
+                    .iter()
+                    .filter(|(_, txn)| criteria.matches(&txn.txn))
+                    .map(|(sequence_number, _)| *sequence_number)
+                    .collect();
+                let first_evicted_sequence_number = match evicted_sequence_numbers.first() {
+                    Some(sequence_number) => *sequence_number,
+                    None => continue,
+                };
+
+                // Park all remaining transactions after the first evicted transaction
+                let park_range = (
+                    Bound::Excluded(first_evicted_sequence_number),
+                    Bound::Unbounded,
+                );
+                for (sequence_number, t) in txns.range_mut(park_range) {
+                    if evicted_sequence_numbers.contains(sequence_number) {
+                        continue;
+                    }
+                    self.parking_lot_index.insert(t);
+                    self.priority_index.remove(t);
+                    let sender_bucket = sender_bucket(&t.get_sender(), self.num_sender_buckets);
+                    self.timeline_index
+                        .get_mut(&sender_bucket)
+                        .unwrap_or_else(|| {
+                            panic!(
+                                "Unable to get the timeline index for the sender bucket {}",
+                                sender_bucket
+                            )
+                        })
+                        .remove(t);
+                    if let TimelineState::Ready(_) = t.timeline_state {
+                        t.timeline_state = TimelineState::NotReady;
+                    }
+                }
+
+                // Remove the evicted transactions
+                let removed_txns: Vec<MempoolTransaction> = evicted_sequence_numbers
+                    .iter()
+                    .filter_map(|sequence_number| txns.remove(sequence_number))
+                    .collect();
+                for txn in removed_txns {
+                    evicted_txns.push((
+                        txn.get_sender(),
+                        txn.sequence_info.transaction_sequence_number,
+                    ));
+                    self.index_remove(&txn);
+                }
+            }
+        }
+
+        self.track_indices();
+        evicted_txns
+    }
 }
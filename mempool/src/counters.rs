@@ -696,6 +696,26 @@ pub fn active_upstream_peers(network_id: &NetworkId) -> IntGauge {
     ACTIVE_UPSTREAM_PEERS_COUNT.with_label_values(&[network_id.as_str()])
 }
 
+// Mempool persistence operation labels
+pub const PERSISTENCE_WRITE_LABEL: &str = "write";
+pub const PERSISTENCE_RESTORE_LABEL: &str = "restore";
+
+/// Counter for the number of transactions written to (and restored from) the mempool snapshot
+static MEMPOOL_PERSISTENCE_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_mempool_persistence_txns_count",
+        "Number of transactions written to or restored from the mempool snapshot",
+        &["operation", "result"]
+    )
+    .unwrap()
+});
+
+pub fn mempool_persistence_txns_inc_by(operation: &str, result: &str, count: u64) {
+    MEMPOOL_PERSISTENCE_TXNS
+        .with_label_values(&[operation, result])
+        .inc_by(count);
+}
+
 /// Duration of each run of the event loop.
 pub static MAIN_LOOP: Lazy<DurationHistogram> = Lazy::new(|| {
     DurationHistogram::new(
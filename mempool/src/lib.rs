@@ -58,12 +58,13 @@
 
 #[cfg(any(test, feature = "fuzzing"))]
 mod tests;
+pub use core_mempool::TimelineState;
 pub use shared_mempool::{
     bootstrap, network,
     network::MempoolSyncMsg,
     types::{
-        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, QuorumStoreRequest,
-        QuorumStoreResponse, SubmissionStatus,
+        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, MempoolEvictionCriteria,
+        MempoolTransactionSummary, QuorumStoreRequest, QuorumStoreResponse, SubmissionStatus,
     },
 };
 #[cfg(any(test, feature = "fuzzing"))]
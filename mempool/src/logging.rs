@@ -166,6 +166,7 @@ pub enum LogEntry {
     AddTxn,
     RemoveTxn,
     MempoolFullEvictedTxn,
+    AdminEvictedTxns,
     GCRemoveTxns,
     CleanCommittedTxn,
     CleanRejectedTxn,
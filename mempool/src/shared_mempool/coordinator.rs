@@ -28,6 +28,7 @@ use aptos_mempool_notifications::{MempoolCommitNotification, MempoolNotification
 use aptos_network::{
     application::{
         interface::{NetworkClientInterface, NetworkServiceEvents},
+        reputation::ReputationEvent,
         storage::PeersAndMetadata,
     },
     protocols::network::Event,
@@ -222,6 +223,33 @@ async fn handle_client_request<NetworkClient, TransactionValidator>(
                 .spawn(tasks::process_parking_lot_addresses(smp.clone(), callback))
                 .await;
         },
+        MempoolClientRequest::GetTransactionSummaries(sender, callback) => {
+            bounded_executor
+                .spawn(tasks::process_transaction_summaries(
+                    smp.clone(),
+                    sender,
+                    callback,
+                ))
+                .await;
+        },
+        MempoolClientRequest::GetTransactionSummaryByHash(hash, callback) => {
+            bounded_executor
+                .spawn(tasks::process_transaction_summary_by_hash(
+                    smp.clone(),
+                    hash,
+                    callback,
+                ))
+                .await;
+        },
+        MempoolClientRequest::EvictTransactions(criteria, callback) => {
+            bounded_executor
+                .spawn(tasks::process_evict_transactions(
+                    smp.clone(),
+                    criteria,
+                    callback,
+                ))
+                .await;
+        },
     }
 }
 
@@ -407,6 +435,12 @@ async fn handle_network_event<NetworkClient, TransactionValidator>(
         },
         Event::RpcRequest(peer_id, _msg, _, _res_tx) => {
             counters::unexpected_msg_count_inc(&network_id);
+
+            // Mempool only expects direct send messages, so treat the request as malformed
+            smp.network_interface.update_peer_reputation(
+                PeerNetworkId::new(network_id, peer_id),
+                ReputationEvent::InvalidMessage,
+            );
             sample!(
                 SampleRate::Duration(Duration::from_secs(60)),
                 warn!(LogSchema::new(LogEntry::UnexpectedNetworkMsg)
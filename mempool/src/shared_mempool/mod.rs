@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod network;
+pub(crate) mod persistence;
 mod priority;
 mod runtime;
 pub(crate) mod types;
@@ -24,7 +24,10 @@ use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::{
-    application::{error::Error, interface::NetworkClientInterface, metadata::PeerMetadata},
+    application::{
+        error::Error, interface::NetworkClientInterface, metadata::PeerMetadata,
+        reputation::ReputationEvent,
+    },
     transport::ConnectionMetadata,
 };
 use aptos_time_service::TimeService;
@@ -596,6 +599,14 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         self.network_client.send_to_peer(message, peer)
     }
 
+    /// Updates the node-wide reputation of the given peer
+    pub fn update_peer_reputation(&self, peer: PeerNetworkId, reputation_event: ReputationEvent) {
+        self.network_client
+            .get_peers_and_metadata()
+            .get_peer_reputations()
+            .update_reputation(peer, reputation_event);
+    }
+
     /// Updates the local tracker for a broadcast.  This is used to handle `DirectSend` tracking of
     /// responses
     fn update_broadcast_state(
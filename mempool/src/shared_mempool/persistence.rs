@@ -0,0 +1,240 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Persistence of pending mempool transactions across node restarts.
+//!
+//! Pending transactions are periodically written to a snapshot file on disk (and once
+//! more when the mempool runtime is shut down). On startup, the transactions in the
+//! snapshot are re-validated and reinserted into the mempool. Transactions that have
+//! since been committed or expired are rejected by the usual validation logic.
+
+use crate::{
+    core_mempool::{CoreMempool, TimelineState},
+    counters,
+    network::MempoolSyncMsg,
+    shared_mempool::{tasks, types::SharedMempool},
+};
+use anyhow::{Context, Result};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_network::application::interface::NetworkClientInterface;
+use aptos_types::{mempool_status::MempoolStatusCode, transaction::SignedTransaction};
+use aptos_vm_validator::vm_validator::TransactionValidation;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::time::interval;
+use tokio_stream::wrappers::IntervalStream;
+
+/// A pending transaction persisted in the mempool snapshot
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct PersistedTransaction {
+    pub transaction: SignedTransaction,
+    pub client_submitted: bool,
+}
+
+/// The mempool snapshot written to disk
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct MempoolSnapshot {
+    pub transactions: Vec<PersistedTransaction>,
+}
+
+/// Writes a snapshot of all transactions currently in mempool to the given path.
+/// The snapshot is first written to a temporary file, and then atomically
+/// moved into place. Returns the number of transactions written.
+pub(crate) fn write_snapshot(mempool: &Mutex<CoreMempool>, snapshot_path: &Path) -> Result<usize> {
+    let transactions: Vec<_> = mempool
+        .lock()
+        .get_all_transactions()
+        .into_iter()
+        .map(|(transaction, client_submitted)| PersistedTransaction {
+            transaction,
+            client_submitted,
+        })
+        .collect();
+    let num_transactions = transactions.len();
+
+    let bytes = bcs::to_bytes(&MempoolSnapshot { transactions })
+        .context("Failed to serialize the mempool snapshot")?;
+    if let Some(parent_dir) = snapshot_path.parent() {
+        fs::create_dir_all(parent_dir).with_context(|| {
+            format!("Failed to create the snapshot directory: {:?}", parent_dir)
+        })?;
+    }
+    let temp_path = snapshot_path.with_extension("tmp");
+    fs::write(&temp_path, bytes)
+        .with_context(|| format!("Failed to write the mempool snapshot: {:?}", temp_path))?;
+    fs::rename(&temp_path, snapshot_path)
+        .with_context(|| format!("Failed to move the mempool snapshot: {:?}", snapshot_path))?;
+
+    Ok(num_transactions)
+}
+
+/// Reads the mempool snapshot at the given path. If no snapshot exists, an
+/// empty snapshot is returned.
+pub(crate) fn read_snapshot(snapshot_path: &Path) -> Result<MempoolSnapshot> {
+    if !snapshot_path.exists() {
+        return Ok(MempoolSnapshot::default());
+    }
+
+    let bytes = fs::read(snapshot_path)
+        .with_context(|| format!("Failed to read the mempool snapshot: {:?}", snapshot_path))?;
+    bcs::from_bytes(&bytes).context("Failed to deserialize the mempool snapshot")
+}
+
+/// Restores the transactions in the mempool snapshot at the given path. All transactions
+/// are re-validated (in the same way as transactions received from peers) before they
+/// are reinserted into mempool. Returns the number of restored transactions.
+pub(crate) fn restore_snapshot<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    snapshot_path: &Path,
+) -> Result<usize>
+where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let snapshot = read_snapshot(snapshot_path)?;
+
+    // The peers that originally sent the transactions are unknown after a restart,
+    // so the transactions are only excluded from broadcast if this node never
+    // broadcasts (in the same way as for client submitted transactions).
+    let ineligible_for_broadcast =
+        smp.network_interface.is_validator() && !smp.broadcast_within_validator_network();
+    let timeline_state = if ineligible_for_broadcast {
+        TimelineState::NonQualified
+    } else {
+        TimelineState::NotReady
+    };
+
+    // Client submitted transactions are restored separately, to ensure they are
+    // still treated as client submissions (e.g., for latency tracking).
+    let (client_transactions, peer_transactions): (Vec<_>, Vec<_>) = snapshot
+        .transactions
+        .into_iter()
+        .partition(|persisted_transaction| persisted_transaction.client_submitted);
+
+    let mut num_restored = 0;
+    for (transactions, client_submitted) in
+        [(client_transactions, true), (peer_transactions, false)]
+    {
+        if transactions.is_empty() {
+            continue;
+        }
+
+        let transactions = transactions
+            .into_iter()
+            .map(|persisted_transaction| (persisted_transaction.transaction, None, None))
+            .collect();
+        let statuses = tasks::process_incoming_transactions(
+            smp,
+            transactions,
+            timeline_state,
+            client_submitted,
+        );
+        let num_accepted = statuses
+            .iter()
+            .filter(|(_, (mempool_status, _))| mempool_status.code == MempoolStatusCode::Accepted)
+            .count();
+        counters::mempool_persistence_txns_inc_by(
+            counters::PERSISTENCE_RESTORE_LABEL,
+            counters::REQUEST_SUCCESS_LABEL,
+            num_accepted as u64,
+        );
+        counters::mempool_persistence_txns_inc_by(
+            counters::PERSISTENCE_RESTORE_LABEL,
+            counters::REQUEST_FAIL_LABEL,
+            (statuses.len() - num_accepted) as u64,
+        );
+        num_restored += num_accepted;
+    }
+
+    Ok(num_restored)
+}
+
+/// Writes the final mempool snapshot when dropped (i.e., when the mempool runtime is shut down)
+struct SnapshotOnDrop {
+    mempool: Arc<Mutex<CoreMempool>>,
+    snapshot_path: PathBuf,
+}
+
+impl Drop for SnapshotOnDrop {
+    fn drop(&mut self) {
+        persist_mempool(&self.mempool, &self.snapshot_path);
+    }
+}
+
+/// Restores the mempool snapshot on startup, and then periodically persists all
+/// transactions in mempool to the snapshot file.
+pub(crate) async fn persistence_job<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    snapshot_path: PathBuf,
+    snapshot_interval_secs: u64,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    // Restore the transactions from the previous run
+    let restore_smp = smp.clone();
+    let restore_path = snapshot_path.clone();
+    match tokio::task::spawn_blocking(move || restore_snapshot(&restore_smp, &restore_path)).await {
+        Ok(Ok(num_restored)) => info!(
+            "Restored {} transactions from the mempool snapshot: {:?}",
+            num_restored, snapshot_path
+        ),
+        Ok(Err(error)) => error!(
+            "Failed to restore the mempool snapshot: {:?}. Error: {:?}",
+            snapshot_path, error
+        ),
+        Err(error) => error!(
+            "Failed to spawn the mempool snapshot restore task! Error: {:?}",
+            error
+        ),
+    }
+
+    // Periodically persist the transactions in mempool
+    let _snapshot_on_drop = SnapshotOnDrop {
+        mempool: smp.mempool.clone(),
+        snapshot_path: snapshot_path.clone(),
+    };
+    let mut interval = IntervalStream::new(interval(Duration::from_secs(snapshot_interval_secs)));
+    while let Some(_interval) = interval.next().await {
+        let mempool = smp.mempool.clone();
+        let snapshot_path = snapshot_path.clone();
+        if let Err(error) =
+            tokio::task::spawn_blocking(move || persist_mempool(&mempool, &snapshot_path)).await
+        {
+            error!(
+                "Failed to spawn the mempool snapshot task! Error: {:?}",
+                error
+            );
+        }
+    }
+}
+
+/// Writes the mempool snapshot and logs the result
+fn persist_mempool(mempool: &Mutex<CoreMempool>, snapshot_path: &Path) {
+    match write_snapshot(mempool, snapshot_path) {
+        Ok(num_transactions) => {
+            debug!(
+                "Persisted {} transactions to the mempool snapshot: {:?}",
+                num_transactions, snapshot_path
+            );
+            counters::mempool_persistence_txns_inc_by(
+                counters::PERSISTENCE_WRITE_LABEL,
+                counters::REQUEST_SUCCESS_LABEL,
+                num_transactions as u64,
+            );
+        },
+        Err(error) => {
+            error!(
+                "Failed to persist the mempool snapshot: {:?}. Error: {:?}",
+                snapshot_path, error
+            );
+        },
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     network::MempoolSyncMsg,
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        persistence::persistence_job,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
@@ -32,6 +33,7 @@ use tokio::runtime::{Handle, Runtime};
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers).
 ///   - inbound_network_task (task that handles inbound mempool messages and network events).
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL).
+///   - persistence_task (task that restores and periodically persists pending transactions, if enabled).
 pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
     executor: &Handle,
     config: &NodeConfig,
@@ -62,6 +64,16 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
             node_type,
         );
 
+    let persistence_config = &config.mempool.persistence;
+    if persistence_config.enabled {
+        let snapshot_path = config.storage.dir().join(&persistence_config.snapshot_path);
+        executor.spawn(persistence_job(
+            smp.clone(),
+            snapshot_path,
+            persistence_config.snapshot_interval_secs,
+        ));
+    }
+
     executor.spawn(coordinator(
         smp,
         executor.clone(),
@@ -11,8 +11,8 @@ use crate::{
     network::{BroadcastError, BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
         types::{
-            notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
-            SubmissionStatusBundle,
+            notify_subscribers, MempoolEvictionCriteria, MempoolTransactionSummary,
+            ScheduledBroadcast, SharedMempool, SharedMempoolNotification, SubmissionStatusBundle,
         },
         use_case_history::UseCaseHistory,
     },
@@ -20,14 +20,14 @@ use crate::{
     QuorumStoreRequest, QuorumStoreResponse, SubmissionStatus,
 };
 use anyhow::Result;
-use aptos_config::network_id::PeerNetworkId;
+use aptos_config::{config::transaction_filter_type::Filter, network_id::PeerNetworkId};
 use aptos_consensus_types::common::RejectedTransactionSummary;
 use aptos_crypto::HashValue;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_mempool_notifications::CommittedTransaction;
 use aptos_metrics_core::HistogramTimer;
-use aptos_network::application::interface::NetworkClientInterface;
+use aptos_network::application::{interface::NetworkClientInterface, reputation::ReputationEvent};
 use aptos_storage_interface::state_store::state_view::db_state_view::LatestDbStateCheckpointView;
 use aptos_types::{
     account_address::AccountAddress,
@@ -170,6 +170,69 @@ pub(crate) async fn process_parking_lot_addresses<NetworkClient, TransactionVali
     }
 }
 
+/// Processes a request for the summaries of transactions in mempool (optionally for a single sender).
+pub(crate) async fn process_transaction_summaries<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    sender: Option<AccountAddress>,
+    callback: oneshot::Sender<Vec<MempoolTransactionSummary>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let summaries = smp
+        .mempool
+        .lock()
+        .get_transaction_summaries(sender.as_ref());
+
+    if callback.send(summaries).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes a request for the summary of a transaction in mempool (by hash).
+pub(crate) async fn process_transaction_summary_by_hash<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    hash: HashValue,
+    callback: oneshot::Sender<Option<MempoolTransactionSummary>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let summary = smp.mempool.lock().get_transaction_summary_by_hash(hash);
+
+    if callback.send(summary).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes a request to evict all transactions matching the given criteria from mempool.
+pub(crate) async fn process_evict_transactions<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    criteria: MempoolEvictionCriteria,
+    callback: oneshot::Sender<Vec<(AccountAddress, u64)>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let evicted_txns = smp.mempool.lock().evict_transactions(&criteria);
+
+    if callback.send(evicted_txns).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes get transaction by hash request by client.
 pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
@@ -217,6 +280,12 @@ pub(crate) async fn process_transaction_broadcast<NetworkClient, TransactionVali
     let results = process_incoming_transactions(&smp, transactions, timeline_state, false);
     log_txn_process_results(&results, Some(peer));
 
+    // Update the reputation of the peer. Note: VM validation results (e.g., invalid
+    // signatures) are not held against the peer, as they depend on the local node's
+    // state and configuration (e.g., across upgrades), not just the peer's behaviour.
+    smp.network_interface
+        .update_peer_reputation(peer, ReputationEvent::ValidMessage);
+
     let ack_response = gen_ack_response(message_id, results, &peer);
 
     // Respond to the peer with an ack. Note: ack response messages should be
@@ -304,6 +373,13 @@ where
 {
     let mut statuses = vec![];
 
+    // Reject the transactions denied by the admission filter before doing any work on them
+    let transactions =
+        filter_transactions(&smp.config.transaction_filter, transactions, &mut statuses);
+    if transactions.is_empty() {
+        return statuses;
+    }
+
     let start_storage_read = Instant::now();
     let state_view = smp
         .db
@@ -370,6 +446,51 @@ where
     statuses
 }
 
+/// Removes the transactions denied by the mempool transaction filter, and
+/// marks them as rejected in the given statuses.
+fn filter_transactions(
+    filter: &Filter,
+    transactions: Vec<(
+        SignedTransaction,
+        Option<u64>,
+        Option<BroadcastPeerPriority>,
+    )>,
+    statuses: &mut Vec<SubmissionStatusBundle>,
+) -> Vec<(
+    SignedTransaction,
+    Option<u64>,
+    Option<BroadcastPeerPriority>,
+)> {
+    // Special case for no filter to avoid unnecessary iteration through all transactions in the default case
+    if filter.is_empty() {
+        return transactions;
+    }
+
+    // Transactions are not part of a block yet, so we use the current time
+    // for block timestamp matchers (block ID matchers are rejected by the
+    // config sanitizer).
+    let timestamp_usecs = aptos_infallible::duration_since_epoch().as_micros() as u64;
+    transactions
+        .into_iter()
+        .filter_map(|(txn, ready_time_at_sender, priority)| {
+            if filter.allows(HashValue::zero(), timestamp_usecs, &txn) {
+                Some((txn, ready_time_at_sender, priority))
+            } else {
+                statuses.push((
+                    txn,
+                    (
+                        MempoolStatus::new(MempoolStatusCode::RejectedByFilter).with_message(
+                            "Transaction was rejected by the mempool transaction filter".into(),
+                        ),
+                        None,
+                    ),
+                ));
+                None
+            }
+        })
+        .collect()
+}
+
 /// Perfoms VM validation on the transactions and inserts those that passes
 /// validation into the mempool.
 #[cfg(not(feature = "consensus-only-perf-test"))]
@@ -4,7 +4,7 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool,
+    core_mempool::{CoreMempool, TimelineState},
     network::{MempoolNetworkInterface, MempoolSyncMsg},
     shared_mempool::use_case_history::UseCaseHistory,
 };
@@ -21,7 +21,9 @@ use aptos_infallible::{Mutex, RwLock};
 use aptos_network::application::interface::NetworkClientInterface;
 use aptos_storage_interface::DbReader;
 use aptos_types::{
-    account_address::AccountAddress, mempool_status::MempoolStatus, transaction::SignedTransaction,
+    account_address::AccountAddress,
+    mempool_status::MempoolStatus,
+    transaction::{SignedTransaction, TransactionPayload},
     vm_status::DiscardedVMStatus,
 };
 use aptos_vm_validator::vm_validator::TransactionValidation;
@@ -243,6 +245,79 @@ pub enum MempoolClientRequest {
     /// Retrieves all addresses with transactions in the mempool's parking lot and
     /// the number of transactions for each address
     GetAddressesFromParkingLot(oneshot::Sender<Vec<(AccountAddress, u64)>>),
+    /// Retrieves the summaries of all transactions in the mempool, optionally
+    /// restricted to the transactions of a single sender
+    GetTransactionSummaries(
+        Option<AccountAddress>,
+        oneshot::Sender<Vec<MempoolTransactionSummary>>,
+    ),
+    /// Retrieves the summary of a transaction in the mempool using its hash
+    GetTransactionSummaryByHash(
+        HashValue,
+        oneshot::Sender<Option<MempoolTransactionSummary>>,
+    ),
+    /// Evicts all transactions matching the given criteria from the mempool and
+    /// returns the sender and sequence number of each evicted transaction
+    EvictTransactions(
+        MempoolEvictionCriteria,
+        oneshot::Sender<Vec<(AccountAddress, u64)>>,
+    ),
+}
+
+/// A summary of a transaction in the mempool, used for live inspection
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MempoolTransactionSummary {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub hash: HashValue,
+    pub gas_unit_price: u64,
+    pub ranking_score: u64,
+    pub insertion_time_usecs: u64,
+    pub timeline_state: TimelineState,
+    pub parked: bool,
+    pub submitted_by: String,
+}
+
+/// The criteria used to select transactions for eviction from the mempool.
+/// A transaction is only evicted if it matches all criteria that are set.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MempoolEvictionCriteria {
+    pub sender: Option<AccountAddress>,
+    /// The entry function, specified as (module address, module name, function name)
+    pub entry_function: Option<(AccountAddress, String, String)>,
+}
+
+impl MempoolEvictionCriteria {
+    /// Returns true iff no criteria are set (i.e., all transactions would match)
+    pub fn is_empty(&self) -> bool {
+        self.sender.is_none() && self.entry_function.is_none()
+    }
+
+    /// Returns true iff the given transaction matches all criteria
+    pub fn matches(&self, txn: &SignedTransaction) -> bool {
+        if let Some(sender) = &self.sender {
+            if txn.sender() != *sender {
+                return false;
+            }
+        }
+
+        if let Some((address, module_name, function_name)) = &self.entry_function {
+            match txn.payload() {
+                TransactionPayload::EntryFunction(entry_function) => {
+                    let module_id = entry_function.module();
+                    if module_id.address() != address
+                        || module_id.name().as_str() != module_name
+                        || entry_function.function().as_str() != function_name
+                    {
+                        return false;
+                    }
+                },
+                _ => return false,
+            }
+        }
+
+        true
+    }
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
@@ -15,7 +15,10 @@ use aptos_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
     mempool_status::MempoolStatusCode,
-    transaction::{RawTransaction, Script, SignedTransaction, TransactionArgument},
+    transaction::{
+        EntryFunction, RawTransaction, Script, SignedTransaction, TransactionArgument,
+        TransactionPayload,
+    },
 };
 use once_cell::sync::Lazy;
 use rand::{rngs::StdRng, SeedableRng};
@@ -143,15 +146,37 @@ impl TestTransaction {
         self.make_signed_transaction_impl(100, u64::MAX)
     }
 
+    pub(crate) fn make_signed_entry_function_transaction(
+        &self,
+        entry_function: EntryFunction,
+    ) -> SignedTransaction {
+        self.make_signed_transaction_with_payload(
+            TransactionPayload::EntryFunction(entry_function),
+            100,
+            u64::MAX,
+        )
+    }
+
     fn make_signed_transaction_impl(
         &self,
         max_gas_amount: u64,
         exp_timestamp_secs: u64,
     ) -> SignedTransaction {
-        let raw_txn = RawTransaction::new_script(
+        let payload =
+            TransactionPayload::Script(self.script.clone().unwrap_or(SMALL_SCRIPT.clone()));
+        self.make_signed_transaction_with_payload(payload, max_gas_amount, exp_timestamp_secs)
+    }
+
+    fn make_signed_transaction_with_payload(
+        &self,
+        payload: TransactionPayload,
+        max_gas_amount: u64,
+        exp_timestamp_secs: u64,
+    ) -> SignedTransaction {
+        let raw_txn = RawTransaction::new(
             self.address,
             self.sequence_number,
-            self.script.clone().unwrap_or(SMALL_SCRIPT.clone()),
+            payload,
             max_gas_amount,
             self.gas_price,
             exp_timestamp_secs,
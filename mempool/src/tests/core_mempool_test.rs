@@ -5,6 +5,7 @@
 use crate::{
     core_mempool::{sender_bucket, CoreMempool, MempoolTransaction, SubmittedBy, TimelineState},
     network::BroadcastPeerPriority,
+    shared_mempool::types::MempoolEvictionCriteria,
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, setup_mempool,
         setup_mempool_with_broadcast_buckets, txn_bytes_len, TestTransaction,
@@ -14,12 +15,18 @@ use aptos_config::config::{MempoolConfig, NodeConfig};
 use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
 use aptos_crypto::HashValue;
 use aptos_types::{
-    account_address::AccountAddress, mempool_status::MempoolStatusCode,
-    transaction::SignedTransaction, vm_status::DiscardedVMStatus,
+    account_address::AccountAddress,
+    mempool_status::MempoolStatusCode,
+    move_utils::MemberId,
+    transaction::{EntryFunction, SignedTransaction},
+    vm_status::DiscardedVMStatus,
 };
 use itertools::Itertools;
 use maplit::btreemap;
-use std::time::{Duration, Instant, SystemTime};
+use std::{
+    str::FromStr,
+    time::{Duration, Instant, SystemTime},
+};
 
 #[test]
 fn test_transaction_ordering_only_seqnos() {
@@ -1311,3 +1318,124 @@ fn test_include_gas_upgraded() {
     });
     assert_eq!(batch.len(), 0);
 }
+
+#[test]
+fn test_transaction_summaries() {
+    let (mut pool, _) = setup_mempool();
+
+    // Add two sequential txns for account 0, and a non-sequential txn for account 1
+    let txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(0, 0, 1),
+        TestTransaction::new(0, 1, 2),
+        TestTransaction::new(1, 1, 3),
+    ]);
+
+    // Verify the summaries of all transactions (ordered by sender and sequence number)
+    let summaries = pool.get_transaction_summaries(None);
+    assert_eq!(summaries.len(), 3);
+    for summary in &summaries {
+        let txn = txns
+            .iter()
+            .find(|txn| txn.committed_hash() == summary.hash)
+            .unwrap();
+        assert_eq!(summary.sender, txn.sender());
+        assert_eq!(summary.sequence_number, txn.sequence_number());
+        assert_eq!(summary.gas_unit_price, txn.gas_unit_price());
+        assert_eq!(summary.submitted_by, "downstream");
+
+        // Only the non-sequential transaction should be parked
+        assert_eq!(
+            summary.parked,
+            summary.sender == TestTransaction::get_address(1)
+        );
+    }
+    assert!(summaries
+        .windows(2)
+        .all(|w| (w[0].sender, w[0].sequence_number) < (w[1].sender, w[1].sequence_number)));
+
+    // Verify the summaries can be restricted to a single sender
+    let sender = TestTransaction::get_address(0);
+    let summaries = pool.get_transaction_summaries(Some(&sender));
+    assert_eq!(summaries.len(), 2);
+    assert!(summaries.iter().all(|summary| summary.sender == sender));
+
+    // Verify the summary can be fetched by hash
+    let summary = pool
+        .get_transaction_summary_by_hash(txns[1].committed_hash())
+        .unwrap();
+    assert_eq!(summary.sender, sender);
+    assert_eq!(summary.sequence_number, 1);
+    assert!(matches!(summary.timeline_state, TimelineState::Ready(_)));
+    assert!(pool
+        .get_transaction_summary_by_hash(HashValue::random())
+        .is_none());
+}
+
+#[test]
+fn test_evict_transactions_by_sender() {
+    let (mut pool, mut consensus) = setup_mempool();
+
+    // Add txns for accounts 0 and 1
+    let txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(0, 0, 1),
+        TestTransaction::new(0, 1, 1),
+        TestTransaction::new(1, 0, 1),
+    ]);
+
+    // Evict all txns from account 0
+    let sender = TestTransaction::get_address(0);
+    let evicted_txns = pool.evict_transactions(&MempoolEvictionCriteria {
+        sender: Some(sender),
+        entry_function: None,
+    });
+    assert_eq!(evicted_txns, vec![(sender, 0), (sender, 1)]);
+
+    // Verify only the txn from account 1 remains
+    assert!(pool.get_transaction_summaries(Some(&sender)).is_empty());
+    assert_eq!(consensus.get_block(&mut pool, 3, 1024), vec![
+        txns[2].clone()
+    ]);
+}
+
+#[test]
+fn test_evict_transactions_by_entry_function() {
+    let (mut pool, mut consensus) = setup_mempool();
+
+    // Add txns for account 0, calling two different entry functions
+    let transfer = MemberId::from_str("0x1::coin::transfer").unwrap();
+    let mint = MemberId::from_str("0x1::coin::mint").unwrap();
+    let txns: Vec<SignedTransaction> = [&transfer, &mint, &transfer]
+        .iter()
+        .enumerate()
+        .map(|(sequence_number, member_id)| {
+            let entry_function = EntryFunction::new(
+                member_id.module_id.clone(),
+                member_id.member_id.clone(),
+                vec![],
+                vec![],
+            );
+            TestTransaction::new(0, sequence_number as u64, 1)
+                .make_signed_entry_function_transaction(entry_function)
+        })
+        .collect();
+    for txn in &txns {
+        add_signed_txn(&mut pool, txn.clone()).unwrap();
+    }
+
+    // Evict the mint txn
+    let sender = TestTransaction::get_address(0);
+    let evicted_txns = pool.evict_transactions(&MempoolEvictionCriteria {
+        sender: None,
+        entry_function: Some((AccountAddress::ONE, "coin".to_string(), "mint".to_string())),
+    });
+    assert_eq!(evicted_txns, vec![(sender, 1)]);
+
+    // Verify the txn following the evicted txn has been parked
+    let summaries = pool.get_transaction_summaries(Some(&sender));
+    assert_eq!(summaries.len(), 2);
+    assert!(!summaries[0].parked);
+    assert!(summaries[1].parked);
+    assert_eq!(consensus.get_block(&mut pool, 3, 1024), vec![
+        txns[0].clone()
+    ]);
+}
@@ -2,11 +2,20 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::tests::{
-    common::TestTransaction,
-    test_framework::{test_transaction, MempoolNode, MempoolTestFrameworkBuilder},
+use crate::{
+    shared_mempool::persistence::{MempoolSnapshot, PersistedTransaction},
+    tests::{
+        common::TestTransaction,
+        test_framework::{
+            sign_transactions, test_transaction, MempoolNode, MempoolTestFramework,
+            MempoolTestFrameworkBuilder,
+        },
+    },
+};
+use aptos_config::{
+    config::{MempoolPersistenceConfig, NodeConfig},
+    network_id::{NetworkId, PeerNetworkId},
 };
-use aptos_config::network_id::PeerNetworkId;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::{
     testutils::{
@@ -19,7 +28,9 @@ use aptos_network::{
     transport::ConnectionMetadata,
     ProtocolId,
 };
+use aptos_temppath::TempPath;
 use once_cell::sync::Lazy;
+use rand::{rngs::StdRng, SeedableRng};
 use std::time::Duration;
 
 const ALL_PROTOCOLS: [ProtocolId; 1] = [ProtocolId::MempoolDirectSend];
@@ -125,6 +136,54 @@ async fn single_outbound_node_test() {
     }
 }
 
+/// Tests that transactions restored from a mempool snapshot are broadcast upstream
+#[tokio::test]
+async fn restored_transactions_broadcast_test() {
+    // Write a snapshot containing a client submitted transaction
+    let snapshot_dir = TempPath::new();
+    snapshot_dir.create_as_dir().unwrap();
+    let snapshot_path = snapshot_dir.path().join("snapshot.bcs");
+    let snapshot = MempoolSnapshot {
+        transactions: sign_transactions(&TXN_1)
+            .into_iter()
+            .map(|transaction| PersistedTransaction {
+                transaction,
+                client_submitted: true,
+            })
+            .collect(),
+    };
+    std::fs::write(&snapshot_path, bcs::to_bytes(&snapshot).unwrap()).unwrap();
+
+    // Start a PFN that restores the snapshot
+    let mut config = NodeConfig::generate_random_config_with_template(
+        &NodeConfig::get_default_pfn_config(),
+        &mut StdRng::from_seed([0u8; 32]),
+    );
+    config.mempool.persistence = MempoolPersistenceConfig {
+        enabled: true,
+        snapshot_path,
+        ..MempoolPersistenceConfig::default()
+    };
+    let peer_id = config
+        .full_node_networks
+        .iter()
+        .find(|network| network.network_id == NetworkId::Public)
+        .expect("Pfn must have a public network")
+        .peer_id();
+    let peer_network_id = PeerNetworkId::new(NetworkId::Public, peer_id);
+    let mut node = MempoolTestFramework::build_node(NodeId::pfn(0), config, &[peer_network_id]);
+    node.wait_on_txns_in_mempool(&TXN_1).await;
+    node.assert_only_txns_in_mempool(&TXN_1);
+
+    // After connecting upstream, the restored transaction should be broadcast
+    let (other_peer_network_id, other_metadata) =
+        pfn_vfn_mock_connection(ConnectionOrigin::Outbound, &ALL_PROTOCOLS);
+    node.connect_self(other_peer_network_id.network_id(), other_metadata);
+    node.send_broadcast_and_receive_ack(other_peer_network_id, &TXN_1)
+        .await;
+    node.assert_only_txns_in_mempool(&TXN_1);
+}
+
 /// Tests if the node is a VFN, and it's getting forwarded messages from a PFN.  It should forward
 /// messages to the upstream VAL.  Upstream and downstream nodes are mocked.
 #[tokio::test]
@@ -11,7 +11,7 @@ use crate::{
 use anyhow::{format_err, Result};
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{NetworkConfig, NodeConfig},
+    config::{MempoolConfig, NetworkConfig, NodeConfig},
     network_id::NetworkId,
 };
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
@@ -60,12 +60,19 @@ impl MockSharedMempool {
     /// Returns the runtime on which the shared mempool is running
     /// and the channel through which shared mempool receives client events.
     pub fn new() -> Self {
+        Self::new_with_mempool_config(MempoolConfig::default())
+    }
+
+    /// Creates a mock of a running instance of shared mempool using the given mempool config.
+    pub fn new_with_mempool_config(mempool_config: MempoolConfig) -> Self {
         // Create the shared mempool
-        let (ac_client, mempool, quorum_store_sender, mempool_notifier) = Self::start(
-            &Handle::current(),
-            &DbReaderWriter::new(MockDbReaderWriter),
-            MockVMValidator,
-        );
+        let (ac_client, mempool, quorum_store_sender, mempool_notifier) =
+            Self::start_with_mempool_config(
+                &Handle::current(),
+                &DbReaderWriter::new(MockDbReaderWriter),
+                MockVMValidator,
+                mempool_config,
+            );
         Self {
             _runtime: Some(Handle::current()),
             _handle: None,
@@ -114,8 +121,23 @@ impl MockSharedMempool {
         Arc<Mutex<CoreMempool>>,
         mpsc::Sender<QuorumStoreRequest>,
         MempoolNotifier,
+    ) {
+        Self::start_with_mempool_config(handle, db, validator, MempoolConfig::default())
+    }
+
+    pub fn start_with_mempool_config<V: TransactionValidation + 'static>(
+        handle: &Handle,
+        db: &DbReaderWriter,
+        validator: V,
+        mempool_config: MempoolConfig,
+    ) -> (
+        MempoolClientSender,
+        Arc<Mutex<CoreMempool>>,
+        mpsc::Sender<QuorumStoreRequest>,
+        MempoolNotifier,
     ) {
         let mut config = NodeConfig::generate_random_config();
+        config.mempool = mempool_config;
         config.validator_network = Some(NetworkConfig::network_with_id(NetworkId::Validator));
 
         let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
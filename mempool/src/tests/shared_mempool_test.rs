@@ -6,13 +6,20 @@ use crate::{
     core_mempool::sender_bucket,
     mocks::MockSharedMempool,
     network::BroadcastPeerPriority,
-    tests::common::{batch_add_signed_txn, TestTransaction},
-    QuorumStoreRequest,
+    shared_mempool::persistence::{read_snapshot, write_snapshot},
+    tests::common::{batch_add_signed_txn, setup_mempool, TestTransaction},
+    MempoolClientRequest, QuorumStoreRequest,
+};
+use aptos_config::config::{
+    transaction_filter_type::Filter, MempoolConfig, MempoolPersistenceConfig,
 };
-use aptos_config::config::MempoolConfig;
 use aptos_consensus_types::common::RejectedTransactionSummary;
+use aptos_infallible::Mutex;
 use aptos_mempool_notifications::MempoolNotificationSender;
-use aptos_types::{transaction::Transaction, vm_status::DiscardedVMStatus};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    mempool_status::MempoolStatusCode, transaction::Transaction, vm_status::DiscardedVMStatus,
+};
 use futures::{channel::oneshot, sink::SinkExt};
 use tokio::time::timeout;
 
@@ -152,3 +159,116 @@ async fn test_mempool_notify_committed_txns() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_transaction_filter_rejects_submission() {
+    // Create a shared mempool that denies all transactions from a single sender
+    let denied_sender = TestTransaction::get_address(0);
+    let mempool_config = MempoolConfig {
+        transaction_filter: Filter::empty().add_deny_sender(denied_sender),
+        ..MempoolConfig::default()
+    };
+    let smp = MockSharedMempool::new_with_mempool_config(mempool_config);
+
+    // Submit a transaction from the denied sender and verify it is rejected
+    let denied_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let (callback, callback_rcv) = oneshot::channel();
+    let mut ac_client = smp.ac_client.clone();
+    ac_client
+        .send(MempoolClientRequest::SubmitTransaction(
+            denied_txn.clone(),
+            callback,
+        ))
+        .await
+        .unwrap();
+    let (mempool_status, vm_status) = callback_rcv.await.unwrap().unwrap();
+    assert_eq!(mempool_status.code, MempoolStatusCode::RejectedByFilter);
+    assert!(vm_status.is_none());
+
+    // Verify the transaction was never inserted into mempool
+    assert!(smp
+        .mempool
+        .lock()
+        .get_by_hash(denied_txn.committed_hash())
+        .is_none());
+}
+
+#[test]
+fn test_mempool_snapshot_round_trip() {
+    // Create a mempool with several transactions
+    let (mut pool, _) = setup_mempool();
+    let txns = vec![
+        TestTransaction::new(0, 0, 1).make_signed_transaction(),
+        TestTransaction::new(0, 1, 1).make_signed_transaction(),
+        TestTransaction::new(1, 0, 2).make_signed_transaction(),
+    ];
+    assert!(batch_add_signed_txn(&mut pool, txns.clone()).is_ok());
+
+    // Verify that reading a missing snapshot returns an empty snapshot
+    let snapshot_dir = TempPath::new();
+    let snapshot_path = snapshot_dir.path().join("mempool").join("snapshot.bcs");
+    assert!(read_snapshot(&snapshot_path)
+        .unwrap()
+        .transactions
+        .is_empty());
+
+    // Write the snapshot and verify all transactions are read back
+    let num_written = write_snapshot(&Mutex::new(pool), &snapshot_path).unwrap();
+    assert_eq!(num_written, txns.len());
+    let snapshot = read_snapshot(&snapshot_path).unwrap();
+    let persisted_txns: Vec<_> = snapshot
+        .transactions
+        .into_iter()
+        .map(|persisted_transaction| {
+            assert!(!persisted_transaction.client_submitted);
+            persisted_transaction.transaction
+        })
+        .collect();
+    assert_eq!(persisted_txns.len(), txns.len());
+    for txn in &txns {
+        assert!(persisted_txns.contains(txn));
+    }
+}
+
+#[tokio::test]
+async fn test_mempool_restored_from_snapshot() {
+    // Write a snapshot containing a single transaction
+    let snapshot_dir = TempPath::new();
+    let snapshot_path = snapshot_dir.path().join("snapshot.bcs");
+    let (mut pool, _) = setup_mempool();
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    assert!(batch_add_signed_txn(&mut pool, vec![txn.clone()]).is_ok());
+    write_snapshot(&Mutex::new(pool), &snapshot_path).unwrap();
+
+    // Start a shared mempool with persistence enabled
+    let mempool_config = MempoolConfig {
+        persistence: MempoolPersistenceConfig {
+            enabled: true,
+            snapshot_path: snapshot_path.clone(),
+            ..MempoolPersistenceConfig::default()
+        },
+        ..MempoolConfig::default()
+    };
+    let smp = MockSharedMempool::new_with_mempool_config(mempool_config);
+
+    // Wait until the transaction is restored into mempool
+    let wait_for_restore = async {
+        loop {
+            if smp
+                .mempool
+                .lock()
+                .get_by_hash(txn.committed_hash())
+                .is_some()
+            {
+                return; // The transaction was restored
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    };
+    if let Err(elapsed) = timeout(std::time::Duration::from_secs(5), wait_for_restore).await {
+        panic!(
+            "Mempool did not restore the snapshot transactions! {:?}",
+            elapsed
+        );
+    }
+}
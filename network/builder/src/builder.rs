@@ -26,6 +26,7 @@ use aptos_network::{
     connectivity_manager::{builder::ConnectivityManagerBuilder, ConnectivityRequest},
     constants::MAX_MESSAGE_SIZE,
     logging::NetworkSchema,
+    peer::outbound_queues::ProtocolQuotas,
     peer_manager::{
         builder::{AuthenticationMode, PeerManagerBuilder},
         ConnectionRequestSender,
@@ -85,6 +86,7 @@ impl NetworkBuilder {
         network_channel_size: usize,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_quotas: ProtocolQuotas,
     ) -> Self {
         // A network cannot exist without a PeerManager
         // TODO:  construct this in create and pass it to new() as a parameter. The complication is manual construction of NetworkBuilder in various tests.
@@ -101,6 +103,7 @@ impl NetworkBuilder {
             enable_proxy_protocol,
             inbound_connection_limit,
             tcp_buffer_cfg,
+            outbound_quotas,
         );
 
         NetworkBuilder {
@@ -140,6 +143,7 @@ impl NetworkBuilder {
             NETWORK_CHANNEL_SIZE,
             MAX_INBOUND_CONNECTIONS,
             TCPBufferCfg::default(),
+            ProtocolQuotas::default(),
         );
 
         builder.add_connectivity_manager(
@@ -195,6 +199,7 @@ impl NetworkBuilder {
                 config.outbound_rx_buffer_size_bytes,
                 config.outbound_tx_buffer_size_bytes,
             ),
+            ProtocolQuotas::from_config(&config.outbound_protocol_quotas),
         );
 
         network_builder.add_connection_monitoring(
@@ -384,6 +389,13 @@ impl NetworkBuilder {
                     Duration::from_secs(rest_discovery.interval_secs),
                     self.time_service.clone(),
                 ),
+                DiscoveryMethod::Dns(dns_discovery) => DiscoveryChangeListener::dns(
+                    self.network_context,
+                    conn_mgr_reqs_tx.clone(),
+                    dns_discovery.name.clone(),
+                    Duration::from_secs(dns_discovery.interval_secs),
+                    self.time_service.clone(),
+                ),
                 DiscoveryMethod::None => {
                     continue;
                 },
@@ -0,0 +1,427 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::DiscoveryError;
+use aptos_config::config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION};
+use aptos_crypto::{x25519, ValidCryptoMaterialStringExt};
+use aptos_logger::{info, warn};
+use aptos_time_service::{Interval, TimeService, TimeServiceTrait};
+use aptos_types::{account_address, network_address::NetworkAddress, PeerId};
+use async_trait::async_trait;
+use futures::{future::BoxFuture, Future, Stream};
+use hickory_resolver::{
+    config::{ResolverConfig, ResolverOpts},
+    TokioAsyncResolver,
+};
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// The TXT record key holding the x25519 network public key of a seed
+const NOISE_PUBKEY_KEY: &str = "aptos_noise_pubkey";
+/// The (optional) TXT record key holding the peer id of a seed. If missing,
+/// the peer id is derived from the public key (as for public fullnodes).
+const PEER_ID_KEY: &str = "aptos_peer_id";
+
+/// A single SRV record, i.e., the target host and port of a seed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SrvRecord {
+    pub target: String,
+    pub port: u16,
+}
+
+/// A resolver for the DNS records used by seed discovery
+#[async_trait]
+pub trait DnsResolver: Send + Sync {
+    /// Returns the SRV records for the given name
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DiscoveryError>;
+
+    /// Returns the TXT records for the given name (with the character
+    /// strings of each record concatenated).
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, DiscoveryError>;
+}
+
+/// A resolver that uses the system DNS configuration
+pub struct SystemDnsResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl SystemDnsResolver {
+    pub fn new() -> Self {
+        let resolver = TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|error| {
+            warn!(
+                "Failed to read the system DNS configuration, using the defaults: {:?}",
+                error
+            );
+            TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+        });
+        Self { resolver }
+    }
+}
+
+impl Default for SystemDnsResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl DnsResolver for SystemDnsResolver {
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DiscoveryError> {
+        let srv_lookup = self
+            .resolver
+            .srv_lookup(name)
+            .await
+            .map_err(|error| DiscoveryError::Dns(error.to_string()))?;
+        Ok(srv_lookup
+            .iter()
+            .map(|srv| SrvRecord {
+                target: srv.target().to_utf8().trim_end_matches('.').to_string(),
+                port: srv.port(),
+            })
+            .collect())
+    }
+
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, DiscoveryError> {
+        let txt_lookup = self
+            .resolver
+            .txt_lookup(name)
+            .await
+            .map_err(|error| DiscoveryError::Dns(error.to_string()))?;
+        Ok(txt_lookup
+            .iter()
+            .map(|txt| {
+                txt.txt_data()
+                    .iter()
+                    .map(|data| String::from_utf8_lossy(data))
+                    .collect::<String>()
+            })
+            .collect())
+    }
+}
+
+/// A discovery stream that periodically resolves seed peers from DNS.
+///
+/// The SRV records of the configured name identify the seeds (by target host
+/// and port). The TXT records of each target hold the seed's network public
+/// key (`aptos_noise_pubkey=<hex>`) and, optionally, its peer id
+/// (`aptos_peer_id=<hex>`). All seeds are added as upstream peers.
+pub struct DnsStream {
+    name: String,
+    resolver: Arc<dyn DnsResolver>,
+    interval: Pin<Box<Interval>>,
+    pending_lookup: Option<BoxFuture<'static, Result<PeerSet, DiscoveryError>>>,
+}
+
+impl DnsStream {
+    pub(crate) fn new(
+        name: String,
+        resolver: Arc<dyn DnsResolver>,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        DnsStream {
+            name,
+            resolver,
+            interval: Box::pin(time_service.interval(interval_duration)),
+            pending_lookup: None,
+        }
+    }
+}
+
+impl Stream for DnsStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            // Finish any lookup that is already in flight
+            if let Some(pending_lookup) = this.pending_lookup.as_mut() {
+                let result = futures::ready!(pending_lookup.as_mut().poll(cx));
+                this.pending_lookup = None;
+                return Poll::Ready(Some(result));
+            }
+
+            // Wait for delay, then start the next lookup
+            futures::ready!(this.interval.as_mut().poll_next(cx));
+            this.pending_lookup = Some(Box::pin(resolve_seeds(
+                this.resolver.clone(),
+                this.name.clone(),
+            )));
+        }
+    }
+}
+
+/// Resolves the seeds of the given name into a peer set. Seeds with missing
+/// or malformed TXT records are skipped.
+async fn resolve_seeds(
+    resolver: Arc<dyn DnsResolver>,
+    name: String,
+) -> Result<PeerSet, DiscoveryError> {
+    let srv_records = resolver.lookup_srv(&name).await?;
+
+    let mut peer_set = PeerSet::new();
+    for srv_record in srv_records {
+        let seed = match resolver.lookup_txt(&srv_record.target).await {
+            Ok(txt_records) => parse_seed(&srv_record, &txt_records),
+            Err(error) => Err(error),
+        };
+        match seed {
+            Ok((peer_id, address, pubkey)) => {
+                let peer = peer_set
+                    .entry(peer_id)
+                    .or_insert_with(|| Peer::new(vec![], HashSet::new(), PeerRole::Upstream));
+                peer.addresses.push(address);
+                peer.keys.insert(pubkey);
+            },
+            Err(error) => {
+                info!(
+                    "Skipping DNS seed {}:{} of {}: {:?}",
+                    srv_record.target, srv_record.port, name, error
+                );
+            },
+        }
+    }
+    Ok(peer_set)
+}
+
+/// Parses the peer id, network address and public key of a seed
+fn parse_seed(
+    srv_record: &SrvRecord,
+    txt_records: &[String],
+) -> Result<(PeerId, NetworkAddress, x25519::PublicKey), DiscoveryError> {
+    let pubkey = find_txt_value(txt_records, NOISE_PUBKEY_KEY)
+        .ok_or_else(|| DiscoveryError::Parsing(format!("Missing TXT record: {}", NOISE_PUBKEY_KEY)))
+        .and_then(|value| {
+            x25519::PublicKey::from_encoded_string(value)
+                .map_err(|error| DiscoveryError::Parsing(error.to_string()))
+        })?;
+    let peer_id = match find_txt_value(txt_records, PEER_ID_KEY) {
+        Some(value) => {
+            PeerId::from_str(value).map_err(|error| DiscoveryError::Parsing(error.to_string()))?
+        },
+        None => account_address::from_identity_public_key(pubkey),
+    };
+    let address = NetworkAddress::from_str(&format!(
+        "/dns/{}/tcp/{}",
+        srv_record.target, srv_record.port
+    ))
+    .map_err(|error| DiscoveryError::Parsing(error.to_string()))?
+    .append_prod_protos(pubkey, HANDSHAKE_VERSION);
+
+    Ok((peer_id, address, pubkey))
+}
+
+/// Returns the value of the first `key=value` TXT record with the given key
+fn find_txt_value<'a>(txt_records: &'a [String], key: &str) -> Option<&'a str> {
+    txt_records.iter().find_map(|record| {
+        let (record_key, value) = record.split_once('=')?;
+        (record_key.trim() == key).then_some(value.trim())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiscoveryChangeListener;
+    use aptos_config::network_id::NetworkContext;
+    use aptos_event_notifications::DbBackedOnChainConfig;
+    use aptos_network::connectivity_manager::{ConnectivityRequest, DiscoverySource};
+    use futures::StreamExt;
+    use std::collections::HashMap;
+
+    const SEEDS_NAME: &str = "_aptosnet._tcp.seeds.example.com";
+    const PUBKEY_1: &str = "080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120";
+    const PUBKEY_2: &str = "ca8bad1f1ec4fc4e88ba6e3ab6e2fc3e5ea0b4c7d0f1a3b44e66ba5e4ab3e452";
+
+    /// A resolver that serves records from memory
+    #[derive(Default)]
+    struct StubResolver {
+        srv_records: HashMap<String, Vec<SrvRecord>>,
+        txt_records: HashMap<String, Vec<String>>,
+    }
+
+    impl StubResolver {
+        fn add_seed(&mut self, target: &str, port: u16, txt_records: Vec<String>) {
+            self.srv_records
+                .entry(SEEDS_NAME.to_string())
+                .or_default()
+                .push(SrvRecord {
+                    target: target.to_string(),
+                    port,
+                });
+            self.txt_records.insert(target.to_string(), txt_records);
+        }
+    }
+
+    #[async_trait]
+    impl DnsResolver for StubResolver {
+        async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DiscoveryError> {
+            self.srv_records
+                .get(name)
+                .cloned()
+                .ok_or_else(|| DiscoveryError::Dns(format!("No SRV records for {}", name)))
+        }
+
+        async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, DiscoveryError> {
+            self.txt_records
+                .get(name)
+                .cloned()
+                .ok_or_else(|| DiscoveryError::Dns(format!("No TXT records for {}", name)))
+        }
+    }
+
+    fn create_stream(resolver: StubResolver) -> DnsStream {
+        DnsStream::new(
+            SEEDS_NAME.to_string(),
+            Arc::new(resolver),
+            Duration::from_millis(5),
+            TimeService::real(),
+        )
+    }
+
+    fn create_address(target: &str, port: u16, pubkey: x25519::PublicKey) -> NetworkAddress {
+        NetworkAddress::from_str(&format!("/dns/{}/tcp/{}", target, port))
+            .unwrap()
+            .append_prod_protos(pubkey, HANDSHAKE_VERSION)
+    }
+
+    #[tokio::test]
+    async fn test_dns_seeds() {
+        // Create a resolver with two valid seeds and two invalid seeds
+        let pubkey_1 = x25519::PublicKey::from_encoded_string(PUBKEY_1).unwrap();
+        let pubkey_2 = x25519::PublicKey::from_encoded_string(PUBKEY_2).unwrap();
+        let peer_id_2 = PeerId::random();
+        let mut resolver = StubResolver::default();
+        resolver.add_seed("seed1.example.com", 6182, vec![
+            "v=aptos1".into(),
+            format!("{}={}", NOISE_PUBKEY_KEY, PUBKEY_1),
+        ]);
+        resolver.add_seed("seed2.example.com", 6180, vec![
+            format!("{}=0x{}", NOISE_PUBKEY_KEY, PUBKEY_2),
+            format!("{}={}", PEER_ID_KEY, peer_id_2.to_hex_literal()),
+        ]);
+        resolver.add_seed("seed3.example.com", 6182, vec![format!(
+            "{}=invalid",
+            NOISE_PUBKEY_KEY
+        )]);
+        resolver
+            .srv_records
+            .get_mut(SEEDS_NAME)
+            .unwrap()
+            .push(SrvRecord {
+                target: "seed4.example.com".into(),
+                port: 6182,
+            });
+
+        // Verify that only the valid seeds are discovered
+        let mut stream = create_stream(resolver);
+        let peer_set = stream.next().await.unwrap().unwrap();
+        let mut expected_peer_set = PeerSet::new();
+        expected_peer_set.insert(
+            account_address::from_identity_public_key(pubkey_1),
+            Peer::new(
+                vec![create_address("seed1.example.com", 6182, pubkey_1)],
+                HashSet::from([pubkey_1]),
+                PeerRole::Upstream,
+            ),
+        );
+        expected_peer_set.insert(
+            peer_id_2,
+            Peer::new(
+                vec![create_address("seed2.example.com", 6180, pubkey_2)],
+                HashSet::from([pubkey_2]),
+                PeerRole::Upstream,
+            ),
+        );
+        assert_eq!(peer_set, expected_peer_set);
+
+        // Verify that the seeds are refreshed periodically
+        let peer_set = stream.next().await.unwrap().unwrap();
+        assert_eq!(peer_set, expected_peer_set);
+    }
+
+    #[tokio::test]
+    async fn test_dns_seeds_merged_by_peer() {
+        // Create a resolver with the same seed behind two targets
+        let pubkey = x25519::PublicKey::from_encoded_string(PUBKEY_1).unwrap();
+        let mut resolver = StubResolver::default();
+        for target in ["seed1.example.com", "seed2.example.com"] {
+            resolver.add_seed(target, 6182, vec![format!(
+                "{}={}",
+                NOISE_PUBKEY_KEY, PUBKEY_1
+            )]);
+        }
+
+        // Verify that the addresses are merged into a single peer
+        let mut stream = create_stream(resolver);
+        let peer_set = stream.next().await.unwrap().unwrap();
+        assert_eq!(peer_set.len(), 1);
+        let peer = peer_set
+            .get(&account_address::from_identity_public_key(pubkey))
+            .unwrap();
+        assert_eq!(peer.addresses, vec![
+            create_address("seed1.example.com", 6182, pubkey),
+            create_address("seed2.example.com", 6182, pubkey),
+        ]);
+        assert_eq!(peer.keys, HashSet::from([pubkey]));
+    }
+
+    #[tokio::test]
+    async fn test_dns_listener() {
+        // Create a resolver with a single seed
+        let pubkey = x25519::PublicKey::from_encoded_string(PUBKEY_1).unwrap();
+        let mut resolver = StubResolver::default();
+        resolver.add_seed("seed1.example.com", 6182, vec![format!(
+            "{}={}",
+            NOISE_PUBKEY_KEY, PUBKEY_1
+        )]);
+
+        // Start a DNS discovery listener
+        let (conn_mgr_reqs_tx, mut conn_mgr_reqs_rx) = aptos_channels::new(
+            1,
+            &aptos_network::counters::PENDING_CONNECTIVITY_MANAGER_REQUESTS,
+        );
+        let listener = DiscoveryChangeListener::<DbBackedOnChainConfig>::dns_with_resolver(
+            NetworkContext::mock(),
+            conn_mgr_reqs_tx,
+            SEEDS_NAME.to_string(),
+            Arc::new(resolver),
+            Duration::from_millis(5),
+            TimeService::real(),
+        );
+        tokio::spawn(Box::pin(listener).run());
+
+        // Verify that the seed is sent to the connectivity manager
+        if let Some(ConnectivityRequest::UpdateDiscoveredPeers(
+            DiscoverySource::Dns,
+            actual_peers,
+        )) = conn_mgr_reqs_rx.next().await
+        {
+            let peer = actual_peers
+                .get(&account_address::from_identity_public_key(pubkey))
+                .unwrap();
+            assert_eq!(peer.role, PeerRole::Upstream);
+            assert_eq!(peer.addresses, vec![create_address(
+                "seed1.example.com",
+                6182,
+                pubkey
+            )]);
+        } else {
+            panic!("No message sent by discovery")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dns_lookup_failure() {
+        // Verify that a failed SRV lookup produces an error
+        let mut stream = create_stream(StubResolver::default());
+        let result = stream.next().await.unwrap();
+        assert!(matches!(result, Err(DiscoveryError::Dns(_))));
+    }
+}
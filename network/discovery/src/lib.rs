@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    counters::DISCOVERY_COUNTS, file::FileStream, rest::RestStream,
+    counters::DISCOVERY_COUNTS, dns::DnsStream, file::FileStream, rest::RestStream,
     validator_set::ValidatorSetStream,
 };
 use aptos_config::{config::PeerSet, network_id::NetworkContext};
@@ -21,21 +21,26 @@ use futures::{Stream, StreamExt};
 use std::{
     path::Path,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
 use tokio::runtime::Handle;
 
 mod counters;
+mod dns;
 mod file;
 mod rest;
 mod validator_set;
 
+pub use dns::{DnsResolver, SrvRecord, SystemDnsResolver};
+
 #[derive(Debug)]
 pub enum DiscoveryError {
     IO(std::io::Error),
     Parsing(String),
     Rest(aptos_rest_client::error::RestError),
+    Dns(String),
 }
 
 /// A union type for all implementations of `DiscoveryChangeListenerTrait`
@@ -50,6 +55,7 @@ enum DiscoveryChangeStream<P: OnChainConfigProvider> {
     ValidatorSet(ValidatorSetStream<P>),
     File(FileStream),
     Rest(RestStream),
+    Dns(DnsStream),
 }
 
 impl<P: OnChainConfigProvider> Stream for DiscoveryChangeStream<P> {
@@ -60,6 +66,7 @@ impl<P: OnChainConfigProvider> Stream for DiscoveryChangeStream<P> {
             Self::ValidatorSet(stream) => Pin::new(stream).poll_next(cx),
             Self::File(stream) => Pin::new(stream).poll_next(cx),
             Self::Rest(stream) => Pin::new(stream).poll_next(cx),
+            Self::Dns(stream) => Pin::new(stream).poll_next(cx),
         }
     }
 }
@@ -125,6 +132,45 @@ impl<P: OnChainConfigProvider> DiscoveryChangeListener<P> {
         }
     }
 
+    pub fn dns(
+        network_context: NetworkContext,
+        update_channel: aptos_channels::Sender<ConnectivityRequest>,
+        name: String,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        Self::dns_with_resolver(
+            network_context,
+            update_channel,
+            name,
+            Arc::new(SystemDnsResolver::new()),
+            interval_duration,
+            time_service,
+        )
+    }
+
+    pub fn dns_with_resolver(
+        network_context: NetworkContext,
+        update_channel: aptos_channels::Sender<ConnectivityRequest>,
+        name: String,
+        resolver: Arc<dyn DnsResolver>,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream = DiscoveryChangeStream::Dns(DnsStream::new(
+            name,
+            resolver,
+            interval_duration,
+            time_service,
+        ));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Dns,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
     pub fn start(self, executor: &Handle) {
         spawn_named!("DiscoveryChangeListener", executor, Box::pin(self).run());
     }
@@ -0,0 +1,653 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A node-wide store of peer reputations. Applications (e.g., state sync,
+//! mempool and the health checker) report good and bad peer behaviour as
+//! [`ReputationEvent`]s, and the resulting scores are used to temporarily
+//! ban misbehaving peers and to prioritize which peers are dialed. The
+//! reputations are periodically persisted to disk, so that they survive
+//! node restarts.
+
+use crate::{application::error::Error, counters};
+use aptos_config::{config::PeerReputationConfig, network_id::PeerNetworkId};
+use aptos_infallible::RwLock;
+use aptos_logger::{info, warn};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Scores for peer reputations (these mirror the data client peer scores)
+const MAX_SCORE: f64 = 100.0;
+const MIN_SCORE: f64 = 0.0;
+pub const STARTING_SCORE: f64 = 50.0;
+/// Add this score on a valid message or response
+const VALID_MESSAGE_DELTA: f64 = 1.0;
+/// Not necessarily malicious behaviour, but not useful either
+const NOT_USEFUL_MULTIPLIER: f64 = 0.95;
+/// Likely to be malicious behaviour
+const MALICIOUS_MULTIPLIER: f64 = 0.8;
+
+/// An event that affects the reputation of a peer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReputationEvent {
+    /// The peer sent a valid (and useful) message or response
+    ValidMessage,
+    /// The peer sent a message or response that is not actively malicious,
+    /// but doesn't help us make progress (e.g., timeouts or missing data).
+    NotUsefulMessage,
+    /// The peer sent a message or response that appears to be malicious
+    /// (e.g., an invalid proof or a malformed message).
+    InvalidMessage,
+    /// The peer failed too many successive health checks
+    FailedHealthCheck,
+    /// The peer failed a connection handshake (e.g., noise or protocol negotiation)
+    FailedHandshake,
+}
+
+impl ReputationEvent {
+    /// Returns a summary label for the event
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::ValidMessage => "valid_message",
+            Self::NotUsefulMessage => "not_useful_message",
+            Self::InvalidMessage => "invalid_message",
+            Self::FailedHealthCheck => "failed_health_check",
+            Self::FailedHandshake => "failed_handshake",
+        }
+    }
+
+    /// Returns the new score after applying the event to the given score
+    fn apply_to_score(&self, score: f64) -> f64 {
+        match self {
+            Self::ValidMessage => f64::min(score + VALID_MESSAGE_DELTA, MAX_SCORE),
+            Self::NotUsefulMessage | Self::FailedHealthCheck => {
+                f64::max(score * NOT_USEFUL_MULTIPLIER, MIN_SCORE)
+            },
+            Self::InvalidMessage | Self::FailedHandshake => {
+                f64::max(score * MALICIOUS_MULTIPLIER, MIN_SCORE)
+            },
+        }
+    }
+}
+
+/// The reputation of a single peer
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PeerReputation {
+    score: f64,                     // The current reputation score of the peer
+    num_bans: u64,                  // The number of times the peer has been banned
+    banned_until_secs: Option<u64>, // The unix time (secs) at which the latest ban expires
+}
+
+impl PeerReputation {
+    pub fn new() -> Self {
+        Self {
+            score: STARTING_SCORE,
+            num_bans: 0,
+            banned_until_secs: None,
+        }
+    }
+
+    /// Returns the number of times the peer has been banned
+    pub fn get_num_bans(&self) -> u64 {
+        self.num_bans
+    }
+
+    /// Returns the unix time (secs) at which the latest ban expires (if any)
+    pub fn get_banned_until_secs(&self) -> Option<u64> {
+        self.banned_until_secs
+    }
+
+    /// Returns the score of the peer at the given time. Once a ban
+    /// expires, the peer is given a fresh (starting) score.
+    pub fn get_score(&self, now_secs: u64) -> f64 {
+        if self.has_ban_expired(now_secs) {
+            STARTING_SCORE
+        } else {
+            self.score
+        }
+    }
+
+    /// Returns true iff the peer is banned at the given time
+    pub fn is_banned(&self, now_secs: u64) -> bool {
+        self.banned_until_secs
+            .map_or(false, |banned_until_secs| now_secs < banned_until_secs)
+    }
+
+    /// Returns true iff the peer was banned, but the ban has now expired
+    fn has_ban_expired(&self, now_secs: u64) -> bool {
+        self.banned_until_secs
+            .map_or(false, |banned_until_secs| now_secs >= banned_until_secs)
+    }
+
+    /// Returns true iff the peer is not banned and has the starting score, i.e., the
+    /// reputation carries no information and can be evicted.
+    fn is_neutral(&self, now_secs: u64) -> bool {
+        !self.is_banned(now_secs) && self.get_score(now_secs) == STARTING_SCORE
+    }
+
+    /// Resets the score of the peer if the latest ban has expired
+    fn reset_expired_ban(&mut self, now_secs: u64) {
+        if self.has_ban_expired(now_secs) {
+            self.score = STARTING_SCORE;
+            self.banned_until_secs = None;
+        }
+    }
+}
+
+impl Default for PeerReputation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A single persisted reputation entry. Entries are persisted as a list
+/// (instead of a map) because JSON map keys must be strings.
+#[derive(Debug, Deserialize, Serialize)]
+struct PersistedPeerReputation {
+    peer_network_id: PeerNetworkId,
+    reputation: PeerReputation,
+}
+
+/// A node-wide container of all peer reputations
+#[derive(Debug)]
+pub struct PeerReputations {
+    config: PeerReputationConfig,
+    reputations: RwLock<HashMap<PeerNetworkId, PeerReputation>>,
+    reputation_file: Option<PathBuf>, // The file to persist the reputations to (if any)
+    reputations_changed: AtomicBool,  // Whether the reputations changed since the last persist
+    time_service: TimeService,
+}
+
+impl PeerReputations {
+    /// Creates a new reputation store. If a reputation file is given,
+    /// any existing reputations are loaded from the file.
+    pub fn new(
+        config: PeerReputationConfig,
+        reputation_file: Option<PathBuf>,
+        time_service: TimeService,
+    ) -> Self {
+        // Load any persisted reputations
+        let reputations = match &reputation_file {
+            Some(reputation_file) if reputation_file.exists() => {
+                load_reputations(reputation_file).unwrap_or_else(|error| {
+                    warn!(
+                        "Failed to load the peer reputations from {:?}! Starting with empty reputations. Error: {:?}",
+                        reputation_file, error
+                    );
+                    HashMap::new()
+                })
+            },
+            _ => HashMap::new(),
+        };
+
+        Self {
+            config,
+            reputations: RwLock::new(reputations),
+            reputation_file,
+            reputations_changed: AtomicBool::new(false),
+            time_service,
+        }
+    }
+
+    /// Updates the reputation of the given peer according to the event.
+    /// If the peer's score drops to the ban threshold, the peer is
+    /// temporarily banned (the ban duration doubles for each repeat ban).
+    pub fn update_reputation(&self, peer_network_id: PeerNetworkId, event: ReputationEvent) {
+        // If peer reputations are disabled, there's nothing to do
+        if !self.config.enable_peer_reputation {
+            return;
+        }
+
+        let network_id = peer_network_id.network_id();
+        counters::peer_reputation_events(network_id.as_str(), event.get_label()).inc();
+
+        // Update the score of the peer
+        let now_secs = self.time_service.now_secs();
+        let mut reputations = self.reputations.write();
+        if !reputations.contains_key(&peer_network_id)
+            && reputations.len() >= self.config.max_num_reputations
+            && !self.evict_reputations(&mut reputations, now_secs)
+        {
+            warn!(
+                "Unable to track the reputation of peer {}! All {} tracked peers are banned.",
+                peer_network_id,
+                reputations.len()
+            );
+            return;
+        }
+        let reputation = reputations.entry(peer_network_id).or_default();
+        reputation.reset_expired_ban(now_secs);
+        if reputation.is_banned(now_secs) {
+            return; // Events don't affect the score of banned peers
+        }
+        reputation.score = event.apply_to_score(reputation.score);
+        self.reputations_changed.store(true, Ordering::Relaxed);
+
+        // Ban the peer if the score is too low. Note: only peers on the public
+        // network are banned, as the validator and VFN networks only connect
+        // to trusted peers (which must always remain connected).
+        if reputation.score <= self.config.ban_score_threshold && network_id.is_public_network() {
+            let ban_duration_secs = self.get_ban_duration_secs(reputation.num_bans);
+            reputation.num_bans += 1;
+            reputation.banned_until_secs = Some(now_secs.saturating_add(ban_duration_secs));
+
+            warn!(
+                "Banning peer {} for {} seconds (ban number: {}) due to a low reputation score: {}. Latest event: {:?}",
+                peer_network_id, ban_duration_secs, reputation.num_bans, reputation.score, event
+            );
+            counters::peer_bans(network_id.as_str()).inc();
+        }
+    }
+
+    /// Makes room for a new reputation once the max number of reputations is reached. All
+    /// neutral reputations (including expired bans) are evicted. If there are none, the
+    /// reputation closest to neutral is evicted (banned peers are never evicted). Returns
+    /// false iff no reputation could be evicted.
+    fn evict_reputations(
+        &self,
+        reputations: &mut HashMap<PeerNetworkId, PeerReputation>,
+        now_secs: u64,
+    ) -> bool {
+        let num_reputations = reputations.len();
+        reputations.retain(|_, reputation| !reputation.is_neutral(now_secs));
+        let mut num_evicted = num_reputations - reputations.len();
+
+        if num_evicted == 0 {
+            let closest_to_neutral = reputations
+                .iter()
+                .filter(|(_, reputation)| !reputation.is_banned(now_secs))
+                .min_by(|(_, reputation), (_, other_reputation)| {
+                    let distance = (reputation.score - STARTING_SCORE).abs();
+                    let other_distance = (other_reputation.score - STARTING_SCORE).abs();
+                    distance.total_cmp(&other_distance)
+                })
+                .map(|(peer_network_id, _)| *peer_network_id);
+            if let Some(peer_network_id) = closest_to_neutral {
+                reputations.remove(&peer_network_id);
+                num_evicted = 1;
+            }
+        }
+
+        if num_evicted > 0 {
+            counters::APTOS_NETWORK_PEER_REPUTATION_EVICTIONS.inc_by(num_evicted as u64);
+            self.reputations_changed.store(true, Ordering::Relaxed);
+        }
+        num_evicted > 0
+    }
+
+    /// Returns the ban duration (secs) for a peer with the given number of previous bans
+    fn get_ban_duration_secs(&self, num_previous_bans: u64) -> u64 {
+        let multiplier = 2u64.saturating_pow(u32::try_from(num_previous_bans).unwrap_or(u32::MAX));
+        self.config
+            .ban_duration_secs
+            .saturating_mul(multiplier)
+            .min(self.config.max_ban_duration_secs)
+    }
+
+    /// Returns true iff the given peer is currently banned
+    pub fn is_banned(&self, peer_network_id: &PeerNetworkId) -> bool {
+        if !self.config.enable_peer_reputation {
+            return false;
+        }
+
+        let now_secs = self.time_service.now_secs();
+        self.reputations
+            .read()
+            .get(peer_network_id)
+            .map_or(false, |reputation| reputation.is_banned(now_secs))
+    }
+
+    /// Returns true iff the given peer should be deprioritized (e.g., when
+    /// choosing peers to dial), because its score is below the threshold.
+    pub fn is_deprioritized(&self, peer_network_id: &PeerNetworkId) -> bool {
+        if !self.config.enable_peer_reputation {
+            return false;
+        }
+
+        let now_secs = self.time_service.now_secs();
+        self.reputations
+            .read()
+            .get(peer_network_id)
+            .map_or(false, |reputation| {
+                reputation.get_score(now_secs) < self.config.deprioritize_score_threshold
+            })
+    }
+
+    /// Returns the reputation of the given peer (if one exists)
+    pub fn get_reputation(&self, peer_network_id: &PeerNetworkId) -> Option<PeerReputation> {
+        self.reputations.read().get(peer_network_id).cloned()
+    }
+
+    /// Returns the reputations of all peers
+    pub fn get_all_reputations(&self) -> HashMap<PeerNetworkId, PeerReputation> {
+        self.reputations.read().clone()
+    }
+
+    /// Returns the current unix time (secs) used to evaluate bans
+    pub fn now_secs(&self) -> u64 {
+        self.time_service.now_secs()
+    }
+
+    /// Persists the reputations to the reputation file (if the
+    /// reputations have changed since they were last persisted).
+    pub fn persist(&self) -> Result<(), Error> {
+        // If there's no reputation file, there's nothing to do
+        let reputation_file = match &self.reputation_file {
+            Some(reputation_file) => reputation_file,
+            None => return Ok(()),
+        };
+
+        // If the reputations haven't changed, there's nothing to do
+        if !self.reputations_changed.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        // Serialize the reputations
+        let persisted_reputations: Vec<_> = self
+            .get_all_reputations()
+            .into_iter()
+            .map(|(peer_network_id, reputation)| PersistedPeerReputation {
+                peer_network_id,
+                reputation,
+            })
+            .collect();
+        let serialized_reputations =
+            serde_json::to_vec(&persisted_reputations).map_err(|error| {
+                Error::UnexpectedError(format!(
+                    "Failed to serialize the peer reputations: {}",
+                    error
+                ))
+            })?;
+
+        // Write the reputations to a temporary file and move it into place (to avoid partial writes)
+        let temp_file = reputation_file.with_extension("tmp");
+        fs::write(&temp_file, serialized_reputations)
+            .and_then(|_| fs::rename(&temp_file, reputation_file))
+            .map_err(|error| {
+                self.reputations_changed.store(true, Ordering::Relaxed); // Retry on the next persist
+                Error::UnexpectedError(format!(
+                    "Failed to write the peer reputations to {:?}: {}",
+                    reputation_file, error
+                ))
+            })
+    }
+
+    /// Periodically persists the reputations to disk. This should be
+    /// spawned once (for the entire node).
+    pub async fn start_persistence_loop(self: Arc<Self>) {
+        // If there's no reputation file, there's nothing to do
+        if self.reputation_file.is_none() {
+            return;
+        }
+
+        info!(
+            "Starting the peer reputation persistence loop. Reputation file: {:?}",
+            self.reputation_file
+        );
+        let persist_interval = Duration::from_secs(self.config.persist_interval_secs);
+        let ticker = self.time_service.interval(persist_interval);
+        futures::pin_mut!(ticker);
+
+        while ticker.next().await.is_some() {
+            if let Err(error) = self.persist() {
+                warn!("Failed to persist the peer reputations! Error: {:?}", error);
+            }
+        }
+    }
+}
+
+impl Default for PeerReputations {
+    fn default() -> Self {
+        Self::new(PeerReputationConfig::default(), None, TimeService::real())
+    }
+}
+
+/// Loads the persisted reputations from the given file
+fn load_reputations(
+    reputation_file: &Path,
+) -> Result<HashMap<PeerNetworkId, PeerReputation>, Error> {
+    let serialized_reputations = fs::read(reputation_file).map_err(|error| {
+        Error::UnexpectedError(format!("Failed to read the reputation file: {}", error))
+    })?;
+    let persisted_reputations: Vec<PersistedPeerReputation> =
+        serde_json::from_slice(&serialized_reputations).map_err(|error| {
+            Error::UnexpectedError(format!("Failed to deserialize the reputations: {}", error))
+        })?;
+
+    Ok(persisted_reputations
+        .into_iter()
+        .map(|persisted_reputation| {
+            (
+                persisted_reputation.peer_network_id,
+                persisted_reputation.reputation,
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+    use aptos_temppath::TempPath;
+    use aptos_types::PeerId;
+
+    /// Returns a reputation config with peer reputations enabled
+    fn enabled_config() -> PeerReputationConfig {
+        PeerReputationConfig {
+            enable_peer_reputation: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ban_and_expiry() {
+        // Create a reputation store with a mock time service
+        let time_service = TimeService::mock();
+        let peer_reputations = PeerReputations::new(enabled_config(), None, time_service.clone());
+
+        // Send invalid messages until the peer is banned
+        let peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        while !peer_reputations.is_banned(&peer) {
+            peer_reputations.update_reputation(peer, ReputationEvent::InvalidMessage);
+        }
+        assert_eq!(
+            peer_reputations
+                .get_reputation(&peer)
+                .unwrap()
+                .get_num_bans(),
+            1
+        );
+        assert!(peer_reputations.is_deprioritized(&peer));
+
+        // Verify that events are ignored while the peer is banned
+        let reputation = peer_reputations.get_reputation(&peer).unwrap();
+        peer_reputations.update_reputation(peer, ReputationEvent::ValidMessage);
+        assert_eq!(peer_reputations.get_reputation(&peer).unwrap(), reputation);
+
+        // Elapse the ban and verify the peer is given a fresh score
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance_secs(enabled_config().ban_duration_secs);
+        assert!(!peer_reputations.is_banned(&peer));
+        assert!(!peer_reputations.is_deprioritized(&peer));
+        assert_eq!(
+            peer_reputations
+                .get_reputation(&peer)
+                .unwrap()
+                .get_score(peer_reputations.now_secs()),
+            STARTING_SCORE
+        );
+    }
+
+    #[test]
+    fn test_ban_duration_doubles() {
+        // Create a reputation store with a mock time service
+        let config = enabled_config();
+        let time_service = TimeService::mock();
+        let peer_reputations = PeerReputations::new(config.clone(), None, time_service.clone());
+        let mock_time_service = time_service.into_mock();
+
+        // Ban the peer several times and verify the ban duration doubles (up to the max)
+        let peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        for num_bans in 1..=10 {
+            while !peer_reputations.is_banned(&peer) {
+                peer_reputations.update_reputation(peer, ReputationEvent::FailedHandshake);
+            }
+
+            let reputation = peer_reputations.get_reputation(&peer).unwrap();
+            let expected_duration_secs =
+                (config.ban_duration_secs << (num_bans - 1)).min(config.max_ban_duration_secs);
+            assert_eq!(reputation.get_num_bans(), num_bans);
+            assert_eq!(
+                reputation.get_banned_until_secs(),
+                Some(peer_reputations.now_secs() + expected_duration_secs)
+            );
+
+            mock_time_service.advance_secs(expected_duration_secs);
+        }
+    }
+
+    #[test]
+    fn test_trusted_networks_are_never_banned() {
+        // Create a reputation store
+        let peer_reputations = PeerReputations::new(enabled_config(), None, TimeService::mock());
+
+        // Send many invalid messages from peers on the validator and VFN networks
+        for network_id in [NetworkId::Validator, NetworkId::Vfn] {
+            let peer = PeerNetworkId::new(network_id, PeerId::random());
+            for _ in 0..100 {
+                peer_reputations.update_reputation(peer, ReputationEvent::InvalidMessage);
+            }
+
+            // Verify the peer is deprioritized, but not banned
+            assert!(peer_reputations.is_deprioritized(&peer));
+            assert!(!peer_reputations.is_banned(&peer));
+        }
+    }
+
+    #[test]
+    fn test_disabled_reputations() {
+        // Create a reputation store with the default config (reputations disabled)
+        let peer_reputations =
+            PeerReputations::new(PeerReputationConfig::default(), None, TimeService::mock());
+
+        // Send many invalid messages and verify the peer is not tracked
+        let peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        for _ in 0..100 {
+            peer_reputations.update_reputation(peer, ReputationEvent::InvalidMessage);
+        }
+        assert!(peer_reputations.get_reputation(&peer).is_none());
+        assert!(!peer_reputations.is_banned(&peer));
+        assert!(!peer_reputations.is_deprioritized(&peer));
+    }
+
+    #[test]
+    fn test_reputation_eviction() {
+        // Create a reputation store that tracks at most 3 peers
+        let config = PeerReputationConfig {
+            max_num_reputations: 3,
+            ..enabled_config()
+        };
+        let time_service = TimeService::mock();
+        let peer_reputations = PeerReputations::new(config.clone(), None, time_service.clone());
+
+        // Ban one peer, and update the score of another
+        let banned_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        while !peer_reputations.is_banned(&banned_peer) {
+            peer_reputations.update_reputation(banned_peer, ReputationEvent::InvalidMessage);
+        }
+        let good_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        for _ in 0..10 {
+            peer_reputations.update_reputation(good_peer, ReputationEvent::ValidMessage);
+        }
+
+        // Track a third peer, which fills the store
+        let slightly_good_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        peer_reputations.update_reputation(slightly_good_peer, ReputationEvent::ValidMessage);
+        assert_eq!(peer_reputations.get_all_reputations().len(), 3);
+
+        // Track a new peer and verify the reputation closest to neutral is evicted
+        let new_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        peer_reputations.update_reputation(new_peer, ReputationEvent::NotUsefulMessage);
+        let reputations = peer_reputations.get_all_reputations();
+        assert_eq!(reputations.len(), 3);
+        assert!(!reputations.contains_key(&slightly_good_peer));
+        assert!(peer_reputations.is_banned(&banned_peer));
+
+        // Elapse the ban, and verify the expired (neutral) reputation is evicted first
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance_secs(config.ban_duration_secs);
+        peer_reputations.update_reputation(slightly_good_peer, ReputationEvent::ValidMessage);
+        let reputations = peer_reputations.get_all_reputations();
+        assert_eq!(reputations.len(), 3);
+        assert!(!reputations.contains_key(&banned_peer));
+        assert!(reputations.contains_key(&good_peer));
+        assert!(reputations.contains_key(&new_peer));
+    }
+
+    #[test]
+    fn test_banned_peers_are_never_evicted() {
+        // Create a reputation store that tracks a single peer
+        let config = PeerReputationConfig {
+            max_num_reputations: 1,
+            ..enabled_config()
+        };
+        let peer_reputations = PeerReputations::new(config, None, TimeService::mock());
+
+        // Ban a peer, and verify a new peer can't evict it
+        let banned_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        while !peer_reputations.is_banned(&banned_peer) {
+            peer_reputations.update_reputation(banned_peer, ReputationEvent::InvalidMessage);
+        }
+        let new_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        peer_reputations.update_reputation(new_peer, ReputationEvent::InvalidMessage);
+        assert!(peer_reputations.is_banned(&banned_peer));
+        assert!(peer_reputations.get_reputation(&new_peer).is_none());
+    }
+
+    #[test]
+    fn test_persist_and_load() {
+        // Create a reputation store backed by a temporary file
+        let reputation_file = TempPath::new();
+        let time_service = TimeService::mock();
+        let peer_reputations = PeerReputations::new(
+            enabled_config(),
+            Some(reputation_file.path().to_path_buf()),
+            time_service.clone(),
+        );
+
+        // Ban one peer and update the score of another
+        let banned_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        while !peer_reputations.is_banned(&banned_peer) {
+            peer_reputations.update_reputation(banned_peer, ReputationEvent::InvalidMessage);
+        }
+        let other_peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+        peer_reputations.update_reputation(other_peer, ReputationEvent::ValidMessage);
+
+        // Persist the reputations and load them into a new store
+        peer_reputations.persist().unwrap();
+        let loaded_peer_reputations = PeerReputations::new(
+            enabled_config(),
+            Some(reputation_file.path().to_path_buf()),
+            time_service,
+        );
+
+        // Verify the reputations (and the ban) survived the reload
+        assert_eq!(
+            loaded_peer_reputations.get_all_reputations(),
+            peer_reputations.get_all_reputations()
+        );
+        assert!(loaded_peer_reputations.is_banned(&banned_peer));
+        assert!(!loaded_peer_reputations.is_banned(&other_peer));
+    }
+}
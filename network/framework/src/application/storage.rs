@@ -6,6 +6,7 @@ use crate::{
     application::{
         error::Error,
         metadata::{ConnectionState, PeerMetadata},
+        reputation::PeerReputations,
     },
     counters,
     peer_manager::ConnectionNotification,
@@ -52,16 +53,28 @@ pub struct PeersAndMetadata {
     cached_peers_and_metadata: Arc<ArcSwap<HashMap<NetworkId, HashMap<PeerId, PeerMetadata>>>>,
 
     subscribers: Mutex<Vec<tokio::sync::mpsc::Sender<ConnectionNotification>>>,
+
+    // The node-wide peer reputations (e.g., used to ban misbehaving peers)
+    peer_reputations: Arc<PeerReputations>,
 }
 
 impl PeersAndMetadata {
     pub fn new(network_ids: &[NetworkId]) -> Arc<PeersAndMetadata> {
+        Self::new_with_peer_reputations(network_ids, Arc::new(PeerReputations::default()))
+    }
+
+    /// Creates the container using the given (e.g., persisted) peer reputations
+    pub fn new_with_peer_reputations(
+        network_ids: &[NetworkId],
+        peer_reputations: Arc<PeerReputations>,
+    ) -> Arc<PeersAndMetadata> {
         // Create the container
         let mut peers_and_metadata = PeersAndMetadata {
             peers_and_metadata: RwLock::new(HashMap::new()),
             trusted_peers: HashMap::new(),
             cached_peers_and_metadata: Arc::new(ArcSwap::from(Arc::new(HashMap::new()))),
             subscribers: Mutex::new(vec![]),
+            peer_reputations,
         };
 
         // Initialize each network mapping and trusted peer set
@@ -87,6 +100,11 @@ impl PeersAndMetadata {
         Arc::new(peers_and_metadata)
     }
 
+    /// Returns the node-wide peer reputations
+    pub fn get_peer_reputations(&self) -> Arc<PeerReputations> {
+        self.peer_reputations.clone()
+    }
+
     /// Returns all peers. Note: this will return disconnected and unhealthy peers, so
     /// it is not recommended for applications to use this interface. Instead,
     /// `get_connected_peers_and_metadata()` should be used.
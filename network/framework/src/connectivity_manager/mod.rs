@@ -37,7 +37,7 @@ use crate::{
 };
 use aptos_config::{
     config::{Peer, PeerRole, PeerSet},
-    network_id::NetworkContext,
+    network_id::{NetworkContext, PeerNetworkId},
 };
 use aptos_crypto::x25519;
 use aptos_infallible::RwLock;
@@ -141,6 +141,7 @@ pub enum DiscoverySource {
     OnChainValidatorSet,
     File,
     Rest,
+    Dns,
     Config,
 }
 
@@ -157,6 +158,7 @@ impl fmt::Display for DiscoverySource {
             DiscoverySource::File => "File",
             DiscoverySource::Config => "Config",
             DiscoverySource::Rest => "Rest",
+            DiscoverySource::Dns => "Dns",
         })
     }
 }
@@ -531,6 +533,47 @@ where
         }
     }
 
+    /// Disconnect from all peers that are currently banned (e.g.,
+    /// because they sent too many invalid messages).
+    async fn close_banned_connections(&mut self) {
+        // Identify the banned peer connections
+        let network_id = self.network_context.network_id();
+        let peer_reputations = self.peers_and_metadata.get_peer_reputations();
+        let banned_peers: Vec<_> = self
+            .connected
+            .keys()
+            .filter(|peer_id| {
+                peer_reputations.is_banned(&PeerNetworkId::new(network_id, **peer_id))
+            })
+            .cloned()
+            .collect();
+
+        // Close existing connections to banned peers
+        for banned_peer in banned_peers {
+            info!(
+                NetworkSchema::new(&self.network_context).remote_peer(&banned_peer),
+                "{} Closing connection to banned peer {}",
+                self.network_context,
+                banned_peer.short_str()
+            );
+
+            if let Err(disconnect_error) = self
+                .connection_reqs_tx
+                .disconnect_peer(banned_peer, DisconnectReason::BannedPeer)
+                .await
+            {
+                info!(
+                    NetworkSchema::new(&self.network_context).remote_peer(&banned_peer),
+                    error = %disconnect_error,
+                    "{} Failed to close connection to banned peer {}, error: {}",
+                    self.network_context,
+                    banned_peer.short_str(),
+                    disconnect_error
+                );
+            }
+        }
+    }
+
     /// Cancel all pending dials to peers that are no longer eligible.
     ///
     /// For instance, a validator might leave the validator set after a
@@ -576,6 +619,7 @@ where
         let role = self.network_context.role();
         let roles_to_dial = network_id.upstream_roles(&role);
         let discovered_peers = self.discovered_peers.read().peer_set.clone();
+        let peer_reputations = self.peers_and_metadata.get_peer_reputations();
         let eligible_peers: Vec<_> = discovered_peers
             .into_iter()
             .filter(|(peer_id, peer)| {
@@ -584,6 +628,10 @@ where
                     && !self.dial_queue.contains_key(peer_id) // There is no pending dial to this node
                     && roles_to_dial.contains(&peer.role) // We can dial this role
             })
+            .filter(|(peer_id, _)| {
+                // The node is not currently banned
+                !peer_reputations.is_banned(&PeerNetworkId::new(network_id, *peer_id))
+            })
             .collect();
 
         // Initialize the dial state for any new peers
@@ -625,7 +673,41 @@ where
             return vec![];
         }
 
-        // Prioritize the eligible peers and select the peers to dial
+        // Separate the peers with a poor reputation, so that they are only
+        // dialed if there aren't enough other peers to dial.
+        let (deprioritized_peers, preferred_peers): (Vec<_>, Vec<_>) =
+            eligible_peers.into_iter().partition(|(peer_id, _)| {
+                peer_reputations.is_deprioritized(&PeerNetworkId::new(network_id, *peer_id))
+            });
+
+        // Select the peers to dial from the preferred peers
+        let mut peers_to_dial = self
+            .select_peers_to_dial(preferred_peers, num_peers_to_dial)
+            .await;
+
+        // If not enough peers were selected, select the remaining peers from the deprioritized peers
+        let num_remaining_peers = num_peers_to_dial.saturating_sub(peers_to_dial.len());
+        if num_remaining_peers > 0 {
+            let remaining_peers_to_dial = self
+                .select_peers_to_dial(deprioritized_peers, num_remaining_peers)
+                .await;
+            peers_to_dial.extend(remaining_peers_to_dial);
+        }
+
+        peers_to_dial
+    }
+
+    /// Prioritizes the given eligible peers and selects the peers to dial
+    async fn select_peers_to_dial(
+        &mut self,
+        eligible_peers: Vec<(PeerId, DiscoveredPeer)>,
+        num_peers_to_dial: usize,
+    ) -> Vec<(PeerId, DiscoveredPeer)> {
+        // If there are no eligible peers, return early
+        if eligible_peers.is_empty() {
+            return vec![];
+        }
+
         if selection::should_select_peers_by_latency(
             &self.network_context,
             self.enable_latency_aware_dialing,
@@ -828,6 +910,8 @@ where
         self.cancel_stale_dials().await;
         // Disconnect from connected peers that are no longer eligible.
         self.close_stale_connections().await;
+        // Disconnect from connected peers that are currently banned.
+        self.close_banned_connections().await;
         // Dial peers which are eligible but are neither connected nor queued for dialing in the
         // future.
         self.dial_eligible_peers(pending_dials).await;
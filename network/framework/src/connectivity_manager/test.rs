@@ -4,12 +4,13 @@
 
 use super::*;
 use crate::{
+    application::reputation::ReputationEvent,
     peer_manager::{conn_notifs_channel, ConnectionNotification, ConnectionRequest},
     transport::ConnectionMetadata,
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION},
+    config::{Peer, PeerRole, PeerSet, RoleType, HANDSHAKE_VERSION},
     network_id::NetworkId,
 };
 use aptos_crypto::{test_utils::TEST_SEED, x25519, Uniform};
@@ -909,6 +910,57 @@ async fn test_stale_peers_vfn_inbound() {
 }
 
 /// Verifies that the trusted peers match the expected set
+#[tokio::test(flavor = "multi_thread")]
+async fn test_banned_peers() {
+    // Create two upstream seed peers
+    let (peer_id_1, mut peer_1, _, _) = test_peer(AccountAddress::ONE);
+    let (peer_id_2, mut peer_2, _, _) = test_peer(AccountAddress::TWO);
+    peer_1.role = PeerRole::Upstream;
+    peer_2.role = PeerRole::Upstream;
+    let seeds = hashmap! {peer_id_1 => peer_1, peer_id_2 => peer_2};
+
+    // Create a connectivity manager for a fullnode on the public network
+    let (mut mock, mut connectivity_manager) = TestHarness::new(seeds);
+    let network_context =
+        NetworkContext::new(RoleType::FullNode, NetworkId::Public, PeerId::random());
+    connectivity_manager.network_context = network_context;
+    connectivity_manager.enable_latency_aware_dialing = false;
+
+    // Ban peer 1 by sending invalid messages
+    let peer_reputations = mock.peers_and_metadata.get_peer_reputations();
+    let peer_network_id_1 = PeerNetworkId::new(NetworkId::Public, peer_id_1);
+    while !peer_reputations.is_banned(&peer_network_id_1) {
+        peer_reputations.update_reputation(peer_network_id_1, ReputationEvent::InvalidMessage);
+    }
+
+    // Verify that only peer 2 is chosen to be dialed
+    let peers_to_dial: Vec<_> = connectivity_manager
+        .choose_peers_to_dial()
+        .await
+        .into_iter()
+        .map(|(peer_id, _)| peer_id)
+        .collect();
+    assert_eq!(peers_to_dial, vec![peer_id_2]);
+
+    // Connect peer 1 (e.g., the connection was established before the ban)
+    let connection_metadata_1 = ConnectionMetadata::mock_with_role_and_origin(
+        peer_id_1,
+        PeerRole::Upstream,
+        ConnectionOrigin::Outbound,
+    );
+    let connection_notification = ConnectionNotification::NewPeer(
+        connection_metadata_1.clone(),
+        network_context.network_id(),
+    );
+    connectivity_manager.handle_control_notification(connection_notification);
+
+    // Close the banned connections and verify that peer 1 is disconnected
+    tokio::join!(
+        connectivity_manager.close_banned_connections(),
+        mock.expect_disconnect_fail(peer_id_1, connection_metadata_1.addr)
+    );
+}
+
 fn verify_trusted_peers(
     peers_and_metadata: &Arc<PeersAndMetadata>,
     network_id: &NetworkId,
@@ -5,9 +5,9 @@
 use crate::protocols::wire::handshake::v1::ProtocolId;
 use aptos_config::network_id::NetworkContext;
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge,
-    register_int_gauge_vec, Histogram, HistogramTimer, HistogramVec, IntCounter, IntCounterVec,
-    IntGauge, IntGaugeVec,
+    exponential_buckets, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec, Histogram, HistogramTimer, HistogramVec,
+    IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_short_hex_str::AsShortHexStr;
@@ -469,6 +469,15 @@ pub static PENDING_MULTIPLEX_STREAM: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of pending messages read from QUIC streams
+pub static PENDING_QUIC_STREAM_MESSAGES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_network_pending_quic_stream_messages",
+        "Number of pending messages read from QUIC streams"
+    )
+    .unwrap()
+});
+
 /// Counter of pending requests in Direct Send
 pub static PENDING_DIRECT_SEND_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -550,6 +559,90 @@ pub static NETWORK_RATE_LIMIT_METRICS: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Outbound bytes allowed and throttled by the protocol rate limits (per peer and second)
+pub static NETWORK_PROTOCOL_RATE_LIMIT_METRICS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_network_protocol_rate_limit",
+        "Network Outbound Protocol Rate Limiting Metrics",
+        &["protocol_id", "metric"]
+    )
+    .unwrap()
+});
+
+/// Time outbound messages wait in the (prioritized) outbound queues of a peer
+pub static APTOS_NETWORK_OUTBOUND_QUEUE_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_network_outbound_queue_latency",
+        "Time outbound messages wait in the outbound queues, by protocol",
+        &["role_type", "network_id", "protocol_id"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
+pub fn outbound_queue_latency(network_context: &NetworkContext, protocol_label: &str) -> Histogram {
+    APTOS_NETWORK_OUTBOUND_QUEUE_LATENCY.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        protocol_label,
+    ])
+}
+
+/// Counter of outbound messages dropped because the queue of their protocol was full
+pub static APTOS_NETWORK_OUTBOUND_QUEUE_DROPPED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_outbound_queue_dropped_messages",
+        "Number of outbound messages dropped from full outbound queues, by protocol",
+        &["role_type", "network_id", "protocol_id"]
+    )
+    .unwrap()
+});
+
+pub fn outbound_queue_dropped_messages(
+    network_context: &NetworkContext,
+    protocol_label: &str,
+) -> IntCounter {
+    APTOS_NETWORK_OUTBOUND_QUEUE_DROPPED_MESSAGES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        protocol_label,
+    ])
+}
+
+pub static APTOS_NETWORK_PEER_REPUTATION_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_peer_reputation_events",
+        "Number of peer reputation events, by network and event type",
+        &["network_id", "event"]
+    )
+    .unwrap()
+});
+
+pub fn peer_reputation_events(network_label: &str, event_label: &str) -> IntCounter {
+    APTOS_NETWORK_PEER_REPUTATION_EVENTS.with_label_values(&[network_label, event_label])
+}
+
+pub static APTOS_NETWORK_PEER_BANS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_peer_bans",
+        "Number of peers temporarily banned due to a low reputation score",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
+pub fn peer_bans(network_label: &str) -> IntCounter {
+    APTOS_NETWORK_PEER_BANS.with_label_values(&[network_label])
+}
+
+pub static APTOS_NETWORK_PEER_REPUTATION_EVICTIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_network_peer_reputation_evictions",
+        "Number of peer reputations evicted to bound the number of tracked peers"
+    )
+    .unwrap()
+});
+
 pub static NETWORK_APPLICATION_INBOUND_METRIC: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         "aptos_network_app_inbound_traffic",
@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    application::reputation::PeerReputations,
     constants,
-    peer::Peer,
+    peer::{outbound_queues::ProtocolQuotas, Peer},
     protocols::wire::{
         handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
         messaging::v1::{MultiplexMessage, MultiplexMessageSink},
@@ -86,7 +87,11 @@ pub fn fuzz(data: &[u8]) {
         ProtocolIdSet::all_known(),
         PeerRole::Unknown,
     );
-    let connection = Connection { socket, metadata };
+    let connection = Connection {
+        socket,
+        metadata,
+        quic_connection: None,
+    };
 
     let (connection_notifs_tx, connection_notifs_rx) = aptos_channels::new_test(8);
     let channel_size = 8;
@@ -108,6 +113,8 @@ pub fn fuzz(data: &[u8]) {
         constants::MAX_CONCURRENT_OUTBOUND_RPCS,
         constants::MAX_FRAME_SIZE,
         constants::MAX_MESSAGE_SIZE,
+        Arc::new(ProtocolQuotas::default()),
+        Arc::new(PeerReputations::default()),
     );
     executor.spawn(peer.start());
 
@@ -11,16 +11,20 @@
 //! shutting down when the [`PeerManager`] requests it or the connection is lost.
 //!
 //! [`Peer`] owns the actual underlying connection socket and is reponsible for
-//! the socket's shutdown, graceful or otherwise.
+//! the socket's shutdown, graceful or otherwise. On QUIC connections, the messages
+//! of each protocol are sent on a separate QUIC stream (alongside the socket), so
+//! that a slow or lossy protocol doesn't block the messages of other protocols.
 //!
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
+    application::reputation::{PeerReputations, ReputationEvent},
     counters::{
         self, network_application_inbound_traffic, network_application_outbound_traffic,
         DECLINED_LABEL, FAILED_LABEL, RECEIVED_LABEL, SENT_LABEL, UNKNOWN_LABEL,
     },
     logging::NetworkSchema,
+    peer::outbound_queues::{NextMessage, OutboundQueues, ProtocolQuotas},
     peer_manager::{PeerManagerError, TransportNotification},
     protocols::{
         direct_send::Message,
@@ -38,6 +42,7 @@ use crate::{
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::network_id::{NetworkContext, PeerNetworkId};
 use aptos_logger::prelude::*;
+use aptos_netcore::transport::quic::QuicConnection;
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::PeerId;
@@ -46,7 +51,7 @@ use futures::{
     channel::oneshot,
     io::{AsyncRead, AsyncWrite},
     stream::StreamExt,
-    SinkExt,
+    FutureExt, SinkExt,
 };
 use futures_util::stream::select;
 use serde::Serialize;
@@ -61,6 +66,10 @@ mod test;
 
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod fuzzing;
+pub mod outbound_queues;
+
+/// The maximum number of queued outbound messages per protocol
+const MAX_QUEUED_MESSAGES_PER_PROTOCOL: usize = 1024;
 
 /// Requests [`Peer`] receives from the [`PeerManager`](crate::peer_manager::PeerManager).
 #[derive(Debug)]
@@ -71,6 +80,11 @@ pub enum PeerRequest {
     SendDirectSend(Message),
 }
 
+/// An outbound message for the writer task, along with the protocol it belongs to
+/// (if any). On QUIC connections, the protocol selects the stream the message is
+/// written to. Messages without a protocol are always written to the socket.
+pub type WriteRequest = (Option<ProtocolId>, NetworkMessage);
+
 /// The reason for closing a network connection
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum DisconnectReason {
@@ -79,6 +93,7 @@ pub enum DisconnectReason {
     NetworkHealthCheckFailure, // The connection failed the network health check (e.g., pings)
     RequestedByPeerManager, // The peer manager requested the connection to be closed
     StaleConnection,  // The connection is stale (e.g., when a validator leaves the validator set)
+    BannedPeer,       // The peer is temporarily banned (e.g., due to a low reputation score)
 }
 
 impl DisconnectReason {
@@ -90,6 +105,7 @@ impl DisconnectReason {
             DisconnectReason::NetworkHealthCheckFailure => "NetworkHealthCheckFailure",
             DisconnectReason::RequestedByPeerManager => "RequestedByPeerManager",
             DisconnectReason::StaleConnection => "StaleConnection",
+            DisconnectReason::BannedPeer => "BannedPeer",
         };
         label.to_string()
     }
@@ -119,6 +135,8 @@ pub struct Peer<TSocket> {
     connection_metadata: ConnectionMetadata,
     /// Underlying connection.
     connection: Option<TSocket>,
+    /// The QUIC connection of the socket (if any), used for per-protocol streams.
+    quic_connection: Option<QuicConnection>,
     /// Channel to notify PeerManager that we've disconnected.
     connection_notifs_tx: aptos_channels::Sender<TransportNotification<TSocket>>,
     /// Channel to receive requests from PeerManager to send messages and rpcs.
@@ -136,6 +154,10 @@ pub struct Peer<TSocket> {
     max_frame_size: usize,
     /// The maximum size of an inbound or outbound request message
     max_message_size: usize,
+    /// The priorities and rate limits of outbound messages, per protocol
+    outbound_quotas: Arc<ProtocolQuotas>,
+    /// The node-wide peer reputations, updated when the peer sends malformed messages
+    peer_reputations: Arc<PeerReputations>,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
 }
@@ -160,10 +182,13 @@ where
         max_concurrent_outbound_rpcs: u32,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_quotas: Arc<ProtocolQuotas>,
+        peer_reputations: Arc<PeerReputations>,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
             socket,
+            quic_connection,
         } = connection;
         let remote_peer_id = connection_metadata.remote_peer_id;
         let max_fragments = max_message_size / max_frame_size;
@@ -173,6 +198,7 @@ where
             time_service: time_service.clone(),
             connection_metadata,
             connection: Some(socket),
+            quic_connection,
             connection_notifs_tx,
             peer_reqs_rx,
             upstream_handlers,
@@ -192,6 +218,8 @@ where
             state: State::Connected,
             max_frame_size,
             max_message_size,
+            outbound_quotas,
+            peer_reputations,
             inbound_stream: InboundStreamBuffer::new(max_fragments),
         }
     }
@@ -218,6 +246,23 @@ where
             MultiplexMessageStream::new(read_socket.compat(), self.max_frame_size).fuse();
         let writer = MultiplexMessageSink::new(write_socket.compat_write(), self.max_frame_size);
 
+        // Start the tasks that read messages from the QUIC streams opened
+        // by the remote peer (if this is a QUIC connection).
+        let quic_connection = self.quic_connection.take();
+        let (quic_messages_tx, mut quic_messages_rx) =
+            aptos_channels::new(1024, &counters::PENDING_QUIC_STREAM_MESSAGES);
+        if let Some(quic_connection) = quic_connection.clone() {
+            Self::start_quic_reader_tasks(
+                &self.executor,
+                quic_connection,
+                quic_messages_tx,
+                self.network_context,
+                remote_peer_id,
+                self.max_frame_size,
+                self.max_message_size / self.max_frame_size,
+            );
+        }
+
         // Start writer "process" as a separate task. We receive two handles to
         // communicate with the task:
         //   1. `write_reqs_tx`: Queue of pending NetworkMessages to write.
@@ -228,6 +273,8 @@ where
             self.connection_metadata.clone(),
             self.network_context,
             writer,
+            quic_connection,
+            self.outbound_quotas.clone(),
             self.max_frame_size,
             self.max_message_size,
         );
@@ -269,6 +316,21 @@ where
                         None => self.shutdown(DisconnectReason::ConnectionClosed),
                     }
                 },
+                // Handle a new inbound NetworkMessage that we've just read off
+                // one of the QUIC streams opened by the remote peer.
+                message = quic_messages_rx.select_next_some() => {
+                    if let Err(err) = self.handle_inbound_network_message(message) {
+                        warn!(
+                            NetworkSchema::new(&self.network_context)
+                                .connection_metadata(&self.connection_metadata),
+                            error = %err,
+                            "{} Error in handling inbound QUIC stream message from peer: {}, error: {}",
+                            self.network_context,
+                            remote_peer_id.short_str(),
+                            err
+                        );
+                    }
+                },
                 // Drive the queue of pending inbound rpcs. When one is fulfilled
                 // by an upstream protocol, send the response to the remote peer.
                 maybe_response = self.inbound_rpcs.next_completed_response() => {
@@ -325,20 +387,26 @@ where
     // 2. The second channel is used to instruct the task to close the connection and terminate.
     // If outbound messages are queued when the task receives a close instruction, it discards
     // them and immediately closes the connection.
+    //
+    // Outbound messages are queued per protocol, and are written in order of protocol priority,
+    // subject to the byte-rate limits of the protocols (see `OutboundQueues`).
+    //
+    // On QUIC connections, messages that belong to a protocol are instead handed to a
+    // separate writer task for the protocol, which writes them to its own QUIC stream.
+    #[allow(clippy::too_many_arguments)]
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
         connection_metadata: ConnectionMetadata,
         network_context: NetworkContext,
         mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
+        quic_connection: Option<QuicConnection>,
+        outbound_quotas: Arc<ProtocolQuotas>,
         max_frame_size: usize,
         max_message_size: usize,
-    ) -> (
-        aptos_channel::Sender<(), NetworkMessage>,
-        oneshot::Sender<()>,
-    ) {
+    ) -> (aptos_channel::Sender<(), WriteRequest>, oneshot::Sender<()>) {
         let remote_peer_id = connection_metadata.remote_peer_id;
-        let (write_reqs_tx, mut write_reqs_rx): (aptos_channel::Sender<(), NetworkMessage>, _) =
+        let (write_reqs_tx, mut write_reqs_rx): (aptos_channel::Sender<(), WriteRequest>, _) =
             aptos_channel::new(
                 QueueStyle::KLAST,
                 1024,
@@ -351,6 +419,7 @@ where
             aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
 
         // this task ends when the multiplex task ends (by dropping the senders) or receiving a close instruction
+        let writer_quic_connection = quic_connection.clone();
         let writer_task = async move {
             let mut stream = select(msg_rx, stream_msg_rx);
             let log_context =
@@ -415,12 +484,92 @@ where
                     );
                 },
             }
+            // Closing the quic connection also closes all of its streams
+            if let Some(quic_connection) = writer_quic_connection {
+                quic_connection.close();
+            }
         };
         // the task ends when the write_reqs_tx is dropped
+        let quic_executor = executor.clone();
         let multiplex_task = async move {
             let mut outbound_stream =
                 OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx);
-            while let Some(message) = write_reqs_rx.next().await {
+            let mut outbound_queues = OutboundQueues::new(
+                network_context,
+                remote_peer_id,
+                outbound_quotas,
+                MAX_QUEUED_MESSAGES_PER_PROTOCOL,
+            );
+            let mut write_reqs_closed = false;
+            let mut quic_stream_writers = HashMap::new();
+            loop {
+                // queue the pending write requests, so they're written in order of priority.
+                // Stop once the queues are backlogged, so the write requests back up instead.
+                while !write_reqs_closed && !outbound_queues.is_backlogged() {
+                    match write_reqs_rx.next().now_or_never() {
+                        Some(Some(write_request)) => outbound_queues.push(write_request),
+                        Some(None) => write_reqs_closed = true,
+                        None => break,
+                    }
+                }
+
+                let (protocol_id, message) = match outbound_queues.pop() {
+                    NextMessage::Ready(write_request) => write_request,
+                    NextMessage::Throttled(throttled_until) => {
+                        // wait until a throttled message can be written, or a new request arrives
+                        let throttle = tokio::time::sleep_until(throttled_until.into()).fuse();
+                        futures::pin_mut!(throttle);
+                        if write_reqs_closed {
+                            throttle.await;
+                        } else {
+                            futures::select! {
+                                _ = throttle => {},
+                                write_request = write_reqs_rx.next() => match write_request {
+                                    Some(write_request) => outbound_queues.push(write_request),
+                                    None => write_reqs_closed = true,
+                                },
+                            }
+                        }
+                        continue;
+                    },
+                    NextMessage::Empty => {
+                        if write_reqs_closed {
+                            break;
+                        }
+                        match write_reqs_rx.next().await {
+                            Some(write_request) => outbound_queues.push(write_request),
+                            None => write_reqs_closed = true,
+                        }
+                        continue;
+                    },
+                };
+
+                // write the message to the protocol's quic stream (if any)
+                if let (Some(quic_connection), Some(protocol_id)) = (&quic_connection, protocol_id)
+                {
+                    let quic_stream_writer =
+                        quic_stream_writers.entry(protocol_id).or_insert_with(|| {
+                            Self::start_quic_stream_writer_task(
+                                &quic_executor,
+                                quic_connection.clone(),
+                                network_context,
+                                remote_peer_id,
+                                protocol_id,
+                                max_frame_size,
+                                max_message_size,
+                            )
+                        });
+                    if let Err(err) = quic_stream_writer.push((), message) {
+                        warn!(
+                            error = %err,
+                            "{} Error in sending message to peer: {}",
+                            network_context,
+                            remote_peer_id.short_str(),
+                        );
+                    }
+                    continue;
+                }
+
                 // either channel full would block the other one
                 let result = if outbound_stream.should_stream(&message) {
                     outbound_stream.stream_message(message).await
@@ -445,6 +594,164 @@ where
         (write_reqs_tx, close_tx)
     }
 
+    // Start a new task on the given executor which is responsible for writing the outbound
+    // messages of a single protocol to a new (unidirectional) QUIC stream. Messages are written
+    // exactly as on the socket (i.e., large messages are streamed in fragments). The task ends
+    // (and finishes the stream) when the returned channel is dropped.
+    fn start_quic_stream_writer_task(
+        executor: &Handle,
+        quic_connection: QuicConnection,
+        network_context: NetworkContext,
+        remote_peer_id: PeerId,
+        protocol_id: ProtocolId,
+        max_frame_size: usize,
+        max_message_size: usize,
+    ) -> aptos_channel::Sender<(), NetworkMessage> {
+        let (messages_tx, mut messages_rx): (aptos_channel::Sender<(), NetworkMessage>, _) =
+            aptos_channel::new(
+                QueueStyle::KLAST,
+                1024,
+                Some(&counters::PENDING_WIRE_MESSAGES),
+            );
+
+        let writer_task = async move {
+            let mut writer = match quic_connection.open_uni().await {
+                Ok(stream) => MultiplexMessageSink::new(stream, max_frame_size),
+                Err(err) => {
+                    warn!(
+                        error = %err,
+                        "{} Failed to open a QUIC stream for protocol {} to peer: {}",
+                        network_context,
+                        protocol_id,
+                        remote_peer_id.short_str(),
+                    );
+                    return;
+                },
+            };
+            let (stream_msg_tx, mut stream_msg_rx) =
+                aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
+            let mut outbound_stream =
+                OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx);
+
+            while let Some(message) = messages_rx.next().await {
+                let write_message = async {
+                    if outbound_stream.should_stream(&message) {
+                        // all fragments are queued once the message has been streamed
+                        outbound_stream.stream_message(message).await?;
+                        while let Some(Some(fragment)) = stream_msg_rx.next().now_or_never() {
+                            writer.send(&fragment).await?;
+                        }
+                    } else {
+                        writer.send(&MultiplexMessage::Message(message)).await?;
+                    }
+                    Ok(()) as anyhow::Result<()>
+                };
+                let result = timeout(transport::TRANSPORT_TIMEOUT, write_message)
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|result| result);
+                if let Err(err) = result {
+                    warn!(
+                        error = %err,
+                        "{} Error in sending message for protocol {} to peer: {}",
+                        network_context,
+                        protocol_id,
+                        remote_peer_id.short_str(),
+                    );
+                }
+            }
+            let _ = writer.close().await;
+        };
+        executor.spawn(writer_task);
+        messages_tx
+    }
+
+    // Start a new task on the given executor which accepts the (unidirectional) QUIC streams
+    // opened by the remote peer, and a task for each stream which reads its messages. Each
+    // stream carries the messages of a single protocol (i.e., the protocol of its first message),
+    // so streamed messages are reassembled per stream. Streams that mix protocols are dropped.
+    // Complete messages are sent to the given channel. The tasks end when the QUIC connection
+    // is closed.
+    fn start_quic_reader_tasks(
+        executor: &Handle,
+        quic_connection: QuicConnection,
+        quic_messages_tx: aptos_channels::Sender<NetworkMessage>,
+        network_context: NetworkContext,
+        remote_peer_id: PeerId,
+        max_frame_size: usize,
+        max_fragments: usize,
+    ) {
+        let reader_executor = executor.clone();
+        let accept_task = async move {
+            while let Ok(stream) = quic_connection.accept_uni().await {
+                let mut quic_messages_tx = quic_messages_tx.clone();
+                let reader_task = async move {
+                    let mut reader = MultiplexMessageStream::new(stream, max_frame_size);
+                    let mut inbound_stream = InboundStreamBuffer::new(max_fragments);
+                    let mut stream_protocol_id = None;
+                    while let Some(message) = reader.next().await {
+                        // ensure the stream only carries the messages of a single protocol
+                        let protocol_id = match &message {
+                            Ok(MultiplexMessage::Message(message)) => message.protocol_id(),
+                            Ok(MultiplexMessage::Stream(StreamMessage::Header(header))) => {
+                                header.message.protocol_id()
+                            },
+                            _ => None,
+                        };
+                        if let Some(protocol_id) = protocol_id {
+                            match stream_protocol_id {
+                                None => stream_protocol_id = Some(protocol_id),
+                                Some(stream_protocol_id) if stream_protocol_id != protocol_id => {
+                                    warn!(
+                                        "{} Dropping QUIC stream with mixed protocols ({} and {}) from peer: {}",
+                                        network_context,
+                                        stream_protocol_id,
+                                        protocol_id,
+                                        remote_peer_id.short_str(),
+                                    );
+                                    break;
+                                },
+                                _ => {},
+                            }
+                        }
+
+                        let result = match message {
+                            Ok(MultiplexMessage::Message(message)) => Ok(Some(message)),
+                            Ok(MultiplexMessage::Stream(StreamMessage::Header(header))) => {
+                                inbound_stream.new_stream(header).map(|_| None)
+                            },
+                            Ok(MultiplexMessage::Stream(StreamMessage::Fragment(fragment))) => {
+                                inbound_stream.append_fragment(fragment)
+                            },
+                            // the stream (or the whole connection) was closed
+                            Err(ReadError::IoError(_)) => break,
+                            Err(err) => Err(err.into()),
+                        };
+                        match result {
+                            Ok(Some(message)) => {
+                                // the peer actor has shut down
+                                if quic_messages_tx.send(message).await.is_err() {
+                                    break;
+                                }
+                            },
+                            Ok(None) => {},
+                            Err(err) => {
+                                warn!(
+                                    error = %err,
+                                    "{} Error in reading QUIC stream message from peer: {}",
+                                    network_context,
+                                    remote_peer_id.short_str(),
+                                );
+                            },
+                        }
+                    }
+                };
+                reader_executor.spawn(reader_task);
+            }
+        };
+        executor.spawn(accept_task);
+    }
+
     fn handle_inbound_network_message(
         &mut self,
         message: NetworkMessage,
@@ -561,7 +868,7 @@ where
     fn handle_inbound_message(
         &mut self,
         message: Result<MultiplexMessage, ReadError>,
-        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
+        write_reqs_tx: &mut aptos_channel::Sender<(), WriteRequest>,
     ) -> Result<(), PeerManagerError> {
         trace!(
             NetworkSchema::new(&self.network_context)
@@ -578,12 +885,19 @@ where
                     // DeserializeError's are recoverable so we'll let the other
                     // peer know about the error and log the issue, but we won't
                     // close the connection.
+                    self.peer_reputations.update_reputation(
+                        PeerNetworkId::new(
+                            self.network_context.network_id(),
+                            self.remote_peer_id(),
+                        ),
+                        ReputationEvent::InvalidMessage,
+                    );
                     let message_type = frame_prefix.as_ref().first().unwrap_or(&0);
                     let protocol_id = frame_prefix.as_ref().get(1).unwrap_or(&0);
                     let error_code = ErrorCode::parsing_error(*message_type, *protocol_id);
                     let message = NetworkMessage::Error(error_code);
 
-                    write_reqs_tx.push((), message)?;
+                    write_reqs_tx.push((), (None, message))?;
                     return Err(err.into());
                 },
                 ReadError::IoError(_) => {
@@ -603,7 +917,7 @@ where
     fn handle_outbound_request(
         &mut self,
         request: PeerRequest,
-        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
+        write_reqs_tx: &mut aptos_channel::Sender<(), WriteRequest>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -623,7 +937,7 @@ where
                     raw_msg: Vec::from(message.mdata.as_ref()),
                 });
 
-                match write_reqs_tx.push((), message) {
+                match write_reqs_tx.push((), (Some(protocol_id), message)) {
                     Ok(_) => {
                         self.update_outbound_direct_send_metrics(protocol_id, message_len as u64);
                     },
@@ -682,7 +996,7 @@ where
 
     async fn do_shutdown(
         mut self,
-        write_req_tx: aptos_channel::Sender<(), NetworkMessage>,
+        write_req_tx: aptos_channel::Sender<(), WriteRequest>,
         writer_close_tx: oneshot::Sender<()>,
         reason: DisconnectReason,
     ) {
@@ -0,0 +1,552 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prioritization and rate limiting of the outbound messages of a peer connection.
+//!
+//! Outbound messages are queued per protocol, and [`OutboundQueues`] always return the
+//! oldest message of the highest priority protocol that isn't throttled by its byte-rate
+//! limit. Messages without a protocol (e.g., error messages) and protocols without a quota
+//! are sent at the default priority, without rate limiting, and are never dropped. Thus, if
+//! no quotas are configured, messages are returned in the order they were queued.
+
+use crate::{
+    counters, peer::WriteRequest, protocols::wire::messaging::v1::NetworkMessage, ProtocolId,
+};
+use aptos_config::{config::ProtocolQuotaConfig, network_id::NetworkContext};
+use aptos_logger::warn;
+use aptos_rate_limiter::rate_limit::Bucket;
+use aptos_short_hex_str::AsShortHexStr;
+use aptos_types::PeerId;
+use std::{
+    cmp::{min, Reverse},
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const ONE_SEC: Duration = Duration::from_secs(1);
+
+/// The priority of messages without a protocol quota
+pub const DEFAULT_PRIORITY: u8 = 0;
+
+/// The outbound quota of a protocol on each peer connection
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProtocolQuota {
+    /// Messages of protocols with a higher priority are always sent first
+    pub priority: u8,
+    /// The byte rate (bytes/s) and burst size of the protocol, if rate limited
+    pub byte_rate_limit: Option<(usize, usize)>,
+}
+
+/// The outbound quotas of the protocols of a network
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProtocolQuotas {
+    quotas: HashMap<ProtocolId, ProtocolQuota>,
+}
+
+impl ProtocolQuotas {
+    pub fn new(quotas: HashMap<ProtocolId, ProtocolQuota>) -> Self {
+        Self { quotas }
+    }
+
+    /// Creates the protocol quotas from the given config (keyed by protocol name). The
+    /// config is verified by the config sanitizer, so invalid quotas are only logged and
+    /// ignored here.
+    pub fn from_config(config: &HashMap<String, ProtocolQuotaConfig>) -> Self {
+        let quotas = config
+            .iter()
+            .filter_map(|(protocol_name, quota_config)| {
+                let Some(protocol_id) = ProtocolId::all()
+                    .iter()
+                    .find(|protocol_id| protocol_id.as_str() == protocol_name)
+                    .copied()
+                else {
+                    warn!(
+                        "Ignoring the outbound quota of an unknown protocol: {}",
+                        protocol_name
+                    );
+                    return None;
+                };
+                let byte_rate_limit = match quota_config.byte_bucket_rate {
+                    Some(rate) => {
+                        let size = quota_config.byte_bucket_size.unwrap_or(rate);
+                        if rate == 0 || size < rate {
+                            warn!(
+                                "Ignoring the invalid outbound quota of protocol {}: {:?}",
+                                protocol_name, quota_config
+                            );
+                            return None;
+                        }
+                        Some((rate, size))
+                    },
+                    None => None,
+                };
+                let quota = ProtocolQuota {
+                    priority: quota_config.priority,
+                    byte_rate_limit,
+                };
+                Some((protocol_id, quota))
+            })
+            .collect();
+        Self { quotas }
+    }
+
+    /// Returns true iff no protocol quotas are configured
+    pub fn is_empty(&self) -> bool {
+        self.quotas.is_empty()
+    }
+
+    /// Returns true iff the given protocol has a quota
+    pub fn has_quota(&self, protocol_id: Option<ProtocolId>) -> bool {
+        protocol_id.is_some_and(|protocol_id| self.quotas.contains_key(&protocol_id))
+    }
+
+    /// Returns the priority of the given protocol
+    pub fn priority(&self, protocol_id: Option<ProtocolId>) -> u8 {
+        protocol_id
+            .and_then(|protocol_id| self.quotas.get(&protocol_id))
+            .map_or(DEFAULT_PRIORITY, |quota| quota.priority)
+    }
+}
+
+/// The next message to send (if any)
+#[derive(Debug)]
+pub enum NextMessage {
+    /// The message can be sent now
+    Ready(WriteRequest),
+    /// All queued messages are throttled until the given time
+    Throttled(Instant),
+    /// There are no queued messages
+    Empty,
+}
+
+/// A queued message, with the order and time it was queued in
+struct QueuedMessage {
+    sequence_number: u64,
+    queued_at: Instant,
+    message: NetworkMessage,
+}
+
+/// The outbound message queues (per protocol) of a peer connection
+pub struct OutboundQueues {
+    network_context: NetworkContext,
+    quotas: Arc<ProtocolQuotas>,
+    max_queue_size: usize,
+    queues: HashMap<Option<ProtocolId>, VecDeque<QueuedMessage>>,
+    buckets: HashMap<ProtocolId, ByteBucket>,
+    next_sequence_number: u64,
+    num_unquoted_messages: usize,
+}
+
+impl OutboundQueues {
+    pub fn new(
+        network_context: NetworkContext,
+        remote_peer_id: PeerId,
+        quotas: Arc<ProtocolQuotas>,
+        max_queue_size: usize,
+    ) -> Self {
+        // Create a (full) token bucket for each rate limited protocol
+        let buckets = quotas
+            .quotas
+            .iter()
+            .filter_map(|(protocol_id, quota)| {
+                quota.byte_rate_limit.map(|(rate, size)| {
+                    let bucket = Bucket::new(
+                        protocol_id.as_str().to_string(),
+                        network_context.to_string(),
+                        remote_peer_id.short_str().to_string(),
+                        size,
+                        size,
+                        rate,
+                        Some(counters::NETWORK_PROTOCOL_RATE_LIMIT_METRICS.clone()),
+                    );
+                    (*protocol_id, ByteBucket {
+                        bucket,
+                        size,
+                        rate,
+                        debt: 0,
+                    })
+                })
+            })
+            .collect();
+
+        Self {
+            network_context,
+            quotas,
+            max_queue_size,
+            queues: HashMap::new(),
+            buckets,
+            next_sequence_number: 0,
+            num_unquoted_messages: 0,
+        }
+    }
+
+    /// Queues the given message. If the protocol has a quota and its queue is
+    /// full, the oldest message in the queue is dropped. Messages without a
+    /// quota are never dropped (see `is_backlogged()`).
+    pub fn push(&mut self, (protocol_id, message): WriteRequest) {
+        let has_quota = self.quotas.has_quota(protocol_id);
+        let queue = self.queues.entry(protocol_id).or_default();
+        if !has_quota {
+            self.num_unquoted_messages += 1;
+        } else if queue.len() >= self.max_queue_size {
+            queue.pop_front();
+            counters::outbound_queue_dropped_messages(
+                &self.network_context,
+                protocol_label(protocol_id),
+            )
+            .inc();
+        }
+        queue.push_back(QueuedMessage {
+            sequence_number: self.next_sequence_number,
+            queued_at: Instant::now(),
+            message,
+        });
+        self.next_sequence_number += 1;
+    }
+
+    /// Returns the oldest message of the highest priority protocol that isn't throttled
+    pub fn pop(&mut self) -> NextMessage {
+        // Order the protocols by priority, and then by the age of their oldest message
+        let mut protocol_ids: Vec<_> = self
+            .queues
+            .iter()
+            .filter_map(|(protocol_id, queue)| {
+                queue.front().map(|queued_message| {
+                    let priority = self.quotas.priority(*protocol_id);
+                    (
+                        Reverse(priority),
+                        queued_message.sequence_number,
+                        *protocol_id,
+                    )
+                })
+            })
+            .collect();
+        protocol_ids
+            .sort_unstable_by_key(|(priority, sequence_number, _)| (*priority, *sequence_number));
+
+        let mut throttled_until: Option<Instant> = None;
+        for (_, _, protocol_id) in protocol_ids {
+            let queue = self.queues.get_mut(&protocol_id).expect("Queue must exist");
+            let num_bytes = queue
+                .front()
+                .expect("Queue can't be empty")
+                .message
+                .data_len();
+            if let Err(instant) = try_acquire_bytes(&mut self.buckets, protocol_id, num_bytes) {
+                throttled_until =
+                    Some(throttled_until.map_or(instant, |until| min(until, instant)));
+                continue;
+            }
+
+            let queued_message = queue.pop_front().expect("Queue can't be empty");
+            if queue.is_empty() {
+                self.queues.remove(&protocol_id);
+            }
+            if !self.quotas.has_quota(protocol_id) {
+                self.num_unquoted_messages -= 1;
+            }
+            counters::outbound_queue_latency(&self.network_context, protocol_label(protocol_id))
+                .observe(queued_message.queued_at.elapsed().as_secs_f64());
+            return NextMessage::Ready((protocol_id, queued_message.message));
+        }
+
+        match throttled_until {
+            Some(instant) => NextMessage::Throttled(instant),
+            None => NextMessage::Empty,
+        }
+    }
+
+    /// Returns true iff no more messages should be queued until the queued messages
+    /// are sent. As messages without a quota are never dropped, the caller should stop
+    /// queueing (i.e., apply backpressure) once the max queue size of them is queued.
+    /// If no quotas are configured, messages aren't buffered at all, and are sent one
+    /// at a time.
+    pub fn is_backlogged(&self) -> bool {
+        if self.quotas.is_empty() {
+            self.num_unquoted_messages > 0
+        } else {
+            self.num_unquoted_messages >= self.max_queue_size
+        }
+    }
+}
+
+/// The token bucket of a rate limited protocol. Messages larger than the bucket size are
+/// sent once the bucket is full, and the bytes exceeding the bucket size are owed, i.e.,
+/// paid off by the following refills before the next message of the protocol can be sent.
+/// Otherwise, they could never be sent (or would exceed the rate).
+struct ByteBucket {
+    bucket: Bucket,
+    size: usize,
+    rate: usize,
+    debt: usize,
+}
+
+impl ByteBucket {
+    /// Acquires the tokens to send the given number of bytes. If the bytes can't be sent
+    /// yet, the time they can be is returned.
+    fn try_acquire_bytes(&mut self, num_bytes: usize) -> Result<(), Instant> {
+        if self.debt > 0 {
+            if let Ok(num_paid) = self.bucket.acquire_tokens(self.debt) {
+                self.debt -= num_paid;
+            }
+            if self.debt > 0 {
+                // The remaining debt is paid off by the next refills
+                let num_refills = self.debt.div_ceil(self.rate) as u32;
+                return Err(self.bucket.time_of_next_refill() + ONE_SEC * (num_refills - 1));
+            }
+        }
+
+        let num_tokens = min(num_bytes, self.size);
+        match self.bucket.acquire_all_tokens(num_tokens) {
+            Ok(()) => {
+                self.debt = num_bytes - num_tokens;
+                Ok(())
+            },
+            Err(Some(instant)) => Err(instant),
+            // This can't happen, as we never request more tokens than the bucket size
+            Err(None) => unreachable!("The requested tokens can't exceed the bucket size"),
+        }
+    }
+}
+
+/// Acquires the tokens to send the given number of bytes for the protocol (if it's rate
+/// limited). If the bytes can't be sent yet, the time they can be is returned.
+fn try_acquire_bytes(
+    buckets: &mut HashMap<ProtocolId, ByteBucket>,
+    protocol_id: Option<ProtocolId>,
+    num_bytes: usize,
+) -> Result<(), Instant> {
+    match protocol_id.and_then(|protocol_id| buckets.get_mut(&protocol_id)) {
+        Some(bucket) => bucket.try_acquire_bytes(num_bytes),
+        None => Ok(()),
+    }
+}
+
+/// Returns the metrics label of the given protocol
+fn protocol_label(protocol_id: Option<ProtocolId>) -> &'static str {
+    protocol_id.map_or(counters::UNKNOWN_LABEL, ProtocolId::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::wire::messaging::v1::{DirectSendMsg, ErrorCode};
+    use aptos_config::config::OUTBOUND_QUOTA_PROTOCOL_NAMES;
+
+    #[test]
+    fn test_fifo_without_quotas() {
+        let mut queues = create_queues(HashMap::new(), 10);
+        queues.push(direct_send(ProtocolId::StorageServiceRpc, 1));
+        queues.push(direct_send(ProtocolId::ConsensusDirectSendBcs, 2));
+        queues.push((None, NetworkMessage::Error(ErrorCode::parsing_error(0, 0))));
+        queues.push(direct_send(ProtocolId::StorageServiceRpc, 3));
+
+        assert_eq!(pop_protocols(&mut queues), vec![
+            Some(ProtocolId::StorageServiceRpc),
+            Some(ProtocolId::ConsensusDirectSendBcs),
+            None,
+            Some(ProtocolId::StorageServiceRpc),
+        ]);
+    }
+
+    #[test]
+    fn test_priorities() {
+        let quotas = HashMap::from([
+            (ProtocolId::ConsensusDirectSendBcs, quota(2, None)),
+            (ProtocolId::MempoolDirectSend, quota(1, None)),
+        ]);
+        let mut queues = create_queues(quotas, 10);
+        queues.push(direct_send(ProtocolId::StorageServiceRpc, 1));
+        queues.push(direct_send(ProtocolId::MempoolDirectSend, 2));
+        queues.push(direct_send(ProtocolId::ConsensusDirectSendBcs, 3));
+        queues.push(direct_send(ProtocolId::StorageServiceRpc, 4));
+        queues.push(direct_send(ProtocolId::ConsensusDirectSendBcs, 5));
+
+        assert_eq!(pop_protocols(&mut queues), vec![
+            Some(ProtocolId::ConsensusDirectSendBcs),
+            Some(ProtocolId::ConsensusDirectSendBcs),
+            Some(ProtocolId::MempoolDirectSend),
+            Some(ProtocolId::StorageServiceRpc),
+            Some(ProtocolId::StorageServiceRpc),
+        ]);
+    }
+
+    #[test]
+    fn test_rate_limits() {
+        let quotas = HashMap::from([(ProtocolId::StorageServiceRpc, quota(1, Some((100, 100))))]);
+        let mut queues = create_queues(quotas, 10);
+        queues.push(direct_send(ProtocolId::StorageServiceRpc, 60));
+        queues.push(direct_send(ProtocolId::StorageServiceRpc, 60));
+        queues.push(direct_send(ProtocolId::MempoolDirectSend, 1000));
+
+        // The first message fits in the bucket, the second one is throttled
+        assert_eq!(pop_protocols(&mut queues), vec![
+            Some(ProtocolId::StorageServiceRpc),
+            Some(ProtocolId::MempoolDirectSend),
+        ]);
+        let now = Instant::now();
+        match queues.pop() {
+            NextMessage::Throttled(instant) => assert!(instant > now),
+            next_message => panic!("Expected the message to be throttled: {:?}", next_message),
+        }
+    }
+
+    #[test]
+    fn test_large_messages_are_fully_charged() {
+        let start = Instant::now();
+        let quotas = HashMap::from([(ProtocolId::StorageServiceRpc, quota(0, Some((100, 100))))]);
+        let mut queues = create_queues(quotas, 10);
+        queues.push(direct_send(ProtocolId::StorageServiceRpc, 1000));
+        queues.push(direct_send(ProtocolId::StorageServiceRpc, 1));
+        queues.push(direct_send(ProtocolId::MempoolDirectSend, 1));
+
+        // The large message is sent with a full bucket, and the other protocols aren't affected
+        assert_eq!(pop_protocols(&mut queues), vec![
+            Some(ProtocolId::StorageServiceRpc),
+            Some(ProtocolId::MempoolDirectSend),
+        ]);
+
+        // The 900 bytes exceeding the bucket size take 9 refills (at 100 bytes/s) to pay off
+        match queues.pop() {
+            NextMessage::Throttled(instant) => {
+                assert!(instant >= start + Duration::from_secs(9));
+                assert!(instant <= Instant::now() + Duration::from_secs(9));
+            },
+            next_message => panic!("Expected the message to be throttled: {:?}", next_message),
+        }
+    }
+
+    #[test]
+    fn test_full_queue_drops_oldest() {
+        let quotas = HashMap::from([(ProtocolId::MempoolDirectSend, quota(0, None))]);
+        let mut queues = create_queues(quotas, 2);
+        for data_len in 1..=3 {
+            queues.push(direct_send(ProtocolId::MempoolDirectSend, data_len));
+        }
+        queues.push(direct_send(ProtocolId::ConsensusDirectSendBcs, 4));
+
+        let mut data_lens = vec![];
+        while let NextMessage::Ready((_, message)) = queues.pop() {
+            data_lens.push(message.data_len());
+        }
+        assert_eq!(data_lens, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_unquoted_messages_are_never_dropped() {
+        let quotas = HashMap::from([(ProtocolId::MempoolDirectSend, quota(1, None))]);
+        let mut queues = create_queues(quotas, 2);
+        for data_len in 1..=2 {
+            queues.push(direct_send(ProtocolId::ConsensusDirectSendBcs, data_len));
+            queues.push(direct_send(ProtocolId::MempoolDirectSend, data_len));
+        }
+
+        // The queues are backlogged once the max queue size of unquoted messages is queued
+        assert!(queues.is_backlogged());
+        queues.push(direct_send(ProtocolId::ConsensusDirectSendBcs, 3));
+
+        let mut data_lens = vec![];
+        while let NextMessage::Ready((_, message)) = queues.pop() {
+            data_lens.push(message.data_len());
+        }
+        assert_eq!(data_lens, vec![1, 2, 1, 2, 3]);
+        assert!(!queues.is_backlogged());
+    }
+
+    #[test]
+    fn test_no_buffering_without_quotas() {
+        let mut queues = create_queues(HashMap::new(), 10);
+        assert!(!queues.is_backlogged());
+
+        // A single queued message backlogs the queues
+        queues.push(direct_send(ProtocolId::MempoolDirectSend, 1));
+        assert!(queues.is_backlogged());
+        assert_eq!(pop_protocols(&mut queues), vec![Some(
+            ProtocolId::MempoolDirectSend
+        )]);
+        assert!(!queues.is_backlogged());
+    }
+
+    #[test]
+    fn test_invalid_protocol_config() {
+        let config = HashMap::from([
+            ("UnknownRpc".to_string(), ProtocolQuotaConfig::default()),
+            ("StorageServiceRpc".to_string(), ProtocolQuotaConfig {
+                priority: 1,
+                byte_bucket_rate: Some(100),
+                byte_bucket_size: Some(10),
+            }),
+        ]);
+        assert_eq!(
+            ProtocolQuotas::from_config(&config),
+            ProtocolQuotas::default()
+        );
+    }
+
+    #[test]
+    fn test_config_protocol_names() {
+        let protocol_names: Vec<_> = ProtocolId::all()
+            .iter()
+            .map(|protocol_id| protocol_id.as_str())
+            .collect();
+        assert_eq!(protocol_names, OUTBOUND_QUOTA_PROTOCOL_NAMES);
+    }
+
+    #[test]
+    fn test_protocol_config() {
+        let config = HashMap::from([("StorageServiceRpc".to_string(), ProtocolQuotaConfig {
+            priority: 1,
+            byte_bucket_rate: Some(100),
+            byte_bucket_size: None,
+        })]);
+        let quotas = ProtocolQuotas::from_config(&config);
+        assert_eq!(
+            quotas,
+            ProtocolQuotas::new(HashMap::from([(
+                ProtocolId::StorageServiceRpc,
+                quota(1, Some((100, 100)))
+            )]))
+        );
+        assert_eq!(quotas.priority(Some(ProtocolId::StorageServiceRpc)), 1);
+        assert_eq!(quotas.priority(Some(ProtocolId::MempoolDirectSend)), 0);
+        assert_eq!(quotas.priority(None), DEFAULT_PRIORITY);
+    }
+
+    fn create_queues(
+        quotas: HashMap<ProtocolId, ProtocolQuota>,
+        max_queue_size: usize,
+    ) -> OutboundQueues {
+        OutboundQueues::new(
+            NetworkContext::mock_with_peer_id(PeerId::random()),
+            PeerId::random(),
+            Arc::new(ProtocolQuotas::new(quotas)),
+            max_queue_size,
+        )
+    }
+
+    fn quota(priority: u8, byte_rate_limit: Option<(usize, usize)>) -> ProtocolQuota {
+        ProtocolQuota {
+            priority,
+            byte_rate_limit,
+        }
+    }
+
+    fn direct_send(protocol_id: ProtocolId, data_len: usize) -> WriteRequest {
+        let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: 0,
+            raw_msg: vec![0; data_len],
+        });
+        (Some(protocol_id), message)
+    }
+
+    /// Pops all messages that are ready, and returns their protocols
+    fn pop_protocols(queues: &mut OutboundQueues) -> Vec<Option<ProtocolId>> {
+        let mut protocol_ids = vec![];
+        while let NextMessage::Ready((protocol_id, _)) = queues.pop() {
+            protocol_ids.push(protocol_id);
+        }
+        protocol_ids
+    }
+}
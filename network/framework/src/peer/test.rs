@@ -3,11 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    application::reputation::PeerReputations,
     constants::{
         INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE, MAX_MESSAGE_SIZE, NETWORK_CHANNEL_SIZE,
     },
-    peer::{DisconnectReason, Peer, PeerRequest},
+    peer::{outbound_queues::ProtocolQuotas, DisconnectReason, Peer, PeerRequest},
     peer_manager::TransportNotification,
     protocols::{
         direct_send::Message,
@@ -21,14 +22,17 @@ use crate::{
             },
         },
     },
-    transport::{Connection, ConnectionId, ConnectionMetadata},
+    transport::{BaseSocket, Connection, ConnectionId, ConnectionMetadata},
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{config::PeerRole, network_id::NetworkContext};
 use aptos_logger::info;
 use aptos_memsocket::MemorySocket;
-use aptos_netcore::transport::ConnectionOrigin;
+use aptos_netcore::transport::{
+    quic::{QuicSocket, QuicTransport},
+    ConnectionOrigin, Transport,
+};
 use aptos_time_service::{MockTimeService, TimeService};
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use bytes::Bytes;
@@ -66,6 +70,24 @@ fn build_test_peer(
     aptos_channels::Receiver<TransportNotification<MemorySocket>>,
 ) {
     let (a, b) = MemorySocket::new_pair();
+    let (peer, peer_handle, connection_notifs_rx) =
+        build_test_peer_with_socket(executor, time_service, origin, upstream_handlers, a);
+    (peer, peer_handle, b, connection_notifs_rx)
+}
+
+fn build_test_peer_with_socket<TSocket: BaseSocket>(
+    executor: Handle,
+    time_service: TimeService,
+    origin: ConnectionOrigin,
+    upstream_handlers: Arc<
+        HashMap<ProtocolId, aptos_channel::Sender<(PeerId, ProtocolId), ReceivedMessage>>,
+    >,
+    socket: TSocket,
+) -> (
+    Peer<TSocket>,
+    PeerHandle,
+    aptos_channels::Receiver<TransportNotification<TSocket>>,
+) {
     let peer_id = PeerId::random();
     let connection = Connection {
         metadata: ConnectionMetadata::new(
@@ -77,7 +99,8 @@ fn build_test_peer(
             ProtocolIdSet::empty(),
             PeerRole::Unknown,
         ),
-        socket: a,
+        quic_connection: socket.quic_connection(),
+        socket,
     };
 
     let (connection_notifs_tx, connection_notifs_rx) = aptos_channels::new_test(1);
@@ -97,10 +120,12 @@ fn build_test_peer(
         MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE,
         MAX_MESSAGE_SIZE,
+        Arc::new(ProtocolQuotas::default()),
+        Arc::new(PeerReputations::default()),
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
-    (peer, peer_handle, b, connection_notifs_rx)
+    (peer, peer_handle, connection_notifs_rx)
 }
 
 fn build_test_connected_peers(
@@ -145,6 +170,22 @@ fn build_test_connected_peers(
     )
 }
 
+// Returns the (outbound, inbound) primary sockets of a new local QUIC connection
+fn build_test_quic_sockets(rt: &Runtime) -> (QuicSocket, QuicSocket) {
+    let _guard = rt.enter();
+    let transport = QuicTransport::new(ProtocolId::all().len() as u32).unwrap();
+    let (mut listener, addr) = transport
+        .listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())
+        .unwrap();
+    let dial = transport.dial(PeerId::random(), addr).unwrap();
+    let accept = async move {
+        let (inbound, _addr) = listener.next().await.unwrap().unwrap();
+        inbound.await
+    };
+    let (outbound, inbound) = rt.block_on(future::join(dial, accept));
+    (outbound.unwrap(), inbound.unwrap())
+}
+
 fn build_network_sink_stream(
     connection: &mut MemorySocket,
 ) -> (
@@ -1033,3 +1074,161 @@ fn peers_send_multiplex() {
 
     rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
 }
+
+// Two Peer actors connected over QUIC should send the messages of each protocol on
+// their own stream, and reassemble streamed messages on the receiving end.
+#[test]
+fn peers_send_messages_over_quic() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (outbound_socket, inbound_socket) = build_test_quic_sockets(&rt);
+
+    let protocols = [
+        ProtocolId::ConsensusDirectSendBcs,
+        ProtocolId::MempoolDirectSend,
+    ];
+    let mut upstream_handlers_b = HashMap::new();
+    let mut protocol_rxs = vec![];
+    for protocol_id in protocols {
+        let (sender, receiver) = aptos_channel::new(QueueStyle::FIFO, 100, None);
+        upstream_handlers_b.insert(protocol_id, sender);
+        protocol_rxs.push((protocol_id, receiver));
+    }
+    let (peer_a, mut peer_handle_a, mut connection_notifs_rx_a) = build_test_peer_with_socket(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Outbound,
+        Arc::new(HashMap::new()),
+        outbound_socket,
+    );
+    let (peer_b, _peer_handle_b, mut connection_notifs_rx_b) = build_test_peer_with_socket(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Inbound,
+        Arc::new(upstream_handlers_b),
+        inbound_socket,
+    );
+
+    let remote_peer_id_a = peer_a.remote_peer_id();
+    let remote_peer_id_b = peer_b.remote_peer_id();
+
+    let test = async move {
+        // Peer A -> stream, normal and stream messages (for each protocol) -> Peer B
+        let messages = |protocol_id: ProtocolId| {
+            let data = protocol_id as u8;
+            vec![
+                Bytes::from(vec![data; 2 * MAX_FRAME_SIZE]), // stream message
+                Bytes::from(vec![data; 1024]),               // normal message
+                Bytes::from(vec![data; 3 * MAX_FRAME_SIZE]), // stream message
+            ]
+        };
+        for protocol_id in protocols {
+            for mdata in messages(protocol_id) {
+                peer_handle_a.send_direct_send(Message { protocol_id, mdata });
+            }
+        }
+
+        // Check that Peer B received the messages of each protocol, in order
+        for (protocol_id, mut protocol_rx) in protocol_rxs {
+            for mdata in messages(protocol_id) {
+                let notif = protocol_rx.next().await.unwrap();
+                assert_eq!(
+                    notif.message,
+                    NetworkMessage::DirectSendMsg(DirectSendMsg {
+                        protocol_id,
+                        priority: 0,
+                        raw_msg: mdata.into(),
+                    })
+                );
+            }
+        }
+
+        // Shut one peer down and the other should shutdown as well
+        drop(peer_handle_a);
+
+        // Check that we received both shutdown events. Note: closing the QUIC connection
+        // may discard the end of the primary stream, so the remote peer may see an error.
+        assert_disconnected_event(
+            remote_peer_id_a,
+            DisconnectReason::RequestedByPeerManager,
+            &mut connection_notifs_rx_a,
+        )
+        .await;
+        match connection_notifs_rx_b.next().await {
+            Some(TransportNotification::Disconnected(metadata, reason)) => {
+                assert_eq!(metadata.remote_peer_id, remote_peer_id_b);
+                assert!(matches!(
+                    reason,
+                    DisconnectReason::ConnectionClosed | DisconnectReason::InputOutputError
+                ));
+            },
+            event => panic!("Expected a Disconnected, received: {:?}", event),
+        }
+    };
+
+    rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
+}
+
+// A Peer actor should drop the QUIC streams that carry the messages of several protocols.
+#[test]
+fn peer_drops_quic_streams_with_mixed_protocols() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (outbound_socket, inbound_socket) = build_test_quic_sockets(&rt);
+    let quic_connection = outbound_socket.connection();
+
+    let (protocol_a, protocol_b) = (ProtocolId::ConsensusDirectSendBcs, PROTOCOL);
+    let (sender_a, mut protocol_a_rx) = aptos_channel::new(QueueStyle::FIFO, 100, None);
+    let (sender_b, mut protocol_b_rx) = aptos_channel::new(QueueStyle::FIFO, 100, None);
+    let upstream_handlers = Arc::new(HashMap::from([
+        (protocol_a, sender_a),
+        (protocol_b, sender_b),
+    ]));
+    let (peer, _peer_handle, _connection_notifs_rx) = build_test_peer_with_socket(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Inbound,
+        upstream_handlers,
+        inbound_socket,
+    );
+    rt.spawn(peer.start());
+
+    let direct_send = |protocol_id: ProtocolId, raw_msg: &str| {
+        NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: 0,
+            raw_msg: Vec::from(raw_msg),
+        })
+    };
+    let send_on_new_stream = |messages: Vec<NetworkMessage>| {
+        let quic_connection = quic_connection.clone();
+        async move {
+            let stream = quic_connection.open_uni().await.unwrap();
+            let mut sink = MultiplexMessageSink::new(stream, MAX_FRAME_SIZE);
+            // Note: the remote peer may stop reading the stream (so errors are ignored)
+            for message in messages {
+                let _ = sink.send(&MultiplexMessage::Message(message)).await;
+            }
+            let _ = sink.close().await;
+        }
+    };
+
+    rt.block_on(async move {
+        // The first stream switches protocols, so it should be dropped at that point
+        send_on_new_stream(vec![
+            direct_send(protocol_a, "first"),
+            direct_send(protocol_b, "second"),
+            direct_send(protocol_a, "third"),
+        ])
+        .await;
+        send_on_new_stream(vec![direct_send(protocol_a, "fourth")]).await;
+        send_on_new_stream(vec![direct_send(protocol_b, "fifth")]).await;
+
+        let notif = protocol_a_rx.next().await.unwrap();
+        assert_eq!(notif.message, direct_send(protocol_a, "first"));
+        let notif = protocol_a_rx.next().await.unwrap();
+        assert_eq!(notif.message, direct_send(protocol_a, "fourth"));
+        let notif = protocol_b_rx.next().await.unwrap();
+        assert_eq!(notif.message, direct_send(protocol_b, "fifth"));
+    });
+}
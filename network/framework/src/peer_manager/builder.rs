@@ -6,6 +6,7 @@ use crate::{
     application::storage::PeersAndMetadata,
     counters,
     noise::{stream::NoiseStream, HandshakeAuthMode},
+    peer::outbound_queues::ProtocolQuotas,
     peer_manager::{
         conn_notifs_channel, ConnectionRequest, ConnectionRequestSender, PeerManager,
         PeerManagerRequest, PeerManagerRequestSender,
@@ -24,6 +25,7 @@ use aptos_logger::prelude::*;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 use aptos_netcore::transport::memory::MemoryTransport;
 use aptos_netcore::transport::{
+    quic::{QuicSocket, QuicTransport},
     tcp::{TCPBufferCfg, TcpSocket, TcpTransport},
     Transport,
 };
@@ -78,6 +80,7 @@ struct PeerManagerContext {
     max_message_size: usize,
     inbound_connection_limit: usize,
     tcp_buffer_cfg: TCPBufferCfg,
+    outbound_quotas: ProtocolQuotas,
 }
 
 impl PeerManagerContext {
@@ -100,6 +103,7 @@ impl PeerManagerContext {
         max_message_size: usize,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_quotas: ProtocolQuotas,
     ) -> Self {
         Self {
             pm_reqs_tx,
@@ -116,6 +120,7 @@ impl PeerManagerContext {
             max_message_size,
             inbound_connection_limit,
             tcp_buffer_cfg,
+            outbound_quotas,
         }
     }
 
@@ -139,11 +144,13 @@ impl PeerManagerContext {
 type MemoryPeerManager =
     PeerManager<AptosNetTransport<MemoryTransport>, NoiseStream<aptos_memsocket::MemorySocket>>;
 type TcpPeerManager = PeerManager<AptosNetTransport<TcpTransport>, NoiseStream<TcpSocket>>;
+type QuicPeerManager = PeerManager<AptosNetTransport<QuicTransport>, NoiseStream<QuicSocket>>;
 
 enum TransportPeerManager {
     #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
     Memory(MemoryPeerManager),
     Tcp(TcpPeerManager),
+    Quic(QuicPeerManager),
 }
 
 pub struct PeerManagerBuilder {
@@ -173,6 +180,7 @@ impl PeerManagerBuilder {
         enable_proxy_protocol: bool,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_quotas: ProtocolQuotas,
     ) -> Self {
         // Setup channel to send requests to peer manager.
         let (pm_reqs_tx, pm_reqs_rx) = aptos_channel::new(
@@ -207,6 +215,7 @@ impl PeerManagerBuilder {
                 max_message_size,
                 inbound_connection_limit,
                 tcp_buffer_cfg,
+                outbound_quotas,
             )),
             peer_manager: None,
             listen_address,
@@ -239,6 +248,10 @@ impl PeerManagerBuilder {
 
     /// Create the configured transport and start PeerManager.
     /// Return the actual NetworkAddress over which this peer is listening.
+    ///
+    /// Note: the transport is selected by the listen address, and is also the
+    /// only transport used to dial peers. E.g., a network listening on QUIC
+    /// can only dial the QUIC addresses of its peers.
     pub fn build(&mut self, executor: &Handle) -> &mut Self {
         use aptos_types::network_address::Protocol::*;
 
@@ -283,6 +296,25 @@ impl PeerManagerBuilder {
                     executor,
                 )))
             },
+            [Ip4(_), Quic(_)] | [Ip6(_), Quic(_)] => {
+                // each protocol is written to (at most) one QUIC stream
+                let quic_transport = QuicTransport::new(ProtocolId::all().len() as u32)
+                    .expect("Failed to create the QUIC transport");
+                Some(TransportPeerManager::Quic(self.build_with_transport(
+                    AptosNetTransport::new(
+                        quic_transport,
+                        self.network_context,
+                        self.time_service.clone(),
+                        key,
+                        auth_mode,
+                        HANDSHAKE_VERSION,
+                        chain_id,
+                        protos,
+                        enable_proxy_protocol,
+                    ),
+                    executor,
+                )))
+            },
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             [Memory(_)] => Some(TransportPeerManager::Memory(self.build_with_transport(
                 AptosNetTransport::new(
@@ -300,7 +332,8 @@ impl PeerManagerBuilder {
             ))),
             _ => panic!(
                 "{} Unsupported listen_address: '{}', expected '/memory/<port>', \
-                 '/ip4/<addr>/tcp/<port>', or '/ip6/<addr>/tcp/<port>'.",
+                 '/ip4/<addr>/tcp/<port>', '/ip6/<addr>/tcp/<port>', \
+                 '/ip4/<addr>/quic/<port>', or '/ip6/<addr>/quic/<port>'.",
                 self.network_context, self.listen_address
             ),
         };
@@ -340,6 +373,7 @@ impl PeerManagerBuilder {
             pm_context.max_frame_size,
             pm_context.max_message_size,
             pm_context.inbound_connection_limit,
+            pm_context.outbound_quotas,
         );
 
         // PeerManager constructor appends a public key to the listen_address.
@@ -370,6 +404,7 @@ impl PeerManagerBuilder {
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             TransportPeerManager::Memory(pm) => self.start_peer_manager(pm, executor),
             TransportPeerManager::Tcp(pm) => self.start_peer_manager(pm, executor),
+            TransportPeerManager::Quic(pm) => self.start_peer_manager(pm, executor),
         }
     }
 
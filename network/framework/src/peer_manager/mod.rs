@@ -15,7 +15,7 @@ use crate::{
     constants,
     counters::{self},
     logging::*,
-    peer::{Peer, PeerRequest},
+    peer::{outbound_queues::ProtocolQuotas, Peer, PeerRequest},
     transport::{
         Connection, ConnectionId, ConnectionMetadata, TSocket as TransportTSocket,
         TRANSPORT_TIMEOUT,
@@ -117,6 +117,8 @@ where
     max_message_size: usize,
     /// Inbound connection limit separate of outbound connections
     inbound_connection_limit: usize,
+    /// The priorities and rate limits of outbound messages on each connection
+    outbound_quotas: Arc<ProtocolQuotas>,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -144,6 +146,7 @@ where
         max_frame_size: usize,
         max_message_size: usize,
         inbound_connection_limit: usize,
+        outbound_quotas: ProtocolQuotas,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = aptos_channels::new(
             channel_size,
@@ -158,6 +161,7 @@ where
         let (transport_handler, listen_addr) = TransportHandler::new(
             network_context,
             time_service.clone(),
+            peers_and_metadata.clone(),
             transport,
             listen_addr,
             transport_reqs_rx,
@@ -185,6 +189,7 @@ where
             max_frame_size,
             max_message_size,
             inbound_connection_limit,
+            outbound_quotas: Arc::new(outbound_quotas),
         }
     }
 
@@ -349,6 +354,30 @@ where
             },
         };
 
+        // Reject inbound connections from banned peers
+        if conn.metadata.origin == ConnectionOrigin::Inbound {
+            let peer_network_id = PeerNetworkId::new(
+                self.network_context.network_id(),
+                conn.metadata.remote_peer_id,
+            );
+            if self
+                .peers_and_metadata
+                .get_peer_reputations()
+                .is_banned(&peer_network_id)
+            {
+                info!(
+                    NetworkSchema::new(&self.network_context)
+                        .connection_metadata_with_address(&conn.metadata),
+                    "{} Connection rejected from banned peer: {}",
+                    self.network_context,
+                    conn.metadata
+                );
+                counters::connections_rejected(&self.network_context, conn.metadata.origin).inc();
+                self.disconnect(conn);
+                return;
+            }
+        }
+
         // Verify that we have not reached the max connection limit for unknown inbound peers
         if conn.metadata.origin == ConnectionOrigin::Inbound {
             // Everything below here is meant for unknown peers only. The role comes from
@@ -676,6 +705,8 @@ where
             constants::MAX_CONCURRENT_OUTBOUND_RPCS,
             self.max_frame_size,
             self.max_message_size,
+            self.outbound_quotas.clone(),
+            self.peers_and_metadata.get_peer_reputations(),
         );
         self.executor.spawn(peer.start());
 
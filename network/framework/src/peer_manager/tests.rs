@@ -5,7 +5,7 @@
 use crate::{
     application::storage::PeersAndMetadata,
     constants,
-    peer::DisconnectReason,
+    peer::{outbound_queues::ProtocolQuotas, DisconnectReason},
     peer_manager::{
         conn_notifs_channel, error::PeerManagerError, ConnectionNotification, ConnectionRequest,
         PeerManager, PeerManagerRequest, TransportNotification,
@@ -61,6 +61,7 @@ pub fn build_test_transport(
                     ProtocolIdSet::mock(),
                     PeerRole::Unknown,
                 ),
+                quic_connection: None,
             })
         })
         .boxed()
@@ -116,6 +117,7 @@ fn build_test_peer_manager(
         constants::MAX_FRAME_SIZE,
         constants::MAX_MESSAGE_SIZE,
         MAX_INBOUND_CONNECTIONS,
+        ProtocolQuotas::default(),
     );
 
     (
@@ -242,6 +244,7 @@ fn create_connection<TSocket: transport::TSocket>(
             ProtocolIdSet::mock(),
             PeerRole::Unknown,
         ),
+        quic_connection: None,
     }
 }
 
@@ -2,14 +2,16 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    application::{reputation::ReputationEvent, storage::PeersAndMetadata},
     counters::{self, FAILED_LABEL, SUCCEEDED_LABEL},
     logging::*,
+    noise::error::NoiseHandshakeError,
     peer_manager::{PeerManagerError, TransportNotification},
-    transport::Connection,
+    transport::{Connection, HandshakeNegotiationError},
 };
 use anyhow::format_err;
 use aptos_channels::{self};
-use aptos_config::network_id::NetworkContext;
+use aptos_config::network_id::{NetworkContext, PeerNetworkId};
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::{ConnectionOrigin, Transport};
 use aptos_short_hex_str::AsShortHexStr;
@@ -22,7 +24,7 @@ use futures::{
     sink::SinkExt,
     stream::{Fuse, FuturesUnordered, StreamExt},
 };
-use std::time::Instant;
+use std::{error::Error, io, sync::Arc, time::Instant};
 
 #[derive(Debug)]
 pub enum TransportRequest {
@@ -41,6 +43,8 @@ where
 {
     network_context: NetworkContext,
     time_service: TimeService,
+    /// Peers and metadata (used to report failed connection handshakes)
+    peers_and_metadata: Arc<PeersAndMetadata>,
     /// [`Transport`] that is used to establish connections
     transport: TTransport,
     listener: Fuse<TTransport::Listener>,
@@ -59,6 +63,7 @@ where
     pub fn new(
         network_context: NetworkContext,
         time_service: TimeService,
+        peers_and_metadata: Arc<PeersAndMetadata>,
         transport: TTransport,
         listen_addr: NetworkAddress,
         transport_reqs_rx: aptos_channels::Receiver<TransportRequest>,
@@ -79,6 +84,7 @@ where
             Self {
                 network_context,
                 time_service,
+                peers_and_metadata,
                 transport,
                 listener: listener.fuse(),
                 transport_reqs_rx,
@@ -240,6 +246,7 @@ where
                 if dialed_peer_id == peer_id {
                     Ok(connection)
                 } else {
+                    self.report_failed_handshake(peer_id);
                     Err(PeerManagerError::from_transport_error(format_err!(
                         "Dialed PeerId '{}' differs from expected PeerId '{}'",
                         dialed_peer_id.short_str(),
@@ -247,7 +254,12 @@ where
                     )))
                 }
             },
-            Err(err) => Err(PeerManagerError::from_transport_error(err)),
+            Err(err) => {
+                if is_handshake_failure(&err) {
+                    self.report_failed_handshake(peer_id);
+                }
+                Err(PeerManagerError::from_transport_error(err))
+            },
         };
 
         let response = match upgrade {
@@ -291,6 +303,14 @@ where
         }
     }
 
+    /// Reports a failed connection handshake with the given peer
+    fn report_failed_handshake(&self, peer_id: PeerId) {
+        let peer_network_id = PeerNetworkId::new(self.network_context.network_id(), peer_id);
+        self.peers_and_metadata
+            .get_peer_reputations()
+            .update_reputation(peer_network_id, ReputationEvent::FailedHandshake);
+    }
+
     /// Notifies `PeerManager` of a completed or failed inbound connection
     async fn handle_completed_inbound_upgrade(
         &mut self,
@@ -364,3 +384,66 @@ where
         }
     }
 }
+
+/// Returns true iff the given upgrade error was caused by the peer failing the
+/// handshake, i.e., it sent an invalid noise handshake response or the protocol
+/// negotiation failed. Other errors (e.g., the peer is unreachable, the connection
+/// was closed or timed out, or a local error) are not the peer's fault.
+fn is_handshake_failure<E: Error + 'static>(error: &E) -> bool {
+    let inner_error = match (error as &dyn Error)
+        .downcast_ref::<io::Error>()
+        .and_then(io::Error::get_ref)
+    {
+        Some(inner_error) => inner_error,
+        None => return false,
+    };
+
+    if let Some(noise_error) = inner_error.downcast_ref::<NoiseHandshakeError>() {
+        matches!(noise_error, NoiseHandshakeError::ClientFinalizeFailed(_))
+    } else {
+        inner_error.is::<HandshakeNegotiationError>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::wire::handshake::v1::HandshakeError;
+    use aptos_crypto::noise::NoiseError;
+
+    #[test]
+    fn test_is_handshake_failure() {
+        // Verify that invalid handshake responses and failed negotiations are handshake failures
+        let handshake_failures = [
+            io::Error::new(
+                io::ErrorKind::Other,
+                NoiseHandshakeError::ClientFinalizeFailed(NoiseError::WrongPublicKeyReceived),
+            ),
+            io::Error::new(io::ErrorKind::Other, HandshakeNegotiationError {
+                remote_peer_id: PeerId::random(),
+                error: HandshakeError::NoCommonProtocols,
+            }),
+        ];
+        for error in handshake_failures {
+            assert!(is_handshake_failure(&error));
+        }
+
+        // Verify that connection errors and local errors are not handshake failures
+        let other_errors = [
+            io::Error::new(io::ErrorKind::ConnectionRefused, "connection refused"),
+            io::Error::new(io::ErrorKind::TimedOut, "timed out"),
+            io::Error::new(io::ErrorKind::Other, "other error"),
+            io::Error::new(
+                io::ErrorKind::Other,
+                NoiseHandshakeError::ClientWriteFailed(io::ErrorKind::BrokenPipe.into()),
+            ),
+            io::Error::new(
+                io::ErrorKind::Other,
+                NoiseHandshakeError::MissingServerPublicKey,
+            ),
+        ];
+        for error in other_errors {
+            assert!(!is_handshake_failure(&error));
+        }
+    }
+}
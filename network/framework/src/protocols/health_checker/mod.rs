@@ -19,7 +19,7 @@
 //! - Use successful inbound pings as a sign of remote note being healthy
 //! - Ping a peer only in periods of no application-level communication with the peer
 use crate::{
-    application::interface::NetworkClientInterface,
+    application::{interface::NetworkClientInterface, reputation::ReputationEvent},
     constants::NETWORK_CHANNEL_SIZE,
     counters,
     logging::NetworkSchema,
@@ -338,6 +338,7 @@ impl<NetworkClient: NetworkClientInterface<HealthCheckerMsg> + Unpin> HealthChec
                         pong.0,
                         req_nonce
                     );
+                    self.update_peer_reputation(peer_id, ReputationEvent::InvalidMessage);
                     debug_assert!(false, "Pong nonce doesn't match our challenge Ping nonce");
                 }
             },
@@ -363,6 +364,7 @@ impl<NetworkClient: NetworkClientInterface<HealthCheckerMsg> + Unpin> HealthChec
                     .get_peer_failures(peer_id)
                     .unwrap_or(0);
                 if failures > self.ping_failures_tolerated {
+                    self.update_peer_reputation(peer_id, ReputationEvent::FailedHealthCheck);
                     info!(
                         NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
                         "{} Disconnecting from peer: {}",
@@ -395,6 +397,15 @@ impl<NetworkClient: NetworkClientInterface<HealthCheckerMsg> + Unpin> HealthChec
         }
     }
 
+    /// Updates the reputation of the given peer
+    fn update_peer_reputation(&self, peer_id: PeerId, event: ReputationEvent) {
+        let peer_network_id = PeerNetworkId::new(self.network_context.network_id(), peer_id);
+        self.network_interface
+            .get_peers_and_metadata()
+            .get_peer_reputations()
+            .update_reputation(peer_network_id, event);
+    }
+
     async fn ping_peer(
         network_context: NetworkContext,
         network_client: NetworkClient, // TODO: we shouldn't need to pass the client directly
@@ -5,9 +5,12 @@
 //! Protocol used to exchange supported protocol information with a remote.
 
 use crate::protocols::wire::handshake::v1::HandshakeMsg;
-use aptos_netcore::framing::{read_u16frame, write_u16frame};
+use aptos_netcore::{
+    framing::{read_u16frame, write_u16frame},
+    transport::quic::CHANNEL_BINDING_LENGTH,
+};
 use bytes::BytesMut;
-use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use std::io;
 
 /// The Handshake exchange protocol.
@@ -40,17 +43,42 @@ where
     Ok(identity)
 }
 
+/// Exchanges the channel bindings of a QUIC connection over the (authenticated)
+/// socket, and ensures they match. This proves the QUIC connection terminates at
+/// the authenticated peer, i.e., that the other QUIC streams can be trusted.
+pub async fn exchange_channel_binding<T>(
+    own_channel_binding: &[u8; CHANNEL_BINDING_LENGTH],
+    socket: &mut T,
+) -> io::Result<()>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    socket.write_all(own_channel_binding).await?;
+    socket.flush().await?;
+
+    let mut remote_channel_binding = [0u8; CHANNEL_BINDING_LENGTH];
+    socket.read_exact(&mut remote_channel_binding).await?;
+    if &remote_channel_binding != own_channel_binding {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "The QUIC channel bindings don't match! The connection may be relayed.",
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         protocols::{
-            identity::exchange_handshake,
+            identity::{exchange_channel_binding, exchange_handshake},
             wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet},
         },
         ProtocolId,
     };
     use aptos_config::network_id::NetworkId;
     use aptos_memsocket::MemorySocket;
+    use aptos_netcore::transport::quic::CHANNEL_BINDING_LENGTH;
     use aptos_types::chain_id::ChainId;
     use futures::{executor::block_on, future::join};
     use std::{collections::BTreeMap, iter::FromIterator};
@@ -186,4 +214,27 @@ mod tests {
 
         block_on(join(server, client));
     }
+
+    #[test]
+    fn channel_binding_mismatch() {
+        let (mut outbound, mut inbound) = MemorySocket::new_pair();
+
+        // Matching channel bindings should be accepted
+        let channel_binding = [1u8; CHANNEL_BINDING_LENGTH];
+        let (server_result, client_result) = block_on(join(
+            exchange_channel_binding(&channel_binding, &mut inbound),
+            exchange_channel_binding(&channel_binding, &mut outbound),
+        ));
+        server_result.unwrap();
+        client_result.unwrap();
+
+        // Different channel bindings should be rejected by both ends
+        let other_channel_binding = [2u8; CHANNEL_BINDING_LENGTH];
+        let (server_result, client_result) = block_on(join(
+            exchange_channel_binding(&channel_binding, &mut inbound),
+            exchange_channel_binding(&other_channel_binding, &mut outbound),
+        ));
+        server_result.unwrap_err();
+        client_result.unwrap_err();
+    }
 }
@@ -51,6 +51,7 @@ use crate::{
         RECEIVED_LABEL, REQUEST_LABEL, RESPONSE_LABEL, SENT_LABEL,
     },
     logging::NetworkSchema,
+    peer::WriteRequest,
     protocols::{
         network::{ReceivedMessage, SerializedRequest},
         wire::messaging::v1::{NetworkMessage, Priority, RequestId, RpcRequest, RpcResponse},
@@ -324,7 +325,7 @@ impl InboundRpcs {
     /// the outbound write queue.
     pub fn send_outbound_response(
         &mut self,
-        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
+        write_reqs_tx: &mut aptos_channel::Sender<(), WriteRequest>,
         maybe_response: Result<(RpcResponse, ProtocolId), RpcError>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
@@ -352,7 +353,7 @@ impl InboundRpcs {
             response.request_id,
         );
         let message = NetworkMessage::RpcResponse(response);
-        write_reqs_tx.push((), message)?;
+        write_reqs_tx.push((), (Some(protocol_id), message))?;
 
         // Update the outbound RPC response metrics
         self.update_outbound_rpc_response_metrics(protocol_id, res_len);
@@ -434,7 +435,7 @@ impl OutboundRpcs {
     pub fn handle_outbound_request(
         &mut self,
         request: OutboundRpcRequest,
-        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
+        write_reqs_tx: &mut aptos_channel::Sender<(), WriteRequest>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
         let peer_id = &self.remote_peer_id;
@@ -497,7 +498,7 @@ impl OutboundRpcs {
             priority: Priority::default(),
             raw_request: Vec::from(request_data.as_ref()),
         });
-        write_reqs_tx.push((), message)?;
+        write_reqs_tx.push((), (Some(protocol_id), message))?;
 
         // Update the outbound RPC request metrics
         self.update_outbound_rpc_request_metrics(protocol_id, req_len);
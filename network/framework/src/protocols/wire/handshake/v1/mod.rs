@@ -15,7 +15,10 @@
 
 use crate::counters::{start_serialization_timer, DESERIALIZATION_LABEL, SERIALIZATION_LABEL};
 use anyhow::anyhow;
-use aptos_compression::client::CompressionClient;
+use aptos_compression::{
+    client::CompressionClient,
+    codec::{CompressionCodec, DEFAULT_ZSTD_COMPRESSION_LEVEL},
+};
 use aptos_config::{config::MAX_APPLICATION_MESSAGE_SIZE, network_id::NetworkId};
 use aptos_types::chain_id::ChainId;
 #[cfg(any(test, feature = "fuzzing"))]
@@ -73,12 +76,14 @@ pub enum ProtocolId {
     JWKConsensusRpcJson = 26,
     ConsensusObserver = 27,
     ConsensusObserverRpc = 28,
+    ConsensusObserverZstd = 29, // Same as ConsensusObserver, but compressed with zstd
+    StorageServiceRpcZstd = 30, // Same as StorageServiceRpc, but compressed with zstd
 }
 
 /// The encoding types for Protocols
 enum Encoding {
     Bcs(usize),
-    CompressedBcs(usize),
+    CompressedBcs(usize, CompressionCodec),
     Json,
 }
 
@@ -115,6 +120,8 @@ impl ProtocolId {
             JWKConsensusRpcJson => "JWKConsensusRpcJson",
             ConsensusObserver => "ConsensusObserver",
             ConsensusObserverRpc => "ConsensusObserverRpc",
+            ConsensusObserverZstd => "ConsensusObserverZstd",
+            StorageServiceRpcZstd => "StorageServiceRpcZstd",
         }
     }
 
@@ -150,6 +157,8 @@ impl ProtocolId {
             ProtocolId::JWKConsensusRpcJson,
             ProtocolId::ConsensusObserver,
             ProtocolId::ConsensusObserverRpc,
+            ProtocolId::ConsensusObserverZstd,
+            ProtocolId::StorageServiceRpcZstd,
         ]
     }
 
@@ -158,15 +167,27 @@ impl ProtocolId {
         match self {
             ProtocolId::ConsensusDirectSendJson | ProtocolId::ConsensusRpcJson => Encoding::Json,
             ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
-                Encoding::CompressedBcs(RECURSION_LIMIT)
+                Encoding::CompressedBcs(RECURSION_LIMIT, CompressionCodec::Lz4)
+            },
+            ProtocolId::ConsensusObserver => {
+                Encoding::CompressedBcs(RECURSION_LIMIT, CompressionCodec::Lz4)
+            },
+            ProtocolId::ConsensusObserverZstd | ProtocolId::StorageServiceRpcZstd => {
+                Encoding::CompressedBcs(
+                    RECURSION_LIMIT,
+                    CompressionCodec::Zstd(DEFAULT_ZSTD_COMPRESSION_LEVEL),
+                )
             },
-            ProtocolId::ConsensusObserver => Encoding::CompressedBcs(RECURSION_LIMIT),
             ProtocolId::DKGDirectSendCompressed | ProtocolId::DKGRpcCompressed => {
-                Encoding::CompressedBcs(RECURSION_LIMIT)
+                Encoding::CompressedBcs(RECURSION_LIMIT, CompressionCodec::Lz4)
             },
             ProtocolId::JWKConsensusDirectSendCompressed
-            | ProtocolId::JWKConsensusRpcCompressed => Encoding::CompressedBcs(RECURSION_LIMIT),
-            ProtocolId::MempoolDirectSend => Encoding::CompressedBcs(USER_INPUT_RECURSION_LIMIT),
+            | ProtocolId::JWKConsensusRpcCompressed => {
+                Encoding::CompressedBcs(RECURSION_LIMIT, CompressionCodec::Lz4)
+            },
+            ProtocolId::MempoolDirectSend => {
+                Encoding::CompressedBcs(USER_INPUT_RECURSION_LIMIT, CompressionCodec::Lz4)
+            },
             ProtocolId::MempoolRpc => Encoding::Bcs(USER_INPUT_RECURSION_LIMIT),
             _ => Encoding::Bcs(RECURSION_LIMIT),
         }
@@ -178,7 +199,10 @@ impl ProtocolId {
             ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
                 CompressionClient::Consensus
             },
-            ProtocolId::ConsensusObserver => CompressionClient::ConsensusObserver,
+            ProtocolId::ConsensusObserver | ProtocolId::ConsensusObserverZstd => {
+                CompressionClient::ConsensusObserver
+            },
+            ProtocolId::StorageServiceRpcZstd => CompressionClient::StateSync,
             ProtocolId::MempoolDirectSend => CompressionClient::Mempool,
             ProtocolId::DKGDirectSendCompressed | ProtocolId::DKGRpcCompressed => {
                 CompressionClient::DKG
@@ -201,11 +225,12 @@ impl ProtocolId {
         // Serialize the message
         let result = match self.encoding() {
             Encoding::Bcs(limit) => self.bcs_encode(value, limit),
-            Encoding::CompressedBcs(limit) => {
+            Encoding::CompressedBcs(limit, codec) => {
                 let compression_client = self.get_compression_client();
                 let bcs_bytes = self.bcs_encode(value, limit)?;
-                aptos_compression::compress(
+                aptos_compression::compress_with_codec(
                     bcs_bytes,
+                    codec,
                     compression_client,
                     MAX_APPLICATION_MESSAGE_SIZE,
                 )
@@ -231,10 +256,11 @@ impl ProtocolId {
         // Deserialize the message
         let result = match self.encoding() {
             Encoding::Bcs(limit) => self.bcs_decode(bytes, limit),
-            Encoding::CompressedBcs(limit) => {
+            Encoding::CompressedBcs(limit, codec) => {
                 let compression_client = self.get_compression_client();
-                let raw_bytes = aptos_compression::decompress(
+                let raw_bytes = aptos_compression::decompress_with_codec(
                     &bytes.to_vec(),
+                    codec,
                     compression_client,
                     MAX_APPLICATION_MESSAGE_SIZE,
                 )
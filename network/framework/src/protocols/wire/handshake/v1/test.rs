@@ -36,6 +36,19 @@ fn test_as_u8_serde_equiv() {
     }
 }
 
+#[test]
+fn test_zstd_protocols_round_trip() {
+    let message = vec![7u8; 1024];
+    for protocol in [
+        ProtocolId::ConsensusObserverZstd,
+        ProtocolId::StorageServiceRpcZstd,
+    ] {
+        let bytes = protocol.to_bytes(&message).unwrap();
+        assert!(bytes.len() < message.len());
+        assert_eq!(protocol.from_bytes::<Vec<u8>>(&bytes).unwrap(), message);
+    }
+}
+
 #[test]
 fn represents_same_network() {
     let mut handshake_msg = HandshakeMsg::new_for_testing();
@@ -62,6 +62,15 @@ impl NetworkMessage {
             NetworkMessage::DirectSendMsg(message) => message.raw_msg.len(),
         }
     }
+
+    /// The protocol of the message (if any). Note: responses and errors don't carry one.
+    pub fn protocol_id(&self) -> Option<ProtocolId> {
+        match self {
+            NetworkMessage::RpcRequest(request) => Some(request.protocol_id),
+            NetworkMessage::DirectSendMsg(message) => Some(message.protocol_id),
+            NetworkMessage::Error(_) | NetworkMessage::RpcResponse(_) => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
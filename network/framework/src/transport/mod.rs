@@ -6,8 +6,10 @@ use crate::{
     logging::NetworkSchema,
     noise::{stream::NoiseStream, AntiReplayTimestamps, HandshakeAuthMode, NoiseUpgrader},
     protocols::{
-        identity::exchange_handshake,
-        wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet},
+        identity::{exchange_channel_binding, exchange_handshake},
+        wire::handshake::v1::{
+            HandshakeError, HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet,
+        },
     },
 };
 use aptos_config::{
@@ -19,12 +21,18 @@ use aptos_id_generator::{IdGenerator, U32IdGenerator};
 use aptos_logger::prelude::*;
 // Re-exposed for aptos-network-checker
 pub use aptos_netcore::transport::tcp::{resolve_and_connect, TCPBufferCfg, TcpSocket};
-use aptos_netcore::transport::{proxy_protocol, tcp, ConnectionOrigin, Transport};
+use aptos_netcore::transport::{
+    proxy_protocol,
+    quic::{QuicConnection, QuicSocket},
+    tcp, ConnectionOrigin, Transport,
+};
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{timeout, TimeService, TimeServiceTrait};
 use aptos_types::{
     chain_id::ChainId,
-    network_address::{parse_dns_tcp, parse_ip_tcp, parse_memory, NetworkAddress},
+    network_address::{
+        parse_dns_quic, parse_dns_tcp, parse_ip_quic, parse_ip_tcp, parse_memory, NetworkAddress,
+    },
     PeerId,
 };
 use futures::{
@@ -45,6 +53,14 @@ pub const TRANSPORT_TIMEOUT: Duration = Duration::from_secs(30);
 /// TODO: Add ability to support more than one messaging protocol.
 pub const SUPPORTED_MESSAGING_PROTOCOL: MessagingProtocolVersion = MessagingProtocolVersion::V1;
 
+/// A failed negotiation of the messaging and application protocols with a peer
+#[derive(Debug, thiserror::Error)]
+#[error("handshake negotiation with peer {remote_peer_id} failed: {error}")]
+pub struct HandshakeNegotiationError {
+    pub remote_peer_id: PeerId,
+    pub error: HandshakeError,
+}
+
 /// Global connection-id generator.
 static CONNECTION_ID_GENERATOR: ConnectionIdGenerator = ConnectionIdGenerator::new();
 
@@ -63,6 +79,26 @@ pub trait TSocket: AsyncRead + AsyncWrite + Send + fmt::Debug + Unpin + 'static
 
 impl<T> TSocket for T where T: AsyncRead + AsyncWrite + Send + fmt::Debug + Unpin + 'static {}
 
+/// The sockets produced by base transports (i.e., before the Noise upgrade).
+/// QUIC sockets expose their connection, so that the connection can be bound
+/// to the Noise session and used to open per-protocol streams.
+pub trait BaseSocket: TSocket {
+    fn quic_connection(&self) -> Option<QuicConnection> {
+        None
+    }
+}
+
+impl BaseSocket for TcpSocket {}
+
+#[cfg(any(test, feature = "testing", feature = "fuzzing"))]
+impl BaseSocket for aptos_memsocket::MemorySocket {}
+
+impl BaseSocket for QuicSocket {
+    fn quic_connection(&self) -> Option<QuicConnection> {
+        Some(self.connection())
+    }
+}
+
 /// Unique local identifier for a connection.
 #[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct ConnectionId(u32);
@@ -184,11 +220,13 @@ impl fmt::Display for ConnectionMetadata {
 }
 
 /// The `Connection` struct consists of connection metadata and the actual socket for
-/// communication.
+/// communication. QUIC connections also carry a handle to the underlying connection,
+/// which has already been bound to the (authenticated) socket.
 #[derive(Debug)]
 pub struct Connection<TSocket> {
     pub socket: TSocket,
     pub metadata: ConnectionMetadata,
+    pub quic_connection: Option<QuicConnection>,
 }
 
 /// Convenience function for adding a timeout to a Future that returns an `io::Result`.
@@ -247,7 +285,7 @@ fn add_pp_addr(proxy_protocol_enabled: bool, error: io::Error, addr: &NetworkAdd
 /// `ctxt.noise.auth_mode` is `HandshakeAuthMode::Mutual( anti_replay_timestamps , trusted_peers )`,
 /// then we will only allow connections from peers with a pubkey in the `trusted_peers`
 /// set. Otherwise, we will allow inbound connections from any pubkey.
-async fn upgrade_inbound<T: TSocket>(
+async fn upgrade_inbound<T: BaseSocket>(
     ctxt: Arc<UpgradeContext>,
     fut_socket: impl Future<Output = io::Result<T>>,
     addr: NetworkAddress,
@@ -273,6 +311,7 @@ async fn upgrade_inbound<T: TSocket>(
     } else {
         addr
     };
+    let quic_connection = socket.quic_connection();
 
     // try authenticating via noise handshake
     let (mut socket, remote_peer_id, peer_role) =
@@ -295,6 +334,13 @@ async fn upgrade_inbound<T: TSocket>(
     let remote_pubkey = socket.get_remote_static();
     let addr = addr.append_prod_protos(remote_pubkey, HANDSHAKE_VERSION);
 
+    // bind the quic connection (if any) to the noise session
+    if let Some(quic_connection) = &quic_connection {
+        exchange_channel_binding(&quic_connection.channel_binding()?, &mut socket)
+            .await
+            .map_err(|err| add_pp_addr(proxy_protocol_enabled, err, &addr))?;
+    }
+
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
         supported_protocols: ctxt.supported_protocols.clone(),
@@ -333,12 +379,13 @@ async fn upgrade_inbound<T: TSocket>(
             application_protocols,
             peer_role,
         ),
+        quic_connection,
     })
 }
 
 /// Upgrade an outbound connection. This means we run a Noise IK handshake for
 /// authentication and then negotiate common supported protocols.
-pub async fn upgrade_outbound<T: TSocket>(
+pub async fn upgrade_outbound<T: BaseSocket>(
     ctxt: Arc<UpgradeContext>,
     fut_socket: impl Future<Output = io::Result<T>>,
     addr: NetworkAddress,
@@ -347,6 +394,7 @@ pub async fn upgrade_outbound<T: TSocket>(
 ) -> io::Result<Connection<NoiseStream<T>>> {
     let origin = ConnectionOrigin::Outbound;
     let socket = fut_socket.await?;
+    let quic_connection = socket.quic_connection();
 
     // noise handshake
     let (mut socket, peer_role) = ctxt
@@ -377,6 +425,11 @@ pub async fn upgrade_outbound<T: TSocket>(
     // sanity check: Noise IK should always guarantee this is true
     debug_assert_eq!(remote_pubkey, socket.get_remote_static());
 
+    // bind the quic connection (if any) to the noise session
+    if let Some(quic_connection) = &quic_connection {
+        exchange_channel_binding(&quic_connection.channel_binding()?, &mut socket).await?;
+    }
+
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
         supported_protocols: ctxt.supported_protocols.clone(),
@@ -388,12 +441,11 @@ pub async fn upgrade_outbound<T: TSocket>(
     // try to negotiate common aptosnet version and supported application protocols
     let (messaging_protocol, application_protocols) = handshake_msg
         .perform_handshake(&remote_handshake)
-        .map_err(|e| {
-            let e = format!(
-                "handshake negotiation with peer {} failed: {}",
-                remote_peer_id, e
-            );
-            io::Error::new(io::ErrorKind::Other, e)
+        .map_err(|error| {
+            io::Error::new(io::ErrorKind::Other, HandshakeNegotiationError {
+                remote_peer_id,
+                error,
+            })
         })?;
 
     // return successful connection
@@ -408,6 +460,7 @@ pub async fn upgrade_outbound<T: TSocket>(
             application_protocols,
             peer_role,
         ),
+        quic_connection,
     })
 }
 
@@ -415,7 +468,7 @@ pub async fn upgrade_outbound<T: TSocket>(
 ///
 /// The base transport layer is pluggable, so long as it provides a reliable,
 /// ordered, connection-oriented, byte-stream abstraction (e.g., TCP). We currently
-/// use either `MemoryTransport` or `TcpTransport` as this base layer.
+/// use either `MemoryTransport`, `TcpTransport` or `QuicTransport` as this base layer.
 ///
 /// Inbound and outbound connections are first established with the `base_transport`
 /// and then negotiate a secure, authenticated transport layer (currently Noise
@@ -434,7 +487,7 @@ pub struct AptosNetTransport<TTransport> {
 impl<TTransport> AptosNetTransport<TTransport>
 where
     TTransport: Transport<Error = io::Error>,
-    TTransport::Output: TSocket,
+    TTransport::Output: BaseSocket,
     TTransport::Outbound: Send + 'static,
     TTransport::Inbound: Send + 'static,
     TTransport::Listener: Send + 'static,
@@ -487,13 +540,15 @@ where
         let (base_transport_protos, base_transport_suffix) = parse_ip_tcp(protos)
             .map(|x| (&protos[..2], x.1))
             .or_else(|| parse_dns_tcp(protos).map(|x| (&protos[..2], x.1)))
+            .or_else(|| parse_ip_quic(protos).map(|x| (&protos[..2], x.1)))
+            .or_else(|| parse_dns_quic(protos).map(|x| (&protos[..2], x.1)))
             .or_else(|| parse_memory(protos).map(|x| (&protos[..1], x.1)))
             .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::InvalidInput,
                     format!(
                         "Unexpected dialing network address: '{}', expected: \
-                         memory, ip+tcp, or dns+tcp",
+                         memory, ip+tcp, dns+tcp, ip+quic, or dns+quic",
                         addr
                     ),
                 )
@@ -539,6 +594,9 @@ where
     /// `/dns/<ipaddr>/tcp/<port>` or
     /// `/dns4/<ipaddr>/tcp/<port>` or
     /// `/dns6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then `/<base_transport>` is one
+    /// of the above, with `/quic/<port>` in place of `/tcp/<port>`.
     pub fn dial(
         &self,
         peer_id: PeerId,
@@ -585,6 +643,11 @@ where
     ///
     /// `/ip4/<ipaddr>/tcp/<port>` or
     /// `/ip6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then we expect:
+    ///
+    /// `/ip4/<ipaddr>/quic/<port>` or
+    /// `/ip6/<ipaddr>/quic/<port>`
     pub fn listen_on(
         &self,
         addr: NetworkAddress,
@@ -641,7 +704,7 @@ where
 impl<TTransport: Transport> Transport for AptosNetTransport<TTransport>
 where
     TTransport: Transport<Error = io::Error> + Send + 'static,
-    TTransport::Output: TSocket,
+    TTransport::Output: BaseSocket,
     TTransport::Outbound: Send + 'static,
     TTransport::Inbound: Send + 'static,
     TTransport::Listener: Send + 'static,
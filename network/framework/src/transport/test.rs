@@ -12,7 +12,7 @@ use aptos_config::config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION};
 use aptos_crypto::{test_utils::TEST_SEED, traits::Uniform, x25519, x25519::PrivateKey};
 use aptos_netcore::{
     framing::{read_u16frame, write_u16frame},
-    transport::{memory, ConnectionOrigin, Transport},
+    transport::{memory, quic::QuicTransport, ConnectionOrigin, Transport},
 };
 use aptos_time_service::MockTimeService;
 use aptos_types::{
@@ -22,7 +22,11 @@ use aptos_types::{
     PeerId,
 };
 use bytes::{Bytes, BytesMut};
-use futures::{future, io::AsyncWriteExt, stream::StreamExt};
+use futures::{
+    future,
+    io::{AsyncReadExt, AsyncWriteExt},
+    stream::StreamExt,
+};
 use rand::{rngs::StdRng, SeedableRng};
 use std::{io, iter::FromIterator, sync::Arc};
 use tokio::runtime::Runtime;
@@ -58,7 +62,7 @@ fn setup<TTransport>(
 )
 where
     TTransport: Transport<Error = io::Error> + Clone,
-    TTransport::Output: TSocket,
+    TTransport::Output: BaseSocket,
     TTransport::Outbound: Send + 'static,
     TTransport::Inbound: Send + 'static,
     TTransport::Listener: Send + 'static,
@@ -223,6 +227,16 @@ fn expect_ip4_tcp_noise_addr(addr: &NetworkAddress) {
     );
 }
 
+/// Check that the network address matches the format
+/// `"/ip4/<ipaddr>/quic/<port>/noise-ik/<pubkey>/handshake/<version>"`
+fn expect_ip4_quic_noise_addr(addr: &NetworkAddress) {
+    assert!(
+        matches!(addr.as_slice(), [Ip4(_), Quic(_), NoiseIK(_), Handshake(_)]),
+        "addr: '{}'",
+        addr
+    );
+}
+
 fn test_transport_success<TTransport>(
     base_transport: TTransport,
     auth: Auth,
@@ -230,7 +244,7 @@ fn test_transport_success<TTransport>(
     expect_formatted_addr: fn(&NetworkAddress),
 ) where
     TTransport: Transport<Error = io::Error> + Clone,
-    TTransport::Output: TSocket,
+    TTransport::Output: BaseSocket,
     TTransport::Outbound: Send + 'static,
     TTransport::Inbound: Send + 'static,
     TTransport::Listener: Send + 'static,
@@ -313,7 +327,7 @@ fn test_transport_rejects_unauthed_dialer<TTransport>(
     expect_formatted_addr: fn(&NetworkAddress),
 ) where
     TTransport: Transport<Error = io::Error> + Clone,
-    TTransport::Output: TSocket,
+    TTransport::Output: BaseSocket,
     TTransport::Outbound: Send + 'static,
     TTransport::Inbound: Send + 'static,
     TTransport::Listener: Send + 'static,
@@ -372,7 +386,7 @@ fn test_transport_maybe_mutual<TTransport>(
     expect_formatted_addr: fn(&NetworkAddress),
 ) where
     TTransport: Transport<Error = io::Error> + Clone,
-    TTransport::Output: TSocket,
+    TTransport::Output: BaseSocket,
     TTransport::Outbound: Send + 'static,
     TTransport::Inbound: Send + 'static,
     TTransport::Listener: Send + 'static,
@@ -587,6 +601,96 @@ fn test_tcp_transport_rejects_unauthed_dialer() {
     );
 }
 
+//////////////////////////////////////
+// AptosNetTransport<QuicTransport> //
+//////////////////////////////////////
+
+#[test]
+fn test_quic_transport_mutual_auth() {
+    test_transport_success(
+        QuicTransport::new(ProtocolId::all().len() as u32).unwrap(),
+        Auth::Mutual,
+        "/ip4/127.0.0.1/quic/0",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+#[test]
+fn test_quic_transport_server_only_auth() {
+    test_transport_success(
+        QuicTransport::new(ProtocolId::all().len() as u32).unwrap(),
+        Auth::ServerOnly,
+        "/ip4/127.0.0.1/quic/0",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+#[test]
+fn test_quic_transport_rejects_unauthed_dialer() {
+    test_transport_rejects_unauthed_dialer(
+        QuicTransport::new(ProtocolId::all().len() as u32).unwrap(),
+        "/ip4/127.0.0.1/quic/0",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+#[test]
+fn test_quic_transport_streams() {
+    let (
+        rt,
+        _mock_time,
+        (listener_peer_id, listener_transport),
+        (_dialer_peer_id, dialer_transport),
+        _,
+        _,
+    ) = setup(
+        QuicTransport::new(ProtocolId::all().len() as u32).unwrap(),
+        Auth::Mutual,
+    );
+
+    let _guard = rt.enter();
+    let (mut inbounds, listener_addr) = listener_transport
+        .listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())
+        .unwrap();
+
+    // the upgraded connections should expose the quic connection, which can
+    // be used to open additional streams (alongside the noise socket).
+    let listener_task = async move {
+        let (inbound, _dialer_addr) = inbounds.next().await.unwrap().unwrap();
+        let conn = inbound.await.unwrap();
+        let quic_connection = conn.quic_connection.unwrap();
+
+        let mut stream = quic_connection.accept_uni().await.unwrap();
+        let mut buf = vec![];
+        AsyncReadExt::read_to_end(&mut stream, &mut buf)
+            .await
+            .unwrap();
+        assert_eq!(&buf, b"foobar");
+    };
+
+    let dialer_task = async move {
+        let conn = dialer_transport
+            .dial(listener_peer_id, listener_addr)
+            .unwrap()
+            .await
+            .unwrap();
+        let quic_connection = conn.quic_connection.unwrap();
+
+        let mut stream = quic_connection.open_uni().await.unwrap();
+        AsyncWriteExt::write_all(&mut stream, b"foobar")
+            .await
+            .unwrap();
+        AsyncWriteExt::close(&mut stream).await.unwrap();
+
+        // keep the connection open until the listener has read the stream
+        // (and closed the connection)
+        let mut socket = conn.socket;
+        let _ = socket.read(&mut [0u8; 1]).await;
+    };
+
+    rt.block_on(future::join(listener_task, dialer_task));
+}
+
 /// Inserts the given peers into the trusted peer set for the specified network
 fn insert_trusted_peers(
     peers_and_metadata: &Arc<PeersAndMetadata>,
@@ -0,0 +1,559 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! QUIC Transport
+//!
+//! Connections are secured with a self-signed TLS certificate that dialers do not verify:
+//! QUIC is only used for its independent streams and loss recovery. Peers are expected to
+//! authenticate each other on top of the primary stream (e.g., with Noise), and to bind that
+//! authenticated session to the TLS session by comparing [`QuicConnection::channel_binding`].
+//!
+//! Each connection is exposed as a [`QuicSocket`], i.e., the first bidirectional stream of the
+//! connection. Additional unidirectional streams can be opened and accepted through the socket's
+//! [`QuicConnection`].
+use crate::transport::Transport;
+use aptos_types::{
+    network_address::{parse_dns_quic, parse_ip_quic, NetworkAddress, Protocol},
+    PeerId,
+};
+use futures::{
+    future::Future,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    stream::{self, Stream, StreamExt},
+};
+use quinn::{
+    crypto::rustls::{QuicClientConfig, QuicServerConfig},
+    Endpoint, RecvStream, SendStream, TransportConfig, VarInt,
+};
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::CryptoProvider,
+    pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime},
+    DigitallySignedStruct, SignatureScheme,
+};
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::net::lookup_host;
+
+/// The ALPN protocol negotiated by all QUIC connections
+const QUIC_ALPN: &[u8] = b"aptos-network";
+
+/// The server name used in the (unverified) TLS certificates
+const QUIC_SERVER_NAME: &str = "aptos-network";
+
+/// The label used to export the channel binding from the TLS session
+const CHANNEL_BINDING_LABEL: &[u8] = b"EXPORTER-aptos-network-channel-binding";
+
+/// The length (in bytes) of the channel binding
+pub const CHANNEL_BINDING_LENGTH: usize = 32;
+
+/// The byte written by the dialer to open the primary stream. QUIC streams only
+/// become visible to the remote peer once data is sent on them, so without this,
+/// a listener waiting to read first would never see the stream.
+const PRIMARY_STREAM_PREAMBLE: u8 = 0;
+
+/// The interval at which keep-alive packets are sent on idle connections
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The maximum number of bytes the remote peer may send on a single stream before
+/// the data is read (i.e., the per-stream flow control window)
+const STREAM_RECEIVE_WINDOW: u32 = 1024 * 1024; /* 1 MiB */
+
+/// The maximum number of bytes the remote peer may send across all streams of a
+/// connection before the data is read (i.e., the connection flow control window)
+const CONNECTION_RECEIVE_WINDOW: u32 = 8 * 1024 * 1024; /* 8 MiB */
+
+/// Transport to build QUIC connections
+#[derive(Clone, Debug)]
+pub struct QuicTransport {
+    server_config: quinn::ServerConfig,
+    client_config: quinn::ClientConfig,
+}
+
+impl QuicTransport {
+    /// Creates a new QUIC transport with a freshly generated, self-signed certificate.
+    /// Remote peers may only open the primary stream, and at most `max_uni_streams`
+    /// concurrent unidirectional streams per connection.
+    pub fn new(max_uni_streams: u32) -> io::Result<Self> {
+        let crypto_provider = Arc::new(rustls::crypto::ring::default_provider());
+
+        let mut transport_config = TransportConfig::default();
+        transport_config
+            .keep_alive_interval(Some(KEEP_ALIVE_INTERVAL))
+            .max_concurrent_bidi_streams(VarInt::from_u32(1))
+            .max_concurrent_uni_streams(VarInt::from_u32(max_uni_streams))
+            .stream_receive_window(VarInt::from_u32(STREAM_RECEIVE_WINDOW))
+            .receive_window(VarInt::from_u32(CONNECTION_RECEIVE_WINDOW));
+        let transport_config = Arc::new(transport_config);
+
+        // Create the server config
+        let certified_key = rcgen::generate_simple_self_signed(vec![QUIC_SERVER_NAME.into()])
+            .map_err(other_error)?;
+        let certificate = certified_key.cert.der().clone();
+        let private_key = PrivatePkcs8KeyDer::from(certified_key.key_pair.serialize_der());
+        let mut server_crypto =
+            rustls::ServerConfig::builder_with_provider(crypto_provider.clone())
+                .with_protocol_versions(&[&rustls::version::TLS13])
+                .map_err(other_error)?
+                .with_no_client_auth()
+                .with_single_cert(vec![certificate], PrivateKeyDer::Pkcs8(private_key))
+                .map_err(other_error)?;
+        server_crypto.alpn_protocols = vec![QUIC_ALPN.to_vec()];
+        let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(
+            QuicServerConfig::try_from(server_crypto).map_err(other_error)?,
+        ));
+        server_config.transport_config(transport_config.clone());
+
+        // Create the client config
+        let mut client_crypto =
+            rustls::ClientConfig::builder_with_provider(crypto_provider.clone())
+                .with_protocol_versions(&[&rustls::version::TLS13])
+                .map_err(other_error)?
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(UnverifiedServerCertificate(
+                    crypto_provider,
+                )))
+                .with_no_client_auth();
+        client_crypto.alpn_protocols = vec![QUIC_ALPN.to_vec()];
+        let mut client_config = quinn::ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(client_crypto).map_err(other_error)?,
+        ));
+        client_config.transport_config(transport_config);
+
+        Ok(Self {
+            server_config,
+            client_config,
+        })
+    }
+}
+
+impl Transport for QuicTransport {
+    type Error = io::Error;
+    type Inbound = Pin<Box<dyn Future<Output = io::Result<QuicSocket>> + Send + 'static>>;
+    type Listener =
+        Pin<Box<dyn Stream<Item = io::Result<(Self::Inbound, NetworkAddress)>> + Send + 'static>>;
+    type Outbound = Pin<Box<dyn Future<Output = io::Result<QuicSocket>> + Send + 'static>>;
+    type Output = QuicSocket;
+
+    fn listen_on(
+        &self,
+        addr: NetworkAddress,
+    ) -> Result<(Self::Listener, NetworkAddress), Self::Error> {
+        let ((ipaddr, port), addr_suffix) =
+            parse_ip_quic(addr.as_slice()).ok_or_else(|| invalid_addr_error(&addr))?;
+        if !addr_suffix.is_empty() {
+            return Err(invalid_addr_error(&addr));
+        }
+
+        let endpoint = Endpoint::server(self.server_config.clone(), SocketAddr::new(ipaddr, port))?;
+        let listen_addr = quic_addr(endpoint.local_addr()?);
+
+        let listener = stream::unfold(endpoint, |endpoint| async move {
+            let incoming = endpoint.accept().await?;
+            let dialer_addr = quic_addr(incoming.remote_address());
+            let inbound: Self::Inbound = Box::pin(async move {
+                let connection = incoming.await.map_err(other_error)?;
+                let (send, mut recv) = connection.accept_bi().await.map_err(other_error)?;
+
+                // Note: quinn streams have inherent read/write methods
+                // (with their own error types), so the io traits are used.
+                let mut preamble = [0u8; 1];
+                AsyncReadExt::read_exact(&mut recv, &mut preamble).await?;
+                if preamble[0] != PRIMARY_STREAM_PREAMBLE {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Unexpected primary stream preamble: {}", preamble[0]),
+                    ));
+                }
+
+                Ok(QuicSocket::new(
+                    QuicConnection::new(connection, None),
+                    send,
+                    recv,
+                ))
+            });
+            Some((Ok((inbound, dialer_addr)), endpoint))
+        });
+
+        Ok((listener.boxed(), listen_addr))
+    }
+
+    fn dial(&self, _peer_id: PeerId, addr: NetworkAddress) -> Result<Self::Outbound, Self::Error> {
+        let protos = addr.as_slice();
+
+        // ensure addr is well formed to save some work before potentially
+        // spawning a dial task that will fail anyway.
+        parse_ip_quic(protos)
+            .map(|_| ())
+            .or_else(|| parse_dns_quic(protos).map(|_| ()))
+            .ok_or_else(|| invalid_addr_error(&addr))?;
+
+        let client_config = self.client_config.clone();
+        Ok(Box::pin(async move {
+            let remote_addr = resolve(&addr).await?;
+
+            // Bind a new client endpoint for the connection. The endpoint
+            // is kept alive (by the connection) until the connection closes.
+            let bind_addr = if remote_addr.is_ipv4() {
+                SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)
+            } else {
+                SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0)
+            };
+            let endpoint = Endpoint::client(bind_addr)?;
+            let connection = endpoint
+                .connect_with(client_config, remote_addr, QUIC_SERVER_NAME)
+                .map_err(other_error)?
+                .await
+                .map_err(other_error)?;
+
+            let (mut send, recv) = connection.open_bi().await.map_err(other_error)?;
+            AsyncWriteExt::write_all(&mut send, &[PRIMARY_STREAM_PREAMBLE]).await?;
+
+            Ok(QuicSocket::new(
+                QuicConnection::new(connection, Some(endpoint)),
+                send,
+                recv,
+            ))
+        }))
+    }
+}
+
+/// Resolves the address to dial. For dns names, the first address
+/// that matches the `IpFilter` is used.
+async fn resolve(addr: &NetworkAddress) -> io::Result<SocketAddr> {
+    let protos = addr.as_slice();
+
+    if let Some(((ipaddr, port), _addr_suffix)) = parse_ip_quic(protos) {
+        Ok(SocketAddr::new(ipaddr, port))
+    } else if let Some(((ip_filter, dns_name, port), _addr_suffix)) = parse_dns_quic(protos) {
+        lookup_host((dns_name.as_ref(), port))
+            .await?
+            .find(|socketaddr| ip_filter.matches(socketaddr.ip()))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "could not resolve dns name to any address: name: {}, ip filter: {:?}",
+                        dns_name.as_ref(),
+                        ip_filter,
+                    ),
+                )
+            })
+    } else {
+        Err(invalid_addr_error(addr))
+    }
+}
+
+/// Returns the `"/ip4/<addr>/quic/<port>"` or `"/ip6/<addr>/quic/<port>"` address
+fn quic_addr(socketaddr: SocketAddr) -> NetworkAddress {
+    NetworkAddress::from_protocols(vec![
+        Protocol::from(socketaddr.ip()),
+        Protocol::Quic(socketaddr.port()),
+    ])
+    .expect("ip + quic is always a valid network address")
+}
+
+fn invalid_addr_error(addr: &NetworkAddress) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Invalid NetworkAddress: '{}'", addr),
+    )
+}
+
+fn other_error<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+/// A certificate verifier that accepts any server certificate (but still checks the
+/// handshake signatures). Peers are authenticated on top of the connection instead.
+#[derive(Debug)]
+struct UnverifiedServerCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for UnverifiedServerCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// A handle to an established QUIC connection, used to open and accept
+/// additional streams. The connection is closed once all handles (and
+/// streams) are dropped, or when [`QuicConnection::close`] is called.
+#[derive(Clone, Debug)]
+pub struct QuicConnection {
+    inner: quinn::Connection,
+    // The client endpoint of outbound connections (held to keep it open)
+    _endpoint: Option<Endpoint>,
+}
+
+impl QuicConnection {
+    fn new(inner: quinn::Connection, endpoint: Option<Endpoint>) -> Self {
+        Self {
+            inner,
+            _endpoint: endpoint,
+        }
+    }
+
+    /// Returns the address of the remote peer
+    pub fn remote_address(&self) -> SocketAddr {
+        self.inner.remote_address()
+    }
+
+    /// Opens a new outbound unidirectional stream. Note: the remote peer
+    /// only accepts the stream once data has been written to it.
+    pub async fn open_uni(&self) -> io::Result<SendStream> {
+        self.inner.open_uni().await.map_err(other_error)
+    }
+
+    /// Accepts the next inbound unidirectional stream
+    pub async fn accept_uni(&self) -> io::Result<RecvStream> {
+        self.inner.accept_uni().await.map_err(other_error)
+    }
+
+    /// Returns a value that is unique to the TLS session of the connection,
+    /// and identical on both ends. Comparing the values over an authenticated
+    /// channel ensures that no one is relaying the QUIC connection.
+    pub fn channel_binding(&self) -> io::Result<[u8; CHANNEL_BINDING_LENGTH]> {
+        let mut channel_binding = [0u8; CHANNEL_BINDING_LENGTH];
+        self.inner
+            .export_keying_material(&mut channel_binding, CHANNEL_BINDING_LABEL, &[])
+            .map_err(|error| {
+                other_error(format!("Failed to export the channel binding: {:?}", error))
+            })?;
+        Ok(channel_binding)
+    }
+
+    /// Immediately closes the connection (and all of its streams)
+    pub fn close(&self) {
+        self.inner.close(VarInt::from_u32(0), b"closed");
+    }
+}
+
+/// The primary (bidirectional) stream of a QUIC connection
+#[derive(Debug)]
+pub struct QuicSocket {
+    connection: QuicConnection,
+    send: SendStream,
+    recv: RecvStream,
+}
+
+impl QuicSocket {
+    fn new(connection: QuicConnection, send: SendStream, recv: RecvStream) -> Self {
+        Self {
+            connection,
+            send,
+            recv,
+        }
+    }
+
+    /// Returns a handle to the connection the socket belongs to
+    pub fn connection(&self) -> QuicConnection {
+        self.connection.clone()
+    }
+}
+
+impl AsyncRead for QuicSocket {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.recv).poll_read(context, buf)
+    }
+}
+
+impl AsyncWrite for QuicSocket {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.send).poll_write(context, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_flush(context)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_close(context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transport::{ConnectionOrigin, Transport, TransportExt};
+    use futures::future::{join, FutureExt};
+
+    const MAX_UNI_STREAMS: u32 = 3;
+
+    #[tokio::test]
+    async fn simple_listen_and_dial() -> Result<(), ::std::io::Error> {
+        // The listener writes first, to ensure it doesn't
+        // depend on the dialer sending data on the stream.
+        let t =
+            QuicTransport::new(MAX_UNI_STREAMS)?.and_then(|mut out, _addr, origin| async move {
+                match origin {
+                    ConnectionOrigin::Inbound => {
+                        out.write_all(b"Earth").await?;
+                        let mut buf = [0; 3];
+                        out.read_exact(&mut buf).await?;
+                        assert_eq!(&buf, b"Air");
+                    },
+                    ConnectionOrigin::Outbound => {
+                        let mut buf = [0; 5];
+                        out.read_exact(&mut buf).await?;
+                        assert_eq!(&buf, b"Earth");
+                        out.write_all(b"Air").await?;
+                    },
+                }
+                Ok(out)
+            });
+
+        let (listener, addr) = t.listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())?;
+        let dial = t.dial(PeerId::random(), addr)?;
+        let listener = listener.into_future().then(|(maybe_result, _stream)| {
+            let (incoming, _addr) = maybe_result.unwrap().unwrap();
+            incoming.map(Result::unwrap)
+        });
+
+        let (outgoing, incoming) = join(dial, listener).await;
+        let outgoing = outgoing.unwrap();
+
+        // Both ends should export the same channel binding
+        assert_eq!(
+            outgoing.connection().channel_binding()?,
+            incoming.connection().channel_binding()?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unidirectional_streams() -> Result<(), ::std::io::Error> {
+        let t = QuicTransport::new(MAX_UNI_STREAMS)?;
+        let (mut listener, addr) = t.listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())?;
+        let dial = t.dial(PeerId::random(), addr)?;
+        let accept = async move {
+            let (inbound, _addr) = listener.next().await.unwrap().unwrap();
+            inbound.await
+        };
+        let (outgoing, incoming) = join(dial, accept).await;
+        let (outgoing, incoming) = (outgoing?.connection(), incoming?.connection());
+
+        // Open several streams and write to them out of order
+        let mut streams = vec![];
+        for _ in 0..3 {
+            streams.push(outgoing.open_uni().await?);
+        }
+        for (index, stream) in streams.iter_mut().enumerate().rev() {
+            AsyncWriteExt::write_all(stream, &[index as u8]).await?;
+            AsyncWriteExt::close(stream).await?;
+        }
+
+        // Each stream should be received independently
+        let mut received = vec![];
+        for _ in 0..3 {
+            let mut stream = incoming.accept_uni().await?;
+            let mut buf = vec![];
+            AsyncReadExt::read_to_end(&mut stream, &mut buf).await?;
+            received.extend(buf);
+        }
+        received.sort_unstable();
+        assert_eq!(received, vec![0, 1, 2]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unidirectional_stream_limit() -> Result<(), ::std::io::Error> {
+        let t = QuicTransport::new(MAX_UNI_STREAMS)?;
+        let (mut listener, addr) = t.listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())?;
+        let dial = t.dial(PeerId::random(), addr)?;
+        let accept = async move {
+            let (inbound, _addr) = listener.next().await.unwrap().unwrap();
+            inbound.await
+        };
+        let (outgoing, incoming) = join(dial, accept).await;
+        let (outgoing, incoming) = (outgoing?.connection(), incoming?.connection());
+
+        // Open (and finish) as many streams as allowed
+        for _ in 0..MAX_UNI_STREAMS {
+            let mut stream = outgoing.open_uni().await?;
+            AsyncWriteExt::write_all(&mut stream, &[0]).await?;
+            AsyncWriteExt::close(&mut stream).await?;
+        }
+
+        // Opening another stream should block until the remote peer reads one
+        let open = tokio::time::timeout(Duration::from_millis(200), outgoing.open_uni()).await;
+        assert!(open.is_err());
+
+        let mut stream = incoming.accept_uni().await?;
+        AsyncReadExt::read_to_end(&mut stream, &mut vec![]).await?;
+        drop(stream);
+        tokio::time::timeout(Duration::from_secs(10), outgoing.open_uni())
+            .await
+            .expect("A stream should be available once the remote peer reads one")?;
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_multiaddrs() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let t = QuicTransport::new(MAX_UNI_STREAMS).unwrap();
+
+        let result = t.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap());
+        assert!(result.is_err());
+
+        let result = t.listen_on("/memory/0".parse().unwrap());
+        assert!(result.is_err());
+
+        let peer_id = PeerId::random();
+        let result = t.dial(peer_id, "/ip4/127.0.0.1/tcp/22".parse().unwrap());
+        assert!(result.is_err());
+    }
+}
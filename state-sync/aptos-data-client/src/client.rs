@@ -27,7 +27,9 @@ use aptos_id_generator::{IdGenerator, U64IdGenerator};
 use aptos_infallible::Mutex;
 use aptos_logger::{info, sample, sample::SampleRate, trace, warn};
 use aptos_network::{
-    application::{interface::NetworkClient, storage::PeersAndMetadata},
+    application::{
+        interface::NetworkClient, reputation::ReputationEvent, storage::PeersAndMetadata,
+    },
     protocols::network::RpcError,
 };
 use aptos_storage_interface::DbReader;
@@ -687,6 +689,23 @@ impl AptosDataClient {
         )))
     }
 
+    /// Disables application level compression for the request if the
+    /// messages exchanged with the peer will already be compressed using
+    /// zstd (by the network layer). This avoids compressing the data twice.
+    fn update_request_compression_for_peer(
+        &self,
+        peer: PeerNetworkId,
+        mut request: StorageServiceRequest,
+    ) -> StorageServiceRequest {
+        if request.use_compression
+            && self.data_client_config.use_zstd_compression
+            && utils::supports_zstd_compression(&self.get_peers_and_metadata(), peer)
+        {
+            request.use_compression = false;
+        }
+        request
+    }
+
     /// Sends a request to a specific peer and decodes the response
     pub async fn send_request_to_peer_and_decode<T, E>(
         &self,
@@ -698,6 +717,9 @@ impl AptosDataClient {
         T: TryFrom<StorageServiceResponse, Error = E> + Send + 'static,
         E: Into<Error>,
     {
+        // Avoid compressing the data twice (if the peer uses zstd)
+        let request = self.update_request_compression_for_peer(peer, request);
+
         // Start the timer for the request
         let timer = start_request_timer(&metrics::REQUEST_LATENCIES, &request.get_label(), peer);
 
@@ -801,6 +823,7 @@ impl AptosDataClient {
                 // is successful or failed but not both; on the other hand, this
                 // feels simpler for the consumer.
                 self.peer_states.update_score_success(peer);
+                self.update_peer_reputation(peer, ReputationEvent::ValidMessage);
 
                 // Package up all of the context needed to fully report an error
                 // with this RPC.
@@ -862,7 +885,19 @@ impl AptosDataClient {
         _request: &StorageServiceRequest,
         error_type: ErrorType,
     ) {
+        let reputation_event = match error_type {
+            ErrorType::NotUseful => ReputationEvent::NotUsefulMessage,
+            ErrorType::Malicious => ReputationEvent::InvalidMessage,
+        };
         self.peer_states.update_score_error(peer, error_type);
+        self.update_peer_reputation(peer, reputation_event);
+    }
+
+    /// Updates the node-wide reputation of the given peer
+    fn update_peer_reputation(&self, peer: PeerNetworkId, reputation_event: ReputationEvent) {
+        self.get_peers_and_metadata()
+            .get_peer_reputations()
+            .update_reputation(peer, reputation_event);
     }
 
     /// Creates a storage service request using the given data request
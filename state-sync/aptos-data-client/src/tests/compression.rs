@@ -268,3 +268,104 @@ async fn disable_compression() {
         assert_eq!(response.payload, TransactionListWithProof::new_empty());
     }
 }
+
+#[tokio::test]
+async fn zstd_compression() {
+    // Create a base config for a VFN
+    let base_config = utils::create_fullnode_base_config();
+    let networks = vec![NetworkId::Vfn, NetworkId::Public];
+
+    // Create a data client config that enables zstd compression
+    let data_client_config = AptosDataClientConfig {
+        use_compression: true,
+        use_zstd_compression: true,
+        ..Default::default()
+    };
+
+    // Ensure the properties hold for all peer priorities
+    for peer_priority in PeerPriority::get_all_ordered_priorities() {
+        // Create the mock network, mock time, client and poller
+        let (mut mock_network, mut mock_time, client, poller) = MockNetwork::new(
+            Some(base_config.clone()),
+            Some(data_client_config),
+            Some(networks.clone()),
+        );
+
+        // Start the poller
+        tokio::spawn(poller::start_poller(poller));
+
+        // Add a connected peer (that supports zstd)
+        let (peer, network_id) = utils::add_peer_to_network(peer_priority, &mut mock_network);
+
+        // Advance time so the poller sends a data summary request
+        utils::advance_polling_timer(&mut mock_time, &data_client_config).await;
+
+        // Verify the received network request uses zstd (and not lz4)
+        let network_request = utils::get_network_request(&mut mock_network, network_id).await;
+        assert_eq!(network_request.peer_network_id, peer);
+        assert_eq!(
+            network_request.protocol_id,
+            ProtocolId::StorageServiceRpcZstd
+        );
+        assert!(!network_request.storage_service_request.use_compression);
+        assert_matches!(
+            network_request.storage_service_request.data_request,
+            DataRequest::GetStorageServerSummary
+        );
+
+        // Fulfill their request
+        let highest_synced_version = 200;
+        let data_response = DataResponse::StorageServerSummary(utils::create_storage_summary(
+            highest_synced_version,
+        ));
+        network_request.response_sender.send(Ok(
+            StorageServiceResponse::new(data_response, false).unwrap()
+        ));
+
+        // Wait for the poller to process the response
+        let transaction_range = CompleteDataRange::new(0, highest_synced_version).unwrap();
+        utils::wait_for_transaction_advertisement(
+            &client,
+            &mut mock_time,
+            &data_client_config,
+            transaction_range,
+        )
+        .await;
+
+        // Handle the client's requests
+        tokio::spawn(async move {
+            loop {
+                // Verify the received network request
+                let network_request =
+                    utils::get_network_request(&mut mock_network, network_id).await;
+                assert_eq!(network_request.peer_network_id, peer);
+                assert_eq!(
+                    network_request.protocol_id,
+                    ProtocolId::StorageServiceRpcZstd
+                );
+                assert!(!network_request.storage_service_request.use_compression);
+
+                // Fulfill the request if it is for transactions
+                if matches!(
+                    network_request.storage_service_request.data_request,
+                    DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+                        start_version: 50,
+                        end_version: 100,
+                        proof_version: 100,
+                        include_events: false,
+                    })
+                ) {
+                    utils::handle_transactions_request(network_request, false);
+                }
+            }
+        });
+
+        // The request should succeed since a peer has advertised the data
+        let request_timeout = data_client_config.response_timeout_ms;
+        let response = client
+            .get_transactions_with_proof(100, 50, 100, false, request_timeout)
+            .await
+            .unwrap();
+        assert_eq!(response.payload, TransactionListWithProof::new_empty());
+    }
+}
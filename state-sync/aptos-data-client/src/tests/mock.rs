@@ -90,9 +90,18 @@ impl MockNetwork {
 
         // Create the network client
         let peers_and_metadata = PeersAndMetadata::new(&networks);
+        let data_client_config = data_client_config.unwrap_or_default();
+        let rpc_protocols = if data_client_config.use_zstd_compression {
+            vec![
+                ProtocolId::StorageServiceRpcZstd,
+                ProtocolId::StorageServiceRpc,
+            ]
+        } else {
+            vec![ProtocolId::StorageServiceRpc]
+        };
         let network_client = NetworkClient::new(
             vec![],
-            vec![ProtocolId::StorageServiceRpc],
+            rpc_protocols,
             network_senders,
             peers_and_metadata.clone(),
         );
@@ -103,7 +112,6 @@ impl MockNetwork {
         // Create an aptos data client
         let mock_time = TimeService::mock();
         let base_config = base_config.unwrap_or_default();
-        let data_client_config = data_client_config.unwrap_or_default();
         let (client, poller) = AptosDataClient::new(
             data_client_config,
             base_config.clone(),
@@ -182,6 +190,9 @@ impl MockNetwork {
         connection_metadata
             .application_protocols
             .insert(ProtocolId::StorageServiceRpc);
+        connection_metadata
+            .application_protocols
+            .insert(ProtocolId::StorageServiceRpcZstd);
         self.peers_and_metadata
             .insert_connection_metadata(peer_network_id, connection_metadata)
             .unwrap();
@@ -238,12 +249,12 @@ impl MockNetwork {
                 let data = network_request.data;
                 let res_tx = network_request.res_tx;
 
-                let message: StorageServiceMessage = bcs::from_bytes(data.as_ref()).unwrap();
+                let message: StorageServiceMessage = protocol_id.from_bytes(data.as_ref()).unwrap();
                 let storage_service_request = match message {
                     StorageServiceMessage::Request(request) => request,
                     _ => panic!("unexpected: {:?}", message),
                 };
-                let response_sender = ResponseSender::new(res_tx);
+                let response_sender = ResponseSender::new(protocol_id, res_tx);
 
                 Some(NetworkRequest {
                     peer_network_id,
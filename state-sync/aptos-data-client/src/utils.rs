@@ -7,7 +7,10 @@ use crate::{
 };
 use aptos_config::{config::AptosDataClientConfig, network_id::PeerNetworkId};
 use aptos_logger::{sample, sample::SampleRate, warn};
-use aptos_network::application::{metadata::PeerMetadata, storage::PeersAndMetadata};
+use aptos_network::{
+    application::{metadata::PeerMetadata, storage::PeersAndMetadata},
+    protocols::wire::handshake::v1::ProtocolId,
+};
 use maplit::hashset;
 use ordered_float::OrderedFloat;
 use rand::seq::{IteratorRandom, SliceRandom};
@@ -259,6 +262,17 @@ fn get_distance_and_latency_for_peer(
     None
 }
 
+/// Returns true iff the specified peer supports zstd compressed storage
+/// service requests (i.e., the peer advertised the zstd protocol).
+pub fn supports_zstd_compression(
+    peers_and_metadata: &Arc<PeersAndMetadata>,
+    peer: PeerNetworkId,
+) -> bool {
+    get_metadata_for_peer(peers_and_metadata, peer)
+        .map(|peer_metadata| peer_metadata.supports_protocol(ProtocolId::StorageServiceRpcZstd))
+        .unwrap_or(false)
+}
+
 /// Returns the metadata for the specified peer. If no metadata
 /// is found, an error is logged and None is returned.
 pub fn get_metadata_for_peer(
@@ -71,7 +71,7 @@ impl StorageServiceNetworkEvents {
                 protocol_id,
                 response_tx,
             ) => {
-                let response_sender = ResponseSender::new(response_tx);
+                let response_sender = ResponseSender::new(protocol_id, response_tx);
                 let peer_network_id = PeerNetworkId::new(network_id, peer_id);
                 Some(NetworkRequest {
                     peer_network_id,
@@ -96,19 +96,28 @@ impl Stream for StorageServiceNetworkEvents {
 /// A channel for fulfilling a pending StorageService RPC request.
 /// Provides a more strongly typed interface around the raw RPC response channel.
 pub struct ResponseSender {
+    protocol_id: ProtocolId, // The protocol of the request (used to encode the response)
     response_tx: oneshot::Sender<Result<Bytes, RpcError>>,
 }
 
 impl ResponseSender {
-    pub fn new(response_tx: oneshot::Sender<Result<Bytes, RpcError>>) -> Self {
-        Self { response_tx }
+    pub fn new(
+        protocol_id: ProtocolId,
+        response_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> Self {
+        Self {
+            protocol_id,
+            response_tx,
+        }
     }
 
     pub fn send(self, response: Result<StorageServiceResponse>) {
         let msg = StorageServiceMessage::Response(response);
-        let result = bcs::to_bytes(&msg)
+        let result = self
+            .protocol_id
+            .to_bytes(&msg)
             .map(Bytes::from)
-            .map_err(RpcError::BcsError);
+            .map_err(RpcError::Error);
         let _ = self.response_tx.send(result);
     }
 }
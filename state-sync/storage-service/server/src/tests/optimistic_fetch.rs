@@ -13,6 +13,7 @@ use aptos_config::{
     config::{AptosDataClientConfig, StorageServiceConfig},
     network_id::PeerNetworkId,
 };
+use aptos_network::protocols::wire::handshake::v1::ProtocolId;
 use aptos_storage_service_types::{
     requests::{
         DataRequest, NewTransactionOutputsWithProofRequest,
@@ -431,7 +432,7 @@ fn create_optimistic_fetch_request(
 
     // Create the response sender
     let (callback, _) = oneshot::channel();
-    let response_sender = ResponseSender::new(callback);
+    let response_sender = ResponseSender::new(ProtocolId::StorageServiceRpc, callback);
 
     // Create and return the optimistic fetch request
     OptimisticFetchRequest::new(storage_service_request, response_sender, time_service)
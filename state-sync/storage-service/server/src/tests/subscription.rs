@@ -14,6 +14,7 @@ use aptos_config::{
     config::{AptosDataClientConfig, StorageServiceConfig},
     network_id::PeerNetworkId,
 };
+use aptos_network::protocols::wire::handshake::v1::ProtocolId;
 use aptos_storage_service_types::{
     requests::{
         DataRequest, StorageServiceRequest, SubscribeTransactionOutputsWithProofRequest,
@@ -1045,7 +1046,7 @@ fn create_subscription_request(
 
     // Create the response sender
     let (callback, _) = oneshot::channel();
-    let response_sender = ResponseSender::new(callback);
+    let response_sender = ResponseSender::new(ProtocolId::StorageServiceRpc, callback);
 
     // Create a subscription request
     SubscriptionRequest::new(
@@ -0,0 +1,384 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ledger_db::LedgerDb,
+    metrics::{
+        CONSISTENCY_CHECKER_CORRUPTIONS, CONSISTENCY_CHECKER_ERRORS,
+        CONSISTENCY_CHECKER_LATEST_CHECKED_VERSION, CONSISTENCY_CHECKER_VERSIONS_CHECKED,
+        CONSISTENCY_CHECKER_VERSIONS_SKIPPED,
+    },
+    schema::transaction_accumulator::TransactionAccumulatorSchema,
+    state_merkle_db::StateMerkleDb,
+};
+use aptos_config::config::ConsistencyCheckerConfig;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::Result;
+use aptos_types::{
+    proof::{accumulator::InMemoryEventAccumulator, position::Position},
+    transaction::{AtomicVersion, Version},
+};
+use rand::Rng;
+use std::{
+    sync::{atomic::Ordering, mpsc, Arc},
+    thread,
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// Keeps verifying randomly sampled ranges of versions in the background: the transaction info
+/// against the accumulator leaf, the transaction, write set and events against the hashes in the
+/// transaction info, and the state checkpoint hash against the state tree root when a snapshot is
+/// available. Inconsistencies are logged and reported via metrics, the node keeps running.
+/// Versions that are pruned, or about to be pruned, are skipped.
+#[derive(Debug)]
+pub(crate) struct ConsistencyChecker {
+    sender: Mutex<mpsc::Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl ConsistencyChecker {
+    pub(crate) fn new(
+        ledger_db: Arc<LedgerDb>,
+        state_merkle_db: Arc<StateMerkleDb>,
+        pruner_target: Arc<AtomicVersion>,
+        config: ConsistencyCheckerConfig,
+    ) -> Self {
+        let (send, recv) = mpsc::channel();
+        let join_handle = Some(
+            thread::Builder::new()
+                .name("db_consistency_checker".to_string())
+                .spawn(move || loop {
+                    let num_versions_checked = match check_random_sample(
+                        &ledger_db,
+                        &state_merkle_db,
+                        &pruner_target,
+                        &config,
+                    ) {
+                        Ok(num_versions) => num_versions,
+                        Err(e) => {
+                            CONSISTENCY_CHECKER_ERRORS.inc();
+                            warn!(error = ?e, "Consistency check failed to read data.");
+                            0
+                        },
+                    };
+
+                    // Wait before taking the next sample to honor the rate limit, and back off
+                    // when there's nothing to check.
+                    let wait = if num_versions_checked == 0 {
+                        Duration::from_secs(10)
+                    } else {
+                        Duration::from_millis(
+                            num_versions_checked * 1000 / config.max_versions_per_second.max(1),
+                        )
+                    };
+                    match recv.recv_timeout(wait) {
+                        Ok(_) => break,
+                        Err(mpsc::RecvTimeoutError::Timeout) => (),
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                })
+                .expect("Failed to spawn the consistency checker thread."),
+        );
+        Self {
+            sender: Mutex::new(send),
+            join_handle,
+        }
+    }
+}
+
+impl Drop for ConsistencyChecker {
+    fn drop(&mut self) {
+        // Notify the checker thread to exit
+        self.sender.lock().send(()).unwrap();
+        self.join_handle
+            .take()
+            .expect("Consistency checker thread must exist.")
+            .join()
+            .expect("Consistency checker thread should join peacefully.");
+    }
+}
+
+/// Result of checking a range of versions.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct RangeCheck {
+    pub num_checked: u64,
+    /// Versions that were pruned, or became pruned while being checked.
+    pub num_skipped: u64,
+    pub num_inconsistencies: usize,
+}
+
+/// Checks a random range of versions that's not pruned, returning the number of versions checked
+/// or skipped.
+fn check_random_sample(
+    ledger_db: &LedgerDb,
+    state_merkle_db: &StateMerkleDb,
+    pruner_target: &AtomicVersion,
+    config: &ConsistencyCheckerConfig,
+) -> Result<u64> {
+    let Some(ledger_info) = ledger_db.metadata_db().get_latest_ledger_info_option() else {
+        return Ok(0);
+    };
+    let latest_version = ledger_info.ledger_info().version();
+    check_hash(
+        "accumulator_root_hash",
+        latest_version,
+        ledger_info.ledger_info().transaction_accumulator_hash(),
+        Some(
+            ledger_db
+                .transaction_accumulator_db()
+                .get_root_hash(latest_version)?,
+        ),
+    );
+
+    let first_version = first_unpruned_version(ledger_db, pruner_target);
+    if first_version > latest_version {
+        return Ok(0);
+    }
+    let start_version = rand::thread_rng().gen_range(first_version, latest_version + 1);
+    let num_versions = std::cmp::min(
+        config.versions_per_sample,
+        latest_version - start_version + 1,
+    );
+    let result = match check_range(
+        ledger_db,
+        state_merkle_db,
+        pruner_target,
+        start_version,
+        num_versions,
+    ) {
+        Ok(result) => result,
+        // Data can be pruned while it's being checked, which is not an error.
+        Err(_) if first_unpruned_version(ledger_db, pruner_target) > start_version => {
+            CONSISTENCY_CHECKER_VERSIONS_SKIPPED.inc_by(num_versions);
+            return Ok(num_versions);
+        },
+        Err(e) => return Err(e),
+    };
+
+    CONSISTENCY_CHECKER_VERSIONS_CHECKED.inc_by(result.num_checked);
+    CONSISTENCY_CHECKER_VERSIONS_SKIPPED.inc_by(result.num_skipped);
+    if result.num_checked > 0 {
+        CONSISTENCY_CHECKER_LATEST_CHECKED_VERSION.set((start_version + num_versions - 1) as i64);
+    }
+    Ok(num_versions)
+}
+
+/// Returns the first version whose ledger data is not pruned and is not about to be pruned.
+fn first_unpruned_version(ledger_db: &LedgerDb, pruner_target: &AtomicVersion) -> Version {
+    std::cmp::max(
+        ledger_db.metadata_db().get_pruner_progress().unwrap_or(0),
+        pruner_target.load(Ordering::SeqCst),
+    )
+}
+
+/// Verifies versions in `[start_version, start_version + num_versions)`. Versions below the
+/// pruner target or progress are skipped. Since the pruner can run concurrently, the pruner
+/// progress is read again before a mismatch is counted, so that pruned data (e.g. missing events
+/// or accumulator leaves) is not mistaken for corruption.
+pub(crate) fn check_range(
+    ledger_db: &LedgerDb,
+    state_merkle_db: &StateMerkleDb,
+    pruner_target: &AtomicVersion,
+    start_version: Version,
+    num_versions: u64,
+) -> Result<RangeCheck> {
+    let mut result = RangeCheck::default();
+    let end_version = start_version + num_versions;
+    for version in start_version..end_version {
+        if version < first_unpruned_version(ledger_db, pruner_target) {
+            result.num_skipped += 1;
+            continue;
+        }
+        let mismatches = match check_version(ledger_db, version) {
+            Ok(mismatches) => mismatches,
+            Err(_) if version < first_unpruned_version(ledger_db, pruner_target) => {
+                result.num_skipped += 1;
+                continue;
+            },
+            Err(e) => return Err(e),
+        };
+        if !mismatches.is_empty() && version < first_unpruned_version(ledger_db, pruner_target) {
+            result.num_skipped += 1;
+            continue;
+        }
+
+        for (check, expected, actual) in mismatches {
+            report_inconsistency(check, version, expected, actual);
+            result.num_inconsistencies += 1;
+        }
+        result.num_checked += 1;
+    }
+
+    // JMT roots are only persisted for some of the state checkpoints, check the latest one.
+    if let Some(snapshot_version) =
+        state_merkle_db.get_state_snapshot_version_before(end_version)?
+    {
+        if snapshot_version >= start_version
+            && snapshot_version >= first_unpruned_version(ledger_db, pruner_target)
+        {
+            let txn_info = ledger_db
+                .transaction_info_db()
+                .get_transaction_info(snapshot_version)?;
+            if let Some(state_checkpoint_hash) = txn_info.state_checkpoint_hash() {
+                if !check_hash(
+                    "state_checkpoint_hash",
+                    snapshot_version,
+                    state_checkpoint_hash,
+                    Some(state_merkle_db.get_root_hash(snapshot_version)?),
+                ) {
+                    result.num_inconsistencies += 1;
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Returns the checks that failed for a version, with the expected and actual hashes.
+fn check_version(
+    ledger_db: &LedgerDb,
+    version: Version,
+) -> Result<Vec<(&'static str, HashValue, Option<HashValue>)>> {
+    let txn_info = ledger_db
+        .transaction_info_db()
+        .get_transaction_info(version)?;
+    let leaf_hash = ledger_db
+        .transaction_accumulator_db_raw()
+        .get::<TransactionAccumulatorSchema>(&Position::from_leaf_index(version))?;
+    let txn = ledger_db.transaction_db().get_transaction(version)?;
+    let write_set = ledger_db.write_set_db().get_write_set(version)?;
+    let event_hashes: Vec<_> = ledger_db
+        .event_db()
+        .get_events_by_version(version)?
+        .iter()
+        .map(CryptoHash::hash)
+        .collect();
+
+    Ok([
+        ("txn_info_hash", txn_info.hash(), leaf_hash),
+        (
+            "transaction_hash",
+            txn_info.transaction_hash(),
+            Some(txn.hash()),
+        ),
+        (
+            "write_set_hash",
+            txn_info.state_change_hash(),
+            Some(write_set.hash()),
+        ),
+        (
+            "event_root_hash",
+            txn_info.event_root_hash(),
+            Some(InMemoryEventAccumulator::from_leaves(&event_hashes).root_hash()),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_check, expected, actual)| actual != &Some(*expected))
+    .collect())
+}
+
+fn check_hash(
+    check: &'static str,
+    version: Version,
+    expected: HashValue,
+    actual: Option<HashValue>,
+) -> bool {
+    if actual == Some(expected) {
+        return true;
+    }
+
+    report_inconsistency(check, version, expected, actual);
+    false
+}
+
+fn report_inconsistency(
+    check: &'static str,
+    version: Version,
+    expected: HashValue,
+    actual: Option<HashValue>,
+) {
+    CONSISTENCY_CHECKER_CORRUPTIONS
+        .with_label_values(&[check])
+        .inc();
+    error!(
+        check = check,
+        version = version,
+        expected = ?expected,
+        actual = ?actual,
+        "DB inconsistency detected."
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::{test_helper::arb_blocks_to_commit, AptosDB};
+    use aptos_temppath::TempPath;
+    use proptest::prelude::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(5))]
+
+        #[test]
+        fn test_check_range(input in arb_blocks_to_commit()) {
+            let tmp_dir = TempPath::new();
+            let db = AptosDB::new_for_test(&tmp_dir);
+            let mut version = 0;
+            for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+                db.save_transactions_for_test(
+                    txns_to_commit,
+                    version,
+                    Some(ledger_info_with_sigs),
+                    true, /* sync_commit */
+                )
+                .unwrap();
+                version += txns_to_commit.len() as u64;
+            }
+            let state_merkle_db = &db.state_store.state_merkle_db;
+            let no_pruning = AtomicVersion::new(0);
+            let check = |pruner_target: &AtomicVersion, start_version, num_versions| {
+                check_range(&db.ledger_db, state_merkle_db, pruner_target, start_version, num_versions)
+            };
+
+            prop_assert_eq!(
+                check(&no_pruning, 0, version).unwrap(),
+                RangeCheck { num_checked: version, num_skipped: 0, num_inconsistencies: 0 }
+            );
+
+            let corrupted_version = version / 2;
+            db.ledger_db
+                .transaction_accumulator_db_raw()
+                .put::<TransactionAccumulatorSchema>(
+                    &Position::from_leaf_index(corrupted_version),
+                    &HashValue::zero(),
+                )
+                .unwrap();
+            prop_assert_eq!(check(&no_pruning, 0, version).unwrap().num_inconsistencies, 1);
+            prop_assert_eq!(
+                check(&no_pruning, corrupted_version + 1, version - corrupted_version - 1).unwrap().num_inconsistencies,
+                0
+            );
+
+            // Reading data that doesn't exist is an error rather than an inconsistency.
+            prop_assert!(check(&no_pruning, version, 1).is_err());
+
+            // Data that's pruned, or about to be pruned, is skipped rather than checked.
+            db.ledger_db
+                .transaction_accumulator_db_raw()
+                .delete::<TransactionAccumulatorSchema>(&Position::from_leaf_index(corrupted_version))
+                .unwrap();
+            let pruned = RangeCheck {
+                num_checked: version - corrupted_version - 1,
+                num_skipped: corrupted_version + 1,
+                num_inconsistencies: 0,
+            };
+            prop_assert_eq!(check(&AtomicVersion::new(corrupted_version + 1), 0, version).unwrap(), pruned);
+            db.ledger_db.write_pruner_progress(corrupted_version + 1).unwrap();
+            prop_assert_eq!(check(&no_pruning, 0, version).unwrap(), pruned);
+        }
+    }
+}
@@ -16,7 +16,10 @@ use aptos_config::config::{
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
-use aptos_storage_interface::{DbReader, Order};
+use aptos_storage_interface::{
+    pruner::{PrunerKind, PrunerStatus},
+    DbReader, DbWriter, Order,
+};
 use aptos_temppath::TempPath;
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
@@ -326,3 +329,133 @@ proptest! {
         test_state_merkle_pruning_impl(input);
     }
 }
+
+fn open_db_with_ledger_pruner(tmp_dir: &TempPath) -> AptosDB {
+    AptosDB::open(
+        StorageDirPaths::from_path(tmp_dir),
+        /*readonly=*/ false,
+        PrunerConfig {
+            // The window is large enough for the pruner to never wake up by itself.
+            ledger_pruner_config: LedgerPrunerConfig {
+                enable: true,
+                prune_window: 1000,
+                batch_size: 1,
+                user_pruning_window_offset: 0,
+            },
+            state_merkle_pruner_config: StateMerklePrunerConfig {
+                enable: false,
+                prune_window: 1000,
+                batch_size: 1,
+            },
+            epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig {
+                enable: false,
+                prune_window: 1000,
+                batch_size: 1,
+            },
+        },
+        RocksdbConfigs::default(),
+        false, /* enable_indexer */
+        BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        None,
+    )
+    .unwrap()
+}
+
+fn get_pruner_status(db: &AptosDB, kind: PrunerKind) -> PrunerStatus {
+    db.get_pruner_statuses()
+        .unwrap()
+        .into_iter()
+        .find(|status| status.kind == kind)
+        .unwrap()
+}
+
+pub fn test_pruner_admin_ops_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = open_db_with_ledger_pruner(&tmp_dir);
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = next_ver - 1;
+
+    let statuses = db.get_pruner_statuses().unwrap();
+    assert_eq!(
+        statuses
+            .iter()
+            .map(|status| status.kind)
+            .collect::<Vec<_>>(),
+        PrunerKind::ALL.to_vec()
+    );
+    let status = get_pruner_status(&db, PrunerKind::Ledger);
+    assert!(status.enabled);
+    assert!(!status.paused);
+    assert_eq!(status.prune_window, 1000);
+    assert_eq!(status.batch_size, Some(1));
+    assert_eq!(status.min_readable_version, 0);
+    assert_eq!(status.progress, 0);
+    assert!(!get_pruner_status(&db, PrunerKind::StateMerkle).enabled);
+    assert!(db.set_pruner_paused(PrunerKind::StateMerkle, true).is_err());
+
+    // A paused pruner takes the target, but doesn't make progress.
+    db.set_pruner_paused(PrunerKind::Ledger, true).unwrap();
+    db.prune_to_version(PrunerKind::Ledger, latest_version, Some(2))
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let status = get_pruner_status(&db, PrunerKind::Ledger);
+    assert!(status.paused);
+    assert_eq!(status.batch_size, Some(2));
+    assert_eq!(status.min_readable_version, latest_version);
+    assert_eq!(status.target_version, latest_version);
+    assert_eq!(status.progress, 0);
+
+    assert!(db
+        .prune_to_version(PrunerKind::Ledger, latest_version + 1, None)
+        .is_err());
+    assert!(db
+        .prune_to_version(PrunerKind::Ledger, latest_version, Some(0))
+        .is_err());
+
+    // The pause and the batch size survive a restart.
+    drop(db);
+    let db = open_db_with_ledger_pruner(&tmp_dir);
+    let status = get_pruner_status(&db, PrunerKind::Ledger);
+    assert!(status.paused);
+    assert_eq!(status.batch_size, Some(2));
+
+    db.set_pruner_paused(PrunerKind::Ledger, false).unwrap();
+    db.prune_to_version(PrunerKind::Ledger, latest_version, None)
+        .unwrap();
+    db.ledger_pruner.wait_for_pruner().unwrap();
+    let status = get_pruner_status(&db, PrunerKind::Ledger);
+    assert!(!status.paused);
+    assert_eq!(status.batch_size, Some(2));
+    assert_eq!(status.progress, latest_version);
+    if latest_version > 0 {
+        assert!(db
+            .get_transaction_by_version(0, latest_version, false)
+            .is_err());
+    }
+
+    drop(db);
+    let db = open_db_with_ledger_pruner(&tmp_dir);
+    assert!(!get_pruner_status(&db, PrunerKind::Ledger).paused);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(5))]
+
+    #[test]
+    fn test_pruner_admin_ops(input in arb_blocks_to_commit()) {
+        test_pruner_admin_ops_impl(input);
+    }
+}
@@ -62,6 +62,7 @@ impl AptosDB {
                 state_merkle_db,
                 state_kv_db,
             ),
+            _consistency_checker: None,
             pre_commit_lock: std::sync::Mutex::new(()),
             commit_lock: std::sync::Mutex::new(()),
             indexer: None,
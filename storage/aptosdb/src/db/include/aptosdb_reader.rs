@@ -754,6 +754,17 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_pruner_statuses(&self) -> Result<Vec<PrunerStatus>> {
+        gauged_api("get_pruner_statuses", || {
+            Ok(vec![
+                self.ledger_pruner.get_pruner_status()?,
+                self.state_store.state_kv_pruner.get_pruner_status()?,
+                self.state_store.state_merkle_pruner.get_pruner_status()?,
+                self.state_store.epoch_snapshot_pruner.get_pruner_status()?,
+            ])
+        })
+    }
+
     fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo> {
         gauged_api("get_table_info", || {
             self.get_table_info_option(handle)?
@@ -209,6 +209,81 @@ impl DbWriter for AptosDB {
             Ok(())
         })
     }
+
+    fn set_pruner_paused(&self, kind: PrunerKind, paused: bool) -> Result<()> {
+        gauged_api("set_pruner_paused", || {
+            match kind {
+                PrunerKind::Ledger => self.ledger_pruner.set_pruner_paused(paused),
+                PrunerKind::StateKv => self.state_store.state_kv_pruner.set_pruner_paused(paused),
+                PrunerKind::StateMerkle => self
+                    .state_store
+                    .state_merkle_pruner
+                    .set_pruner_paused(paused),
+                PrunerKind::EpochSnapshot => self
+                    .state_store
+                    .epoch_snapshot_pruner
+                    .set_pruner_paused(paused),
+            }?;
+            info!(
+                pruner = kind.as_str(),
+                paused = paused,
+                "Pruner paused state changed."
+            );
+            Ok(())
+        })
+    }
+
+    fn prune_to_version(
+        &self,
+        kind: PrunerKind,
+        target_version: Version,
+        batch_size: Option<usize>,
+    ) -> Result<()> {
+        gauged_api("prune_to_version", || {
+            // The state tree can only be pruned up to the latest persisted snapshot, the rest of the
+            // data up to the latest synced version.
+            let max_version = match kind {
+                PrunerKind::Ledger | PrunerKind::StateKv => self.ensure_synced_version()?,
+                PrunerKind::StateMerkle | PrunerKind::EpochSnapshot => self
+                    .state_store
+                    .state_merkle_db
+                    .get_state_snapshot_version_before(Version::MAX)?
+                    .ok_or_else(|| AptosDbError::NotFound("State snapshot.".to_string()))?,
+            };
+            ensure!(
+                target_version <= max_version,
+                "Can't prune the {} data to version {}, beyond version {}.",
+                kind,
+                target_version,
+                max_version,
+            );
+
+            match kind {
+                PrunerKind::Ledger => self
+                    .ledger_pruner
+                    .prune_to_version(target_version, batch_size),
+                PrunerKind::StateKv => self
+                    .state_store
+                    .state_kv_pruner
+                    .prune_to_version(target_version, batch_size),
+                PrunerKind::StateMerkle => self
+                    .state_store
+                    .state_merkle_pruner
+                    .prune_to_version(target_version, batch_size),
+                PrunerKind::EpochSnapshot => self
+                    .state_store
+                    .epoch_snapshot_pruner
+                    .prune_to_version(target_version, batch_size),
+            }?;
+            info!(
+                pruner = kind.as_str(),
+                target_version = target_version,
+                batch_size = ?batch_size,
+                "Manual pruning requested."
+            );
+            Ok(())
+        })
+    }
 }
 
 impl AptosDB {
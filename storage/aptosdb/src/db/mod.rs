@@ -5,6 +5,7 @@
 use crate::{
     backup::{backup_handler::BackupHandler, restore_utils},
     common::MAX_NUM_EPOCH_ENDING_LEDGER_INFO,
+    consistency_checker::ConsistencyChecker,
     event_store::EventStore,
     ledger_db::{
         ledger_metadata_db::LedgerMetadataDb,
@@ -28,7 +29,8 @@ use crate::{
     transaction_store::TransactionStore,
 };
 use aptos_config::config::{
-    PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG,
+    ConsistencyCheckerConfig, PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths,
+    NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::HashValue;
 use aptos_db_indexer::{db_indexer::InternalIndexerDB, Indexer};
@@ -38,8 +40,10 @@ use aptos_metrics_core::TimerHelper;
 use aptos_resource_viewer::AptosValueAnnotator;
 use aptos_schemadb::batch::SchemaBatch;
 use aptos_storage_interface::{
-    db_ensure as ensure, db_other_bail as bail, AptosDbError, DbReader, DbWriter, LedgerSummary,
-    Order, Result, StateSnapshotReceiver, MAX_REQUEST_LIMIT,
+    db_ensure as ensure, db_other_bail as bail,
+    pruner::{PrunerKind, PrunerStatus},
+    AptosDbError, DbReader, DbWriter, LedgerSummary, Order, Result, StateSnapshotReceiver,
+    MAX_REQUEST_LIMIT,
 };
 use aptos_types::{
     account_address::AccountAddress,
@@ -94,6 +98,7 @@ pub struct AptosDB {
     pub(crate) transaction_store: Arc<TransactionStore>,
     ledger_pruner: LedgerPrunerManager,
     _rocksdb_property_reporter: RocksdbPropertyReporter,
+    _consistency_checker: Option<ConsistencyChecker>,
     /// This is just to detect concurrent calls to `pre_commit_ledger()`
     pre_commit_lock: std::sync::Mutex<()>,
     /// This is just to detect concurrent calls to `commit_ledger()`
@@ -194,6 +199,19 @@ impl AptosDB {
         Ok(())
     }
 
+    /// Starts verifying the data in the background if enabled in the config, see
+    /// `ConsistencyChecker`.
+    pub fn start_consistency_checker(&mut self, config: ConsistencyCheckerConfig) {
+        if config.enable {
+            self._consistency_checker = Some(ConsistencyChecker::new(
+                Arc::clone(&self.ledger_db),
+                Arc::clone(&self.state_store.state_merkle_db),
+                self.ledger_pruner.min_readable_version_handle(),
+                config,
+            ));
+        }
+    }
+
     /// Gets an instance of `BackupHandler` for data backup purpose.
     pub fn get_backup_handler(&self) -> BackupHandler {
         BackupHandler::new(Arc::clone(&self.state_store), Arc::clone(&self.ledger_db))
@@ -5,6 +5,8 @@ pub mod checkpoint;
 mod common;
 mod examine;
 pub mod ledger;
+pub mod pruner;
+pub mod state_diff;
 pub mod state_kv;
 pub mod state_tree;
 pub mod truncate;
@@ -27,6 +29,8 @@ pub enum Cmd {
     #[clap(subcommand)]
     StateKv(state_kv::Cmd),
 
+    StateDiff(state_diff::Cmd),
+
     Checkpoint(checkpoint::Cmd),
 
     #[clap(subcommand)]
@@ -37,6 +41,9 @@ pub enum Cmd {
     #[clap(subcommand)]
     Examine(examine::Cmd),
 
+    #[clap(subcommand)]
+    Pruner(pruner::Cmd),
+
     #[clap(subcommand)]
     IndexerValidation(validation::Cmd),
 }
@@ -46,10 +53,12 @@ impl Cmd {
         match self {
             Cmd::StateTree(cmd) => cmd.run(),
             Cmd::StateKv(cmd) => cmd.run(),
+            Cmd::StateDiff(cmd) => cmd.run(),
             Cmd::Checkpoint(cmd) => cmd.run(),
             Cmd::Ledger(cmd) => cmd.run(),
             Cmd::Truncate(cmd) => cmd.run(),
             Cmd::Examine(cmd) => cmd.run(),
+            Cmd::Pruner(cmd) => cmd.run(),
             Cmd::IndexerValidation(cmd) => cmd.run(),
         }
     }
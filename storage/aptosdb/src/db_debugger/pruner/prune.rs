@@ -0,0 +1,152 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_debugger::ShardingConfig,
+    pruner::{DBPruner, LedgerPruner, StateKvPruner, StateMerklePruner},
+    schema::{
+        stale_node_index::StaleNodeIndexSchema,
+        stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
+    },
+    AptosDB,
+};
+use aptos_config::config::{
+    internal_indexer_db_config::InternalIndexerDBConfig, EpochSnapshotPrunerConfig,
+    LedgerPrunerConfig, RocksdbConfig, RocksdbConfigs, StateMerklePrunerConfig, StorageDirPaths,
+};
+use aptos_db_indexer::{db_indexer::InternalIndexerDB, db_ops::open_internal_indexer_db};
+use aptos_storage_interface::{db_ensure as ensure, pruner::PrunerKind, AptosDbError, Result};
+use aptos_types::transaction::Version;
+use clap::Parser;
+use std::{path::PathBuf, sync::Arc};
+
+#[derive(Parser)]
+#[clap(
+    about = "Prune everything before the target version, the same way the pruner of a running \
+    node does, persisting the progress in the DB."
+)]
+pub struct Cmd {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    /// One of ledger, state_kv, state_merkle and epoch_snapshot.
+    #[clap(long)]
+    pruner: PrunerKind,
+
+    #[clap(long)]
+    target_version: Version,
+
+    /// Number of versions pruned in one batch, defaults to the `batch_size` of the pruner's
+    /// default config. Ignored by the state merkle pruners, which prune one version at a time.
+    #[clap(long)]
+    batch_size: Option<usize>,
+
+    /// The internal indexer DB of the node, whose indices are pruned along with the ledger data.
+    /// Defaults to the `internal_indexer_db` under `db_dir`, if it exists.
+    #[clap(long, value_parser)]
+    internal_indexer_db_dir: Option<PathBuf>,
+
+    #[clap(flatten)]
+    sharding_config: ShardingConfig,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let rocksdb_config = RocksdbConfigs {
+            enable_storage_sharding: self.sharding_config.enable_storage_sharding,
+            ..Default::default()
+        };
+        let (ledger_db, state_merkle_db, state_kv_db) = AptosDB::open_dbs(
+            &StorageDirPaths::from_path(&self.db_dir),
+            rocksdb_config,
+            /*readonly=*/ false,
+            /*max_num_nodes_per_lru_cache_shard=*/ 0,
+        )?;
+
+        // The state tree can only be pruned up to the latest persisted snapshot, the rest of the
+        // data up to the latest synced version.
+        let max_version = match self.pruner {
+            PrunerKind::Ledger | PrunerKind::StateKv => ledger_db
+                .metadata_db()
+                .get_synced_version()?
+                .ok_or_else(|| AptosDbError::NotFound("Synced version.".to_string()))?,
+            PrunerKind::StateMerkle | PrunerKind::EpochSnapshot => state_merkle_db
+                .get_state_snapshot_version_before(Version::MAX)?
+                .ok_or_else(|| AptosDbError::NotFound("State snapshot.".to_string()))?,
+        };
+        ensure!(
+            self.target_version <= max_version,
+            "Can't prune the {} data to version {}, beyond version {}.",
+            self.pruner,
+            self.target_version,
+            max_version,
+        );
+
+        // The state kv pruner shares the ledger pruner config.
+        let default_batch_size = match self.pruner {
+            PrunerKind::Ledger | PrunerKind::StateKv => LedgerPrunerConfig::default().batch_size,
+            PrunerKind::StateMerkle => StateMerklePrunerConfig::default().batch_size,
+            PrunerKind::EpochSnapshot => EpochSnapshotPrunerConfig::default().batch_size,
+        };
+        let batch_size = self.batch_size.unwrap_or(default_batch_size);
+        ensure!(batch_size > 0, "Batch size must be positive.");
+
+        let pruner: Box<dyn DBPruner> = match self.pruner {
+            PrunerKind::Ledger => Box::new(LedgerPruner::new(
+                Arc::new(ledger_db),
+                self.open_internal_indexer_db()?,
+            )?),
+            PrunerKind::StateKv => Box::new(StateKvPruner::new(Arc::new(state_kv_db))?),
+            PrunerKind::StateMerkle => Box::new(StateMerklePruner::<StaleNodeIndexSchema>::new(
+                Arc::new(state_merkle_db),
+            )?),
+            PrunerKind::EpochSnapshot => Box::new(StateMerklePruner::<
+                StaleNodeIndexCrossEpochSchema,
+            >::new(Arc::new(state_merkle_db))?),
+        };
+
+        let progress = pruner.progress();
+        if self.target_version <= progress {
+            println!(
+                "The {} data is already pruned to version {}.",
+                self.pruner, progress
+            );
+            return Ok(());
+        }
+
+        println!(
+            "Pruning the {} data from version {} to version {}.",
+            self.pruner, progress, self.target_version
+        );
+        pruner.set_target_version(self.target_version);
+        pruner.prune(batch_size)?;
+        println!("Done! Progress: {}", pruner.progress());
+
+        Ok(())
+    }
+
+    fn open_internal_indexer_db(&self) -> Result<Option<InternalIndexerDB>> {
+        let db_dir = match &self.internal_indexer_db_dir {
+            Some(db_dir) => db_dir.clone(),
+            None => {
+                let db_dir = self.db_dir.join("internal_indexer_db");
+                if !db_dir.exists() {
+                    return Ok(None);
+                }
+                db_dir
+            },
+        };
+        let db = Arc::new(open_internal_indexer_db(db_dir, &RocksdbConfig::default())?);
+
+        // Only prune the indices the node has been building, which are the ones with a version
+        // recorded.
+        let indexer_db =
+            InternalIndexerDB::new(Arc::clone(&db), InternalIndexerDBConfig::default());
+        let config = InternalIndexerDBConfig {
+            enable_transaction: indexer_db.get_transaction_version()?.is_some(),
+            enable_event: indexer_db.get_event_version()?.is_some(),
+            ..Default::default()
+        };
+        Ok(Some(InternalIndexerDB::new(db, config)))
+    }
+}
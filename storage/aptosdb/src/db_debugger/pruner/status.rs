@@ -0,0 +1,91 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_debugger::ShardingConfig,
+    pruner::{
+        get_ledger_pruner_progress, get_ledger_sub_pruner_progress, get_state_kv_pruner_progress,
+        get_state_kv_shard_pruner_progress, get_state_merkle_pruner_progress,
+        get_state_merkle_shard_pruner_progress,
+    },
+    schema::{
+        stale_node_index::StaleNodeIndexSchema,
+        stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
+    },
+    AptosDB,
+};
+use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
+use aptos_storage_interface::{pruner::PrunerKind, Result};
+use aptos_types::transaction::Version;
+use clap::Parser;
+use std::{collections::BTreeMap, path::PathBuf};
+
+#[derive(Parser)]
+#[clap(about = "Print the persisted progress of each pruner and its sub-pruners.")]
+pub struct Cmd {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(flatten)]
+    sharding_config: ShardingConfig,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let rocksdb_config = RocksdbConfigs {
+            enable_storage_sharding: self.sharding_config.enable_storage_sharding,
+            ..Default::default()
+        };
+        let (ledger_db, state_merkle_db, state_kv_db) = AptosDB::open_dbs(
+            &StorageDirPaths::from_path(&self.db_dir),
+            rocksdb_config,
+            /*readonly=*/ true,
+            /*max_num_nodes_per_lru_cache_shard=*/ 0,
+        )?;
+
+        println!(
+            "Synced version: {:?}",
+            ledger_db.metadata_db().get_synced_version()?
+        );
+        print_progress(
+            PrunerKind::Ledger,
+            get_ledger_pruner_progress(&ledger_db)?,
+            get_ledger_sub_pruner_progress(&ledger_db)?,
+        );
+        print_progress(
+            PrunerKind::StateKv,
+            get_state_kv_pruner_progress(&state_kv_db)?,
+            get_state_kv_shard_pruner_progress(&state_kv_db)?,
+        );
+        print_progress(
+            PrunerKind::StateMerkle,
+            get_state_merkle_pruner_progress::<StaleNodeIndexSchema>(&state_merkle_db)?,
+            get_state_merkle_shard_pruner_progress::<StaleNodeIndexSchema>(&state_merkle_db)?,
+        );
+        print_progress(
+            PrunerKind::EpochSnapshot,
+            get_state_merkle_pruner_progress::<StaleNodeIndexCrossEpochSchema>(&state_merkle_db)?,
+            get_state_merkle_shard_pruner_progress::<StaleNodeIndexCrossEpochSchema>(
+                &state_merkle_db,
+            )?,
+        );
+
+        Ok(())
+    }
+}
+
+fn print_progress(
+    kind: PrunerKind,
+    progress: Version,
+    sub_pruner_progress: BTreeMap<String, Version>,
+) {
+    println!("{} pruner progress: {}", kind, progress);
+    for (name, sub_progress) in sub_pruner_progress {
+        let lagging = if sub_progress < progress {
+            " (lagging)"
+        } else {
+            ""
+        };
+        println!("    {}: {}{}", name, sub_progress, lagging);
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_debugger::common::DbDir,
+    schema::db_metadata::{DbMetadataKey, DbMetadataSchema},
+    state_kv_db::StateKvDb,
+};
+use aptos_crypto::HashValue;
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
+use aptos_types::{
+    access_path::Path,
+    account_address::AccountAddress,
+    state_store::{
+        errors::StateViewError,
+        state_key::{inner::StateKeyInner, StateKey},
+        state_storage_usage::StateStorageUsage,
+        state_value::StateValue,
+        StateView, StateViewResult, TStateView,
+    },
+    transaction::Version,
+};
+use clap::Parser;
+use move_core_types::language_storage::StructTag;
+use owo_colors::OwoColorize;
+use std::{cmp::Ordering, collections::BTreeMap, str::FromStr, sync::Arc};
+
+const MAX_VERSIONS_PER_READ: Version = 10_000;
+
+#[derive(Parser)]
+#[clap(
+    about = "Print state keys created, modified or deleted between two versions, with decoded \
+    values before and after. Changed keys are collected from the write sets in the ledger DB, or \
+    by comparing the two JMT snapshots if the write sets are pruned."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long, help = "Compare against the state right after this version.")]
+    from_version: Version,
+
+    #[clap(long, help = "Compare the state right after this version.")]
+    to_version: Version,
+
+    #[clap(long, help = "Only show state keys under this account.")]
+    account: Option<AccountAddress>,
+
+    #[clap(
+        long,
+        help = "Only show resources of this type, e.g. 0x1::coin::CoinStore. Type arguments are \
+        matched only if provided. Resource groups are shown if any of their members match."
+    )]
+    resource_type: Option<String>,
+}
+
+/// A state key whose value differs between the two versions compared.
+#[derive(Debug)]
+pub struct StateDiffRow {
+    pub key: StateKey,
+    /// The value right after `from_version`, `None` if the key was created.
+    pub before: Option<StateValue>,
+    /// The value right after `to_version`, `None` if the key was deleted.
+    pub after: Option<StateValue>,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let rows = self.diff()?;
+        println!(
+            "{}",
+            format!(
+                "* State diff from version {} to version {}. \n",
+                self.from_version, self.to_version,
+            )
+            .yellow()
+        );
+
+        let state_kv_db = self.db_dir.open_state_kv_db()?;
+        let before_view = StateKvView::new(&state_kv_db, self.from_version);
+        let after_view = StateKvView::new(&state_kv_db, self.to_version);
+        let before_annotator = AptosValueAnnotator::new(&before_view);
+        let after_annotator = AptosValueAnnotator::new(&after_view);
+
+        let (mut num_created, mut num_modified, mut num_deleted) = (0, 0, 0);
+        for row in &rows {
+            let header = match (&row.before, &row.after) {
+                (None, Some(_)) => {
+                    num_created += 1;
+                    format!("+ created  {}", describe_key(&row.key))
+                        .green()
+                        .to_string()
+                },
+                (Some(_), Some(_)) => {
+                    num_modified += 1;
+                    format!("~ modified {}", describe_key(&row.key))
+                        .yellow()
+                        .to_string()
+                },
+                (Some(_), None) => {
+                    num_deleted += 1;
+                    format!("- deleted  {}", describe_key(&row.key))
+                        .red()
+                        .to_string()
+                },
+                (None, None) => unreachable!("Unchanged keys are not part of the diff."),
+            };
+            println!("{header}");
+            if let Some(before) = &row.before {
+                println!(
+                    "  before: {}",
+                    decode_value(&before_annotator, &row.key, before)
+                );
+            }
+            if let Some(after) = &row.after {
+                println!(
+                    "   after: {}",
+                    decode_value(&after_annotator, &row.key, after)
+                );
+            }
+            println!();
+        }
+
+        println!(
+            "{}",
+            format!(
+                "* {} created, {} modified, {} deleted.",
+                num_created, num_modified, num_deleted
+            )
+            .yellow()
+        );
+
+        Ok(())
+    }
+
+    /// Returns the state keys (matching the filters) whose values differ between the two
+    /// versions, ordered by key.
+    pub fn diff(&self) -> Result<Vec<StateDiffRow>> {
+        ensure!(
+            self.from_version < self.to_version,
+            "from_version must be smaller than to_version."
+        );
+        let resource_type = self
+            .resource_type
+            .as_deref()
+            .map(ResourceTypeFilter::parse)
+            .transpose()?;
+
+        let ledger_db = self.db_dir.open_ledger_db()?;
+        let state_kv_db = self.db_dir.open_state_kv_db()?;
+        let synced_version = ledger_db
+            .metadata_db()
+            .get_synced_version()?
+            .expect("DB is empty.");
+        ensure!(
+            self.to_version <= synced_version,
+            "to_version {} is greater than latest version {}.",
+            self.to_version,
+            synced_version,
+        );
+        let state_kv_pruner_progress = state_kv_db
+            .metadata_db()
+            .get::<DbMetadataSchema>(&DbMetadataKey::StateKvPrunerProgress)?
+            .map_or(0, |v| v.expect_version());
+        ensure!(
+            self.from_version >= state_kv_pruner_progress,
+            "State values before version {} are pruned.",
+            state_kv_pruner_progress,
+        );
+
+        let ledger_pruner_progress = ledger_db.metadata_db().get_pruner_progress().unwrap_or(0);
+        let mut keys = if self.from_version + 1 >= ledger_pruner_progress {
+            let mut keys = Vec::new();
+            let mut version = self.from_version + 1;
+            while version <= self.to_version {
+                let end = std::cmp::min(self.to_version + 1, version + MAX_VERSIONS_PER_READ);
+                for write_set in ledger_db.write_set_db().get_write_sets(version, end)? {
+                    keys.extend(write_set.iter().map(|(key, _op)| key.clone()));
+                }
+                version = end;
+            }
+            keys
+        } else {
+            self.changed_keys_in_jmt()?
+        };
+        keys.sort();
+        keys.dedup();
+
+        let before_view = StateKvView::new(&state_kv_db, self.from_version);
+        let after_view = StateKvView::new(&state_kv_db, self.to_version);
+        let mut rows = Vec::new();
+        for key in keys {
+            if let Some(account) = &self.account {
+                if key_address(&key) != Some(*account) {
+                    continue;
+                }
+            }
+            let before = before_view.get(&key)?;
+            let after = after_view.get(&key)?;
+            if let Some(filter) = &resource_type {
+                if !filter.matches(&key, before.as_ref(), after.as_ref()) {
+                    continue;
+                }
+            }
+            let changed = match (&before, &after) {
+                (None, None) => false,
+                (Some(before), Some(after)) => before.bytes() != after.bytes(),
+                _ => true,
+            };
+            if changed {
+                rows.push(StateDiffRow { key, before, after });
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Merges the leaves of the two snapshots in key hash order, returning keys that exist in only
+    /// one of them, or whose leaf was updated in between.
+    fn changed_keys_in_jmt(&self) -> Result<Vec<StateKey>> {
+        let state_merkle_db = Arc::new(self.db_dir.open_state_merkle_db()?);
+        let mut before_iter = JellyfishMerkleIterator::new(
+            state_merkle_db.clone(),
+            self.from_version,
+            HashValue::zero(),
+        )?
+        .peekable();
+        let mut after_iter =
+            JellyfishMerkleIterator::new(state_merkle_db, self.to_version, HashValue::zero())?
+                .peekable();
+
+        let mut keys = Vec::new();
+        loop {
+            let order = match (before_iter.peek(), after_iter.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(Ok((before_hash, _))), Some(Ok((after_hash, _)))) => {
+                    before_hash.cmp(after_hash)
+                },
+                (Some(Err(_)), _) => return Err(before_iter.next().unwrap().unwrap_err()),
+                (_, Some(Err(_))) => return Err(after_iter.next().unwrap().unwrap_err()),
+            };
+            match order {
+                Ordering::Less => keys.push(before_iter.next().unwrap()?.1 .0),
+                Ordering::Greater => keys.push(after_iter.next().unwrap()?.1 .0),
+                Ordering::Equal => {
+                    let (_, (_, before_leaf_version)) = before_iter.next().unwrap()?;
+                    let (_, (key, after_leaf_version)) = after_iter.next().unwrap()?;
+                    if before_leaf_version != after_leaf_version {
+                        keys.push(key);
+                    }
+                },
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+/// Reads state values as of a version directly from the state kv db, so that values can be
+/// annotated with the module definitions at the time.
+struct StateKvView<'a> {
+    db: &'a StateKvDb,
+    version: Version,
+}
+
+impl<'a> StateKvView<'a> {
+    fn new(db: &'a StateKvDb, version: Version) -> Self {
+        Self { db, version }
+    }
+
+    fn get(&self, key: &StateKey) -> Result<Option<StateValue>> {
+        Ok(self
+            .db
+            .get_state_value_with_version_by_version(key, self.version)?
+            .map(|(_version, value)| value))
+    }
+}
+
+impl TStateView for StateKvView<'_> {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        self.get(state_key)
+            .map_err(|e| StateViewError::Other(e.to_string()))
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        Ok(StateStorageUsage::new_untracked())
+    }
+}
+
+struct ResourceTypeFilter {
+    struct_tag: StructTag,
+    match_type_args: bool,
+}
+
+impl ResourceTypeFilter {
+    fn parse(s: &str) -> Result<Self> {
+        let struct_tag = StructTag::from_str(s)
+            .map_err(|e| AptosDbError::Other(format!("Invalid resource type {}: {}", s, e)))?;
+        Ok(Self {
+            match_type_args: s.contains('<'),
+            struct_tag,
+        })
+    }
+
+    fn matches_tag(&self, tag: &StructTag) -> bool {
+        if self.match_type_args {
+            tag == &self.struct_tag
+        } else {
+            tag.address == self.struct_tag.address
+                && tag.module == self.struct_tag.module
+                && tag.name == self.struct_tag.name
+        }
+    }
+
+    fn matches(
+        &self,
+        key: &StateKey,
+        before: Option<&StateValue>,
+        after: Option<&StateValue>,
+    ) -> bool {
+        let StateKeyInner::AccessPath(access_path) = key.inner() else {
+            return false;
+        };
+        match access_path.get_path() {
+            Path::Code(_) => false,
+            Path::Resource(tag) => self.matches_tag(&tag),
+            Path::ResourceGroup(tag) => {
+                self.matches_tag(&tag)
+                    || [before, after].into_iter().flatten().any(|value| {
+                        bcs::from_bytes::<BTreeMap<StructTag, Vec<u8>>>(value.bytes())
+                            .map_or(false, |group| group.keys().any(|tag| self.matches_tag(tag)))
+                    })
+            },
+        }
+    }
+}
+
+fn key_address(key: &StateKey) -> Option<AccountAddress> {
+    match key.inner() {
+        StateKeyInner::AccessPath(access_path) => Some(access_path.address),
+        StateKeyInner::TableItem { .. } | StateKeyInner::Raw(_) => None,
+    }
+}
+
+fn describe_key(key: &StateKey) -> String {
+    match key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            Path::Code(module_id) => format!("module {}", module_id.short_str_lossless()),
+            Path::Resource(tag) => format!(
+                "resource {} at {}",
+                tag.to_canonical_string(),
+                access_path.address.to_hex_literal()
+            ),
+            Path::ResourceGroup(tag) => format!(
+                "resource group {} at {}",
+                tag.to_canonical_string(),
+                access_path.address.to_hex_literal()
+            ),
+        },
+        StateKeyInner::TableItem { handle, key } => format!(
+            "table item {} in table {}",
+            hex::encode(key),
+            handle.0.to_hex_literal()
+        ),
+        StateKeyInner::Raw(bytes) => format!("raw key {}", hex::encode(bytes)),
+    }
+}
+
+/// Decodes resources (and members of resource groups) with their Move type layouts. Falls back to
+/// the hex encoded bytes for table items, or when decoding fails.
+fn decode_value<S: StateView>(
+    annotator: &AptosValueAnnotator<S>,
+    key: &StateKey,
+    value: &StateValue,
+) -> String {
+    let bytes = value.bytes();
+    let decoded = match key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            Path::Code(_) => Some(format!("module, {} bytes", bytes.len())),
+            Path::Resource(tag) => annotator
+                .view_resource(&tag, bytes)
+                .ok()
+                .map(|s| s.to_string()),
+            Path::ResourceGroup(_) => bcs::from_bytes::<BTreeMap<StructTag, Vec<u8>>>(bytes)
+                .ok()
+                .map(|group| {
+                    group
+                        .iter()
+                        .map(|(tag, blob)| match annotator.view_resource(tag, blob) {
+                            Ok(s) => s.to_string(),
+                            Err(_) => {
+                                format!("{} {}", tag.to_canonical_string(), hex::encode(blob))
+                            },
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }),
+        },
+        StateKeyInner::TableItem { .. } | StateKeyInner::Raw(_) => None,
+    };
+
+    decoded.unwrap_or_else(|| hex::encode(bytes))
+}
@@ -13,9 +13,13 @@ use crate::{
 use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
 use aptos_schemadb::batch::SchemaBatch;
 use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
-use claims::assert_le;
+use aptos_types::transaction::Version;
 use clap::Parser;
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 #[derive(Parser)]
 #[clap(about = "Delete all data after the provided version.")]
@@ -64,95 +68,124 @@ impl Cmd {
             println!("Opted out backup creation!.");
         }
 
-        let rocksdb_config = RocksdbConfigs {
-            enable_storage_sharding: self.sharding_config.enable_storage_sharding,
-            ..Default::default()
-        };
-        let (ledger_db, state_merkle_db, state_kv_db) = AptosDB::open_dbs(
-            &StorageDirPaths::from_path(&self.db_dir),
-            rocksdb_config,
-            /*readonly=*/ false,
-            /*max_num_nodes_per_lru_cache_shard=*/ 0,
+        truncate(
+            &self.db_dir,
+            self.target_version,
+            self.sharding_config.enable_storage_sharding,
         )?;
 
-        let ledger_db = Arc::new(ledger_db);
-        let state_merkle_db = Arc::new(state_merkle_db);
-        let state_kv_db = Arc::new(state_kv_db);
-        let overall_version = ledger_db
-            .metadata_db()
-            .get_synced_version()
-            .expect("DB read failed.")
-            .expect("Overall commit progress must exist.");
-        let ledger_db_version = ledger_db
-            .metadata_db()
-            .get_ledger_commit_progress()
-            .expect("Current version of ledger db must exist.");
-        let state_kv_db_version = get_state_kv_commit_progress(&state_kv_db)?
-            .expect("Current version of state kv db must exist.");
-        let state_merkle_db_version = get_current_version_in_state_merkle_db(&state_merkle_db)?
-            .expect("Current version of state merkle db must exist.");
-
-        let mut target_version = self.target_version;
-
-        assert_le!(overall_version, ledger_db_version);
-        assert_le!(overall_version, state_kv_db_version);
-        assert_le!(state_merkle_db_version, overall_version);
-        assert_le!(target_version, overall_version);
+        Ok(())
+    }
+}
 
+/// Deletes all data after `target_version` from the DB at `db_dir`, falling back to the largest
+/// version before it that has VersionData. Returns the version the DB was truncated to.
+pub fn truncate(
+    db_dir: &Path,
+    target_version: Version,
+    enable_storage_sharding: bool,
+) -> Result<Version> {
+    let rocksdb_config = RocksdbConfigs {
+        enable_storage_sharding,
+        ..Default::default()
+    };
+    let (ledger_db, state_merkle_db, state_kv_db) = AptosDB::open_dbs(
+        &StorageDirPaths::from_path(db_dir),
+        rocksdb_config,
+        /*readonly=*/ false,
+        /*max_num_nodes_per_lru_cache_shard=*/ 0,
+    )?;
+
+    let ledger_db = Arc::new(ledger_db);
+    let state_merkle_db = Arc::new(state_merkle_db);
+    let state_kv_db = Arc::new(state_kv_db);
+    let overall_version = ledger_db
+        .metadata_db()
+        .get_synced_version()?
+        .ok_or_else(|| AptosDbError::NotFound("Overall commit progress".to_string()))?;
+    let ledger_db_version = ledger_db.metadata_db().get_ledger_commit_progress()?;
+    let state_kv_db_version = get_state_kv_commit_progress(&state_kv_db)?
+        .ok_or_else(|| AptosDbError::NotFound("State kv db commit progress".to_string()))?;
+    let state_merkle_db_version = get_current_version_in_state_merkle_db(&state_merkle_db)?
+        .ok_or_else(|| AptosDbError::NotFound("State merkle db version".to_string()))?;
+
+    let mut target_version = target_version;
+
+    ensure!(
+        overall_version <= ledger_db_version,
+        "Overall version {} is newer than the ledger db version {}.",
+        overall_version,
+        ledger_db_version,
+    );
+    ensure!(
+        overall_version <= state_kv_db_version,
+        "Overall version {} is newer than the state kv db version {}.",
+        overall_version,
+        state_kv_db_version,
+    );
+    ensure!(
+        state_merkle_db_version <= overall_version,
+        "State merkle db version {} is newer than the overall version {}.",
+        state_merkle_db_version,
+        overall_version,
+    );
+    ensure!(
+        target_version <= overall_version,
+        "Target version {} is newer than the latest version {}.",
+        target_version,
+        overall_version,
+    );
+
+    println!(
+        "overall_version: {}, ledger_db_version: {}, state_kv_db_version: {}, state_merkle_db_version: {}, target_version: {}",
+        overall_version, ledger_db_version, state_kv_db_version, state_merkle_db_version, target_version,
+    );
+
+    if ledger_db.metadata_db().get_usage(target_version).is_err() {
         println!(
-            "overall_version: {}, ledger_db_version: {}, state_kv_db_version: {}, state_merkle_db_version: {}, target_version: {}",
-            overall_version, ledger_db_version, state_kv_db_version, state_merkle_db_version, target_version,
+            "Unable to truncate to version {}, since there is no VersionData on that version.",
+            target_version
         );
-
-        if ledger_db.metadata_db().get_usage(target_version).is_err() {
-            println!(
-                "Unable to truncate to version {}, since there is no VersionData on that version.",
-                target_version
-            );
-            println!(
-                "Trying to fallback to the largest valid version before version {}.",
-                target_version,
-            );
-            target_version = ledger_db
-                .metadata_db()
-                .get_usage_before_or_at(target_version)?
-                .0;
-        }
-
-        println!("Starting db truncation...");
-        let mut batch = SchemaBatch::new();
-        batch.put::<DbMetadataSchema>(
-            &DbMetadataKey::OverallCommitProgress,
-            &DbMetadataValue::Version(target_version),
-        )?;
-        ledger_db.metadata_db().write_schemas(batch)?;
-
-        StateStore::sync_commit_progress(
-            Arc::clone(&ledger_db),
-            Arc::clone(&state_kv_db),
-            Arc::clone(&state_merkle_db),
-            /*crash_if_difference_is_too_large=*/ false,
+        println!(
+            "Trying to fallback to the largest valid version before version {}.",
+            target_version,
         );
-        println!("Done!");
-
-        if let Some(state_merkle_db_version) =
-            get_current_version_in_state_merkle_db(&state_merkle_db)?
-        {
-            if state_merkle_db_version < target_version {
-                println!(
-                    "Trying to catch up state merkle db, by replaying write set in ledger db."
-                );
-                let version = StateStore::catch_up_state_merkle_db(
-                    Arc::clone(&ledger_db),
-                    Arc::clone(&state_merkle_db),
-                    Arc::clone(&state_kv_db),
-                )?;
-                println!("Done! current_version: {:?}", version);
-            }
-        }
+        target_version = ledger_db
+            .metadata_db()
+            .get_usage_before_or_at(target_version)?
+            .0;
+    }
 
-        Ok(())
+    println!("Starting db truncation...");
+    let mut batch = SchemaBatch::new();
+    batch.put::<DbMetadataSchema>(
+        &DbMetadataKey::OverallCommitProgress,
+        &DbMetadataValue::Version(target_version),
+    )?;
+    ledger_db.metadata_db().write_schemas(batch)?;
+
+    StateStore::sync_commit_progress(
+        Arc::clone(&ledger_db),
+        Arc::clone(&state_kv_db),
+        Arc::clone(&state_merkle_db),
+        /*crash_if_difference_is_too_large=*/ false,
+    );
+    println!("Done!");
+
+    if let Some(state_merkle_db_version) = get_current_version_in_state_merkle_db(&state_merkle_db)?
+    {
+        if state_merkle_db_version < target_version {
+            println!("Trying to catch up state merkle db, by replaying write set in ledger db.");
+            let version = StateStore::catch_up_state_merkle_db(
+                Arc::clone(&ledger_db),
+                Arc::clone(&state_merkle_db),
+                Arc::clone(&state_kv_db),
+            )?;
+            println!("Done! current_version: {:?}", version);
+        }
     }
+
+    Ok(target_version)
 }
 
 #[cfg(test)]
@@ -208,6 +241,9 @@ mod test {
 
             drop(db);
 
+            // Truncating beyond the latest version is an error rather than a panic.
+            prop_assert!(truncate(tmp_dir.path(), db_version + 1, input.1).is_err());
+
             let mut target_version = db_version - 70;
 
             let cmd = Cmd {
@@ -8,7 +8,8 @@ use aptos_crypto::HashValue;
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_infallible::RwLock;
 use aptos_storage_interface::{
-    chunk_to_commit::ChunkToCommit, DbReader, DbWriter, Result, StateSnapshotReceiver,
+    chunk_to_commit::ChunkToCommit, pruner::PrunerKind, DbReader, DbWriter, Result,
+    StateSnapshotReceiver,
 };
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
@@ -59,6 +60,7 @@ impl FastSyncStorageWrapper {
         if let Some(sender) = update_sender {
             db_main.add_version_update_subscriber(sender)?;
         }
+        db_main.start_consistency_checker(config.storage.consistency_checker_config);
 
         let mut db_dir = config.storage.dir();
         // when the db is empty and configured to do fast sync, we will create a second DB
@@ -181,6 +183,21 @@ impl DbWriter for FastSyncStorageWrapper {
         self.get_aptos_db_write_ref()
             .commit_ledger(version, ledger_info_with_sigs, chunk_opt)
     }
+
+    fn set_pruner_paused(&self, kind: PrunerKind, paused: bool) -> Result<()> {
+        self.get_aptos_db_write_ref()
+            .set_pruner_paused(kind, paused)
+    }
+
+    fn prune_to_version(
+        &self,
+        kind: PrunerKind,
+        target_version: Version,
+        batch_size: Option<usize>,
+    ) -> Result<()> {
+        self.get_aptos_db_write_ref()
+            .prune_to_version(kind, target_version, batch_size)
+    }
 }
 
 impl DbReader for FastSyncStorageWrapper {
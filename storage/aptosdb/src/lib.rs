@@ -16,6 +16,7 @@ pub use crate::db::AptosDB;
 
 pub mod backup;
 pub mod common;
+pub(crate) mod consistency_checker;
 pub mod db;
 pub mod get_restore_handler;
 pub mod metrics;
@@ -254,3 +254,45 @@ pub static COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static CONSISTENCY_CHECKER_VERSIONS_CHECKED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_consistency_checker_versions_checked",
+        "Number of versions verified by the background consistency checker."
+    )
+    .unwrap()
+});
+
+pub static CONSISTENCY_CHECKER_VERSIONS_SKIPPED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_consistency_checker_versions_skipped",
+        "Number of sampled versions the background consistency checker skipped because they \
+        were pruned."
+    )
+    .unwrap()
+});
+
+pub static CONSISTENCY_CHECKER_LATEST_CHECKED_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_consistency_checker_latest_checked_version",
+        "Last version verified by the background consistency checker."
+    )
+    .unwrap()
+});
+
+pub static CONSISTENCY_CHECKER_CORRUPTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_consistency_checker_corruptions",
+        "Number of inconsistencies found by the background consistency checker, by check.",
+        &["check"]
+    )
+    .unwrap()
+});
+
+pub static CONSISTENCY_CHECKER_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_consistency_checker_errors",
+        "Number of samples the background consistency checker failed to read."
+    )
+    .unwrap()
+});
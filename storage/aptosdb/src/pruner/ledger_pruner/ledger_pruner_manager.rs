@@ -8,13 +8,18 @@ use crate::{
         ledger_pruner::LedgerPruner, pruner_manager::PrunerManager, pruner_utils,
         pruner_worker::PrunerWorker,
     },
+    schema::db_metadata::DbMetadataKey,
 };
 use aptos_config::config::LedgerPrunerConfig;
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_infallible::Mutex;
-use aptos_storage_interface::Result;
+use aptos_schemadb::DB;
+use aptos_storage_interface::{pruner::PrunerKind, Result};
 use aptos_types::transaction::{AtomicVersion, Version};
-use std::sync::{atomic::Ordering, Arc};
+use std::{
+    collections::BTreeMap,
+    sync::{atomic::Ordering, Arc},
+};
 
 /// The `PrunerManager` for `LedgerPruner`.
 pub(crate) struct LedgerPrunerManager {
@@ -30,8 +35,8 @@ pub(crate) struct LedgerPrunerManager {
     latest_version: Arc<Mutex<Version>>,
     /// Offset for displaying to users
     user_pruning_window_offset: u64,
-    /// The minimal readable version for the ledger data.
-    min_readable_version: AtomicVersion,
+    /// The minimal readable version for the ledger data, which is also the target of the pruner.
+    min_readable_version: Arc<AtomicVersion>,
 }
 
 impl PrunerManager for LedgerPrunerManager {
@@ -94,6 +99,44 @@ impl PrunerManager for LedgerPrunerManager {
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn pruner_kind(&self) -> PrunerKind {
+        PrunerKind::Ledger
+    }
+
+    fn pruner_worker(&self) -> Option<&PrunerWorker> {
+        self.pruner_worker.as_ref()
+    }
+
+    fn set_pruner_target_version(&self, target_version: Version) {
+        assert!(self.pruner_worker.is_some());
+        let min_readable_version = self
+            .min_readable_version
+            .fetch_max(target_version, Ordering::SeqCst)
+            .max(target_version);
+
+        PRUNER_VERSIONS
+            .with_label_values(&["ledger_pruner", "min_readable"])
+            .set(min_readable_version as i64);
+
+        self.pruner_worker
+            .as_ref()
+            .unwrap()
+            .set_target_db_version(min_readable_version);
+    }
+
+    fn get_sub_pruner_progress(&self) -> Result<BTreeMap<String, Version>> {
+        Ok(pruner_utils::get_ledger_sub_pruner_progress(
+            &self.ledger_db,
+        )?)
+    }
+
+    fn overrides_location(&self) -> (&DB, DbMetadataKey) {
+        (
+            self.ledger_db.metadata_db(),
+            DbMetadataKey::LedgerPrunerOverrides,
+        )
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -134,15 +177,30 @@ impl LedgerPrunerManager {
             pruning_batch_size: ledger_pruner_config.batch_size,
             latest_version: Arc::new(Mutex::new(min_readable_version)),
             user_pruning_window_offset: ledger_pruner_config.user_pruning_window_offset,
-            min_readable_version: AtomicVersion::new(min_readable_version),
+            min_readable_version: Arc::new(AtomicVersion::new(min_readable_version)),
         }
     }
 
+    /// Returns a handle to the minimal readable version, for readers that need to tell pruned
+    /// data apart from missing data without holding on to the manager.
+    pub(crate) fn min_readable_version_handle(&self) -> Arc<AtomicVersion> {
+        Arc::clone(&self.min_readable_version)
+    }
+
     fn init_pruner(
         ledger_db: Arc<LedgerDb>,
         ledger_pruner_config: LedgerPrunerConfig,
         internal_indexer_db: Option<InternalIndexerDB>,
     ) -> PrunerWorker {
+        let overrides = pruner_utils::get_pruner_overrides(
+            ledger_db.metadata_db(),
+            &DbMetadataKey::LedgerPrunerOverrides,
+        )
+        .expect("Failed to read ledger pruner overrides.");
+        let batch_size = overrides
+            .batch_size
+            .map_or(ledger_pruner_config.batch_size, |b| b as usize);
+
         let pruner = Arc::new(
             LedgerPruner::new(ledger_db, internal_indexer_db)
                 .expect("Failed to create ledger pruner."),
@@ -154,24 +212,12 @@ impl LedgerPrunerManager {
 
         PRUNER_BATCH_SIZE
             .with_label_values(&["ledger_pruner"])
-            .set(ledger_pruner_config.batch_size as i64);
+            .set(batch_size as i64);
 
-        PrunerWorker::new(pruner, ledger_pruner_config.batch_size, "ledger")
+        PrunerWorker::new(pruner, batch_size, overrides.paused, "ledger")
     }
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
-        assert!(self.pruner_worker.is_some());
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
-        self.min_readable_version
-            .store(min_readable_version, Ordering::SeqCst);
-
-        PRUNER_VERSIONS
-            .with_label_values(&["ledger_pruner", "min_readable"])
-            .set(min_readable_version as i64);
-
-        self.pruner_worker
-            .as_ref()
-            .unwrap()
-            .set_target_db_version(min_readable_version);
+        self.set_pruner_target_version(latest_version.saturating_sub(self.prune_window));
     }
 }
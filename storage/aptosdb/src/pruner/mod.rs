@@ -11,7 +11,15 @@ mod pruner_worker;
 mod state_kv_pruner;
 mod state_merkle_pruner;
 
-pub(crate) use ledger_pruner::ledger_pruner_manager::LedgerPrunerManager;
+pub(crate) use db_pruner::DBPruner;
+pub(crate) use ledger_pruner::{ledger_pruner_manager::LedgerPrunerManager, LedgerPruner};
 pub(crate) use pruner_manager::PrunerManager;
-pub(crate) use state_kv_pruner::state_kv_pruner_manager::StateKvPrunerManager;
-pub(crate) use state_merkle_pruner::state_merkle_pruner_manager::StateMerklePrunerManager;
+pub(crate) use pruner_utils::{
+    get_ledger_pruner_progress, get_ledger_sub_pruner_progress, get_state_kv_pruner_progress,
+    get_state_kv_shard_pruner_progress, get_state_merkle_pruner_progress,
+    get_state_merkle_shard_pruner_progress,
+};
+pub(crate) use state_kv_pruner::{state_kv_pruner_manager::StateKvPrunerManager, StateKvPruner};
+pub(crate) use state_merkle_pruner::{
+    state_merkle_pruner_manager::StateMerklePrunerManager, StateMerklePruner,
+};
@@ -1,9 +1,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::pruner::db_pruner::DBPruner;
-use aptos_storage_interface::Result;
+use crate::{
+    pruner::{db_pruner::DBPruner, pruner_utils, pruner_worker::PrunerWorker},
+    schema::db_metadata::DbMetadataKey,
+};
+use aptos_schemadb::DB;
+use aptos_storage_interface::{
+    db_ensure as ensure, db_other_bail,
+    pruner::{PrunerKind, PrunerStatus},
+    AptosDbError, Result,
+};
 use aptos_types::transaction::Version;
+use std::collections::BTreeMap;
 
 /// This module provides `Pruner` which manages a thread pruning old data in the background and is
 /// meant to be triggered by other threads as they commit new data to the DB.
@@ -37,6 +46,67 @@ pub trait PrunerManager: Sync {
     #[allow(unused)]
     fn is_pruning_pending(&self) -> bool;
 
+    fn pruner_kind(&self) -> PrunerKind;
+
+    /// Returns the worker running the pruner, None if the pruner is not enabled.
+    fn pruner_worker(&self) -> Option<&PrunerWorker>;
+
+    /// Marks versions before `target_version` as unreadable and has the worker prune them. Never
+    /// moves the min readable version backwards.
+    fn set_pruner_target_version(&self, target_version: Version);
+
+    /// Returns the progress persisted by each sub-pruner.
+    fn get_sub_pruner_progress(&self) -> Result<BTreeMap<String, Version>>;
+
+    /// Returns the DB and the key the runtime overrides of the pruner config are persisted under.
+    fn overrides_location(&self) -> (&DB, DbMetadataKey);
+
+    fn get_pruner_status(&self) -> Result<PrunerStatus> {
+        let worker = self.pruner_worker();
+        let min_readable_version = self.get_min_readable_version();
+        Ok(PrunerStatus {
+            kind: self.pruner_kind(),
+            enabled: worker.is_some(),
+            paused: worker.map_or(false, |w| w.is_paused()),
+            prune_window: self.get_prune_window(),
+            batch_size: worker.map(|w| w.batch_size()),
+            min_readable_version,
+            target_version: worker.map_or(min_readable_version, |w| w.target_version()),
+            progress: worker.map_or(min_readable_version, |w| w.progress()),
+            sub_pruner_progress: self.get_sub_pruner_progress()?,
+        })
+    }
+
+    fn set_pruner_paused(&self, paused: bool) -> Result<()> {
+        let Some(worker) = self.pruner_worker() else {
+            db_other_bail!("The {} pruner is not enabled.", self.pruner_kind());
+        };
+        let (db, overrides_key) = self.overrides_location();
+        let mut overrides = pruner_utils::get_pruner_overrides(db, &overrides_key)?;
+        overrides.paused = paused;
+        pruner_utils::save_pruner_overrides(db, &overrides_key, overrides)?;
+        worker.set_paused(paused);
+        Ok(())
+    }
+
+    /// Prunes everything before `target_version` ahead of the prune window, the caller makes
+    /// sure the version is committed.
+    fn prune_to_version(&self, target_version: Version, batch_size: Option<usize>) -> Result<()> {
+        let Some(worker) = self.pruner_worker() else {
+            db_other_bail!("The {} pruner is not enabled.", self.pruner_kind());
+        };
+        if let Some(batch_size) = batch_size {
+            ensure!(batch_size > 0, "Batch size must be positive.");
+            let (db, overrides_key) = self.overrides_location();
+            let mut overrides = pruner_utils::get_pruner_overrides(db, &overrides_key)?;
+            overrides.batch_size = Some(batch_size as u64);
+            pruner_utils::save_pruner_overrides(db, &overrides_key, overrides)?;
+            worker.set_batch_size(batch_size);
+        }
+        self.set_pruner_target_version(target_version);
+        Ok(())
+    }
+
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
     /// an internal counter.
     #[cfg(test)]
@@ -47,7 +117,6 @@ pub trait PrunerManager: Sync {
 
     #[cfg(test)]
     fn wait_for_pruner(&self) -> Result<()> {
-        use aptos_storage_interface::{db_other_bail, AptosDbError};
         use std::{
             thread::sleep,
             time::{Duration, Instant},
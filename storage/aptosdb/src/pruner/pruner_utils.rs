@@ -6,7 +6,7 @@
 use crate::{
     ledger_db::LedgerDb,
     pruner::state_merkle_pruner::generics::StaleNodeIndexSchemaTrait,
-    schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+    schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue, PrunerOverrides},
     state_kv_db::StateKvDb,
     state_merkle_db::StateMerkleDb,
     utils::get_progress,
@@ -15,6 +15,7 @@ use anyhow::Result;
 use aptos_jellyfish_merkle::StaleNodeIndex;
 use aptos_schemadb::{schema::KeyCodec, DB};
 use aptos_types::transaction::Version;
+use std::collections::BTreeMap;
 
 pub(crate) fn get_ledger_pruner_progress(ledger_db: &LedgerDb) -> Result<Version> {
     Ok(ledger_db.metadata_db().get_pruner_progress().unwrap_or(0))
@@ -58,3 +59,108 @@ pub(crate) fn get_or_initialize_subpruner_progress(
         },
     )
 }
+
+pub(crate) fn get_pruner_overrides(
+    db: &DB,
+    overrides_key: &DbMetadataKey,
+) -> Result<PrunerOverrides> {
+    Ok(db
+        .get::<DbMetadataSchema>(overrides_key)?
+        .map(DbMetadataValue::expect_pruner_overrides)
+        .unwrap_or_default())
+}
+
+pub(crate) fn save_pruner_overrides(
+    db: &DB,
+    overrides_key: &DbMetadataKey,
+    overrides: PrunerOverrides,
+) -> Result<()> {
+    Ok(db.put::<DbMetadataSchema>(overrides_key, &DbMetadataValue::PrunerOverrides(overrides))?)
+}
+
+/// Returns the progress persisted by each sub-pruner of the ledger pruner.
+pub(crate) fn get_ledger_sub_pruner_progress(
+    ledger_db: &LedgerDb,
+) -> Result<BTreeMap<String, Version>> {
+    collect_sub_pruner_progress([
+        (
+            "EventStorePruner".to_string(),
+            ledger_db.event_db_raw(),
+            DbMetadataKey::EventPrunerProgress,
+        ),
+        (
+            "TransactionAccumulatorPruner".to_string(),
+            ledger_db.transaction_accumulator_db_raw(),
+            DbMetadataKey::TransactionAccumulatorPrunerProgress,
+        ),
+        (
+            "TransactionAuxiliaryDataPruner".to_string(),
+            ledger_db.transaction_auxiliary_data_db_raw(),
+            DbMetadataKey::TransactionAuxiliaryDataPrunerProgress,
+        ),
+        (
+            "TransactionInfoPruner".to_string(),
+            ledger_db.transaction_info_db_raw(),
+            DbMetadataKey::TransactionInfoPrunerProgress,
+        ),
+        (
+            "TransactionPruner".to_string(),
+            ledger_db.transaction_db_raw(),
+            DbMetadataKey::TransactionPrunerProgress,
+        ),
+        (
+            "WriteSetPruner".to_string(),
+            ledger_db.write_set_db_raw(),
+            DbMetadataKey::WriteSetPrunerProgress,
+        ),
+    ])
+}
+
+/// Returns the progress persisted by each shard pruner of the state kv pruner, empty if sharding
+/// is not enabled.
+pub(crate) fn get_state_kv_shard_pruner_progress(
+    state_kv_db: &StateKvDb,
+) -> Result<BTreeMap<String, Version>> {
+    if !state_kv_db.enabled_sharding() {
+        return Ok(BTreeMap::new());
+    }
+    collect_sub_pruner_progress((0..state_kv_db.num_shards()).map(|shard_id| {
+        (
+            format!("shard_{shard_id}"),
+            state_kv_db.db_shard(shard_id),
+            DbMetadataKey::StateKvShardPrunerProgress(shard_id as usize),
+        )
+    }))
+}
+
+/// Returns the progress persisted by each shard pruner of a state merkle pruner, empty if
+/// sharding is not enabled.
+pub(crate) fn get_state_merkle_shard_pruner_progress<S: StaleNodeIndexSchemaTrait>(
+    state_merkle_db: &StateMerkleDb,
+) -> Result<BTreeMap<String, Version>>
+where
+    StaleNodeIndex: KeyCodec<S>,
+{
+    if !state_merkle_db.sharding_enabled() {
+        return Ok(BTreeMap::new());
+    }
+    collect_sub_pruner_progress((0..state_merkle_db.num_shards()).map(|shard_id| {
+        (
+            format!("shard_{shard_id}"),
+            state_merkle_db.db_shard(shard_id),
+            S::progress_metadata_key(Some(shard_id)),
+        )
+    }))
+}
+
+fn collect_sub_pruner_progress<'a>(
+    sub_pruners: impl IntoIterator<Item = (String, &'a DB, DbMetadataKey)>,
+) -> Result<BTreeMap<String, Version>> {
+    let mut progress = BTreeMap::new();
+    for (name, db, progress_key) in sub_pruners {
+        if let Some(version) = get_progress(db, &progress_key)? {
+            progress.insert(name, version);
+        }
+    }
+    Ok(progress)
+}
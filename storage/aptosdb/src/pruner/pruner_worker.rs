@@ -9,7 +9,7 @@ use aptos_logger::{
 use aptos_types::transaction::Version;
 use std::{
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
     thread::{sleep, JoinHandle},
@@ -33,18 +33,21 @@ pub struct PrunerWorkerInner {
     /// The pruner.
     pruner: Arc<dyn DBPruner>,
     /// A threshold to control how many items we prune for each batch.
-    batch_size: usize,
+    batch_size: AtomicUsize,
+    /// Set to true to stop pruning without quitting the worker thread.
+    paused: AtomicBool,
     /// Indicates whether the pruning loop should be running. Will only be set to true on pruner
     /// destruction.
     quit_worker: AtomicBool,
 }
 
 impl PrunerWorkerInner {
-    fn new(pruner: Arc<dyn DBPruner>, batch_size: usize) -> Arc<Self> {
+    fn new(pruner: Arc<dyn DBPruner>, batch_size: usize, paused: bool) -> Arc<Self> {
         Arc::new(Self {
             pruning_time_interval_in_ms: if cfg!(test) { 100 } else { 1 },
             pruner,
-            batch_size,
+            batch_size: AtomicUsize::new(batch_size),
+            paused: AtomicBool::new(paused),
             quit_worker: AtomicBool::new(false),
         })
     }
@@ -52,7 +55,11 @@ impl PrunerWorkerInner {
     // Loop that does the real pruning job.
     fn work(&self) {
         while !self.quit_worker.load(Ordering::SeqCst) {
-            let pruner_result = self.pruner.prune(self.batch_size);
+            if self.paused.load(Ordering::SeqCst) {
+                sleep(Duration::from_millis(self.pruning_time_interval_in_ms));
+                continue;
+            }
+            let pruner_result = self.pruner.prune(self.batch_size.load(Ordering::SeqCst));
             if pruner_result.is_err() {
                 sample!(
                     SampleRate::Duration(Duration::from_secs(1)),
@@ -74,8 +81,13 @@ impl PrunerWorkerInner {
 }
 
 impl PrunerWorker {
-    pub(crate) fn new(pruner: Arc<dyn DBPruner>, batch_size: usize, name: &str) -> Self {
-        let inner = PrunerWorkerInner::new(pruner, batch_size);
+    pub(crate) fn new(
+        pruner: Arc<dyn DBPruner>,
+        batch_size: usize,
+        paused: bool,
+        name: &str,
+    ) -> Self {
+        let inner = PrunerWorkerInner::new(pruner, batch_size, paused);
         let inner_cloned = Arc::clone(&inner);
 
         let worker_thread = std::thread::Builder::new()
@@ -99,6 +111,30 @@ impl PrunerWorker {
     pub fn is_pruning_pending(&self) -> bool {
         self.inner.pruner.is_pruning_pending()
     }
+
+    pub fn progress(&self) -> Version {
+        self.inner.pruner.progress()
+    }
+
+    pub fn target_version(&self) -> Version {
+        self.inner.pruner.target_version()
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.inner.batch_size.load(Ordering::SeqCst)
+    }
+
+    pub fn set_batch_size(&self, batch_size: usize) {
+        self.inner.batch_size.store(batch_size, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.inner.paused.store(paused, Ordering::SeqCst);
+    }
 }
 
 impl Drop for PrunerWorker {
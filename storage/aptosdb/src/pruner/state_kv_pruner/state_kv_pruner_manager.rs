@@ -7,12 +7,17 @@ use crate::{
         pruner_manager::PrunerManager, pruner_utils, pruner_worker::PrunerWorker,
         state_kv_pruner::StateKvPruner,
     },
+    schema::db_metadata::DbMetadataKey,
     state_kv_db::StateKvDb,
 };
 use aptos_config::config::LedgerPrunerConfig;
-use aptos_storage_interface::Result;
+use aptos_schemadb::DB;
+use aptos_storage_interface::{pruner::PrunerKind, Result};
 use aptos_types::transaction::{AtomicVersion, Version};
-use std::sync::{atomic::Ordering, Arc};
+use std::{
+    collections::BTreeMap,
+    sync::{atomic::Ordering, Arc},
+};
 
 /// The `PrunerManager` for `StateKvPruner`.
 pub(crate) struct StateKvPrunerManager {
@@ -71,6 +76,44 @@ impl PrunerManager for StateKvPrunerManager {
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn pruner_kind(&self) -> PrunerKind {
+        PrunerKind::StateKv
+    }
+
+    fn pruner_worker(&self) -> Option<&PrunerWorker> {
+        self.pruner_worker.as_ref()
+    }
+
+    fn set_pruner_target_version(&self, target_version: Version) {
+        assert!(self.pruner_worker.is_some());
+        let min_readable_version = self
+            .min_readable_version
+            .fetch_max(target_version, Ordering::SeqCst)
+            .max(target_version);
+
+        PRUNER_VERSIONS
+            .with_label_values(&["state_kv_pruner", "min_readable"])
+            .set(min_readable_version as i64);
+
+        self.pruner_worker
+            .as_ref()
+            .unwrap()
+            .set_target_db_version(min_readable_version);
+    }
+
+    fn get_sub_pruner_progress(&self) -> Result<BTreeMap<String, Version>> {
+        Ok(pruner_utils::get_state_kv_shard_pruner_progress(
+            &self.state_kv_db,
+        )?)
+    }
+
+    fn overrides_location(&self) -> (&DB, DbMetadataKey) {
+        (
+            self.state_kv_db.metadata_db(),
+            DbMetadataKey::StateKvPrunerOverrides,
+        )
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -111,6 +154,15 @@ impl StateKvPrunerManager {
         state_kv_db: Arc<StateKvDb>,
         state_kv_pruner_config: LedgerPrunerConfig,
     ) -> PrunerWorker {
+        let overrides = pruner_utils::get_pruner_overrides(
+            state_kv_db.metadata_db(),
+            &DbMetadataKey::StateKvPrunerOverrides,
+        )
+        .expect("Failed to read state kv pruner overrides.");
+        let batch_size = overrides
+            .batch_size
+            .map_or(state_kv_pruner_config.batch_size, |b| b as usize);
+
         let pruner =
             Arc::new(StateKvPruner::new(state_kv_db).expect("Failed to create state kv pruner."));
 
@@ -120,24 +172,12 @@ impl StateKvPrunerManager {
 
         PRUNER_BATCH_SIZE
             .with_label_values(&["state_kv_pruner"])
-            .set(state_kv_pruner_config.batch_size as i64);
+            .set(batch_size as i64);
 
-        PrunerWorker::new(pruner, state_kv_pruner_config.batch_size, "state_kv")
+        PrunerWorker::new(pruner, batch_size, overrides.paused, "state_kv")
     }
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
-        assert!(self.pruner_worker.is_some());
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
-        self.min_readable_version
-            .store(min_readable_version, Ordering::SeqCst);
-
-        PRUNER_VERSIONS
-            .with_label_values(&["state_kv_pruner", "min_readable"])
-            .set(min_readable_version as i64);
-
-        self.pruner_worker
-            .as_ref()
-            .unwrap()
-            .set_target_db_version(min_readable_version);
+        self.set_pruner_target_version(latest_version.saturating_sub(self.prune_window));
     }
 }
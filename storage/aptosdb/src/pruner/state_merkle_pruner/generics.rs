@@ -7,13 +7,16 @@ use crate::schema::{
 };
 use aptos_jellyfish_merkle::StaleNodeIndex;
 use aptos_schemadb::schema::{KeyCodec, Schema};
+use aptos_storage_interface::pruner::PrunerKind;
 
 pub trait StaleNodeIndexSchemaTrait: Schema<Key = StaleNodeIndex>
 where
     StaleNodeIndex: KeyCodec<Self>,
 {
     fn progress_metadata_key(shard_id: Option<u8>) -> DbMetadataKey;
+    fn overrides_metadata_key() -> DbMetadataKey;
     fn name() -> &'static str;
+    fn pruner_kind() -> PrunerKind;
 }
 
 impl StaleNodeIndexSchemaTrait for StaleNodeIndexSchema {
@@ -25,9 +28,17 @@ impl StaleNodeIndexSchemaTrait for StaleNodeIndexSchema {
         }
     }
 
+    fn overrides_metadata_key() -> DbMetadataKey {
+        DbMetadataKey::StateMerklePrunerOverrides
+    }
+
     fn name() -> &'static str {
         "state_merkle_pruner"
     }
+
+    fn pruner_kind() -> PrunerKind {
+        PrunerKind::StateMerkle
+    }
 }
 
 impl StaleNodeIndexSchemaTrait for StaleNodeIndexCrossEpochSchema {
@@ -39,7 +50,15 @@ impl StaleNodeIndexSchemaTrait for StaleNodeIndexCrossEpochSchema {
         }
     }
 
+    fn overrides_metadata_key() -> DbMetadataKey {
+        DbMetadataKey::EpochEndingStateMerklePrunerOverrides
+    }
+
     fn name() -> &'static str {
         "epoch_snapshot_pruner"
     }
+
+    fn pruner_kind() -> PrunerKind {
+        PrunerKind::EpochSnapshot
+    }
 }
@@ -12,14 +12,16 @@ use crate::{
         pruner_worker::PrunerWorker,
         state_merkle_pruner::{generics::StaleNodeIndexSchemaTrait, StateMerklePruner},
     },
+    schema::db_metadata::DbMetadataKey,
     state_merkle_db::StateMerkleDb,
 };
 use aptos_config::config::StateMerklePrunerConfig;
 use aptos_jellyfish_merkle::StaleNodeIndex;
-use aptos_schemadb::schema::KeyCodec;
-use aptos_storage_interface::Result;
+use aptos_schemadb::{schema::KeyCodec, DB};
+use aptos_storage_interface::{pruner::PrunerKind, Result};
 use aptos_types::transaction::{AtomicVersion, Version};
 use std::{
+    collections::BTreeMap,
     marker::PhantomData,
     sync::{atomic::Ordering, Arc},
 };
@@ -89,6 +91,45 @@ where
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn pruner_kind(&self) -> PrunerKind {
+        S::pruner_kind()
+    }
+
+    fn pruner_worker(&self) -> Option<&PrunerWorker> {
+        self.pruner_worker.as_ref()
+    }
+
+    fn set_pruner_target_version(&self, target_version: Version) {
+        assert!(self.pruner_worker.is_some());
+
+        let min_readable_version = self
+            .min_readable_version
+            .fetch_max(target_version, Ordering::SeqCst)
+            .max(target_version);
+
+        PRUNER_VERSIONS
+            .with_label_values(&[S::name(), "min_readable"])
+            .set(min_readable_version as i64);
+
+        self.pruner_worker
+            .as_ref()
+            .unwrap()
+            .set_target_db_version(min_readable_version);
+    }
+
+    fn get_sub_pruner_progress(&self) -> Result<BTreeMap<String, Version>> {
+        Ok(pruner_utils::get_state_merkle_shard_pruner_progress::<S>(
+            &self.state_merkle_db,
+        )?)
+    }
+
+    fn overrides_location(&self) -> (&DB, DbMetadataKey) {
+        (
+            self.state_merkle_db.metadata_db(),
+            S::overrides_metadata_key(),
+        )
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -136,6 +177,15 @@ where
         state_merkle_db: Arc<StateMerkleDb>,
         state_merkle_pruner_config: StateMerklePrunerConfig,
     ) -> PrunerWorker {
+        let overrides = pruner_utils::get_pruner_overrides(
+            state_merkle_db.metadata_db(),
+            &S::overrides_metadata_key(),
+        )
+        .expect("Failed to read state merkle pruner overrides.");
+        let batch_size = overrides
+            .batch_size
+            .map_or(state_merkle_pruner_config.batch_size, |b| b as usize);
+
         let pruner = Arc::new(
             StateMerklePruner::<S>::new(Arc::clone(&state_merkle_db))
                 .expect("Failed to create state merkle pruner."),
@@ -147,29 +197,12 @@ where
 
         PRUNER_BATCH_SIZE
             .with_label_values(&[S::name()])
-            .set(state_merkle_pruner_config.batch_size as i64);
+            .set(batch_size as i64);
 
-        PrunerWorker::new(
-            pruner,
-            state_merkle_pruner_config.batch_size,
-            "state_merkle",
-        )
+        PrunerWorker::new(pruner, batch_size, overrides.paused, "state_merkle")
     }
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
-        assert!(self.pruner_worker.is_some());
-
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
-        self.min_readable_version
-            .store(min_readable_version, Ordering::SeqCst);
-
-        PRUNER_VERSIONS
-            .with_label_values(&[S::name(), "min_readable"])
-            .set(min_readable_version as i64);
-
-        self.pruner_worker
-            .as_ref()
-            .unwrap()
-            .set_target_db_version(min_readable_version);
+        self.set_pruner_target_version(latest_version.saturating_sub(self.prune_window));
     }
 }
@@ -26,6 +26,7 @@ type ShardId = usize;
 pub(crate) enum DbMetadataValue {
     Version(Version),
     StateSnapshotProgress(StateSnapshotProgress),
+    PrunerOverrides(PrunerOverrides),
 }
 
 impl DbMetadataValue {
@@ -42,6 +43,22 @@ impl DbMetadataValue {
             _ => unreachable!("expected KeyHashAndUsage, got {:?}", self),
         }
     }
+
+    pub fn expect_pruner_overrides(self) -> PrunerOverrides {
+        match self {
+            Self::PrunerOverrides(overrides) => overrides,
+            _ => unreachable!("expected PrunerOverrides, got {:?}", self),
+        }
+    }
+}
+
+/// Runtime overrides of a pruner's config, kept across restarts.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+pub(crate) struct PrunerOverrides {
+    pub paused: bool,
+    /// Replaces the batch size in the pruner config if set.
+    pub batch_size: Option<u64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -68,6 +85,10 @@ pub enum DbMetadataKey {
     StateKvShardPrunerProgress(ShardId),
     StateMerkleShardRestoreProgress(ShardId, Version),
     TransactionAuxiliaryDataPrunerProgress,
+    LedgerPrunerOverrides,
+    StateKvPrunerOverrides,
+    StateMerklePrunerOverrides,
+    EpochEndingStateMerklePrunerOverrides,
 }
 
 define_schema!(
@@ -1194,6 +1194,24 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
                         &MetadataValue::Version(version - 1),
                     )?;
                 }
+                if internal_indexer_db.participant_transactions_enabled() {
+                    batch.put::<InternalIndexerMetadataSchema>(
+                        &MetadataKey::ParticipantTransactionVersion,
+                        &MetadataValue::Version(version - 1),
+                    )?;
+                }
+                if internal_indexer_db.fungible_asset_activities_enabled() {
+                    batch.put::<InternalIndexerMetadataSchema>(
+                        &MetadataKey::FungibleAssetActivityVersion,
+                        &MetadataValue::Version(version - 1),
+                    )?;
+                }
+                if internal_indexer_db.state_value_history_enabled() {
+                    batch.put::<InternalIndexerMetadataSchema>(
+                        &MetadataKey::StateValueHistoryVersion,
+                        &MetadataValue::Version(version - 1),
+                    )?;
+                }
                 internal_indexer_db
                     .get_inner_db_ref()
                     .write_schemas(batch)?;
@@ -7,8 +7,12 @@ use crate::{
     metadata::Metadata,
     storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
     utils::{
-        backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
-        should_cut_chunk, storage_ext::BackupStorageExt, GlobalBackupOpt,
+        backup_service_client::BackupServiceClient,
+        file_encoding::{FileEncoding, FileEncodingOpt},
+        read_record_bytes::ReadRecordBytes,
+        should_cut_chunk,
+        storage_ext::BackupStorageExt,
+        GlobalBackupOpt,
     },
 };
 use anyhow::{anyhow, ensure, Result};
@@ -35,6 +39,7 @@ pub struct EpochEndingBackupController {
     start_epoch: u64,
     end_epoch: u64,
     max_chunk_size: usize,
+    file_encoding: FileEncodingOpt,
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
 }
@@ -50,6 +55,7 @@ impl EpochEndingBackupController {
             start_epoch: opt.start_epoch,
             end_epoch: opt.end_epoch,
             max_chunk_size: global_opt.max_chunk_size,
+            file_encoding: global_opt.file_encoding,
             client,
             storage,
         }
@@ -76,6 +82,7 @@ impl EpochEndingBackupController {
             .storage
             .create_backup_with_random_suffix(&self.backup_name())
             .await?;
+        let encoding = self.file_encoding.encoding()?;
 
         let mut chunks = Vec::new();
         let mut waypoints = Vec::new();
@@ -96,6 +103,7 @@ impl EpochEndingBackupController {
                         &chunk_bytes,
                         chunk_first_epoch,
                         current_epoch - 1,
+                        &encoding,
                     )
                     .await?;
                 chunks.push(chunk);
@@ -117,11 +125,13 @@ impl EpochEndingBackupController {
                 &chunk_bytes,
                 chunk_first_epoch,
                 current_epoch - 1,
+                &encoding,
             )
             .await?;
         chunks.push(chunk);
 
-        self.write_manifest(&backup_handle, waypoints, chunks).await
+        self.write_manifest(&backup_handle, waypoints, chunks, encoding)
+            .await
     }
 
     fn backup_name(&self) -> String {
@@ -155,13 +165,17 @@ impl EpochEndingBackupController {
         chunk_bytes: &[u8],
         first_epoch: u64,
         last_epoch: u64,
+        encoding: &FileEncoding,
     ) -> Result<EpochEndingChunk> {
-        let (chunk_handle, mut chunk_file) = self
+        let chunk_handle = self
             .storage
-            .create_for_write(backup_handle, &Self::chunk_name(first_epoch))
+            .create_encoded_file(
+                backup_handle,
+                &Self::chunk_name(first_epoch),
+                chunk_bytes,
+                encoding,
+            )
             .await?;
-        chunk_file.write_all(chunk_bytes).await?;
-        chunk_file.shutdown().await?;
         Ok(EpochEndingChunk {
             first_epoch,
             last_epoch,
@@ -174,6 +188,7 @@ impl EpochEndingBackupController {
         backup_handle: &BackupHandleRef,
         waypoints: Vec<Waypoint>,
         chunks: Vec<EpochEndingChunk>,
+        encoding: FileEncoding,
     ) -> Result<FileHandle> {
         let first_epoch = self.start_epoch;
         let last_epoch = self.end_epoch - 1;
@@ -183,6 +198,7 @@ impl EpochEndingBackupController {
             last_epoch,
             waypoints,
             chunks,
+            encoding,
         };
         let (manifest_handle, mut manifest_file) = self
             .storage
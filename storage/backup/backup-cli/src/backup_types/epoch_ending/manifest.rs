@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::FileHandle;
+use crate::{storage::FileHandle, utils::file_encoding::FileEncoding};
 use anyhow::{ensure, Result};
 use aptos_types::waypoint::Waypoint;
 use serde::{Deserialize, Serialize};
@@ -24,6 +24,9 @@ pub struct EpochEndingBackup {
     pub last_epoch: u64,
     pub waypoints: Vec<Waypoint>,
     pub chunks: Vec<EpochEndingChunk>,
+    /// How the chunk files are encoded.
+    #[serde(default, skip_serializing_if = "FileEncoding::is_plain")]
+    pub encoding: FileEncoding,
 }
 
 impl EpochEndingBackup {
@@ -10,8 +10,8 @@ use crate::{
     },
     storage::{BackupStorage, FileHandle, FileHandleRef},
     utils::{
-        read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt, stream::StreamX,
-        GlobalRestoreOptions, RestoreRunMode,
+        file_encoding::FileEncoding, read_record_bytes::ReadRecordBytes,
+        storage_ext::BackupStorageExt, stream::StreamX, GlobalRestoreOptions, RestoreRunMode,
     },
 };
 use anyhow::{anyhow, ensure, Result};
@@ -95,7 +95,9 @@ impl EpochEndingRestoreController {
                 break;
             }
 
-            let lis = self.read_chunk(&chunk.ledger_infos).await?;
+            let lis = self
+                .read_chunk(&chunk.ledger_infos, &manifest.encoding)
+                .await?;
             ensure!(
                 chunk.first_epoch + lis.len() as u64 == chunk.last_epoch + 1,
                 "Number of items in chunks doesn't match that in manifest. \
@@ -161,8 +163,12 @@ impl EpochEndingRestoreController {
     async fn read_chunk(
         &self,
         file_handle: &FileHandleRef,
+        encoding: &FileEncoding,
     ) -> Result<Vec<LedgerInfoWithSignatures>> {
-        let mut file = self.storage.open_for_read(file_handle).await?;
+        let mut file = self
+            .storage
+            .open_for_read_decoded(file_handle, encoding)
+            .await?;
         let mut chunk = vec![];
 
         while let Some(record_bytes) = file.read_record_bytes().await? {
@@ -11,9 +11,9 @@ use crate::{
     },
     storage::{local_fs::LocalFs, BackupStorage},
     utils::{
-        backup_service_client::BackupServiceClient, test_utils::tmp_db_with_random_content,
-        ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
-        RocksdbOpt, TrustedWaypointOpt,
+        backup_service_client::BackupServiceClient, file_encoding::FileEncodingOpt,
+        test_utils::tmp_db_with_random_content, ConcurrentDownloadsOpt, GlobalBackupOpt,
+        GlobalRestoreOpt, ReplayConcurrencyLevelOpt, RocksdbOpt, TrustedWaypointOpt,
     },
 };
 use aptos_backup_service::start_backup_service;
@@ -69,6 +69,7 @@ fn end_to_end() {
                 GlobalBackupOpt {
                     max_chunk_size: 1024,
                     concurrent_data_requests: 2,
+                    file_encoding: FileEncodingOpt::default(),
                 },
                 client,
                 Arc::clone(&store),
@@ -201,6 +202,7 @@ async fn test_trusted_waypoints_impl(
             GlobalBackupOpt {
                 max_chunk_size: 1024,
                 concurrent_data_requests: 2,
+                file_encoding: FileEncodingOpt::default(),
             },
             client.clone(),
             Arc::clone(&store),
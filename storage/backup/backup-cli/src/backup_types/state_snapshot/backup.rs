@@ -8,8 +8,13 @@ use crate::{
     metrics::backup::BACKUP_TIMER,
     storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
     utils::{
-        backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
-        should_cut_chunk, storage_ext::BackupStorageExt, stream::TryStreamX, GlobalBackupOpt,
+        backup_service_client::BackupServiceClient,
+        file_encoding::{FileEncoding, FileEncodingOpt},
+        read_record_bytes::ReadRecordBytes,
+        should_cut_chunk,
+        storage_ext::BackupStorageExt,
+        stream::TryStreamX,
+        GlobalBackupOpt,
     },
 };
 use anyhow::{anyhow, ensure, Result};
@@ -26,8 +31,17 @@ use bytes::{BufMut, Bytes, BytesMut};
 use clap::Parser;
 use futures::{StreamExt, TryStream, TryStreamExt};
 use once_cell::sync::Lazy;
-use std::{convert::TryInto, str::FromStr, sync::Arc, time::Instant};
-use tokio::{io::AsyncWriteExt, sync::mpsc::Sender};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    str::FromStr,
+    sync::Arc,
+    time::Instant,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::mpsc::Sender,
+};
 use tokio_stream::wrappers::ReceiverStream;
 
 #[derive(Parser)]
@@ -37,8 +51,21 @@ pub struct StateSnapshotBackupOpt {
         help = "Epoch at the end of which a state snapshot is to be taken."
     )]
     pub epoch: u64,
+    #[clap(
+        long = "base-state-manifest",
+        help = "Manifest of an earlier state snapshot to take an incremental snapshot on top of. \
+        Chunks are cut at the same keys as in the base, and those whose content didn't change since \
+        are not written again but refer to the existing files. Changed chunks that became small \
+        are merged with the following ones."
+    )]
+    pub base_manifest: Option<FileHandle>,
 }
 
+/// Changed chunks smaller than `max_chunk_size / MIN_CHUNK_SIZE_DIVISOR` are not cut at the
+/// boundaries of the base snapshot but merged with the following chunk, so that the number of
+/// chunks doesn't keep growing over generations of incremental snapshots.
+const MIN_CHUNK_SIZE_DIVISOR: usize = 4;
+
 struct Chunk {
     bytes: Bytes,
     first_key: HashValue,
@@ -55,13 +82,27 @@ struct ChunkerState<RecordStream> {
     current_idx: usize,
     chunk_first_idx: usize,
     max_chunk_size: usize,
+    /// Last keys of the chunks in the base snapshot, chunks are cut there as well so that
+    /// unchanged key ranges result in identical chunks. Empty if not taking an incremental
+    /// snapshot.
+    boundaries: Vec<HashValue>,
+    /// Index into `boundaries` of the first one not less than keys in the current chunk.
+    boundary_idx: usize,
+    /// Hashes of the chunks in the base snapshot that can be reused. These are always cut at
+    /// the boundaries, regardless of their size.
+    reusable_blobs: HashSet<HashValue>,
 }
 
 impl<RecordStream> ChunkerState<RecordStream>
 where
     RecordStream: TryStream<Ok = Bytes, Error = anyhow::Error> + Unpin,
 {
-    async fn new(mut record_stream: RecordStream, max_chunk_size: usize) -> Result<Self> {
+    async fn new(
+        mut record_stream: RecordStream,
+        max_chunk_size: usize,
+        boundaries: Vec<HashValue>,
+        reusable_blobs: HashSet<HashValue>,
+    ) -> Result<Self> {
         let first_record = record_stream
             .try_next()
             .await?
@@ -69,6 +110,7 @@ where
 
         let chunk_first_key = Self::parse_key(&first_record)?;
         let prev_record_len = first_record.len();
+        let boundary_idx = boundaries.partition_point(|key| *key < chunk_first_key);
 
         let mut buf = BytesMut::new();
         buf.put_slice(&(first_record.len() as u32).to_be_bytes());
@@ -82,6 +124,9 @@ where
             current_idx: 0,
             chunk_first_idx: 0,
             max_chunk_size,
+            boundaries,
+            boundary_idx,
+            reusable_blobs,
         })
     }
 
@@ -96,8 +141,12 @@ where
         while let Some(record_bytes) = input.try_next().await? {
             let _timer = BACKUP_TIMER.timer_with(&["state_snapshot_process_records"]);
 
-            // If buf + current_record exceeds max_chunk_size, dump current buf to a new chunk
-            let chunk_cut_opt = should_cut_chunk(&self.buf, &record_bytes, self.max_chunk_size)
+            // If buf + current_record exceeds max_chunk_size, or current_record falls into a later
+            // chunk of the base snapshot (unless buf is a small changed chunk), dump current buf
+            // to a new chunk
+            let should_cut = (self.crosses_boundary(&record_bytes)? && !self.should_merge_buf())
+                || should_cut_chunk(&self.buf, &record_bytes, self.max_chunk_size);
+            let chunk_cut_opt = should_cut
                 .then(|| {
                     let bytes = self.buf.split().freeze();
                     let last_key = Self::parse_key(&bytes[bytes.len() - self.prev_record_len..])?;
@@ -145,6 +194,9 @@ where
             current_idx,
             chunk_first_idx,
             max_chunk_size: _,
+            boundaries: _,
+            boundary_idx: _,
+            reusable_blobs: _,
         } = self;
         ensure!(
             state_snapshot_file.is_none(),
@@ -164,6 +216,32 @@ where
         })
     }
 
+    /// Advances `boundary_idx` past the boundaries below the key of the record, returning whether
+    /// any was passed, i.e. whether the record belongs to a different chunk than the previous one
+    /// in the base snapshot.
+    fn crosses_boundary(&mut self, record: &[u8]) -> Result<bool> {
+        if self.boundaries.is_empty() {
+            return Ok(false);
+        }
+
+        let key = Self::parse_key(record)?;
+        let prev_boundary_idx = self.boundary_idx;
+        while self.boundary_idx < self.boundaries.len() && self.boundaries[self.boundary_idx] < key
+        {
+            self.boundary_idx += 1;
+        }
+        Ok(self.boundary_idx > prev_boundary_idx)
+    }
+
+    /// Whether the buffered records should be merged with the following ones instead of being cut
+    /// at a boundary of the base snapshot, i.e. whether they are small and can't be reused.
+    fn should_merge_buf(&self) -> bool {
+        self.buf.len() < self.max_chunk_size / MIN_CHUNK_SIZE_DIVISOR
+            && !self
+                .reusable_blobs
+                .contains(&HashValue::sha3_256_of(&self.buf))
+    }
+
     fn parse_key(record: &[u8]) -> Result<HashValue> {
         let (key, _): (StateKey, StateValue) = bcs::from_bytes(record)?;
         Ok(key.hash())
@@ -178,9 +256,17 @@ impl<RecordStream> Chunker<RecordStream>
 where
     RecordStream: TryStream<Ok = Bytes, Error = anyhow::Error> + Unpin,
 {
-    async fn new(record_stream: RecordStream, max_chunk_size: usize) -> Result<Self> {
+    async fn new(
+        record_stream: RecordStream,
+        max_chunk_size: usize,
+        boundaries: Vec<HashValue>,
+        reusable_blobs: HashSet<HashValue>,
+    ) -> Result<Self> {
         Ok(Self {
-            state: Some(ChunkerState::new(record_stream, max_chunk_size).await?),
+            state: Some(
+                ChunkerState::new(record_stream, max_chunk_size, boundaries, reusable_blobs)
+                    .await?,
+            ),
         })
     }
 
@@ -197,10 +283,109 @@ where
     }
 }
 
+/// What an incremental snapshot needs to know about the snapshot it's based on.
+struct BaseSnapshot {
+    manifest_handle: FileHandle,
+    /// Last keys of all chunks.
+    boundaries: Vec<HashValue>,
+    /// Blobs hash -> (blobs file, manifest of the backup that wrote it)
+    reusable_blobs: HashMap<HashValue, (FileHandle, FileHandle)>,
+}
+
+impl BaseSnapshot {
+    async fn load(
+        storage: &Arc<dyn BackupStorage>,
+        manifest_handle: FileHandle,
+        encoding: &FileEncoding,
+    ) -> Result<Self> {
+        let manifest: StateSnapshotBackup = storage
+            .load_json_file(&manifest_handle)
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to load base state snapshot manifest {}: {}",
+                    manifest_handle,
+                    e
+                )
+            })?;
+        let boundaries = manifest.chunks.iter().map(|chunk| chunk.last_key).collect();
+        if &manifest.encoding != encoding {
+            warn!(
+                base_manifest = manifest_handle.as_str(),
+                "Base snapshot files are encoded differently, no chunk will be reused."
+            );
+            return Ok(Self {
+                manifest_handle,
+                boundaries,
+                reusable_blobs: HashMap::new(),
+            });
+        }
+
+        // Only chunks that are still there in the backups that wrote them are reused, so that
+        // the new snapshot doesn't refer to files of a backup that's gone.
+        let mut written_blobs_by_writer = HashMap::new();
+        let mut reusable_blobs = HashMap::new();
+        for chunk in manifest.chunks {
+            // Chunks written by older versions have no hash, and can't be reused without
+            // downloading them.
+            let Some(blobs_hash) = chunk.blobs_hash else {
+                continue;
+            };
+            let Some(written_by) = chunk.reused_from else {
+                reusable_blobs.insert(blobs_hash, (chunk.blobs, manifest_handle.clone()));
+                continue;
+            };
+            if !written_blobs_by_writer.contains_key(&written_by) {
+                let written_blobs = match Self::load_written_blobs(storage, &written_by).await {
+                    Ok(written_blobs) => Some(written_blobs),
+                    Err(err) => {
+                        warn!(
+                            written_by = written_by.as_str(),
+                            error = ?err,
+                            "Failed to load state snapshot manifest, its chunks won't be reused."
+                        );
+                        None
+                    },
+                };
+                written_blobs_by_writer.insert(written_by.clone(), written_blobs);
+            }
+            let still_written = written_blobs_by_writer[&written_by]
+                .as_ref()
+                .and_then(|written_blobs| written_blobs.get(&chunk.blobs))
+                == Some(&blobs_hash);
+            if still_written {
+                reusable_blobs.insert(blobs_hash, (chunk.blobs, written_by));
+            }
+        }
+
+        Ok(Self {
+            manifest_handle,
+            boundaries,
+            reusable_blobs,
+        })
+    }
+
+    /// Returns the hashes of the chunks written (not reused) by the given state snapshot.
+    async fn load_written_blobs(
+        storage: &Arc<dyn BackupStorage>,
+        manifest_handle: &FileHandle,
+    ) -> Result<HashMap<FileHandle, HashValue>> {
+        let manifest: StateSnapshotBackup = storage.load_json_file(manifest_handle).await?;
+        Ok(manifest
+            .chunks
+            .into_iter()
+            .filter(|chunk| chunk.reused_from.is_none())
+            .filter_map(|chunk| Some((chunk.blobs, chunk.blobs_hash?)))
+            .collect())
+    }
+}
+
 pub struct StateSnapshotBackupController {
     epoch: u64,
     version: Option<Version>, // initialize before using
+    base_manifest: Option<FileHandle>,
     max_chunk_size: usize,
+    file_encoding: FileEncodingOpt,
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
     concurrent_data_requests: usize,
@@ -216,7 +401,9 @@ impl StateSnapshotBackupController {
         Self {
             epoch: opt.epoch,
             version: None,
+            base_manifest: opt.base_manifest,
             max_chunk_size: global_opt.max_chunk_size,
+            file_encoding: global_opt.file_encoding,
             client,
             storage,
             concurrent_data_requests: global_opt.concurrent_data_requests,
@@ -239,17 +426,41 @@ impl StateSnapshotBackupController {
             .storage
             .create_backup_with_random_suffix(&self.backup_name())
             .await?;
+        let encoding = self.file_encoding.encoding()?;
+
+        let base = match self.base_manifest.clone() {
+            Some(manifest_handle) => {
+                info!(
+                    base_manifest = manifest_handle.as_str(),
+                    "Taking incremental state snapshot."
+                );
+                Some(BaseSnapshot::load(&self.storage, manifest_handle, &encoding).await?)
+            },
+            None => None,
+        };
 
         let record_stream = Box::pin(self.record_stream(self.concurrent_data_requests).await?);
-        let chunker = Chunker::new(record_stream, self.max_chunk_size).await?;
+        let (boundaries, reusable_blobs) = base.as_ref().map_or_else(Default::default, |base| {
+            (
+                base.boundaries.clone(),
+                base.reusable_blobs.keys().copied().collect(),
+            )
+        });
+        let chunker = Chunker::new(
+            record_stream,
+            self.max_chunk_size,
+            boundaries,
+            reusable_blobs,
+        )
+        .await?;
 
         let start = Instant::now();
         let chunk_stream = futures::stream::try_unfold(chunker, |mut chunker| async {
             Ok(chunker.next_chunk().await?.map(|chunk| (chunk, chunker)))
         });
 
-        let chunk_manifest_fut_stream =
-            chunk_stream.map_ok(|chunk| self.write_chunk(&backup_handle, chunk));
+        let chunk_manifest_fut_stream = chunk_stream
+            .map_ok(|chunk| self.write_chunk(&backup_handle, base.as_ref(), chunk, &encoding));
 
         let chunks: Vec<_> = chunk_manifest_fut_stream
             .try_buffered_x(8, 4) // 4 concurrently, at most 8 results in buffer.
@@ -257,6 +468,7 @@ impl StateSnapshotBackupController {
                 let last_idx = chunk_manifest.last_idx;
                 info!(
                     last_idx = last_idx,
+                    reused = chunk_manifest.reused_from.is_some(),
                     values_per_second =
                         ((last_idx + 1) as f64 / start.elapsed().as_secs_f64()) as u64,
                     "Chunk written."
@@ -265,8 +477,21 @@ impl StateSnapshotBackupController {
             })
             .try_collect()
             .await?;
+        if base.is_some() {
+            info!(
+                reused_chunks = chunks.iter().filter(|c| c.reused_from.is_some()).count(),
+                total_chunks = chunks.len(),
+                "Incremental state snapshot chunks written."
+            );
+        }
 
-        self.write_manifest(&backup_handle, chunks).await
+        self.write_manifest(
+            &backup_handle,
+            chunks,
+            base.map(|base| base.manifest_handle),
+            encoding,
+        )
+        .await
     }
 
     async fn record_stream(
@@ -404,7 +629,9 @@ impl StateSnapshotBackupController {
     async fn write_chunk(
         &self,
         backup_handle: &BackupHandleRef,
+        base: Option<&BaseSnapshot>,
         chunk: Chunk,
+        encoding: &FileEncoding,
     ) -> Result<StateSnapshotChunk> {
         let _timer = BACKUP_TIMER.timer_with(&["state_snapshot_write_chunk"]);
 
@@ -416,25 +643,38 @@ impl StateSnapshotBackupController {
             last_key,
         } = chunk;
 
-        let (chunk_handle, mut chunk_file) = self
-            .storage
-            .create_for_write(backup_handle, &Self::chunk_name(first_idx))
+        let blobs_hash = HashValue::sha3_256_of(&bytes);
+        let (chunk_handle, reused_from) =
+            match base.and_then(|base| base.reusable_blobs.get(&blobs_hash)) {
+                Some((blobs, written_by)) => (blobs.clone(), Some(written_by.clone())),
+                None => {
+                    let chunk_handle = self
+                        .storage
+                        .create_encoded_file(
+                            backup_handle,
+                            &Self::chunk_name(first_idx),
+                            &bytes,
+                            encoding,
+                        )
+                        .await?;
+                    (chunk_handle, None)
+                },
+            };
+        let mut proof_bytes = Vec::new();
+        self.client
+            .get_account_range_proof(last_key, self.version())
+            .await?
+            .read_to_end(&mut proof_bytes)
             .await?;
-        chunk_file.write_all(&bytes).await?;
-        chunk_file.shutdown().await?;
-        let (proof_handle, mut proof_file) = self
+        let proof_handle = self
             .storage
-            .create_for_write(backup_handle, &Self::chunk_proof_name(first_idx, last_idx))
+            .create_encoded_file(
+                backup_handle,
+                &Self::chunk_proof_name(first_idx, last_idx),
+                &proof_bytes,
+                encoding,
+            )
             .await?;
-        tokio::io::copy(
-            &mut self
-                .client
-                .get_account_range_proof(last_key, self.version())
-                .await?,
-            &mut proof_file,
-        )
-        .await?;
-        proof_file.shutdown().await?;
 
         Ok(StateSnapshotChunk {
             first_idx,
@@ -443,6 +683,8 @@ impl StateSnapshotBackupController {
             last_key,
             blobs: chunk_handle,
             proof: proof_handle,
+            blobs_hash: Some(blobs_hash),
+            reused_from,
         })
     }
 
@@ -450,23 +692,25 @@ impl StateSnapshotBackupController {
         &self,
         backup_handle: &BackupHandleRef,
         chunks: Vec<StateSnapshotChunk>,
+        base_manifest: Option<FileHandle>,
+        encoding: FileEncoding,
     ) -> Result<FileHandle> {
         let proof_bytes = self.client.get_state_root_proof(self.version()).await?;
         let (txn_info, _): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
             bcs::from_bytes(&proof_bytes)?;
 
-        let (proof_handle, mut proof_file) = self
+        let proof_handle = self
             .storage
-            .create_for_write(backup_handle, Self::proof_name())
+            .create_encoded_file(backup_handle, Self::proof_name(), &proof_bytes, &encoding)
             .await?;
-        proof_file.write_all(&proof_bytes).await?;
-        proof_file.shutdown().await?;
 
         let manifest = StateSnapshotBackup {
             epoch: self.epoch,
             version: self.version(),
             root_hash: txn_info.transaction_info().ensure_state_checkpoint_hash()?,
             chunks,
+            base_manifest,
+            encoding,
             proof: proof_handle,
         };
 
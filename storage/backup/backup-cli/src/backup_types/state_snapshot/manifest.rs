@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::FileHandle;
+use crate::{storage::FileHandle, utils::file_encoding::FileEncoding};
 use aptos_crypto::HashValue;
 use aptos_types::transaction::Version;
 use serde::{Deserialize, Serialize};
@@ -25,6 +25,14 @@ pub struct StateSnapshotChunk {
     /// BCS serialized `SparseMerkleRangeProof` that proves this chunk adds up to the root hash
     /// indicated in the backup (`StateSnapshotBackup::root_hash`).
     pub proof: FileHandle,
+    /// SHA3-256 of the content of `blobs`, which identifies the chunk when deciding whether an
+    /// incremental snapshot can reuse it. Missing in manifests written by older versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blobs_hash: Option<HashValue>,
+    /// Set if `blobs` is not written by this backup but reused from an earlier one, to the
+    /// manifest of the backup which wrote it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reused_from: Option<FileHandle>,
 }
 
 /// State snapshot backup manifest, representing a complete state view at specified version.
@@ -38,6 +46,16 @@ pub struct StateSnapshotBackup {
     pub root_hash: HashValue,
     /// All account blobs in chunks.
     pub chunks: Vec<StateSnapshotChunk>,
+    /// Set if this is an incremental snapshot, to the manifest of the snapshot it's based on.
+    /// Chunk boundaries follow those of the base, and chunks whose content didn't change since
+    /// are not written again but refer to the existing files, see
+    /// `StateSnapshotChunk::reused_from`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_manifest: Option<FileHandle>,
+    /// How the chunk and proof files are encoded. Chunks reused from the base snapshot are
+    /// encoded the same way.
+    #[serde(default, skip_serializing_if = "FileEncoding::is_plain")]
+    pub encoding: FileEncoding,
     /// BCS serialized
     /// `Tuple(TransactionInfoWithProof, LedgerInfoWithSignatures)`.
     ///   - The `TransactionInfoWithProof` is at `Version` above, and carries the same `root_hash`
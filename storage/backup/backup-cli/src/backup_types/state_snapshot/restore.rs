@@ -4,7 +4,8 @@
 
 use crate::{
     backup_types::{
-        epoch_ending::restore::EpochHistory, state_snapshot::manifest::StateSnapshotBackup,
+        epoch_ending::restore::EpochHistory,
+        state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotChunk},
     },
     metrics::{
         restore::{
@@ -18,11 +19,12 @@ use crate::{
     },
     storage::{BackupStorage, FileHandle},
     utils::{
-        read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt, stream::StreamX,
-        GlobalRestoreOptions, RestoreRunMode,
+        file_encoding::FileEncoding, read_record_bytes::ReadRecordBytes,
+        storage_ext::BackupStorageExt, stream::StreamX, GlobalRestoreOptions, RestoreRunMode,
     },
 };
 use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::HashValue;
 use aptos_db::state_restore::StateSnapshotRestoreMode;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -43,7 +45,7 @@ use clap::Parser;
 use futures::{stream, TryStreamExt};
 use move_binary_format::CompiledModule;
 use move_bytecode_verifier::verify_module_with_config;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::time::Instant;
 
 #[derive(Parser)]
@@ -122,8 +124,10 @@ impl StateSnapshotRestoreController {
 
         let manifest: StateSnapshotBackup =
             self.storage.load_json_file(&self.manifest_handle).await?;
-        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
-            self.storage.load_bcs_file(&manifest.proof).await?;
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) = self
+            .storage
+            .load_encoded_bcs_file(&manifest.proof, &manifest.encoding)
+            .await?;
         txn_info_with_proof.verify(li.ledger_info(), manifest.version)?;
         let state_root_hash = txn_info_with_proof
             .transaction_info()
@@ -137,6 +141,13 @@ impl StateSnapshotRestoreController {
         if let Some(epoch_history) = self.epoch_history.as_ref() {
             epoch_history.verify_ledger_info(&li)?;
         }
+        if let Some(base_manifest) = manifest.base_manifest.as_ref() {
+            info!(
+                base_manifest = base_manifest.as_str(),
+                "Restoring incremental state snapshot."
+            );
+            Self::resolve_reused_chunks(&self.storage, &manifest).await?;
+        }
 
         let receiver = Arc::new(Mutex::new(Some(self.run_mode.get_state_restore_receiver(
             self.version,
@@ -186,10 +197,19 @@ impl StateSnapshotRestoreController {
         let storage = self.storage.clone();
         let futs_iter = chunks.into_iter().enumerate().map(|(chunk_idx, chunk)| {
             let storage = storage.clone();
+            let encoding = manifest.encoding.clone();
             async move {
                 tokio::spawn(async move {
-                    let blobs = Self::read_state_value(&storage, chunk.blobs.clone()).await?;
-                    let proof = storage.load_bcs_file(&chunk.proof).await?;
+                    let blobs = Self::read_state_value(
+                        &storage,
+                        chunk.blobs.clone(),
+                        &encoding,
+                        chunk.blobs_hash,
+                    )
+                    .await?;
+                    let proof = storage
+                        .load_encoded_bcs_file(&chunk.proof, &encoding)
+                        .await?;
                     Result::<_>::Ok((chunk_idx, chunk, blobs, proof))
                 })
                 .await?
@@ -252,11 +272,74 @@ impl StateSnapshotRestoreController {
         }
     }
 
+    /// Checks that the chunks an incremental snapshot reuses are still there in the backups that
+    /// wrote them, so that a broken chain fails the restore up front rather than midway. The
+    /// content of each chunk is further checked against its hash when read, and against the root
+    /// hash by the range proof as usual.
+    async fn resolve_reused_chunks(
+        storage: &Arc<dyn BackupStorage>,
+        manifest: &StateSnapshotBackup,
+    ) -> Result<()> {
+        let mut reused_chunks: HashMap<&FileHandle, Vec<&StateSnapshotChunk>> = HashMap::new();
+        for chunk in &manifest.chunks {
+            if let Some(written_by) = chunk.reused_from.as_ref() {
+                ensure!(
+                    chunk.blobs_hash.is_some(),
+                    "Reused chunk {} has no hash.",
+                    chunk.blobs,
+                );
+                reused_chunks.entry(written_by).or_default().push(chunk);
+            }
+        }
+
+        for (written_by, chunks) in reused_chunks {
+            let origin: StateSnapshotBackup = storage.load_json_file(written_by).await?;
+            ensure!(
+                origin.encoding == manifest.encoding,
+                "Chunks reused from state snapshot {} are encoded differently.",
+                written_by,
+            );
+            let written_blobs: HashMap<&FileHandle, Option<HashValue>> = origin
+                .chunks
+                .iter()
+                .filter(|chunk| chunk.reused_from.is_none())
+                .map(|chunk| (&chunk.blobs, chunk.blobs_hash))
+                .collect();
+            for chunk in &chunks {
+                ensure!(
+                    written_blobs.get(&chunk.blobs) == Some(&chunk.blobs_hash),
+                    "Chunk {} is not written by state snapshot {}.",
+                    chunk.blobs,
+                    written_by,
+                );
+            }
+            info!(
+                written_by = written_by.as_str(),
+                reused_chunks = chunks.len(),
+                "Resolved chunks reused from earlier state snapshot."
+            );
+        }
+        Ok(())
+    }
+
     async fn read_state_value(
         storage: &Arc<dyn BackupStorage>,
         file_handle: FileHandle,
+        encoding: &FileEncoding,
+        expected_hash: Option<HashValue>,
     ) -> Result<Vec<(StateKey, StateValue)>> {
-        let mut file = storage.open_for_read(&file_handle).await?;
+        let bytes = storage.read_all_decoded(&file_handle, encoding).await?;
+        if let Some(expected_hash) = expected_hash {
+            let hash = HashValue::sha3_256_of(&bytes);
+            ensure!(
+                hash == expected_hash,
+                "Hash mismatch for {}. expected: {}, got: {}",
+                file_handle,
+                expected_hash,
+                hash,
+            );
+        }
+        let mut file = bytes.as_slice();
 
         let mut chunk = vec![];
 
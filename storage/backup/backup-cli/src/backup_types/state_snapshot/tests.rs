@@ -5,12 +5,17 @@
 use crate::{
     backup_types::state_snapshot::{
         backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        manifest::StateSnapshotBackup,
         restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
     },
     storage::{local_fs::LocalFs, BackupStorage},
     utils::{
         backup_service_client::BackupServiceClient,
-        test_utils::{start_local_backup_service, tmp_db_with_random_content},
+        file_encoding::FileEncodingOpt,
+        storage_ext::BackupStorageExt,
+        test_utils::{
+            set_test_encryption_key, start_local_backup_service, tmp_db_with_random_content,
+        },
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
         RocksdbOpt, TrustedWaypointOpt,
     },
@@ -21,8 +26,7 @@ use aptos_temppath::TempPath;
 use std::{convert::TryInto, sync::Arc};
 use tokio::time::Duration;
 
-#[test]
-fn end_to_end() {
+fn end_to_end_impl(file_encoding: FileEncodingOpt) {
     let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
     let tgt_db_dir = TempPath::new();
     tgt_db_dir.create_as_dir().unwrap();
@@ -61,10 +65,14 @@ fn end_to_end() {
     let manifest_handle = rt
         .block_on(
             StateSnapshotBackupController::new(
-                StateSnapshotBackupOpt { epoch },
+                StateSnapshotBackupOpt {
+                    epoch,
+                    base_manifest: None,
+                },
                 GlobalBackupOpt {
                     max_chunk_size: 500,
                     concurrent_data_requests: 2,
+                    file_encoding,
                 },
                 client,
                 Arc::clone(&store),
@@ -73,6 +81,17 @@ fn end_to_end() {
         )
         .unwrap();
 
+    let manifest: StateSnapshotBackup =
+        rt.block_on(store.load_json_file(&manifest_handle)).unwrap();
+    if !manifest.encoding.is_plain() {
+        let chunk = &manifest.chunks[0];
+        let raw = rt.block_on(store.read_all(&chunk.blobs)).unwrap();
+        let decoded = rt
+            .block_on(store.read_all_decoded(&chunk.blobs, &manifest.encoding))
+            .unwrap();
+        assert_ne!(raw, decoded);
+    }
+
     rt.block_on(
         StateSnapshotRestoreController::new(
             StateSnapshotRestoreOpt {
@@ -111,3 +130,149 @@ fn end_to_end() {
 
     rt.shutdown_timeout(Duration::from_secs(1));
 }
+
+#[test]
+fn end_to_end() {
+    end_to_end_impl(FileEncodingOpt::default())
+}
+
+#[test]
+fn end_to_end_compressed_and_encrypted() {
+    set_test_encryption_key();
+    end_to_end_impl(FileEncodingOpt {
+        compression_level: Some(3),
+        encrypt: true,
+    })
+}
+
+#[test]
+fn incremental() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let tgt_db_dir = TempPath::new();
+    tgt_db_dir.create_as_dir().unwrap();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let epoch = src_db
+        .get_latest_ledger_info()
+        .unwrap()
+        .ledger_info()
+        .next_block_epoch()
+        - 1;
+    let version = src_db
+        .get_epoch_ending_ledger_infos(epoch, epoch + 1)
+        .unwrap()
+        .ledger_info_with_sigs
+        .pop()
+        .unwrap()
+        .ledger_info()
+        .version();
+
+    let (rt, port) = start_local_backup_service(src_db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let backup = |base_manifest, max_chunk_size| {
+        rt.block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt {
+                    epoch,
+                    base_manifest,
+                },
+                GlobalBackupOpt {
+                    max_chunk_size,
+                    concurrent_data_requests: 2,
+                    file_encoding: FileEncodingOpt::default(),
+                },
+                Arc::clone(&client),
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap()
+    };
+    let load_manifest = |manifest_handle: &str| -> StateSnapshotBackup {
+        rt.block_on(store.load_json_file(manifest_handle)).unwrap()
+    };
+
+    let full_handle = backup(None, 500);
+    let full = load_manifest(&full_handle);
+    assert!(full.base_manifest.is_none());
+    assert!(full.chunks.iter().all(|chunk| chunk.reused_from.is_none()));
+
+    // Nothing changed, so every chunk is reused, and chunks reused by the base are still
+    // attributed to the full snapshot which wrote them.
+    let mut base_handle = full_handle.clone();
+    for _ in 0..2 {
+        let incremental_handle = backup(Some(base_handle.clone()), 500);
+        let incremental = load_manifest(&incremental_handle);
+        assert_eq!(incremental.base_manifest, Some(base_handle));
+        assert_eq!(incremental.root_hash, full.root_hash);
+        assert_eq!(incremental.chunks.len(), full.chunks.len());
+        for (chunk, full_chunk) in incremental.chunks.iter().zip(full.chunks.iter()) {
+            assert_eq!(chunk.blobs, full_chunk.blobs);
+            assert_eq!(chunk.blobs_hash, full_chunk.blobs_hash);
+            assert_eq!(chunk.reused_from.as_ref(), Some(&full_handle));
+            assert_ne!(chunk.proof, full_chunk.proof);
+        }
+        base_handle = incremental_handle;
+    }
+
+    rt.block_on(
+        StateSnapshotRestoreController::new(
+            StateSnapshotRestoreOpt {
+                manifest_handle: base_handle.clone(),
+                version,
+                validate_modules: false,
+                restore_mode: StateSnapshotRestoreMode::Default,
+            },
+            GlobalRestoreOpt {
+                dry_run: false,
+                db_dir: Some(tgt_db_dir.path().to_path_buf()),
+                target_version: None, // max
+                trusted_waypoints: TrustedWaypointOpt::default(),
+                rocksdb_opt: RocksdbOpt::default(),
+                concurrent_downloads: ConcurrentDownloadsOpt::default(),
+                replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+                enable_state_indices: false,
+            }
+            .try_into()
+            .unwrap(),
+            Arc::clone(&store),
+            None, /* epoch_history */
+        )
+        .run(),
+    )
+    .unwrap();
+
+    let tgt_db = AptosDB::new_readonly_for_test(&tgt_db_dir);
+    assert_eq!(
+        tgt_db
+            .get_state_snapshot_before(version + 1)
+            .unwrap()
+            .unwrap(),
+        (version, full.root_hash)
+    );
+
+    // Once the backup which wrote the chunks is gone, they are no longer reused, and the chunks
+    // that are small for the new max chunk size are merged instead of being cut at the
+    // boundaries of the base.
+    std::fs::remove_file(backup_dir.path().join(&full_handle)).unwrap();
+    let merged_handle = backup(Some(base_handle), 4000);
+    let merged = load_manifest(&merged_handle);
+    assert_eq!(merged.root_hash, full.root_hash);
+    assert!(merged
+        .chunks
+        .iter()
+        .all(|chunk| chunk.reused_from.is_none()));
+    assert!(merged.chunks.len() < full.chunks.len());
+    assert_eq!(merged.chunks.first().unwrap().first_idx, 0);
+    assert_eq!(
+        merged.chunks.last().unwrap().last_idx,
+        full.chunks.last().unwrap().last_idx
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
@@ -15,9 +15,10 @@ use crate::{
     },
     storage::{local_fs::LocalFs, BackupStorage},
     utils::{
-        backup_service_client::BackupServiceClient, test_utils::start_local_backup_service,
-        ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, GlobalRestoreOptions,
-        ReplayConcurrencyLevelOpt, RocksdbOpt, TrustedWaypointOpt,
+        backup_service_client::BackupServiceClient, file_encoding::FileEncodingOpt,
+        test_utils::start_local_backup_service, ConcurrentDownloadsOpt, GlobalBackupOpt,
+        GlobalRestoreOpt, GlobalRestoreOptions, ReplayConcurrencyLevelOpt, RocksdbOpt,
+        TrustedWaypointOpt,
     },
 };
 use aptos_db::{state_restore::StateSnapshotRestoreMode, AptosDB};
@@ -89,11 +90,15 @@ fn test_end_to_end_impl(d: TestData) {
     let global_backup_opt = GlobalBackupOpt {
         max_chunk_size: 2048,
         concurrent_data_requests: 2,
+        file_encoding: FileEncodingOpt::default(),
     };
     let state_snapshot_manifest = d.state_snapshot_epoch.map(|epoch| {
         rt.block_on(
             StateSnapshotBackupController::new(
-                StateSnapshotBackupOpt { epoch },
+                StateSnapshotBackupOpt {
+                    epoch,
+                    base_manifest: None,
+                },
                 global_backup_opt.clone(),
                 Arc::clone(&client),
                 Arc::clone(&store),
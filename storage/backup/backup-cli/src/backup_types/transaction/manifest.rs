@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::FileHandle;
+use crate::{storage::FileHandle, utils::file_encoding::FileEncoding};
 use anyhow::{ensure, Result};
 use aptos_types::transaction::Version;
 use serde::{Deserialize, Serialize};
@@ -31,6 +31,9 @@ pub struct TransactionBackup {
     pub first_version: Version,
     pub last_version: Version,
     pub chunks: Vec<TransactionChunk>,
+    /// How the chunk and proof files are encoded.
+    #[serde(default, skip_serializing_if = "FileEncoding::is_plain")]
+    pub encoding: FileEncoding,
 }
 
 impl TransactionBackup {
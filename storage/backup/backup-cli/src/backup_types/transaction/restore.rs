@@ -18,6 +18,7 @@ use crate::{
     storage::{BackupStorage, FileHandle},
     utils::{
         error_notes::ErrorNotes,
+        file_encoding::FileEncoding,
         read_record_bytes::ReadRecordBytes,
         storage_ext::BackupStorageExt,
         stream::{StreamX, TryStreamX},
@@ -85,7 +86,7 @@ pub struct TransactionRestoreController {
 }
 
 #[allow(dead_code)]
-struct LoadedChunk {
+pub(crate) struct LoadedChunk {
     pub manifest: TransactionChunk,
     pub txns: Vec<Transaction>,
     pub txn_infos: Vec<TransactionInfo>,
@@ -96,12 +97,17 @@ struct LoadedChunk {
 }
 
 impl LoadedChunk {
-    async fn load(
+    pub(crate) async fn load(
         manifest: TransactionChunk,
         storage: &Arc<dyn BackupStorage>,
+        encoding: &FileEncoding,
         epoch_history: Option<&Arc<EpochHistory>>,
     ) -> Result<Self> {
-        let mut file = BufReader::new(storage.open_for_read(&manifest.transactions).await?);
+        let mut file = BufReader::new(
+            storage
+                .open_for_read_decoded(&manifest.transactions, encoding)
+                .await?,
+        );
         let mut txns = Vec::new();
         let mut txn_infos = Vec::new();
         let mut event_vecs = Vec::new();
@@ -125,8 +131,9 @@ impl LoadedChunk {
         );
 
         let (range_proof, ledger_info) = storage
-            .load_bcs_file::<(TransactionAccumulatorRangeProof, LedgerInfoWithSignatures)>(
+            .load_encoded_bcs_file::<(TransactionAccumulatorRangeProof, LedgerInfoWithSignatures)>(
                 &manifest.proof,
+                encoding,
             )
             .await?;
         if let Some(epoch_history) = epoch_history {
@@ -329,14 +336,21 @@ impl TransactionRestoreBatchController {
         let target_version = self.global_opt.target_version;
         let first_version = self.first_version.unwrap_or(0);
         let chunk_manifest_stream = manifest_stream
-            .map_ok(|m| stream::iter(m.chunks.into_iter().map(Result::<_>::Ok)))
+            .map_ok(|m| {
+                let encoding = m.encoding;
+                stream::iter(
+                    m.chunks
+                        .into_iter()
+                        .map(move |c| Result::<_>::Ok((c, encoding.clone()))),
+                )
+            })
             .try_flatten()
-            .try_filter(move |c| {
+            .try_filter(move |(c, _encoding)| {
                 future::ready(c.first_version <= target_version && c.last_version >= first_version)
             })
             .scan(0, |last_chunk_last_version, chunk_res| {
                 let res = match &chunk_res {
-                    Ok(chunk) => {
+                    Ok((chunk, _encoding)) => {
                         if *last_chunk_last_version != 0
                             && chunk.first_version != *last_chunk_last_version + 1
                         {
@@ -358,12 +372,12 @@ impl TransactionRestoreBatchController {
         let storage = self.storage.clone();
         let epoch_history = self.epoch_history.clone();
         chunk_manifest_stream
-            .and_then(move |chunk| {
+            .and_then(move |(chunk, encoding)| {
                 let storage = storage.clone();
                 let epoch_history = epoch_history.clone();
                 future::ok(async move {
                     tokio::task::spawn(async move {
-                        LoadedChunk::load(chunk, &storage, epoch_history.as_ref()).await
+                        LoadedChunk::load(chunk, &storage, &encoding, epoch_history.as_ref()).await
                     })
                     .err_into::<anyhow::Error>()
                     .await
@@ -10,6 +10,7 @@ use crate::{
     storage::{local_fs::LocalFs, BackupStorage},
     utils::{
         backup_service_client::BackupServiceClient,
+        file_encoding::FileEncodingOpt,
         test_utils::{start_local_backup_service, tmp_db_with_random_content},
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
         RocksdbOpt, TrustedWaypointOpt,
@@ -69,6 +70,7 @@ fn end_to_end() {
                     GlobalBackupOpt {
                         max_chunk_size,
                         concurrent_data_requests: 2,
+                        file_encoding: FileEncodingOpt::default(),
                     },
                     client.clone(),
                     Arc::clone(&store),
@@ -89,6 +91,7 @@ fn end_to_end() {
                 GlobalBackupOpt {
                     max_chunk_size,
                     concurrent_data_requests: 2,
+                    file_encoding: FileEncodingOpt::default(),
                 },
                 client,
                 Arc::clone(&store),
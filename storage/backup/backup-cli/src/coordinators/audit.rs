@@ -0,0 +1,550 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::{
+            manifest::EpochEndingBackup,
+            restore::{EpochHistory, EpochHistoryRestoreController},
+        },
+        state_snapshot::manifest::StateSnapshotBackup,
+        transaction::{manifest::TransactionBackup, restore::LoadedChunk},
+    },
+    metadata,
+    metadata::{
+        cache::MetadataCacheOpt, EpochEndingBackupMeta, StateSnapshotBackupMeta,
+        TransactionBackupMeta,
+    },
+    storage::{BackupStorage, FileHandle, FileHandleRef},
+    utils::{
+        storage_ext::BackupStorageExt, stream::StreamX, GlobalRestoreOptions, RestoreRunMode,
+        TrustedWaypointOpt,
+    },
+};
+use anyhow::{ensure, Result};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, proof::TransactionInfoWithProof, transaction::Version,
+};
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use std::{
+    cmp::{max, min},
+    sync::Arc,
+};
+use tokio::io::AsyncReadExt;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditedBackupType {
+    EpochEnding,
+    StateSnapshot,
+    Transaction,
+}
+
+/// A problem found by the audit. Ranges are in epochs for epoch ending backups and in versions
+/// otherwise, right side inclusive.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum AuditIssue {
+    /// Nothing in the backup covers this range.
+    Gap {
+        backup_type: AuditedBackupType,
+        first: u64,
+        last: u64,
+    },
+    /// This range of the backup is already covered by a backup listed before it.
+    Overlap {
+        backup_type: AuditedBackupType,
+        manifest: FileHandle,
+        first: u64,
+        last: u64,
+    },
+    /// A file referenced by the metadata or a manifest can't be read.
+    MissingFile {
+        backup_type: AuditedBackupType,
+        manifest: FileHandle,
+        file: FileHandle,
+        error: String,
+    },
+    /// A manifest is malformed or the data it refers to doesn't pass proof verification.
+    VerificationFailed {
+        backup_type: AuditedBackupType,
+        manifest: Option<FileHandle>,
+        error: String,
+    },
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CoverageReport {
+    pub num_backups: usize,
+    /// The end of the contiguous range covered starting from genesis (epoch 0 or version 0).
+    pub covered_from_genesis_until: Option<u64>,
+    /// The end of the latest range covered, which is beyond `covered_from_genesis_until` if
+    /// there are gaps.
+    pub latest: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AuditReport {
+    pub epoch_endings: CoverageReport,
+    pub transactions: CoverageReport,
+    pub num_state_snapshots: usize,
+    pub num_files_checked: usize,
+    pub issues: Vec<AuditIssue>,
+}
+
+impl AuditReport {
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks that everything the backup metadata refers to exists and carries valid proofs, without
+/// restoring anything. Epoch ending LedgerInfos are verified in a chain from genesis, and
+/// transaction chunks and state snapshots against the resulting epoch history.
+pub struct AuditCoordinator {
+    storage: Arc<dyn BackupStorage>,
+    metadata_cache_opt: MetadataCacheOpt,
+    trusted_waypoints_opt: TrustedWaypointOpt,
+    concurrent_downloads: usize,
+}
+
+impl AuditCoordinator {
+    pub fn new(
+        storage: Arc<dyn BackupStorage>,
+        metadata_cache_opt: MetadataCacheOpt,
+        trusted_waypoints_opt: TrustedWaypointOpt,
+        concurrent_downloads: usize,
+    ) -> Self {
+        Self {
+            storage,
+            metadata_cache_opt,
+            trusted_waypoints_opt,
+            concurrent_downloads,
+        }
+    }
+
+    pub async fn run(self) -> Result<AuditReport> {
+        info!("Audit coordinator started.");
+        let ret = self.run_impl().await;
+
+        match &ret {
+            Ok(report) => info!(
+                num_files_checked = report.num_files_checked,
+                num_issues = report.issues.len(),
+                "Audit coordinator finished."
+            ),
+            Err(e) => error!(error = ?e, "Audit coordinator failed."),
+        }
+        ret
+    }
+
+    async fn run_impl(self) -> Result<AuditReport> {
+        let metadata_view = metadata::cache::sync_and_load(
+            &self.metadata_cache_opt,
+            Arc::clone(&self.storage),
+            self.concurrent_downloads,
+        )
+        .await?;
+        let global_opt = GlobalRestoreOptions {
+            target_version: Version::max_value(),
+            trusted_waypoints: Arc::new(self.trusted_waypoints_opt.verify()?),
+            run_mode: Arc::new(RestoreRunMode::Verify),
+            concurrent_downloads: self.concurrent_downloads,
+            replay_concurrency_level: 0, // won't replay, doesn't matter
+        };
+
+        let mut report = AuditReport::default();
+        let epoch_history = self
+            .audit_epoch_endings(
+                metadata_view.all_epoch_ending_backups(),
+                global_opt,
+                &mut report,
+            )
+            .await;
+        self.audit_transactions(
+            metadata_view.all_transaction_backups(),
+            epoch_history.as_ref(),
+            &mut report,
+        )
+        .await;
+        self.audit_state_snapshots(
+            metadata_view.all_state_snapshots(),
+            epoch_history.as_ref(),
+            &mut report,
+        )
+        .await;
+
+        Ok(report)
+    }
+
+    /// Returns the epoch history if the epoch endings chain from genesis is verified.
+    async fn audit_epoch_endings(
+        &self,
+        backups: &[EpochEndingBackupMeta],
+        global_opt: GlobalRestoreOptions,
+        report: &mut AuditReport,
+    ) -> Option<Arc<EpochHistory>> {
+        let backup_type = AuditedBackupType::EpochEnding;
+        let (coverage, chain) = check_coverage(
+            backup_type,
+            backups,
+            |b| (b.first_epoch, b.last_epoch),
+            |b| &b.manifest,
+            &mut report.issues,
+        );
+        report.epoch_endings = coverage;
+
+        for backup in backups {
+            let manifest: EpochEndingBackup = match self
+                .load_manifest(backup_type, &backup.manifest, report)
+                .await
+            {
+                Some(manifest) => manifest,
+                None => continue,
+            };
+            if let Err(e) = manifest.verify() {
+                report
+                    .issues
+                    .push(verification_failed(backup_type, &backup.manifest, e));
+            }
+            for chunk in &manifest.chunks {
+                self.check_file(backup_type, &backup.manifest, &chunk.ledger_infos, report)
+                    .await;
+            }
+        }
+
+        if chain.is_empty() {
+            return None;
+        }
+        match EpochHistoryRestoreController::new(
+            chain.into_iter().map(|b| b.manifest.clone()).collect(),
+            global_opt,
+            self.storage.clone(),
+        )
+        .run()
+        .await
+        {
+            Ok(epoch_history) => Some(Arc::new(epoch_history)),
+            Err(e) => {
+                report.issues.push(AuditIssue::VerificationFailed {
+                    backup_type,
+                    manifest: None,
+                    error: format!("{:#}", e),
+                });
+                None
+            },
+        }
+    }
+
+    async fn audit_transactions(
+        &self,
+        backups: &[TransactionBackupMeta],
+        epoch_history: Option<&Arc<EpochHistory>>,
+        report: &mut AuditReport,
+    ) {
+        let backup_type = AuditedBackupType::Transaction;
+        let (coverage, _chain) = check_coverage(
+            backup_type,
+            backups,
+            |b| (b.first_version, b.last_version),
+            |b| &b.manifest,
+            &mut report.issues,
+        );
+        report.transactions = coverage;
+
+        for backup in backups {
+            let manifest: TransactionBackup = match self
+                .load_manifest(backup_type, &backup.manifest, report)
+                .await
+            {
+                Some(manifest) => manifest,
+                None => continue,
+            };
+            if let Err(e) = manifest.verify().and_then(|()| {
+                ensure!(
+                    manifest.first_version == backup.first_version
+                        && manifest.last_version == backup.last_version,
+                    "Manifest range [{}, {}] doesn't match that in metadata [{}, {}].",
+                    manifest.first_version,
+                    manifest.last_version,
+                    backup.first_version,
+                    backup.last_version,
+                );
+                Ok(())
+            }) {
+                report
+                    .issues
+                    .push(verification_failed(backup_type, &backup.manifest, e));
+            }
+
+            let mut chunks_to_verify = Vec::new();
+            for chunk in manifest.chunks {
+                let txns_exist = self
+                    .check_file(backup_type, &backup.manifest, &chunk.transactions, report)
+                    .await;
+                let proof_exists = self
+                    .check_file(backup_type, &backup.manifest, &chunk.proof, report)
+                    .await;
+                if txns_exist && proof_exists {
+                    chunks_to_verify.push(chunk);
+                }
+            }
+
+            let storage = &self.storage;
+            let encoding = &manifest.encoding;
+            let results = stream::iter(chunks_to_verify)
+                .map(|chunk| async move {
+                    LoadedChunk::load(chunk, storage, encoding, epoch_history)
+                        .await
+                        .map(|_| ())
+                })
+                .buffered_x(self.concurrent_downloads * 2, self.concurrent_downloads)
+                .collect::<Vec<_>>()
+                .await;
+            for res in results {
+                if let Err(e) = res {
+                    report
+                        .issues
+                        .push(verification_failed(backup_type, &backup.manifest, e));
+                }
+            }
+        }
+    }
+
+    async fn audit_state_snapshots(
+        &self,
+        backups: &[StateSnapshotBackupMeta],
+        epoch_history: Option<&Arc<EpochHistory>>,
+        report: &mut AuditReport,
+    ) {
+        let backup_type = AuditedBackupType::StateSnapshot;
+        report.num_state_snapshots = backups.len();
+
+        for backup in backups {
+            let manifest: StateSnapshotBackup = match self
+                .load_manifest(backup_type, &backup.manifest, report)
+                .await
+            {
+                Some(manifest) => manifest,
+                None => continue,
+            };
+            if self
+                .check_file(backup_type, &backup.manifest, &manifest.proof, report)
+                .await
+            {
+                if let Err(e) = self
+                    .verify_state_snapshot_proof(backup, &manifest, epoch_history)
+                    .await
+                {
+                    report
+                        .issues
+                        .push(verification_failed(backup_type, &backup.manifest, e));
+                }
+            }
+            for chunk in &manifest.chunks {
+                self.check_file(backup_type, &backup.manifest, &chunk.blobs, report)
+                    .await;
+                self.check_file(backup_type, &backup.manifest, &chunk.proof, report)
+                    .await;
+            }
+        }
+    }
+
+    async fn verify_state_snapshot_proof(
+        &self,
+        backup: &StateSnapshotBackupMeta,
+        manifest: &StateSnapshotBackup,
+        epoch_history: Option<&Arc<EpochHistory>>,
+    ) -> Result<()> {
+        ensure!(
+            manifest.version == backup.version && manifest.epoch == backup.epoch,
+            "Manifest version {} epoch {} doesn't match that in metadata: version {} epoch {}.",
+            manifest.version,
+            manifest.epoch,
+            backup.version,
+            backup.epoch,
+        );
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) = self
+            .storage
+            .load_encoded_bcs_file(&manifest.proof, &manifest.encoding)
+            .await?;
+        txn_info_with_proof.verify(li.ledger_info(), manifest.version)?;
+        let state_root_hash = txn_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        ensure!(
+            state_root_hash == manifest.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            manifest.root_hash,
+            state_root_hash,
+        );
+        if let Some(epoch_history) = epoch_history {
+            epoch_history.verify_ledger_info(&li)?;
+        }
+        Ok(())
+    }
+
+    async fn load_manifest<T: serde::de::DeserializeOwned>(
+        &self,
+        backup_type: AuditedBackupType,
+        manifest: &FileHandleRef,
+        report: &mut AuditReport,
+    ) -> Option<T> {
+        if !self
+            .check_file(backup_type, manifest, manifest, report)
+            .await
+        {
+            return None;
+        }
+        match self.storage.load_json_file(manifest).await {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                report
+                    .issues
+                    .push(verification_failed(backup_type, manifest, e));
+                None
+            },
+        }
+    }
+
+    /// Returns whether `file` can be opened and read from, recording a `MissingFile` issue
+    /// otherwise.
+    async fn check_file(
+        &self,
+        backup_type: AuditedBackupType,
+        manifest: &FileHandleRef,
+        file: &FileHandleRef,
+        report: &mut AuditReport,
+    ) -> bool {
+        report.num_files_checked += 1;
+        // Some storages only fail on the first read, so reading the open file is necessary.
+        let res = async {
+            let mut buf = [0u8; 1];
+            self.storage
+                .open_for_read(file)
+                .await?
+                .read(&mut buf)
+                .await?;
+            Result::<()>::Ok(())
+        }
+        .await;
+
+        match res {
+            Ok(()) => true,
+            Err(e) => {
+                report.issues.push(AuditIssue::MissingFile {
+                    backup_type,
+                    manifest: manifest.to_string(),
+                    file: file.to_string(),
+                    error: format!("{:#}", e),
+                });
+                false
+            },
+        }
+    }
+}
+
+fn verification_failed(
+    backup_type: AuditedBackupType,
+    manifest: &FileHandleRef,
+    error: anyhow::Error,
+) -> AuditIssue {
+    AuditIssue::VerificationFailed {
+        backup_type,
+        manifest: Some(manifest.to_string()),
+        error: format!("{:#}", error),
+    }
+}
+
+/// Records gaps and overlaps among the (inclusive) ranges of `backups`, starting from 0. Returns
+/// the coverage summary and the backups forming the contiguous chain from 0, up to the first gap.
+fn check_coverage<'a, T>(
+    backup_type: AuditedBackupType,
+    backups: &'a [T],
+    range: impl Fn(&T) -> (u64, u64),
+    manifest: impl Fn(&T) -> &FileHandle,
+    issues: &mut Vec<AuditIssue>,
+) -> (CoverageReport, Vec<&'a T>) {
+    let mut sorted: Vec<&T> = backups.iter().collect();
+    sorted.sort_by_key(|b| range(*b));
+
+    let mut next = 0;
+    let mut chain = Vec::new();
+    let mut chain_next = 0;
+    let mut covered_from_genesis_until = None;
+    for backup in sorted {
+        let (first, last) = range(backup);
+        if first > next {
+            issues.push(AuditIssue::Gap {
+                backup_type,
+                first: next,
+                last: first - 1,
+            });
+        } else if first < next {
+            issues.push(AuditIssue::Overlap {
+                backup_type,
+                manifest: manifest(backup).clone(),
+                first,
+                last: min(last, next - 1),
+            });
+        }
+        if first == chain_next {
+            chain.push(backup);
+            chain_next = last + 1;
+        }
+        next = max(next, last + 1);
+        if first <= covered_from_genesis_until.map_or(0, |v| v + 1) {
+            covered_from_genesis_until = Some(next - 1);
+        }
+    }
+
+    let coverage = CoverageReport {
+        num_backups: backups.len(),
+        covered_from_genesis_until,
+        latest: if backups.is_empty() {
+            None
+        } else {
+            Some(next - 1)
+        },
+    };
+    (coverage, chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_coverage() {
+        let backups = vec![(10, 14), (0, 4), (5, 9), (12, 19), (25, 29)];
+        let manifest = "manifest".to_string();
+        let mut issues = Vec::new();
+        let (coverage, chain) = check_coverage(
+            AuditedBackupType::Transaction,
+            &backups,
+            |b| *b,
+            |_| &manifest,
+            &mut issues,
+        );
+
+        assert_eq!(chain, vec![&(0, 4), &(5, 9), &(10, 14)]);
+        assert_eq!(coverage.num_backups, 5);
+        assert_eq!(coverage.covered_from_genesis_until, Some(19));
+        assert_eq!(coverage.latest, Some(29));
+        assert_eq!(issues, vec![
+            AuditIssue::Overlap {
+                backup_type: AuditedBackupType::Transaction,
+                manifest: "manifest".to_string(),
+                first: 12,
+                last: 14,
+            },
+            AuditIssue::Gap {
+                backup_type: AuditedBackupType::Transaction,
+                first: 20,
+                last: 24,
+            },
+        ]);
+    }
+}
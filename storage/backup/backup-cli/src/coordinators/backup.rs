@@ -21,7 +21,7 @@ use crate::{
 };
 use anyhow::{anyhow, ensure, Result};
 use aptos_db::backup::backup_handler::DbState;
-use aptos_infallible::duration_since_epoch;
+use aptos_infallible::{duration_since_epoch, Mutex};
 use aptos_logger::prelude::*;
 use aptos_types::transaction::Version;
 use clap::Parser;
@@ -69,6 +69,13 @@ pub struct BackupCoordinatorOpt {
         many small files. "
     )]
     pub transaction_batch_size: usize,
+    #[clap(
+        long,
+        help = "Take each state snapshot incrementally on top of the latest one in the backup \
+        storage, only writing the chunks whose content changed since. Restoring an incremental \
+        snapshot requires the files of the snapshots it reuses chunks from."
+    )]
+    pub incremental_state_snapshots: bool,
     #[clap(flatten)]
     pub concurrent_downloads: ConcurrentDownloadsOpt,
 }
@@ -90,6 +97,9 @@ pub struct BackupCoordinator {
     metadata_cache_opt: MetadataCacheOpt,
     state_snapshot_interval_epochs: usize,
     transaction_batch_size: usize,
+    incremental_state_snapshots: bool,
+    /// Base of the next incremental state snapshot.
+    latest_state_snapshot_manifest: Mutex<Option<FileHandle>>,
     concurrent_downloads: usize,
 }
 
@@ -108,19 +118,24 @@ impl BackupCoordinator {
             metadata_cache_opt: opt.metadata_cache_opt,
             state_snapshot_interval_epochs: opt.state_snapshot_interval_epochs,
             transaction_batch_size: opt.transaction_batch_size,
+            incremental_state_snapshots: opt.incremental_state_snapshots,
+            latest_state_snapshot_manifest: Mutex::new(None),
             concurrent_downloads: opt.concurrent_downloads.get(),
         }
     }
 
     pub async fn run(&self) -> Result<()> {
         // Connect to both the local node and the backup storage.
-        let backup_state = metadata::cache::sync_and_load(
+        let metadata_view = metadata::cache::sync_and_load(
             &self.metadata_cache_opt,
             Arc::clone(&self.storage),
             self.concurrent_downloads,
         )
-        .await?
-        .get_storage_state()?;
+        .await?;
+        let backup_state = metadata_view.get_storage_state()?;
+        *self.latest_state_snapshot_manifest.lock() = metadata_view
+            .select_state_snapshot(Version::MAX)?
+            .map(|snapshot| snapshot.manifest);
 
         // On new DbState retrieved:
         // `watch_db_state` informs `backup_epoch_endings` via channel 1,
@@ -255,14 +270,34 @@ impl BackupCoordinator {
             return Ok(last_snapshot_epoch_in_backup);
         }
 
-        StateSnapshotBackupController::new(
-            StateSnapshotBackupOpt { epoch },
+        let base_manifest = if self.incremental_state_snapshots {
+            let base_manifest = self.latest_state_snapshot_manifest.lock().clone();
+            match base_manifest {
+                // The base can be gone since it was selected, e.g. if the backup was removed.
+                Some(manifest) if self.storage.open_for_read(&manifest).await.is_err() => {
+                    warn!(
+                        base_manifest = manifest.as_str(),
+                        "Base state snapshot manifest is not available, taking a full snapshot."
+                    );
+                    None
+                },
+                base_manifest => base_manifest,
+            }
+        } else {
+            None
+        };
+        let manifest = StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt {
+                epoch,
+                base_manifest,
+            },
             self.global_opt.clone(),
             Arc::clone(&self.client),
             Arc::clone(&self.storage),
         )
         .run()
         .await?;
+        *self.latest_state_snapshot_manifest.lock() = Some(manifest);
 
         Ok(Some(epoch))
     }
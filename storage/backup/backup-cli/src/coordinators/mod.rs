@@ -2,6 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod audit;
 pub mod backup;
 pub mod replay_verify;
 pub mod restore;
@@ -4,6 +4,7 @@
 
 pub mod command_adapter;
 pub mod local_fs;
+pub mod s3;
 
 #[cfg(test)]
 mod test_util;
@@ -13,6 +14,7 @@ mod tests;
 use crate::storage::{
     command_adapter::{CommandAdapter, CommandAdapterOpt},
     local_fs::{LocalFs, LocalFsOpt},
+    s3::{S3Opt, S3},
 };
 use anyhow::{ensure, Result};
 use async_trait::async_trait;
@@ -198,6 +200,13 @@ pub enum StorageOpt {
     https://github.com/aptos-labs/aptos-core/tree/main/storage/backup/backup-cli/src/storage/command_adapter/sample_configs/"
     )]
     CommandAdapter(CommandAdapterOpt),
+    #[clap(
+        about = "Select the S3 backup storage type, which talks to S3 or any S3-compatible object \
+    store directly, with multipart uploads, retries and a cap on concurrent requests. See sample \
+    configs here: \
+    https://github.com/aptos-labs/aptos-core/tree/main/storage/backup/backup-cli/src/storage/s3/sample_configs/"
+    )]
+    S3(S3Opt),
 }
 
 impl StorageOpt {
@@ -205,6 +214,7 @@ impl StorageOpt {
         Ok(match self {
             StorageOpt::LocalFs(opt) => Arc::new(LocalFs::new_with_opt(opt)),
             StorageOpt::CommandAdapter(opt) => Arc::new(CommandAdapter::new_with_opt(opt).await?),
+            StorageOpt::S3(opt) => Arc::new(S3::new_with_opt(opt).await?),
         })
     }
 }
@@ -213,7 +223,7 @@ impl StorageOpt {
 #[clap(group(
     ArgGroup::new("storage")
     .required(true)
-    .args(&["local_fs_dir", "command_adapter_config"]),
+    .args(&["local_fs_dir", "command_adapter_config", "s3_config"]),
 ))]
 pub struct DBToolStorageOpt {
     #[clap(
@@ -229,12 +239,22 @@ pub struct DBToolStorageOpt {
     https://github.com/aptos-labs/aptos-networks/tree/main/testnet/backups "
     )]
     command_adapter_config: Option<CommandAdapterOpt>,
+    #[clap(
+        long,
+        help = "Select the S3 backup storage type, which talks to S3 or any S3-compatible object \
+    store directly. Credentials are read from the AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY and \
+    AWS_SESSION_TOKEN environment variables. See sample configs here: \
+    https://github.com/aptos-labs/aptos-core/tree/main/storage/backup/backup-cli/src/storage/s3/sample_configs/"
+    )]
+    s3_config: Option<S3Opt>,
 }
 
 impl DBToolStorageOpt {
     pub async fn init_storage(self) -> Result<Arc<dyn BackupStorage>> {
         Ok(if self.local_fs_dir.is_some() {
             Arc::new(LocalFs::new_with_opt(self.local_fs_dir.unwrap()))
+        } else if self.s3_config.is_some() {
+            Arc::new(S3::new_with_opt(self.s3_config.unwrap()).await?)
         } else {
             Arc::new(CommandAdapter::new_with_opt(self.command_adapter_config.unwrap()).await?)
         })
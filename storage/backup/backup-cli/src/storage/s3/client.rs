@@ -0,0 +1,526 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::s3::config::{S3Config, S3Credentials};
+use anyhow::{bail, format_err, Result};
+use aptos_logger::prelude::*;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::{Method, StatusCode, Url};
+use sha2_0_10_6::{Digest, Sha256};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use tokio::{
+    io::AsyncRead,
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
+use tokio_io_timeout::TimeoutReader;
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+/// Characters left alone by the URI encoding of SigV4, see
+/// https://docs.aws.amazon.com/AmazonS3/latest/API/sig-v4-header-based-auth.html
+const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// A minimal client of the S3 REST API, covering what `S3` needs and signing requests with
+/// AWS Signature Version 4.
+pub(super) struct S3Client {
+    config: S3Config,
+    credentials: S3Credentials,
+    http: reqwest::Client,
+    concurrency: Arc<Semaphore>,
+}
+
+struct S3Request<'a> {
+    method: Method,
+    /// Full key of the object, or empty for requests on the bucket.
+    key: &'a str,
+    query: Vec<(&'static str, String)>,
+    headers: Vec<(&'static str, String)>,
+    body: Bytes,
+}
+
+impl<'a> S3Request<'a> {
+    fn new(method: Method, key: &'a str) -> Self {
+        Self {
+            method,
+            key,
+            query: vec![],
+            headers: vec![],
+            body: Bytes::new(),
+        }
+    }
+
+    fn query(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.query.push((key, value.into()));
+        self
+    }
+
+    fn header(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((key, value.into()));
+        self
+    }
+
+    fn body(mut self, body: Bytes) -> Self {
+        self.body = body;
+        self
+    }
+}
+
+impl S3Client {
+    const CONNECT_TIMEOUT_SECS: u64 = 10;
+    const MAX_BACKOFF_MS: u64 = 10_000;
+    const READ_TIMEOUT_SECS: u64 = 60;
+    /// Applies to whole requests except downloads, which can take arbitrarily long and are
+    /// protected by `READ_TIMEOUT_SECS` instead.
+    const REQUEST_TIMEOUT_SECS: u64 = 600;
+
+    pub fn new(config: S3Config, credentials: S3Credentials) -> Self {
+        let concurrency = Arc::new(Semaphore::new(config.max_concurrent_requests));
+        Self {
+            config,
+            credentials,
+            http: reqwest::Client::builder()
+                .connect_timeout(Duration::from_secs(Self::CONNECT_TIMEOUT_SECS))
+                .build()
+                .expect("Http client should build."),
+            concurrency,
+        }
+    }
+
+    pub fn config(&self) -> &S3Config {
+        &self.config
+    }
+
+    /// Prepends the configured prefix to a key.
+    pub fn full_key(&self, key: &str) -> String {
+        let prefix = self.config.prefix.trim_matches('/');
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", prefix, key)
+        }
+    }
+
+    pub async fn put_object(&self, key: &str, body: Bytes) -> Result<()> {
+        let full_key = self.full_key(key);
+        self.send(S3Request::new(Method::PUT, &full_key).body(body))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_object(&self, key: &str) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let full_key = self.full_key(key);
+        let (response, permit) = self
+            .send_with_permit(S3Request::new(Method::GET, &full_key), false)
+            .await?;
+        let reader = response
+            .bytes_stream()
+            .map_ok(move |bytes| {
+                // Keep the request counted against the concurrency limit until it's fully read.
+                let _ = &permit;
+                bytes
+            })
+            .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
+            .into_async_read()
+            .compat();
+        let mut reader_with_read_timeout = TimeoutReader::new(reader);
+        reader_with_read_timeout.set_timeout(Some(Duration::from_secs(Self::READ_TIMEOUT_SECS)));
+
+        Ok(Box::new(Box::pin(reader_with_read_timeout)))
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        let full_key = self.full_key(key);
+        self.send(S3Request::new(Method::DELETE, &full_key)).await?;
+        Ok(())
+    }
+
+    pub async fn copy_object(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        let copy_source = format!(
+            "/{}/{}",
+            self.config.bucket,
+            uri_encode_path(&self.full_key(src_key))
+        );
+        let full_key = self.full_key(dst_key);
+        let body = self
+            .send(S3Request::new(Method::PUT, &full_key).header("x-amz-copy-source", copy_source))
+            .await?;
+        // Copies can fail after the response status has been sent.
+        ensure_no_error(&body)
+    }
+
+    /// Lists the keys of all objects under `prefix`, relative to the configured prefix.
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let full_prefix = self.full_key(prefix);
+        let strip = full_prefix.len() - prefix.len();
+
+        let mut keys = vec![];
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let mut request = S3Request::new(Method::GET, "")
+                .query("list-type", "2")
+                .query("prefix", full_prefix.clone());
+            if let Some(token) = continuation_token.take() {
+                request = request.query("continuation-token", token);
+            }
+            let body = self.send(request).await?;
+            keys.extend(
+                xml_values(&body, "Key")
+                    .into_iter()
+                    .map(|key| key[strip..].to_string()),
+            );
+            if xml_values(&body, "IsTruncated").first().map(String::as_str) != Some("true") {
+                break;
+            }
+            continuation_token = Some(
+                xml_values(&body, "NextContinuationToken")
+                    .pop()
+                    .ok_or_else(|| format_err!("Truncated listing without continuation token."))?,
+            );
+        }
+        Ok(keys)
+    }
+
+    /// Starts a multipart upload and returns its upload id.
+    pub async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        let full_key = self.full_key(key);
+        let body = self
+            .send(S3Request::new(Method::POST, &full_key).query("uploads", ""))
+            .await?;
+        xml_values(&body, "UploadId")
+            .pop()
+            .ok_or_else(|| format_err!("No UploadId in response: {}", body))
+    }
+
+    /// Uploads a part of a multipart upload and returns its ETag.
+    pub async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: usize,
+        body: Bytes,
+    ) -> Result<String> {
+        let full_key = self.full_key(key);
+        let (response, _permit) = self
+            .send_with_permit(
+                S3Request::new(Method::PUT, &full_key)
+                    .query("partNumber", part_number.to_string())
+                    .query("uploadId", upload_id)
+                    .body(body),
+                true,
+            )
+            .await?;
+        Ok(response
+            .headers()
+            .get("etag")
+            .ok_or_else(|| format_err!("No ETag for part {} of {}.", part_number, key))?
+            .to_str()?
+            .to_string())
+    }
+
+    /// Completes a multipart upload, given the ETags of all parts in order.
+    pub async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<()> {
+        let parts: String = etags
+            .iter()
+            .enumerate()
+            .map(|(idx, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    idx + 1,
+                    xml_escape(etag)
+                )
+            })
+            .collect();
+        let full_key = self.full_key(key);
+        let body = self
+            .send(
+                S3Request::new(Method::POST, &full_key)
+                    .query("uploadId", upload_id)
+                    .body(Bytes::from(format!(
+                        "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+                        parts
+                    ))),
+            )
+            .await?;
+        // Like copies, completing an upload can fail after the response status has been sent.
+        ensure_no_error(&body)
+    }
+
+    pub async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        let full_key = self.full_key(key);
+        self.send(S3Request::new(Method::DELETE, &full_key).query("uploadId", upload_id))
+            .await?;
+        Ok(())
+    }
+
+    /// Sends a request and returns the response body.
+    async fn send(&self, request: S3Request<'_>) -> Result<String> {
+        let (response, _permit) = self.send_with_permit(request, true).await?;
+        Ok(response.text().await?)
+    }
+
+    /// Sends a request, retrying on connection errors, server errors and throttling, and returns
+    /// the response together with the permit counting it against `max_concurrent_requests`.
+    async fn send_with_permit(
+        &self,
+        request: S3Request<'_>,
+        with_timeout: bool,
+    ) -> Result<(reqwest::Response, OwnedSemaphorePermit)> {
+        let mut attempt = 0;
+        loop {
+            let permit = self.concurrency.clone().acquire_owned().await?;
+            let error = match self.send_once(&request, with_timeout).await {
+                Ok(response) if response.status().is_success() => return Ok((response, permit)),
+                Ok(response) => {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    let error = format_err!(
+                        "S3 request {} {} failed with status {}: {}",
+                        request.method,
+                        request.key,
+                        status,
+                        body,
+                    );
+                    if !(status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS) {
+                        return Err(error);
+                    }
+                    error
+                },
+                Err(error) => error,
+            };
+            drop(permit);
+
+            if attempt >= self.config.max_retries {
+                return Err(error.context(format!("Giving up after {} retries.", attempt)));
+            }
+            let backoff = Duration::from_millis(min_backoff_ms(attempt, Self::MAX_BACKOFF_MS));
+            warn!(
+                error = ?error,
+                attempt = attempt,
+                backoff_ms = backoff.as_millis() as u64,
+                "S3 request failed, retrying."
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send_once(
+        &self,
+        request: &S3Request<'_>,
+        with_timeout: bool,
+    ) -> Result<reqwest::Response> {
+        let (mut url, host, canonical_path) = self.url(request.key)?;
+        let canonical_query = canonical_query(&request.query);
+        if !canonical_query.is_empty() {
+            url.set_query(Some(&canonical_query));
+        }
+
+        let now = Utc::now();
+        let payload_hash = hex::encode(Sha256::digest(&request.body));
+        let mut headers: BTreeMap<String, String> = request
+            .headers
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+        headers.insert("host".to_string(), host);
+        headers.insert("x-amz-content-sha256".to_string(), payload_hash.clone());
+        headers.insert(
+            "x-amz-date".to_string(),
+            now.format("%Y%m%dT%H%M%SZ").to_string(),
+        );
+        if let Some(token) = &self.credentials.session_token {
+            headers.insert("x-amz-security-token".to_string(), token.clone());
+        }
+        let authorization = sign(
+            &self.credentials,
+            &self.config.region,
+            request.method.as_str(),
+            &canonical_path,
+            &canonical_query,
+            &headers,
+            &payload_hash,
+            now,
+        );
+
+        let mut builder = self
+            .http
+            .request(request.method.clone(), url)
+            .header("authorization", authorization)
+            .body(request.body.clone());
+        for (key, value) in headers.iter().filter(|(key, _)| key.as_str() != "host") {
+            builder = builder.header(key.as_str(), value.as_str());
+        }
+        if with_timeout {
+            builder = builder.timeout(Duration::from_secs(Self::REQUEST_TIMEOUT_SECS));
+        }
+        Ok(builder.send().await?)
+    }
+
+    /// Returns the URL of an object, the host header and the canonical path to sign.
+    fn url(&self, key: &str) -> Result<(Url, String, String)> {
+        let endpoint = Url::parse(&self.config.endpoint())?;
+        let endpoint_host = endpoint
+            .host_str()
+            .ok_or_else(|| format_err!("No host in S3 endpoint {}.", endpoint))?;
+        let port = endpoint
+            .port()
+            .map(|port| format!(":{}", port))
+            .unwrap_or_default();
+
+        let (host, path) = if self.config.force_path_style {
+            let path = if key.is_empty() {
+                format!("/{}", self.config.bucket)
+            } else {
+                format!("/{}/{}", self.config.bucket, uri_encode_path(key))
+            };
+            (format!("{}{}", endpoint_host, port), path)
+        } else {
+            (
+                format!("{}.{}{}", self.config.bucket, endpoint_host, port),
+                format!("/{}", uri_encode_path(key)),
+            )
+        };
+        let url = Url::parse(&format!("{}://{}{}", endpoint.scheme(), host, path))?;
+        Ok((url, host, path))
+    }
+}
+
+fn min_backoff_ms(attempt: usize, max_backoff_ms: u64) -> u64 {
+    100u64
+        .saturating_mul(1 << attempt.min(16))
+        .min(max_backoff_ms)
+}
+
+/// Encodes each segment of a key, keeping the slashes between them.
+fn uri_encode_path(key: &str) -> String {
+    key.split('/')
+        .map(|segment| utf8_percent_encode(segment, URI_ENCODE_SET).to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical_query(query: &[(&'static str, String)]) -> String {
+    let mut pairs: Vec<_> = query
+        .iter()
+        .map(|(key, value)| {
+            (
+                utf8_percent_encode(key, URI_ENCODE_SET).to_string(),
+                utf8_percent_encode(value, URI_ENCODE_SET).to_string(),
+            )
+        })
+        .collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Computes the `Authorization` header of a request with AWS Signature Version 4. All `headers`
+/// are signed, their names must be in lower case.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn sign(
+    credentials: &S3Credentials,
+    region: &str,
+    method: &str,
+    canonical_path: &str,
+    canonical_query: &str,
+    headers: &BTreeMap<String, String>,
+    payload_hash: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(key, value)| format!("{}:{}\n", key, value.trim()))
+        .collect();
+    let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method, canonical_path, canonical_query, canonical_headers, signed_headers, payload_hash
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let signing_key = [region, "s3", "aws4_request"].iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            &date,
+        ),
+        |key, part| hmac_sha256(&key, part),
+    );
+    let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{},SignedHeaders={},Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    )
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any size.");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn ensure_no_error(body: &str) -> Result<()> {
+    if body.contains("<Error>") {
+        bail!("S3 request failed: {}", body);
+    }
+    Ok(())
+}
+
+/// Returns the text of all elements named `tag`. This is only meant for the flat responses of
+/// the few APIs used here, where the elements of interest never nest.
+pub(super) fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+
+    let mut values = vec![];
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        values.push(xml_unescape(&rest[..end]));
+        rest = &rest[end + close.len()..];
+    }
+    values
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,115 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::error_notes::ErrorNotes;
+use anyhow::{ensure, format_err, Result};
+use serde::Deserialize;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct S3Config {
+    /// Name of the bucket holding the backups.
+    pub bucket: String,
+    /// Prefix prepended to the key of every object, like "backup1/e1". Can be empty.
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default = "S3Config::default_region")]
+    pub region: String,
+    /// Endpoint of the S3-compatible service, like "http://localhost:9000" for a local MinIO.
+    /// Defaults to the AWS endpoint of `region`.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Address the bucket as part of the path instead of as part of the host name, which is
+    /// required by most S3-compatible services.
+    #[serde(default)]
+    pub force_path_style: bool,
+    /// Max number of requests in flight at the same time, across all files.
+    #[serde(default = "S3Config::default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// Number of times a request failing with a retriable error is retried.
+    #[serde(default = "S3Config::default_max_retries")]
+    pub max_retries: usize,
+    /// Files larger than this are uploaded in parts of this size.
+    #[serde(default = "S3Config::default_multipart_part_size")]
+    pub multipart_part_size: usize,
+}
+
+impl S3Config {
+    /// S3 rejects multipart uploads with parts smaller than this, except for the last one.
+    const MIN_MULTIPART_PART_SIZE: usize = 5 * 1024 * 1024;
+
+    fn default_region() -> String {
+        "us-east-1".to_string()
+    }
+
+    fn default_max_concurrent_requests() -> usize {
+        16
+    }
+
+    fn default_max_retries() -> usize {
+        5
+    }
+
+    fn default_multipart_part_size() -> usize {
+        64 * 1024 * 1024
+    }
+
+    pub async fn load_from_file(path: &Path) -> Result<Self> {
+        let path_str = path.to_str().unwrap_or_default();
+        let mut file = tokio::fs::File::open(path).await.err_notes(path_str)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).await.err_notes(path_str)?;
+
+        Self::load_from_str(std::str::from_utf8(&content)?)
+    }
+
+    pub fn load_from_str(content: &str) -> Result<Self> {
+        let config: Self = serde_yaml::from_str(content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(!self.bucket.is_empty(), "bucket must be specified.");
+        ensure!(
+            self.max_concurrent_requests > 0,
+            "max_concurrent_requests must be positive."
+        );
+        ensure!(
+            self.multipart_part_size >= Self::MIN_MULTIPART_PART_SIZE,
+            "multipart_part_size must be at least {} bytes.",
+            Self::MIN_MULTIPART_PART_SIZE,
+        );
+        Ok(())
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", self.region))
+    }
+}
+
+/// Credentials used to sign requests, read from the standard AWS environment variables so that
+/// secrets don't end up in config files.
+#[derive(Clone)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl S3Credentials {
+    pub fn from_env() -> Result<Self> {
+        let var = |key: &str| {
+            std::env::var(key).map_err(|_| format_err!("Environment variable {} not set.", key))
+        };
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
@@ -0,0 +1,348 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod client;
+pub mod config;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{
+    s3::{
+        client::S3Client,
+        config::{S3Config, S3Credentials},
+    },
+    BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    TextLine,
+};
+use anyhow::{ensure, format_err, Result};
+use aptos_logger::prelude::*;
+use async_trait::async_trait;
+use bytes::Bytes;
+use clap::Parser;
+use futures::ready;
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, DuplexStream},
+    task::{JoinHandle, JoinSet},
+};
+
+#[derive(Parser, Clone, Debug, Serialize, Deserialize)]
+pub struct S3Opt {
+    #[clap(
+        long = "config",
+        help = "Config file for the S3 backup store. Credentials are read from the \
+        AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY and AWS_SESSION_TOKEN environment variables."
+    )]
+    config: PathBuf,
+}
+
+impl FromStr for S3Opt {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(S3Opt {
+            config: PathBuf::from(s),
+        })
+    }
+}
+
+/// A BackupStorage that talks to S3 or any S3-compatible object store (MinIO, GCS in
+/// interoperability mode, etc.) directly over HTTP, without shelling out. Large files are
+/// uploaded in parts. See `S3Config`.
+pub struct S3 {
+    client: Arc<S3Client>,
+}
+
+impl S3 {
+    const DUPLEX_BUFFER_SIZE: usize = 1024 * 1024;
+    /// Max number of parts of a single file being uploaded at the same time, which bounds the
+    /// memory used per file to this many times `multipart_part_size`.
+    const MAX_IN_FLIGHT_PARTS: usize = 4;
+    const METADATA_BACKUP_DIR: &'static str = "metadata_backup";
+    const METADATA_DIR: &'static str = "metadata";
+
+    pub fn new(config: S3Config, credentials: S3Credentials) -> Self {
+        Self {
+            client: Arc::new(S3Client::new(config, credentials)),
+        }
+    }
+
+    pub async fn new_with_opt(opt: S3Opt) -> Result<Self> {
+        let config = S3Config::load_from_file(&opt.config).await?;
+
+        Ok(Self::new(config, S3Credentials::from_env()?))
+    }
+
+    fn file_name(file_handle: &FileHandleRef) -> Result<&str> {
+        Path::new(file_handle)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format_err!("cannot extract filename from {}", file_handle))
+    }
+}
+
+#[async_trait]
+impl BackupStorage for S3 {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        // There are no directories in an object store, keys under the backup are simply
+        // prefixed with its name.
+        Ok(name.to_string())
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let file_handle = format!("{}/{}", backup_handle, name.as_ref());
+        let (writer, reader) = tokio::io::duplex(Self::DUPLEX_BUFFER_SIZE);
+        let finished = Arc::new(AtomicBool::new(false));
+        let upload = tokio::spawn(upload(
+            self.client.clone(),
+            file_handle.clone(),
+            reader,
+            finished.clone(),
+        ));
+
+        Ok((
+            file_handle,
+            Box::new(S3Sink {
+                writer,
+                finished,
+                upload: Some(upload),
+            }),
+        ))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        self.client.get_object(file_handle).await
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        self.client
+            .list_objects(&format!("{}/", Self::METADATA_DIR))
+            .await
+    }
+
+    /// file_handle are expected to be the return results from list_metadata_files
+    /// file_handle is a path with `metadata` in the path, Ex: metadata/epoch_ending_1.meta
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let backup_key = format!(
+            "{}/{}",
+            Self::METADATA_BACKUP_DIR,
+            Self::file_name(file_handle)?
+        );
+        // Objects can't be renamed, so copy then delete.
+        self.client.copy_object(file_handle, &backup_key).await?;
+        self.client.delete_object(file_handle).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let content = lines
+            .iter()
+            .map(|e| e.as_ref())
+            .collect::<Vec<&str>>()
+            .join("");
+        let file_handle = format!("{}/{}", Self::METADATA_DIR, name.as_ref());
+        self.client
+            .put_object(&file_handle, Bytes::from(content))
+            .await?;
+        Ok(file_handle)
+    }
+}
+
+/// Reads up to `part_size` bytes, less only if the end of the stream is reached.
+async fn read_part(reader: &mut DuplexStream, part_size: usize) -> Result<Bytes> {
+    let mut buf = Vec::new();
+    reader.take(part_size as u64).read_to_end(&mut buf).await?;
+    Ok(Bytes::from(buf))
+}
+
+/// Uploads everything written to the other end of `reader`, in a single request if it fits in
+/// one part, or as a multipart upload otherwise. Nothing is stored if the writer goes away
+/// without being shut down, i.e. `finished` is not set when the stream ends.
+async fn upload(
+    client: Arc<S3Client>,
+    key: String,
+    mut reader: DuplexStream,
+    finished: Arc<AtomicBool>,
+) -> Result<()> {
+    let part_size = client.config().multipart_part_size;
+
+    let first_part = read_part(&mut reader, part_size).await?;
+    if first_part.len() < part_size {
+        ensure!(
+            finished.load(Ordering::SeqCst),
+            "Writer of {} dropped before shutdown, not uploaded.",
+            key,
+        );
+        return client.put_object(&key, first_part).await;
+    }
+
+    let upload_id = client.create_multipart_upload(&key).await?;
+    match upload_parts(
+        &client,
+        &key,
+        &upload_id,
+        first_part,
+        &mut reader,
+        &finished,
+    )
+    .await
+    {
+        Ok(etags) => {
+            client
+                .complete_multipart_upload(&key, &upload_id, &etags)
+                .await
+        },
+        Err(err) => {
+            if let Err(abort_err) = client.abort_multipart_upload(&key, &upload_id).await {
+                warn!(
+                    key = key,
+                    upload_id = upload_id,
+                    error = ?abort_err,
+                    "Failed to abort multipart upload."
+                );
+            }
+            Err(err)
+        },
+    }
+}
+
+/// Uploads the parts of a multipart upload, returning their ETags in order. On failure, the
+/// remaining part uploads are cancelled before returning, so none of them is still running when
+/// the multipart upload is aborted.
+async fn upload_parts(
+    client: &Arc<S3Client>,
+    key: &str,
+    upload_id: &str,
+    first_part: Bytes,
+    reader: &mut DuplexStream,
+    finished: &AtomicBool,
+) -> Result<Vec<String>> {
+    let part_size = client.config().multipart_part_size;
+
+    let mut in_flight = JoinSet::new();
+    let res: Result<Vec<String>> = async {
+        let mut etags = Vec::new();
+        let mut part = first_part;
+        let mut part_number = 1;
+        loop {
+            let is_last = part.len() < part_size;
+            if !part.is_empty() {
+                let client = client.clone();
+                let key = key.to_string();
+                let upload_id = upload_id.to_string();
+                in_flight.spawn(async move {
+                    let etag = client
+                        .upload_part(&key, &upload_id, part_number, part)
+                        .await?;
+                    Ok::<_, anyhow::Error>((part_number, etag))
+                });
+                part_number += 1;
+            }
+            if is_last {
+                break;
+            }
+            while in_flight.len() >= S3::MAX_IN_FLIGHT_PARTS {
+                etags.push(in_flight.join_next().await.expect("Not empty.")??);
+            }
+            part = read_part(reader, part_size).await?;
+        }
+        ensure!(
+            finished.load(Ordering::SeqCst),
+            "Writer of {} dropped before shutdown, not uploaded.",
+            key,
+        );
+        while let Some(etag) = in_flight.join_next().await {
+            etags.push(etag??);
+        }
+
+        // Parts finish out of order
+        etags.sort_unstable_by_key(|(part_number, _)| *part_number);
+        Ok(etags.into_iter().map(|(_, etag)| etag).collect())
+    }
+    .await;
+
+    if res.is_err() {
+        in_flight.shutdown().await;
+    }
+    res
+}
+
+/// Returned by `S3::create_for_write()`, feeds the upload task and surfaces its result on
+/// shutdown.
+struct S3Sink {
+    writer: DuplexStream,
+    finished: Arc<AtomicBool>,
+    upload: Option<JoinHandle<Result<()>>>,
+}
+
+impl S3Sink {
+    fn poll_upload(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let upload = match self.upload.as_mut() {
+            Some(upload) => upload,
+            None => {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "Upload already finished.",
+                )))
+            },
+        };
+        let res = ready!(Pin::new(upload).poll(cx));
+        self.upload = None;
+
+        Poll::Ready(match res {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(io::Error::new(io::ErrorKind::Other, format!("{:#}", e))),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        })
+    }
+}
+
+impl AsyncWrite for S3Sink {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        match ready!(Pin::new(&mut this.writer).poll_write(cx, buf)) {
+            Ok(written) => Poll::Ready(Ok(written)),
+            // The upload task only stops reading when it fails, report why.
+            Err(e) => this.poll_upload(cx).map(|res| res.and(Err(e))),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().writer).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.finished.store(true, Ordering::SeqCst);
+        ready!(Pin::new(&mut this.writer).poll_shutdown(cx))?;
+        this.poll_upload(cx)
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use aptos_crypto::HashValue;
+use clap::Parser;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Hex encoded 32 byte key used to encrypt and decrypt backup files.
+pub const ENCRYPTION_KEY_ENV: &str = "BACKUP_ENCRYPTION_KEY";
+/// Alternatively, path to a file containing the hex encoded key.
+pub const ENCRYPTION_KEY_FILE_ENV: &str = "BACKUP_ENCRYPTION_KEY_FILE";
+
+#[derive(Clone, Default, Parser)]
+pub struct FileEncodingOpt {
+    #[clap(
+        long = "file-compression-level",
+        help = "Compress the backup files with zstd at this level (1 to 22, 3 is a reasonable \
+        default). Files are not compressed if not set."
+    )]
+    pub compression_level: Option<i32>,
+    #[clap(
+        long = "encrypt-files",
+        help = "Encrypt the backup files with AES-256-GCM. The hex encoded 32 byte key is read from \
+        the BACKUP_ENCRYPTION_KEY environment variable, or the file pointed to by \
+        BACKUP_ENCRYPTION_KEY_FILE. The same key needs to be provided when restoring or verifying."
+    )]
+    pub encrypt: bool,
+}
+
+impl FileEncodingOpt {
+    pub fn encoding(&self) -> Result<FileEncoding> {
+        let compression = self
+            .compression_level
+            .map(|level| {
+                ensure!(
+                    zstd::compression_level_range().contains(&level),
+                    "Invalid zstd compression level {}.",
+                    level,
+                );
+                Ok(Compression::Zstd { level })
+            })
+            .transpose()?;
+        let encryption = if self.encrypt {
+            Some(Encryption::Aes256Gcm {
+                key_id: EncryptionKey::from_env()?.id(),
+            })
+        } else {
+            None
+        };
+
+        Ok(FileEncoding {
+            compression,
+            encryption,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum Compression {
+    Zstd { level: i32 },
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum Encryption {
+    /// Each file is a random 12 byte nonce followed by the ciphertext and the tag.
+    Aes256Gcm {
+        /// Identifies the key without revealing it, so a wrong key is reported as such.
+        key_id: String,
+    },
+}
+
+/// How the data files (chunks and proofs, but not the manifest itself) of a backup are encoded,
+/// recorded in its manifest. Data is compressed before being encrypted.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileEncoding {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<Compression>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<Encryption>,
+}
+
+impl FileEncoding {
+    pub fn is_plain(&self) -> bool {
+        self.compression.is_none() && self.encryption.is_none()
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        let compressed = match &self.compression {
+            None => None,
+            Some(Compression::Zstd { level }) => Some(zstd::encode_all(bytes, *level)?),
+        };
+        let bytes = compressed.as_deref().unwrap_or(bytes);
+
+        Ok(match &self.encryption {
+            None => bytes.to_vec(),
+            Some(Encryption::Aes256Gcm { key_id }) => {
+                let cipher = EncryptionKey::from_env()?.cipher(key_id)?;
+                let mut nonce = [0u8; 12];
+                rand::thread_rng().fill_bytes(&mut nonce);
+                let ciphertext = cipher
+                    .encrypt(Nonce::from_slice(&nonce), bytes)
+                    .map_err(|e| anyhow!("Failed to encrypt: {}", e))?;
+                [&nonce[..], &ciphertext[..]].concat()
+            },
+        })
+    }
+
+    pub fn decode(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        let bytes = match &self.encryption {
+            None => bytes,
+            Some(Encryption::Aes256Gcm { key_id }) => {
+                let cipher = EncryptionKey::from_env()?.cipher(key_id)?;
+                ensure!(bytes.len() >= 12, "Encrypted file too short.");
+                let (nonce, ciphertext) = bytes.split_at(12);
+                cipher
+                    .decrypt(Nonce::from_slice(nonce), ciphertext)
+                    .map_err(|_| anyhow!("Failed to decrypt, data corrupted or tampered with."))?
+            },
+        };
+
+        Ok(match &self.compression {
+            None => bytes,
+            Some(Compression::Zstd { .. }) => zstd::decode_all(bytes.as_slice())?,
+        })
+    }
+}
+
+struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    fn from_env() -> Result<Self> {
+        let hex_key = if let Ok(key) = std::env::var(ENCRYPTION_KEY_ENV) {
+            key
+        } else if let Ok(path) = std::env::var(ENCRYPTION_KEY_FILE_ENV) {
+            std::fs::read_to_string(PathBuf::from(&path))
+                .map_err(|e| anyhow!("Failed to read encryption key file {}: {}", path, e))?
+        } else {
+            bail!(
+                "Encryption key not provided, set {} or {}.",
+                ENCRYPTION_KEY_ENV,
+                ENCRYPTION_KEY_FILE_ENV,
+            )
+        };
+        let key = hex::decode(hex_key.trim())?;
+
+        Ok(Self(key.try_into().map_err(|key: Vec<u8>| {
+            anyhow!("Encryption key must be 32 bytes, got {}.", key.len())
+        })?))
+    }
+
+    fn id(&self) -> String {
+        HashValue::sha3_256_of(&self.0).to_hex()[..16].to_string()
+    }
+
+    fn cipher(&self, expected_key_id: &str) -> Result<Aes256Gcm> {
+        ensure!(
+            self.id() == expected_key_id,
+            "Wrong encryption key, expecting key id {}, got {}.",
+            expected_key_id,
+            self.id(),
+        );
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::set_test_encryption_key;
+
+    #[test]
+    fn test_encode_decode() {
+        set_test_encryption_key();
+        let data: Vec<u8> = (0..10000u32).flat_map(|i| (i % 7).to_be_bytes()).collect();
+
+        for (compression_level, encrypt) in [
+            (None, false),
+            (Some(3), false),
+            (None, true),
+            (Some(3), true),
+        ] {
+            let encoding = FileEncodingOpt {
+                compression_level,
+                encrypt,
+            }
+            .encoding()
+            .unwrap();
+            let encoded = encoding.encode(&data).unwrap();
+            if compression_level.is_some() {
+                assert!(encoded.len() < data.len());
+            }
+            if !encoding.is_plain() {
+                assert_ne!(encoded, data);
+            }
+            assert_eq!(encoding.decode(encoded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_failures() {
+        set_test_encryption_key();
+        let encoding = FileEncodingOpt {
+            compression_level: None,
+            encrypt: true,
+        }
+        .encoding()
+        .unwrap();
+        let mut encoded = encoding.encode(b"data").unwrap();
+
+        let wrong_key = FileEncoding {
+            compression: None,
+            encryption: Some(Encryption::Aes256Gcm {
+                key_id: "0000000000000000".to_string(),
+            }),
+        };
+        assert!(wrong_key.decode(encoded.clone()).is_err());
+
+        *encoded.last_mut().unwrap() ^= 1;
+        assert!(encoding.decode(encoded).is_err());
+    }
+}
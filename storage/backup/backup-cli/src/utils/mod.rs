@@ -4,6 +4,7 @@
 
 pub mod backup_service_client;
 pub(crate) mod error_notes;
+pub mod file_encoding;
 pub mod read_record_bytes;
 pub mod storage_ext;
 pub(crate) mod stream;
@@ -11,6 +12,7 @@ pub(crate) mod stream;
 #[cfg(any(test, feature = "testing"))]
 pub mod test_utils;
 
+use crate::utils::file_encoding::FileEncodingOpt;
 use aptos_config::config::{
     RocksdbConfig, RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
@@ -63,6 +65,8 @@ pub struct GlobalBackupOpt {
         concurrent requests to the fullnode backup service. "
     )]
     pub concurrent_data_requests: usize,
+    #[clap(flatten)]
+    pub file_encoding: FileEncodingOpt,
 }
 
 #[derive(Clone, Parser)]
@@ -2,19 +2,49 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::{BackupHandle, BackupStorage, FileHandleRef};
+use crate::{
+    storage::{
+        BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    },
+    utils::file_encoding::FileEncoding,
+};
 use anyhow::Result;
 use async_trait::async_trait;
 use rand::random;
 use serde::de::DeserializeOwned;
-use std::{convert::TryInto, sync::Arc};
-use tokio::io::AsyncReadExt;
+use std::{convert::TryInto, io::Cursor, sync::Arc};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 
 #[async_trait]
 pub trait BackupStorageExt {
     async fn read_all(&self, file_handle: &FileHandleRef) -> Result<Vec<u8>>;
     async fn load_json_file<T: DeserializeOwned>(&self, file_handle: &FileHandleRef) -> Result<T>;
     async fn load_bcs_file<T: DeserializeOwned>(&self, file_handle: &FileHandleRef) -> Result<T>;
+    /// Like `open_for_read()`, but decodes a file written by `create_encoded_file()`. Encoded
+    /// files are read into memory entirely before being decoded.
+    async fn open_for_read_decoded(
+        &self,
+        file_handle: &FileHandleRef,
+        encoding: &FileEncoding,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>>;
+    async fn read_all_decoded(
+        &self,
+        file_handle: &FileHandleRef,
+        encoding: &FileEncoding,
+    ) -> Result<Vec<u8>>;
+    async fn load_encoded_bcs_file<T: DeserializeOwned>(
+        &self,
+        file_handle: &FileHandleRef,
+        encoding: &FileEncoding,
+    ) -> Result<T>;
+    /// Creates a file with `bytes` encoded as specified.
+    async fn create_encoded_file(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+        bytes: &[u8],
+        encoding: &FileEncoding,
+    ) -> Result<FileHandle>;
     /// Adds a random suffix ".XXXX" to the backup name, so a retry won't pass a same backup name to
     /// the storage.
     async fn create_backup_with_random_suffix(&self, name: &str) -> Result<BackupHandle>;
@@ -37,6 +67,62 @@ impl BackupStorageExt for Arc<dyn BackupStorage> {
         Ok(serde_json::from_slice(&self.read_all(file_handle).await?)?)
     }
 
+    async fn open_for_read_decoded(
+        &self,
+        file_handle: &FileHandleRef,
+        encoding: &FileEncoding,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        if encoding.is_plain() {
+            self.open_for_read(file_handle).await
+        } else {
+            Ok(Box::new(Cursor::new(
+                self.read_all_decoded(file_handle, encoding).await?,
+            )))
+        }
+    }
+
+    async fn read_all_decoded(
+        &self,
+        file_handle: &FileHandleRef,
+        encoding: &FileEncoding,
+    ) -> Result<Vec<u8>> {
+        let bytes = self.read_all(file_handle).await?;
+        if encoding.is_plain() {
+            return Ok(bytes);
+        }
+        let encoding = encoding.clone();
+        tokio::task::spawn_blocking(move || encoding.decode(bytes)).await?
+    }
+
+    async fn load_encoded_bcs_file<T: DeserializeOwned>(
+        &self,
+        file_handle: &FileHandleRef,
+        encoding: &FileEncoding,
+    ) -> Result<T> {
+        Ok(bcs::from_bytes(
+            &self.read_all_decoded(file_handle, encoding).await?,
+        )?)
+    }
+
+    async fn create_encoded_file(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+        bytes: &[u8],
+        encoding: &FileEncoding,
+    ) -> Result<FileHandle> {
+        let (file_handle, mut file) = self.create_for_write(backup_handle, name).await?;
+        if encoding.is_plain() {
+            file.write_all(bytes).await?;
+        } else {
+            let (encoding, bytes) = (encoding.clone(), bytes.to_vec());
+            let encoded = tokio::task::spawn_blocking(move || encoding.encode(&bytes)).await??;
+            file.write_all(&encoded).await?;
+        }
+        file.shutdown().await?;
+        Ok(file_handle)
+    }
+
     async fn create_backup_with_random_suffix(&self, name: &str) -> Result<BackupHandle> {
         self.create_backup(&format!("{}.{:04x}", name, random::<u16>()).try_into()?)
             .await
@@ -51,3 +51,12 @@ pub fn start_local_backup_service(db: Arc<AptosDB>) -> (Runtime, u16) {
     let rt = start_backup_service(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port), db);
     (rt, port)
 }
+
+/// Sets the key used to encrypt and decrypt backup files. Every test uses the same key, so it's
+/// fine for tests running in parallel to set it.
+pub fn set_test_encryption_key() {
+    std::env::set_var(
+        crate::utils::file_encoding::ENCRYPTION_KEY_ENV,
+        "0101010101010101010101010101010101010101010101010101010101010101",
+    );
+}
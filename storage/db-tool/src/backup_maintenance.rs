@@ -1,19 +1,27 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use aptos_backup_cli::{
-    coordinators::backup::BackupCompactor, metadata::cache::MetadataCacheOpt,
-    storage::DBToolStorageOpt, utils::ConcurrentDownloadsOpt,
+    coordinators::{audit::AuditCoordinator, backup::BackupCompactor},
+    metadata::cache::MetadataCacheOpt,
+    storage::DBToolStorageOpt,
+    utils::{ConcurrentDownloadsOpt, TrustedWaypointOpt},
 };
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
-/// Support compacting and cleaning obsolete metadata files
+/// Support compacting, cleaning obsolete metadata files and auditing backups
 #[derive(Subcommand)]
 pub enum Command {
     #[clap(about = "Compact metdata files")]
     Compact(CompactionOpt),
     #[clap(about = "Cleanup the backup metadata files")]
     Cleanup(CleanupOpt),
+    #[clap(
+        about = "Check that all files referenced by the backup metadata exist and verify their \
+        proofs without restoring, reporting gaps and overlaps in the backup coverage"
+    )]
+    Audit(AuditOpt),
 }
 
 #[derive(Parser)]
@@ -48,6 +56,21 @@ pub struct CleanupOpt {
     pub storage: DBToolStorageOpt,
 }
 
+#[derive(Parser)]
+pub struct AuditOpt {
+    #[clap(flatten)]
+    pub metadata_cache_opt: MetadataCacheOpt,
+    #[clap(flatten)]
+    pub trusted_waypoints_opt: TrustedWaypointOpt,
+    #[clap(flatten)]
+    pub storage: DBToolStorageOpt,
+    #[clap(flatten)]
+    pub concurrent_downloads: ConcurrentDownloadsOpt,
+    /// Write the report as JSON to this file instead of stdout
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+}
+
 impl Command {
     pub async fn run(self) -> Result<()> {
         match self {
@@ -66,6 +89,26 @@ impl Command {
             Command::Cleanup(_) => {
                 // TODO: add cleanup logic for removing obsolete metadata files
             },
+            Command::Audit(opt) => {
+                let report = AuditCoordinator::new(
+                    opt.storage.init_storage().await?,
+                    opt.metadata_cache_opt,
+                    opt.trusted_waypoints_opt,
+                    opt.concurrent_downloads.get(),
+                )
+                .run()
+                .await?;
+                let json = serde_json::to_string_pretty(&report)?;
+                match opt.output {
+                    Some(path) => std::fs::write(path, json)?,
+                    None => println!("{}", json),
+                }
+                ensure!(
+                    report.is_healthy(),
+                    "Backup audit found {} issue(s).",
+                    report.issues.len()
+                );
+            },
         }
         Ok(())
     }
@@ -0,0 +1,574 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, ensure, Result};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::AptosDB;
+use aptos_logger::info;
+use aptos_storage_interface::{DbReader, MAX_REQUEST_LIMIT};
+use aptos_types::{
+    access_path::Path,
+    contract_event::ContractEvent,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+    transaction::{
+        EntryFunction, MultisigTransactionPayload, Transaction, TransactionInfo,
+        TransactionPayload, Version,
+    },
+    write_set::WriteOp,
+};
+use clap::{Parser, ValueEnum};
+use itertools::izip;
+use parquet::{
+    basic::{Compression, ZstdLevel},
+    data_type::{ByteArray, ByteArrayType, Int64Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path as FsPath, PathBuf},
+    sync::Arc,
+};
+
+const PROGRESS_FILE: &str = "export_progress.json";
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    Parquet,
+    Csv,
+    Jsonl,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Parquet => "parquet",
+            Format::Csv => "csv",
+            Format::Jsonl => "jsonl",
+        }
+    }
+}
+
+#[derive(Parser)]
+#[clap(
+    about = "Export a range of the ledger history to Parquet, CSV or JSON Lines files for analytics. \
+    One file per table (transactions, events and write set changes) is written per chunk of \
+    versions, and an interrupted export resumes from the last fully exported chunk."
+)]
+pub struct Command {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(long, value_parser)]
+    output_dir: PathBuf,
+
+    #[clap(long, default_value_t = 0)]
+    start_version: Version,
+
+    #[clap(
+        long,
+        help = "Last version to export, inclusive. Defaults to the latest synced version in the DB."
+    )]
+    end_version: Option<Version>,
+
+    #[clap(long, value_enum, default_value_t = Format::Parquet)]
+    format: Format,
+
+    #[clap(long, default_value_t = 100_000)]
+    versions_per_file: u64,
+}
+
+impl Command {
+    pub fn run(self) -> Result<()> {
+        ensure!(
+            self.versions_per_file > 0,
+            "--versions-per-file must be positive."
+        );
+
+        // Opened read only, so it's fine to export from the DB of a running node.
+        let db = AptosDB::open(
+            StorageDirPaths::from_path(&self.db_dir),
+            true,                        /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            RocksdbConfigs::default(),
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+        )?;
+        let synced_version = db.ensure_synced_version()?;
+        let end_version = self.end_version.unwrap_or(synced_version);
+        ensure!(
+            end_version <= synced_version,
+            "End version {} is beyond the latest synced version {}.",
+            end_version,
+            synced_version,
+        );
+
+        std::fs::create_dir_all(&self.output_dir)?;
+        let mut next_version = self.start_version;
+        if let Some(progress) = ExportProgress::load(&self.output_dir)? {
+            ensure!(
+                progress.format == self.format,
+                "Output directory has a previous export in {:?} format, requested {:?}.",
+                progress.format,
+                self.format,
+            );
+            if progress.next_version > next_version {
+                info!(
+                    next_version = progress.next_version,
+                    "Resuming previous export."
+                );
+                next_version = progress.next_version;
+            }
+        }
+
+        while next_version <= end_version {
+            let last_version = end_version.min(next_version + self.versions_per_file - 1);
+            let tables = read_tables(&db, next_version, last_version)?;
+            for table in &tables {
+                table.write(&self.output_dir, next_version, last_version, self.format)?;
+            }
+            info!(
+                first_version = next_version,
+                last_version = last_version,
+                "Exported chunk."
+            );
+
+            next_version = last_version + 1;
+            ExportProgress {
+                next_version,
+                format: self.format,
+            }
+            .save(&self.output_dir)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Persisted after each chunk is fully written, so that an interrupted export can resume.
+#[derive(Deserialize, Serialize)]
+struct ExportProgress {
+    next_version: Version,
+    format: Format,
+}
+
+impl ExportProgress {
+    fn load(output_dir: &FsPath) -> Result<Option<Self>> {
+        let path = output_dir.join(PROGRESS_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&std::fs::read(path)?)?))
+    }
+
+    fn save(&self, output_dir: &FsPath) -> Result<()> {
+        let tmp_path = output_dir.join(format!("{}.tmp", PROGRESS_FILE));
+        std::fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        std::fs::rename(tmp_path, output_dir.join(PROGRESS_FILE))?;
+        Ok(())
+    }
+}
+
+fn read_tables(db: &AptosDB, first_version: Version, last_version: Version) -> Result<[Table; 3]> {
+    let mut transactions = Table::new("transactions", TRANSACTION_COLUMNS);
+    let mut events = Table::new("events", EVENT_COLUMNS);
+    let mut write_set_changes = Table::new("write_set_changes", WRITE_SET_CHANGE_COLUMNS);
+
+    let mut version = first_version;
+    while version <= last_version {
+        let limit = MAX_REQUEST_LIMIT.min(last_version - version + 1);
+        let end = version + limit;
+        for item in izip!(
+            db.get_transaction_iterator(version, limit)?,
+            db.get_transaction_info_iterator(version, limit)?,
+            db.get_events_iterator(version, limit)?,
+            db.get_write_set_iterator(version, limit)?,
+        ) {
+            let (txn, txn_info, txn_events, write_set) = (item.0?, item.1?, item.2?, item.3?);
+            transactions.push(transaction_row(
+                version,
+                &txn,
+                &txn_info,
+                txn_events.len(),
+                write_set.iter().count(),
+            ));
+            for (index, event) in txn_events.iter().enumerate() {
+                events.push(event_row(version, index, event)?);
+            }
+            for (index, (state_key, write_op)) in write_set.iter().enumerate() {
+                write_set_changes.push(write_set_change_row(version, index, state_key, write_op));
+            }
+            version += 1;
+        }
+        ensure!(
+            version == end,
+            "DB returned less data than expected, got up to version {}, expecting {}.",
+            version,
+            end,
+        );
+    }
+
+    Ok([transactions, events, write_set_changes])
+}
+
+const TRANSACTION_COLUMNS: &[Column] = &[
+    Column::u64("version"),
+    Column::str("hash"),
+    Column::str("type"),
+    Column::opt_str("sender"),
+    Column::opt_u64("sequence_number"),
+    Column::opt_str("entry_function"),
+    Column::str("status"),
+    Column::u64("gas_used"),
+    Column::u64("num_events"),
+    Column::u64("num_write_set_changes"),
+];
+
+fn transaction_row(
+    version: Version,
+    txn: &Transaction,
+    txn_info: &TransactionInfo,
+    num_events: usize,
+    num_write_set_changes: usize,
+) -> Vec<Value> {
+    let user_txn = txn.try_as_signed_user_txn();
+    let entry_function = user_txn.and_then(|t| match t.payload() {
+        TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+        TransactionPayload::Multisig(multisig) => multisig
+            .transaction_payload
+            .as_ref()
+            .map(|MultisigTransactionPayload::EntryFunction(entry_function)| entry_function),
+        TransactionPayload::Script(_) | TransactionPayload::ModuleBundle(_) => None,
+    });
+
+    vec![
+        Value::U64(Some(version)),
+        Value::Str(Some(txn_info.transaction_hash().to_hex_literal())),
+        Value::Str(Some(txn.type_name().to_string())),
+        Value::Str(user_txn.map(|t| t.sender().to_hex_literal())),
+        Value::U64(user_txn.map(|t| t.sequence_number())),
+        Value::Str(entry_function.map(entry_function_name)),
+        Value::Str(Some(format!("{:?}", txn_info.status()))),
+        Value::U64(Some(txn_info.gas_used())),
+        Value::U64(Some(num_events as u64)),
+        Value::U64(Some(num_write_set_changes as u64)),
+    ]
+}
+
+fn entry_function_name(entry_function: &EntryFunction) -> String {
+    format!("{}::{}", entry_function.module(), entry_function.function())
+}
+
+const EVENT_COLUMNS: &[Column] = &[
+    Column::u64("version"),
+    Column::u64("event_index"),
+    Column::str("type_tag"),
+    Column::opt_str("event_key"),
+    Column::opt_u64("sequence_number"),
+    Column::str("data"),
+];
+
+fn event_row(version: Version, index: usize, event: &ContractEvent) -> Result<Vec<Value>> {
+    let v1 = if event.is_v1() {
+        Some(event.v1()?)
+    } else {
+        None
+    };
+
+    Ok(vec![
+        Value::U64(Some(version)),
+        Value::U64(Some(index as u64)),
+        Value::Str(Some(event.type_tag().to_canonical_string())),
+        Value::Str(v1.map(|e| e.key().to_string())),
+        Value::U64(v1.map(|e| e.sequence_number())),
+        Value::Str(Some(hex::encode(event.event_data()))),
+    ])
+}
+
+const WRITE_SET_CHANGE_COLUMNS: &[Column] = &[
+    Column::u64("version"),
+    Column::u64("change_index"),
+    Column::str("state_key_type"),
+    Column::opt_str("address"),
+    Column::opt_str("path"),
+    Column::opt_str("table_handle"),
+    Column::opt_str("table_key"),
+    Column::str("op"),
+    Column::opt_u64("value_size"),
+    Column::opt_str("value"),
+];
+
+fn write_set_change_row(
+    version: Version,
+    index: usize,
+    state_key: &StateKey,
+    write_op: &WriteOp,
+) -> Vec<Value> {
+    let (state_key_type, address, path, table_handle, table_key) = match state_key.inner() {
+        StateKeyInner::AccessPath(access_path) => {
+            let (state_key_type, path) = match access_path.get_path() {
+                Path::Code(module_id) => ("code", module_id.to_string()),
+                Path::Resource(struct_tag) => ("resource", struct_tag.to_canonical_string()),
+                Path::ResourceGroup(struct_tag) => {
+                    ("resource_group", struct_tag.to_canonical_string())
+                },
+            };
+            (
+                state_key_type,
+                Some(access_path.address.to_hex_literal()),
+                Some(path),
+                None,
+                None,
+            )
+        },
+        StateKeyInner::TableItem { handle, key } => (
+            "table_item",
+            None,
+            None,
+            Some(handle.0.to_hex_literal()),
+            Some(hex::encode(key)),
+        ),
+        StateKeyInner::Raw(bytes) => ("raw", None, Some(hex::encode(bytes)), None, None),
+    };
+    let (op, value) = match write_op {
+        WriteOp::Creation(state_value) => ("creation", Some(state_value.bytes())),
+        WriteOp::Modification(state_value) => ("modification", Some(state_value.bytes())),
+        WriteOp::Deletion(_) => ("deletion", None),
+    };
+
+    vec![
+        Value::U64(Some(version)),
+        Value::U64(Some(index as u64)),
+        Value::Str(Some(state_key_type.to_string())),
+        Value::Str(address),
+        Value::Str(path),
+        Value::Str(table_handle),
+        Value::Str(table_key),
+        Value::Str(Some(op.to_string())),
+        Value::U64(value.map(|bytes| bytes.len() as u64)),
+        Value::Str(value.map(hex::encode)),
+    ]
+}
+
+#[derive(Clone, Copy)]
+enum ColumnType {
+    U64,
+    Str,
+}
+
+struct Column {
+    name: &'static str,
+    column_type: ColumnType,
+    nullable: bool,
+}
+
+impl Column {
+    const fn u64(name: &'static str) -> Self {
+        Self::new(name, ColumnType::U64, false)
+    }
+
+    const fn opt_u64(name: &'static str) -> Self {
+        Self::new(name, ColumnType::U64, true)
+    }
+
+    const fn str(name: &'static str) -> Self {
+        Self::new(name, ColumnType::Str, false)
+    }
+
+    const fn opt_str(name: &'static str) -> Self {
+        Self::new(name, ColumnType::Str, true)
+    }
+
+    const fn new(name: &'static str, column_type: ColumnType, nullable: bool) -> Self {
+        Self {
+            name,
+            column_type,
+            nullable,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    U64(Option<u64>),
+    Str(Option<String>),
+}
+
+impl Value {
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::U64(v) => v.map_or(serde_json::Value::Null, Into::into),
+            Value::Str(v) => v.clone().map_or(serde_json::Value::Null, Into::into),
+        }
+    }
+
+    fn to_csv_field(&self) -> String {
+        match self {
+            Value::U64(v) => v.map(|v| v.to_string()).unwrap_or_default(),
+            Value::Str(v) => v.clone().unwrap_or_default(),
+        }
+    }
+}
+
+struct Table {
+    name: &'static str,
+    columns: &'static [Column],
+    rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    fn new(name: &'static str, columns: &'static [Column]) -> Self {
+        Self {
+            name,
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, row: Vec<Value>) {
+        assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    /// Writes to a temporary file first, so that a file with the final name is always complete.
+    fn write(
+        &self,
+        output_dir: &FsPath,
+        first_version: Version,
+        last_version: Version,
+        format: Format,
+    ) -> Result<()> {
+        let name = format!(
+            "{}_{}-{}.{}",
+            self.name,
+            first_version,
+            last_version,
+            format.extension()
+        );
+        let tmp_path = output_dir.join(format!("{}.tmp", name));
+        let file = File::create(&tmp_path)?;
+        match format {
+            Format::Parquet => self.write_parquet(file)?,
+            Format::Csv => self.write_csv(file)?,
+            Format::Jsonl => self.write_jsonl(file)?,
+        }
+        std::fs::rename(tmp_path, output_dir.join(name))?;
+        Ok(())
+    }
+
+    fn write_csv(&self, file: File) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(file);
+        writer.write_record(self.columns.iter().map(|c| c.name))?;
+        for row in &self.rows {
+            writer.write_record(row.iter().map(Value::to_csv_field))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_jsonl(&self, file: File) -> Result<()> {
+        let mut writer = BufWriter::new(file);
+        for row in &self.rows {
+            let object: serde_json::Map<_, _> = self
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| (column.name.to_string(), value.to_json()))
+                .collect();
+            serde_json::to_writer(&mut writer, &object)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_parquet(&self, file: File) -> Result<()> {
+        let schema = Arc::new(parse_message_type(&self.parquet_schema())?);
+        let props = Arc::new(
+            WriterProperties::builder()
+                .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                .build(),
+        );
+        let mut writer = SerializedFileWriter::new(file, schema, props)?;
+        let mut row_group = writer.next_row_group()?;
+        for (idx, column) in self.columns.iter().enumerate() {
+            let mut column_writer = row_group
+                .next_column()?
+                .ok_or_else(|| anyhow!("Missing column writer for {}.", column.name))?;
+            // A definition level of 0 marks a null in an optional column.
+            let def_levels: Vec<i16> = self
+                .rows
+                .iter()
+                .map(|row| match &row[idx] {
+                    Value::U64(v) => v.is_some() as i16,
+                    Value::Str(v) => v.is_some() as i16,
+                })
+                .collect();
+            match column.column_type {
+                ColumnType::U64 => {
+                    let values: Vec<i64> = self
+                        .rows
+                        .iter()
+                        .filter_map(|row| match &row[idx] {
+                            Value::U64(v) => v.map(|v| v as i64),
+                            Value::Str(_) => unreachable!("Column type mismatch."),
+                        })
+                        .collect();
+                    column_writer.typed::<Int64Type>().write_batch(
+                        &values,
+                        Some(&def_levels),
+                        None,
+                    )?;
+                },
+                ColumnType::Str => {
+                    let values: Vec<ByteArray> = self
+                        .rows
+                        .iter()
+                        .filter_map(|row| match &row[idx] {
+                            Value::Str(v) => v.as_deref().map(ByteArray::from),
+                            Value::U64(_) => unreachable!("Column type mismatch."),
+                        })
+                        .collect();
+                    column_writer.typed::<ByteArrayType>().write_batch(
+                        &values,
+                        Some(&def_levels),
+                        None,
+                    )?;
+                },
+            }
+            column_writer.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+
+    fn parquet_schema(&self) -> String {
+        let fields: String = self
+            .columns
+            .iter()
+            .map(|column| {
+                let repetition = if column.nullable {
+                    "OPTIONAL"
+                } else {
+                    "REQUIRED"
+                };
+                match column.column_type {
+                    ColumnType::U64 => {
+                        format!("{} INT64 {} (INTEGER(64,false));", repetition, column.name)
+                    },
+                    ColumnType::Str => format!("{} BYTE_ARRAY {} (UTF8);", repetition, column.name),
+                }
+            })
+            .collect();
+        format!("message {} {{ {} }}", self.name, fields)
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Context, Result};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::{bls12381, ValidCryptoMaterialStringExt};
+use aptos_db::{
+    db_debugger::{truncate::truncate, ShardingConfig},
+    AptosDB,
+};
+use aptos_executor::db_bootstrapper::calculate_genesis;
+use aptos_storage_interface::{
+    state_store::state_view::db_state_view::DbStateViewAtVersion, DbReaderWriter,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NEW_EPOCH_EVENT_V2_MOVE_TYPE_TAG,
+    chain_id::ChainId,
+    contract_event::ContractEvent,
+    network_address::NetworkAddress,
+    on_chain_config::{ConfigurationResource, OnChainConfig, ValidatorSet},
+    state_store::{state_key::StateKey, MoveResourceExt},
+    timestamp::TimestampResource,
+    transaction::{ChangeSet, Transaction, Version, WriteSetPayload},
+    validator_config::ValidatorConfig,
+    validator_info::ValidatorInfo,
+    validator_performances::{ValidatorPerformance, ValidatorPerformances},
+    waypoint::Waypoint,
+    write_set::{WriteOp, WriteSetMut},
+};
+use aptos_vm::aptos_vm::AptosVMBlockExecutor;
+use clap::Parser;
+use serde::Serialize;
+use std::{fs, path::PathBuf};
+
+/// Creates a writable copy of a DB, truncated to the given version, whose validator set is
+/// replaced by a single validator and whose chain id is changed. A local node configured with
+/// the new validator's keys and the printed waypoint can keep producing blocks on top of it.
+#[derive(Parser)]
+#[clap(about = "Fork a local DB at a version, with a new validator set and chain id.")]
+pub struct Command {
+    /// DB to fork. It's only read from, the fork is made of hardlinks to its files.
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    /// Where the forked DB is created, must not exist.
+    #[clap(long, value_parser)]
+    output_dir: PathBuf,
+
+    /// Version to fork at. Falls back to the latest version before it that can be truncated to.
+    /// Defaults to the latest version in the DB.
+    #[clap(long)]
+    target_version: Option<Version>,
+
+    /// Chain id of the fork, should not collide with any live network.
+    #[clap(long)]
+    chain_id: ChainId,
+
+    /// Stake pool that becomes the only validator. Its stake keeps the validator set non-empty
+    /// across epochs. Defaults to the active validator with the most voting power.
+    #[clap(long)]
+    validator_address: Option<AccountAddress>,
+
+    /// Consensus public key of the local validator.
+    #[clap(long, value_parser = bls12381::PublicKey::from_encoded_string)]
+    consensus_public_key: bls12381::PublicKey,
+
+    /// Validator network address of the local validator, including its noise public key.
+    #[clap(long)]
+    validator_network_address: NetworkAddress,
+
+    /// Full node network address of the local validator.
+    #[clap(long)]
+    fullnode_network_address: Option<NetworkAddress>,
+
+    #[clap(flatten)]
+    sharding_config: ShardingConfig,
+}
+
+impl Command {
+    pub fn run(self) -> Result<()> {
+        self.fork().map(|_waypoint| ())
+    }
+
+    /// Creates the fork, returning the waypoint of its new epoch.
+    pub(crate) fn fork(self) -> Result<Waypoint> {
+        ensure!(!self.output_dir.exists(), "Output dir already exists.");
+        let enable_storage_sharding = self.sharding_config.enable_storage_sharding;
+
+        println!("Creating checkpoint at: {:?}", &self.output_dir);
+        fs::create_dir_all(&self.output_dir)?;
+        AptosDB::create_checkpoint(&self.db_dir, &self.output_dir, enable_storage_sharding)?;
+
+        if let Some(target_version) = self.target_version {
+            let version = truncate(&self.output_dir, target_version, enable_storage_sharding)?;
+            println!("Truncated to version {}.", version);
+        }
+
+        let db = AptosDB::open(
+            StorageDirPaths::from_path(&self.output_dir),
+            false,                       /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            RocksdbConfigs {
+                enable_storage_sharding,
+                ..Default::default()
+            },
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+        )?;
+        let db = DbReaderWriter::new(db);
+        let ledger_summary = db
+            .reader
+            .get_pre_committed_ledger_summary()
+            .with_context(|| format_err!("Failed to get latest tree state."))?;
+        let version = ledger_summary
+            .version()
+            .ok_or_else(|| format_err!("Can't fork an empty DB."))?;
+
+        let fork_txn = self.fork_txn(&db, version)?;
+        let committer = calculate_genesis::<AptosVMBlockExecutor>(&db, ledger_summary, &fork_txn)
+            .with_context(|| format_err!("Failed to calculate fork genesis."))?;
+        let waypoint = committer.waypoint();
+        committer
+            .commit()
+            .with_context(|| format_err!("Committing fork genesis to DB."))?;
+
+        println!("Forked at version {}. Waypoint: {}", version, waypoint);
+        Ok(waypoint)
+    }
+
+    /// Builds the write set transaction that installs the new validator set and chain id on top
+    /// of the state at `version`, and starts a new epoch.
+    fn fork_txn(&self, db: &DbReaderWriter, version: Version) -> Result<Transaction> {
+        let state_view = db.reader.state_view_at_version(Some(version))?;
+        let configuration = ConfigurationResource::fetch_config(&state_view)
+            .ok_or_else(|| format_err!("ConfigurationResource missing."))?;
+        let validator_set = ValidatorSet::fetch_config(&state_view)
+            .ok_or_else(|| format_err!("ValidatorSet missing."))?;
+        let timestamp = TimestampResource::fetch_move_resource(&state_view, &AccountAddress::ONE)?
+            .ok_or_else(|| format_err!("TimestampResource missing."))?;
+
+        let validator = match self.validator_address {
+            Some(address) => validator_set
+                .payload()
+                .find(|v| v.account_address() == &address)
+                .ok_or_else(|| format_err!("{} is not in the validator set.", address))?,
+            None => validator_set
+                .active_validators
+                .iter()
+                .max_by_key(|v| v.consensus_voting_power())
+                .ok_or_else(|| format_err!("Validator set is empty."))?,
+        };
+        let address = *validator.account_address();
+        let voting_power = validator.consensus_voting_power();
+        println!(
+            "Taking over validator {} with voting power {}.",
+            address, voting_power
+        );
+
+        let fullnode_network_addresses = self
+            .fullnode_network_address
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let validator_config = ValidatorConfig::new(
+            self.consensus_public_key.clone(),
+            bcs::to_bytes(&vec![self.validator_network_address.clone()])?,
+            bcs::to_bytes(&fullnode_network_addresses)?,
+            0, /* validator_index */
+        );
+        let mut new_validator_set = ValidatorSet::new(vec![ValidatorInfo::new(
+            address,
+            voting_power,
+            validator_config.clone(),
+        )]);
+        new_validator_set.total_voting_power = voting_power as u128;
+        let next_configuration = configuration.bump_epoch(timestamp.timestamp.microseconds);
+
+        let write_set = WriteSetMut::new(vec![
+            (
+                StateKey::on_chain_config::<ChainId>()?,
+                modification(&self.chain_id)?,
+            ),
+            (
+                StateKey::on_chain_config::<ValidatorSet>()?,
+                modification(&new_validator_set)?,
+            ),
+            (
+                StateKey::resource_typed::<ValidatorConfig>(&address)?,
+                modification(&validator_config)?,
+            ),
+            (
+                StateKey::resource_typed::<ValidatorPerformances>(&AccountAddress::ONE)?,
+                modification(&ValidatorPerformances {
+                    validators: vec![ValidatorPerformance {
+                        successful_proposals: 0,
+                        failed_proposals: 0,
+                    }],
+                })?,
+            ),
+            (
+                StateKey::on_chain_config::<ConfigurationResource>()?,
+                modification(&next_configuration)?,
+            ),
+        ])
+        .freeze()?;
+        let events = vec![ContractEvent::new_v2(
+            NEW_EPOCH_EVENT_V2_MOVE_TYPE_TAG.clone(),
+            bcs::to_bytes(&next_configuration.epoch())?,
+        )];
+
+        Ok(Transaction::GenesisTransaction(WriteSetPayload::Direct(
+            ChangeSet::new(write_set, events),
+        )))
+    }
+}
+
+fn modification<T: Serialize>(value: &T) -> Result<WriteOp> {
+    Ok(WriteOp::legacy_modification(bcs::to_bytes(value)?.into()))
+}
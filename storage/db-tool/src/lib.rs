@@ -6,6 +6,8 @@ extern crate core;
 mod backup;
 mod backup_maintenance;
 mod bootstrap;
+mod export;
+mod fork;
 mod gen_replay_verify_jobs;
 mod replay_on_archive;
 mod replay_verify;
@@ -33,6 +35,10 @@ pub enum DBTool {
     #[clap(subcommand)]
     Debug(db_debugger::Cmd),
 
+    Export(export::Command),
+
+    Fork(fork::Command),
+
     ReplayVerify(replay_verify::Opt),
 
     GenReplayVerifyJobs(gen_replay_verify_jobs::Opt),
@@ -50,6 +56,8 @@ impl DBTool {
             DBTool::BackupMaintenance(cmd) => cmd.run().await,
             DBTool::Bootstrap(cmd) => cmd.run(),
             DBTool::Debug(cmd) => Ok(cmd.run()?),
+            DBTool::Export(cmd) => cmd.run(),
+            DBTool::Fork(cmd) => cmd.run(),
             DBTool::ReplayVerify(cmd) => {
                 let ret = cmd.run().await;
                 info!("Replay verify result: {:?}", ret);
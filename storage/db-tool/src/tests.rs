@@ -64,6 +64,94 @@ fn test_various_cmd_parsing() {
         "--start-version",
         "Max",
     ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "backup-maintenance",
+        "audit",
+        "--local-fs-dir",
+        ".",
+        "--output",
+        "report.json",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "export",
+        "--db-dir",
+        ".",
+        "--output-dir",
+        ".",
+        "--end-version",
+        "100",
+        "--format",
+        "csv",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "debug",
+        "state-diff",
+        "--db-dir",
+        ".",
+        "--from-version",
+        "10",
+        "--to-version",
+        "20",
+        "--account",
+        "0x1",
+        "--resource-type",
+        "0x1::coin::CoinStore",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "fork",
+        "--db-dir",
+        ".",
+        "--output-dir",
+        "./fork",
+        "--target-version",
+        "100",
+        "--chain-id",
+        "testing",
+        "--consensus-public-key",
+        "0xaa7023a1ea88520c0b163c530367dfbfe9269621c96dc9dc9147aca9c278b1340c07b21600b2b5423a2fb88c679b0c5a",
+        "--validator-network-address",
+        "/ip4/127.0.0.1/tcp/6180/noise-ik/0x080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/handshake/0",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "debug",
+        "pruner",
+        "status",
+        "--db-dir",
+        ".",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "debug",
+        "pruner",
+        "prune",
+        "--db-dir",
+        ".",
+        "--pruner",
+        "state_kv",
+        "--target-version",
+        "100",
+        "--batch-size",
+        "500",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "debug",
+        "pruner",
+        "prune",
+        "--db-dir",
+        ".",
+        "--pruner",
+        "ledger",
+        "--target-version",
+        "100",
+        "--internal-indexer-db-dir",
+        "./internal_indexer_db",
+    ]);
 }
 
 fn run_cmd(args: &[&str]) {
@@ -278,6 +366,407 @@ mod dbtool_tests {
         rt.shutdown_timeout(Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_backup_audit() {
+        let db = test_execution_with_storage_impl();
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        let (rt, port) = start_local_backup_service(db);
+        let server_addr = format!(" http://localhost:{}", port);
+        let backup_dir_str = backup_dir.path().to_str().unwrap();
+
+        let backup = |args: &[&str]| {
+            let mut cmd = vec![
+                "aptos-db-tool",
+                "backup",
+                "oneoff",
+                "--backup-service-address",
+                server_addr.as_str(),
+            ];
+            cmd.extend_from_slice(args);
+            cmd.extend_from_slice(&["--local-fs-dir", backup_dir_str]);
+            rt.block_on(DBTool::try_parse_from(cmd).unwrap().run())
+                .unwrap();
+        };
+        backup(&["epoch-ending", "--start-epoch", "0", "--end-epoch", "2"]);
+        backup(&["state-snapshot", "--state-snapshot-epoch", "1"]);
+        backup(&[
+            "transaction",
+            "--start-version",
+            "0",
+            "--num_transactions",
+            "15",
+        ]);
+        // Leave versions [15, 19] out.
+        backup(&[
+            "transaction",
+            "--start-version",
+            "20",
+            "--num_transactions",
+            "10",
+        ]);
+
+        let report_path = TempPath::new();
+        let audit = || {
+            let metadata_cache_dir = TempPath::new();
+            let res = rt.block_on(
+                DBTool::try_parse_from([
+                    "aptos-db-tool",
+                    "backup-maintenance",
+                    "audit",
+                    "--metadata-cache-dir",
+                    metadata_cache_dir.path().to_str().unwrap(),
+                    "--local-fs-dir",
+                    backup_dir_str,
+                    "--output",
+                    report_path.path().to_str().unwrap(),
+                ])
+                .unwrap()
+                .run(),
+            );
+            assert!(res.is_err(), "Issues are expected to fail the audit.");
+            serde_json::from_slice::<serde_json::Value>(&fs::read(report_path.path()).unwrap())
+                .unwrap()
+        };
+
+        let report = audit();
+        assert_eq!(report["transactions"]["covered_from_genesis_until"], 14);
+        assert_eq!(report["transactions"]["latest"], 29);
+        assert_eq!(
+            report["issues"],
+            serde_json::json!([{
+                "issue": "gap",
+                "backup_type": "transaction",
+                "first": 15,
+                "last": 19,
+            }])
+        );
+
+        // Remove a transaction chunk.
+        let backup_handle = fs::read_dir(backup_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .starts_with("transaction_20-")
+            })
+            .unwrap();
+        fs::remove_file(backup_handle.join("20-.chunk")).unwrap();
+
+        let report = audit();
+        let issues = report["issues"].as_array().unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[1]["issue"], "missing_file");
+        assert_eq!(issues[1]["backup_type"], "transaction");
+        assert!(issues[1]["file"].as_str().unwrap().ends_with("/20-.chunk"));
+        rt.shutdown_timeout(Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_export() {
+        let db_dir = TempPath::new();
+        let db = test_execution_with_storage_impl_inner(false, db_dir.path());
+        let synced_version = db.expect_synced_version();
+        drop(db);
+        let output_dir = TempPath::new();
+
+        let export = |format: &str, end_version: Option<&str>| {
+            let mut cmd = vec![
+                "aptos-db-tool",
+                "export",
+                "--db-dir",
+                db_dir.path().to_str().unwrap(),
+                "--output-dir",
+                output_dir.path().to_str().unwrap(),
+                "--versions-per-file",
+                "10",
+                "--format",
+                format,
+            ];
+            if let Some(end_version) = end_version {
+                cmd.extend_from_slice(&["--end-version", end_version]);
+            }
+            DBTool::try_parse_from(cmd).unwrap().run()
+        };
+        let rt = Runtime::new().unwrap();
+
+        rt.block_on(export("csv", Some("19"))).unwrap();
+        let mut reader =
+            csv::Reader::from_path(output_dir.path().join("transactions_10-19.csv")).unwrap();
+        let versions: Vec<String> = reader
+            .records()
+            .map(|record| record.unwrap()[0].to_string())
+            .collect();
+        assert_eq!(
+            versions,
+            (10..20).map(|v| v.to_string()).collect::<Vec<_>>()
+        );
+        assert!(output_dir.path().join("events_0-9.csv").exists());
+        assert!(output_dir.path().join("write_set_changes_0-9.csv").exists());
+
+        // Can't resume in a different format.
+        assert!(rt.block_on(export("jsonl", None)).is_err());
+
+        // Resumes from version 20 and exports up to the latest version.
+        rt.block_on(export("csv", None)).unwrap();
+        let last_file_start = synced_version / 10 * 10;
+        assert!(output_dir
+            .path()
+            .join(format!(
+                "transactions_{}-{}.csv",
+                last_file_start, synced_version
+            ))
+            .exists());
+        let progress: serde_json::Value = serde_json::from_slice(
+            &fs::read(output_dir.path().join("export_progress.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(progress["next_version"], synced_version + 1);
+
+        let parquet_dir = TempPath::new();
+        rt.block_on(
+            DBTool::try_parse_from([
+                "aptos-db-tool",
+                "export",
+                "--db-dir",
+                db_dir.path().to_str().unwrap(),
+                "--output-dir",
+                parquet_dir.path().to_str().unwrap(),
+            ])
+            .unwrap()
+            .run(),
+        )
+        .unwrap();
+        assert!(parquet_dir
+            .path()
+            .join(format!("transactions_0-{}.parquet", synced_version))
+            .exists());
+    }
+
+    #[test]
+    fn test_fork() {
+        use crate::fork;
+        use aptos_crypto::{bls12381, PrivateKey, Uniform, ValidCryptoMaterialStringExt};
+        use aptos_executor::block_executor::BlockExecutor;
+        use aptos_executor_test_helpers::{gen_block_id, gen_ledger_info_with_sigs};
+        use aptos_executor_types::BlockExecutorTrait;
+        use aptos_storage_interface::{
+            state_store::state_view::db_state_view::DbStateViewAtVersion, DbReaderWriter,
+        };
+        use aptos_types::{
+            account_address::AccountAddress,
+            block_metadata::BlockMetadata,
+            chain_id::ChainId,
+            on_chain_config::{ConfigurationResource, OnChainConfig, ValidatorSet},
+            state_store::MoveResourceExt,
+            test_helpers::transaction_test_helpers::{block, TEST_BLOCK_EXECUTOR_ONCHAIN_CONFIG},
+            timestamp::TimestampResource,
+            transaction::Transaction,
+            validator_signer::ValidatorSigner,
+        };
+        use aptos_vm::aptos_vm::AptosVMBlockExecutor;
+
+        let db_dir = TempPath::new();
+        let db = test_execution_with_storage_impl_inner(false, db_dir.path());
+        let synced_version = db.expect_synced_version();
+        let reader: Arc<dyn DbReader> = db.clone();
+        let old_configuration = ConfigurationResource::fetch_config(
+            &reader.state_view_at_version(Some(synced_version)).unwrap(),
+        )
+        .unwrap();
+        drop(reader);
+        drop(db);
+
+        let consensus_key = bls12381::PrivateKey::generate_for_testing();
+        let output_dir = TempPath::new();
+        let waypoint = fork::Command::try_parse_from([
+            "fork",
+            "--db-dir",
+            db_dir.path().to_str().unwrap(),
+            "--output-dir",
+            output_dir.path().to_str().unwrap(),
+            "--chain-id",
+            "100",
+            "--consensus-public-key",
+            &consensus_key.public_key().to_encoded_string().unwrap(),
+            "--validator-network-address",
+            "/ip4/127.0.0.1/tcp/6180/noise-ik/0x080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/handshake/0",
+        ])
+        .unwrap()
+        .fork()
+        .unwrap();
+        assert_eq!(waypoint.version(), synced_version + 1);
+
+        // The reopened fork starts a new epoch, verifiable from the waypoint.
+        let db = DbReaderWriter::new(AptosDB::new_for_test(output_dir.path()));
+        let ledger_info = db.reader.get_latest_ledger_info().unwrap();
+        assert_eq!(ledger_info.ledger_info().version(), waypoint.version());
+        waypoint.verify(ledger_info.ledger_info()).unwrap();
+        let epoch_state = ledger_info
+            .ledger_info()
+            .next_epoch_state()
+            .unwrap()
+            .clone();
+        assert_eq!(epoch_state.epoch, old_configuration.epoch() + 1);
+
+        let state_view = db
+            .reader
+            .state_view_at_version(Some(waypoint.version()))
+            .unwrap();
+        assert_eq!(ChainId::fetch_config(&state_view), Some(ChainId::new(100)));
+        assert_eq!(
+            ConfigurationResource::fetch_config(&state_view)
+                .unwrap()
+                .epoch(),
+            epoch_state.epoch
+        );
+        let validator_set = ValidatorSet::fetch_config(&state_view).unwrap();
+        assert_eq!(validator_set.active_validators.len(), 1);
+        let validator = &validator_set.active_validators[0];
+        assert_eq!(
+            validator.consensus_public_key(),
+            &consensus_key.public_key()
+        );
+        let timestamp = TimestampResource::fetch_move_resource(&state_view, &AccountAddress::ONE)
+            .unwrap()
+            .unwrap();
+
+        // The new validator can sign blocks on top of the fork.
+        let signer = ValidatorSigner::new(*validator.account_address(), Arc::new(consensus_key));
+        let executor = BlockExecutor::<AptosVMBlockExecutor>::new(db.clone());
+        let block_id = gen_block_id(1);
+        let block_meta = Transaction::BlockMetadata(BlockMetadata::new(
+            block_id,
+            epoch_state.epoch,
+            1,
+            signer.author(),
+            vec![0],
+            vec![],
+            timestamp.timestamp.microseconds + 1,
+        ));
+        let output = executor
+            .execute_block(
+                (block_id, block(vec![block_meta])).into(),
+                executor.committed_block_id(),
+                TEST_BLOCK_EXECUTOR_ONCHAIN_CONFIG,
+            )
+            .unwrap();
+        let ledger_info =
+            gen_ledger_info_with_sigs(epoch_state.epoch, &output, block_id, &[signer]);
+        ledger_info
+            .verify_signatures(&epoch_state.verifier)
+            .unwrap();
+        executor.commit_blocks(vec![block_id], ledger_info).unwrap();
+        assert_eq!(
+            db.reader.get_synced_version().unwrap(),
+            Some(waypoint.version() + 2)
+        );
+    }
+
+    #[test]
+    fn test_state_diff() {
+        use aptos_db::db_debugger::state_diff;
+        use aptos_types::{
+            account_address::AccountAddress, state_store::state_key::inner::StateKeyInner,
+        };
+        use std::collections::BTreeMap;
+
+        let db_dir = TempPath::new();
+        let db = test_execution_with_storage_impl_inner(false, db_dir.path());
+        let synced_version = db.expect_synced_version();
+
+        // Every key written after version 0 whose value changed, read through the DB.
+        let mut expected = BTreeMap::new();
+        for write_set in db.get_write_set_iterator(1, synced_version).unwrap() {
+            for (key, _op) in write_set.unwrap().iter() {
+                let before = db.get_state_value_by_version(key, 0).unwrap();
+                let after = db.get_state_value_by_version(key, synced_version).unwrap();
+                if before != after {
+                    expected.insert(key.clone(), (before, after));
+                }
+            }
+        }
+        assert!(!expected.is_empty());
+        drop(db);
+
+        let db_path = db_dir.path().to_str().unwrap();
+        let diff = |args: &[&str]| {
+            let mut cmd = vec!["state-diff", "--db-dir", db_path];
+            cmd.extend_from_slice(args);
+            state_diff::Cmd::try_parse_from(cmd).unwrap().diff()
+        };
+        let to_version = synced_version.to_string();
+
+        let rows = diff(&["--from-version", "0", "--to-version", &to_version]).unwrap();
+        assert_eq!(
+            rows.iter().map(|row| &row.key).collect::<Vec<_>>(),
+            expected.keys().collect::<Vec<_>>(),
+        );
+        for row in &rows {
+            let (before, after) = &expected[&row.key];
+            assert_eq!(&row.before, before);
+            assert_eq!(&row.after, after);
+        }
+
+        // Only keys of the framework account are kept.
+        let is_framework_key = |key: &StateKeyInner| {
+            matches!(
+                key,
+                StateKeyInner::AccessPath(access_path) if access_path.address == AccountAddress::ONE
+            )
+        };
+        let rows = diff(&[
+            "--from-version",
+            "0",
+            "--to-version",
+            &to_version,
+            "--account",
+            "0x1",
+        ])
+        .unwrap();
+        assert_eq!(
+            rows.len(),
+            expected
+                .keys()
+                .filter(|key| is_framework_key(key.inner()))
+                .count()
+        );
+        assert!(rows.iter().all(|row| is_framework_key(row.key.inner())));
+
+        // The printing command succeeds on the same range.
+        let state_diff = |args: &[&str]| {
+            let mut cmd = vec!["aptos-db-tool", "debug", "state-diff", "--db-dir", db_path];
+            cmd.extend_from_slice(args);
+            Runtime::new()
+                .unwrap()
+                .block_on(DBTool::try_parse_from(cmd).unwrap().run())
+        };
+        state_diff(&[
+            "--from-version",
+            "0",
+            "--to-version",
+            &to_version,
+            "--account",
+            "0x1",
+            "--resource-type",
+            "0x1::coin::CoinInfo<0x1::aptos_coin::AptosCoin>",
+        ])
+        .unwrap();
+        // Versions out of order.
+        assert!(diff(&["--from-version", &to_version, "--to-version", "0"]).is_err());
+        // Beyond the latest version.
+        assert!(diff(&[
+            "--from-version",
+            "0",
+            "--to-version",
+            &(synced_version + 1).to_string(),
+        ])
+        .is_err());
+    }
+
     #[cfg(test)]
     fn db_restore_test_setup(
         start: Version,
@@ -6,13 +6,17 @@ use crate::{
     utils::PrefixedStateValueIterator,
 };
 use aptos_config::config::internal_indexer_db_config::InternalIndexerDBConfig;
+use aptos_crypto::HashValue;
 use aptos_db_indexer_schemas::{
     metadata::{MetadataKey, MetadataValue, StateSnapshotProgress},
     schema::{
         event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
         event_sequence_number::EventSequenceNumberSchema,
+        fungible_asset_activity::FungibleAssetActivitySchema,
         indexer_metadata::InternalIndexerMetadataSchema, state_keys::StateKeysSchema,
+        state_value_history::StateValueHistorySchema,
         transaction_by_account::TransactionByAccountSchema,
+        transaction_by_participant::TransactionByParticipantSchema,
         translated_v1_event::TranslatedV1EventSchema,
     },
     utils::{
@@ -26,21 +30,34 @@ use aptos_storage_interface::{
     db_ensure as ensure, db_other_bail as bail, AptosDbError, DbReader, Result,
 };
 use aptos_types::{
-    account_address::AccountAddress,
-    account_config::{BURN_TYPE, MINT_TYPE},
+    access_path::Path,
+    account_address::{create_object_address, AccountAddress},
+    account_config::{
+        CoinStoreResourceUntyped, DepositEvent, DepositFAEvent, FungibleStoreResource,
+        ObjectCoreResource, ObjectGroupResource, WithdrawEvent, WithdrawFAEvent, BURN_TYPE,
+        DEPOSIT_EVENT_TYPE, MINT_TYPE, WITHDRAW_EVENT_TYPE,
+    },
     contract_event::{ContractEvent, ContractEventV1, ContractEventV2, EventWithVersion},
     event::EventKey,
-    indexer::indexer_db_reader::Order,
+    indexer::{
+        fungible_asset_activity::{FungibleAssetActivity, FungibleAssetActivityType},
+        indexer_db_reader::Order,
+    },
     state_store::{
-        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_key::{inner::StateKeyInner, prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
     },
     transaction::{AccountTransactionsWithProof, Transaction, Version},
-    write_set::{TransactionWrite, WriteSet},
+    utility_coin::{AptosCoinType, CoinType},
+    write_set::{TransactionWrite, WriteOp, WriteSet},
+};
+use move_core_types::{
+    language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS},
+    move_resource::MoveStructType,
 };
 use std::{
-    cmp::min,
-    collections::HashSet,
+    cmp::{max, min},
+    collections::{BTreeMap, BTreeSet, HashSet},
     sync::{
         mpsc::{self, Receiver, Sender},
         Arc,
@@ -126,6 +143,18 @@ impl InternalIndexerDB {
         self.get_version(&MetadataKey::EventV2TranslationVersion)
     }
 
+    pub fn get_participant_transaction_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::ParticipantTransactionVersion)
+    }
+
+    pub fn get_fungible_asset_activity_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::FungibleAssetActivityVersion)
+    }
+
+    pub fn get_state_value_history_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::StateValueHistoryVersion)
+    }
+
     pub fn event_enabled(&self) -> bool {
         self.config.enable_event
     }
@@ -142,6 +171,18 @@ impl InternalIndexerDB {
         self.config.enable_statekeys
     }
 
+    pub fn participant_transactions_enabled(&self) -> bool {
+        self.config.enable_participant_transactions
+    }
+
+    pub fn fungible_asset_activities_enabled(&self) -> bool {
+        self.config.enable_fungible_asset_activities
+    }
+
+    pub fn state_value_history_enabled(&self) -> bool {
+        self.config.enable_state_value_history
+    }
+
     pub fn get_inner_db_ref(&self) -> &Arc<DB> {
         &self.db
     }
@@ -189,6 +230,192 @@ impl InternalIndexerDB {
         ))
     }
 
+    /// Returns the versions of up to `limit` transactions that `address` participated in, in
+    /// ascending order and not newer than `ledger_version`. If `start_version` is not given, the
+    /// latest `limit` transactions are returned.
+    pub fn get_participant_transaction_versions(
+        &self,
+        address: AccountAddress,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let versions = match start_version {
+            Some(start_version) => {
+                let mut iter = self.db.iter::<TransactionByParticipantSchema>()?;
+                iter.seek(&(address, start_version))?;
+                Self::collect_participant_versions(iter, address, limit, ledger_version)?
+            },
+            None => {
+                let mut iter = self.db.rev_iter::<TransactionByParticipantSchema>()?;
+                iter.seek_for_prev(&(address, ledger_version))?;
+                let mut versions =
+                    Self::collect_participant_versions(iter, address, limit, ledger_version)?;
+                versions.reverse();
+                versions
+            },
+        };
+        Ok(versions)
+    }
+
+    fn collect_participant_versions(
+        iter: impl Iterator<Item = Result<((AccountAddress, Version), ())>>,
+        address: AccountAddress,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let mut versions = vec![];
+        for res in iter {
+            let ((participant, version), ()) = res?;
+            if participant != address || version > ledger_version {
+                break;
+            }
+            versions.push(version);
+            if versions.len() as u64 >= limit {
+                break;
+            }
+        }
+        Ok(versions)
+    }
+
+    /// Returns up to `limit` balance changes of the asset with metadata at `metadata_address` held
+    /// by `owner`, in ascending order and not newer than `ledger_version`. If `start_version` is
+    /// not given, the latest balance changes are returned. The balance changes of a transaction are
+    /// never split, so the last transaction of the result is complete even if that exceeds `limit`.
+    pub fn get_fungible_asset_activities(
+        &self,
+        owner: AccountAddress,
+        metadata_address: AccountAddress,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<FungibleAssetActivity>> {
+        let activities = match start_version {
+            Some(start_version) => {
+                let mut iter = self.db.iter::<FungibleAssetActivitySchema>()?;
+                iter.seek(&(owner, metadata_address, start_version, 0))?;
+                Self::collect_fungible_asset_activities(
+                    iter,
+                    owner,
+                    metadata_address,
+                    limit,
+                    ledger_version,
+                )?
+            },
+            None => {
+                let mut iter = self.db.rev_iter::<FungibleAssetActivitySchema>()?;
+                iter.seek_for_prev(&(owner, metadata_address, ledger_version, u64::MAX))?;
+                let mut activities = Self::collect_fungible_asset_activities(
+                    iter,
+                    owner,
+                    metadata_address,
+                    limit,
+                    ledger_version,
+                )?;
+                activities.reverse();
+                activities
+            },
+        };
+        Ok(activities)
+    }
+
+    fn collect_fungible_asset_activities(
+        iter: impl Iterator<
+            Item = Result<(
+                (AccountAddress, AccountAddress, Version, u64),
+                (FungibleAssetActivityType, AccountAddress, u64),
+            )>,
+        >,
+        owner: AccountAddress,
+        metadata_address: AccountAddress,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<FungibleAssetActivity>> {
+        let mut activities: Vec<FungibleAssetActivity> = vec![];
+        for res in iter {
+            let ((activity_owner, activity_metadata_address, version, event_index), value) = res?;
+            if activity_owner != owner
+                || activity_metadata_address != metadata_address
+                || version > ledger_version
+            {
+                break;
+            }
+            if activities.len() as u64 >= limit
+                && activities
+                    .last()
+                    .map_or(true, |last| last.version != version)
+            {
+                break;
+            }
+            let (activity_type, store, amount) = value;
+            activities.push(FungibleAssetActivity {
+                version,
+                event_index,
+                activity_type,
+                store,
+                amount,
+            });
+        }
+        Ok(activities)
+    }
+
+    /// Returns up to `limit` versions at which the state key with hash `state_key_hash` was
+    /// written, in ascending order and not newer than `ledger_version`. If `start_version` is not
+    /// given, the latest `limit` versions are returned.
+    pub fn get_state_value_history_versions(
+        &self,
+        state_key_hash: HashValue,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let versions = match start_version {
+            Some(start_version) => {
+                let mut iter = self.db.iter::<StateValueHistorySchema>()?;
+                iter.seek(&(state_key_hash, start_version))?;
+                Self::collect_state_value_history_versions(
+                    iter,
+                    state_key_hash,
+                    limit,
+                    ledger_version,
+                )?
+            },
+            None => {
+                let mut iter = self.db.rev_iter::<StateValueHistorySchema>()?;
+                iter.seek_for_prev(&(state_key_hash, ledger_version))?;
+                let mut versions = Self::collect_state_value_history_versions(
+                    iter,
+                    state_key_hash,
+                    limit,
+                    ledger_version,
+                )?;
+                versions.reverse();
+                versions
+            },
+        };
+        Ok(versions)
+    }
+
+    fn collect_state_value_history_versions(
+        iter: impl Iterator<Item = Result<((HashValue, Version), ())>>,
+        state_key_hash: HashValue,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let mut versions = vec![];
+        for res in iter {
+            let ((key_hash, version), ()) = res?;
+            if key_hash != state_key_hash || version > ledger_version {
+                break;
+            }
+            versions.push(version);
+            if versions.len() as u64 >= limit {
+                break;
+            }
+        }
+        Ok(versions)
+    }
+
     pub fn get_latest_sequence_number(
         &self,
         ledger_version: Version,
@@ -426,6 +653,23 @@ impl DBIndexer {
                 }
             }
 
+            if self.indexer_db.participant_transactions_enabled() {
+                for participant in transaction_participants(&txn, &events, &writeset) {
+                    batch.put::<TransactionByParticipantSchema>(&(participant, version), &())?;
+                }
+            }
+
+            if self.indexer_db.fungible_asset_activities_enabled() {
+                for (owner, metadata_address, activity) in
+                    fungible_asset_activities(version, &events, &writeset)
+                {
+                    batch.put::<FungibleAssetActivitySchema>(
+                        &(owner, metadata_address, version, activity.event_index),
+                        &(activity.activity_type, activity.store, activity.amount),
+                    )?;
+                }
+            }
+
             if self.indexer_db.event_enabled() {
                 events.iter().enumerate().try_for_each(|(idx, event)| {
                     if let ContractEvent::V1(v1) = event {
@@ -483,6 +727,15 @@ impl DBIndexer {
                 })?;
             }
 
+            if self.indexer_db.state_value_history_enabled() {
+                for (state_key, _write_op) in writeset.iter() {
+                    batch.put::<StateValueHistorySchema>(
+                        &(*state_key.crypto_hash_ref(), version),
+                        &(),
+                    )?;
+                }
+            }
+
             if self.indexer_db.statekeys_enabled() {
                 writeset.iter().for_each(|(state_key, write_op)| {
                     if write_op.is_creation() || write_op.is_modification() {
@@ -536,6 +789,24 @@ impl DBIndexer {
                 &MetadataValue::Version(version - 1),
             )?;
         }
+        if self.indexer_db.participant_transactions_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::ParticipantTransactionVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
+        if self.indexer_db.fungible_asset_activities_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::FungibleAssetActivityVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
+        if self.indexer_db.state_value_history_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::StateValueHistoryVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
         batch.put::<InternalIndexerMetadataSchema>(
             &MetadataKey::LatestVersion,
             &MetadataValue::Version(version - 1),
@@ -610,6 +881,98 @@ impl DBIndexer {
         Ok(AccountTransactionsWithProof::new(txns_with_proofs))
     }
 
+    /// Returns the transactions `address` participated in, see
+    /// [`InternalIndexerDB::get_participant_transaction_versions`]. Transactions that have been
+    /// pruned from the main DB are skipped.
+    pub fn get_account_participant_transactions(
+        &self,
+        address: AccountAddress,
+        start_version: Option<Version>,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionsWithProof> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        let first_version = self.get_main_db_lowest_viable_version()?;
+        let txns_with_proofs = self
+            .indexer_db
+            .get_participant_transaction_versions(
+                address,
+                start_version.map(|version| max(version, first_version)),
+                limit,
+                ledger_version,
+            )?
+            .into_iter()
+            .filter(|version| *version >= first_version)
+            .map(|version| {
+                self.main_db_reader.get_transaction_by_version(
+                    version,
+                    ledger_version,
+                    include_events,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(AccountTransactionsWithProof::new(txns_with_proofs))
+    }
+
+    pub fn get_fungible_asset_activities(
+        &self,
+        owner: AccountAddress,
+        metadata_address: AccountAddress,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<FungibleAssetActivity>> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        self.indexer_db.get_fungible_asset_activities(
+            owner,
+            metadata_address,
+            start_version,
+            limit,
+            ledger_version,
+        )
+    }
+
+    /// Returns the versions at which `state_key` was written together with the value written,
+    /// `None` for deletions, see [`InternalIndexerDB::get_state_value_history_versions`]. Versions
+    /// that have been pruned from the main DB are skipped.
+    pub fn get_state_value_history(
+        &self,
+        state_key: &StateKey,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, Option<StateValue>)>> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        let first_version = self.get_main_db_lowest_viable_version()?;
+        self.indexer_db
+            .get_state_value_history_versions(
+                *state_key.crypto_hash_ref(),
+                start_version.map(|version| max(version, first_version)),
+                limit,
+                ledger_version,
+            )?
+            .into_iter()
+            .filter(|version| *version >= first_version)
+            .map(|version| {
+                let value = self
+                    .main_db_reader
+                    .get_state_value_by_version(state_key, version)?;
+                Ok((version, value))
+            })
+            .collect()
+    }
+
     pub fn get_prefixed_state_value_iterator(
         &self,
         key_prefix: &StateKeyPrefix,
@@ -722,3 +1085,188 @@ impl DBIndexer {
         Ok(events_with_version)
     }
 }
+
+/// Returns all accounts that participated in a transaction: the sender, fee payer and secondary
+/// signers of user transactions, the creators of the event handles of emitted v1 events and the
+/// owners of the fungible stores written by the transaction.
+fn transaction_participants(
+    txn: &Transaction,
+    events: &[ContractEvent],
+    write_set: &WriteSet,
+) -> BTreeSet<AccountAddress> {
+    let mut participants = BTreeSet::new();
+
+    if let Some(txn) = txn.try_as_signed_user_txn() {
+        participants.insert(txn.sender());
+        let authenticator = txn.authenticator_ref();
+        participants.extend(authenticator.secondary_signer_addresses());
+        participants.extend(authenticator.fee_payer_address());
+    }
+
+    for event in events {
+        if let ContractEvent::V1(v1) = event {
+            participants.insert(v1.key().get_creator_address());
+        }
+    }
+
+    for (state_key, write_op) in write_set.iter() {
+        if let Some(owner) = fungible_store_owner(state_key, write_op) {
+            participants.insert(owner);
+        }
+    }
+
+    participants
+}
+
+/// Fungible stores live in object groups at their own addresses, so the account holding the
+/// balance has to be looked up in the `ObjectCore` of the same group.
+fn fungible_store_owner(state_key: &StateKey, write_op: &WriteOp) -> Option<AccountAddress> {
+    let StateKeyInner::AccessPath(access_path) = state_key.inner() else {
+        return None;
+    };
+    if access_path.get_path() != Path::ResourceGroup(ObjectGroupResource::struct_tag()) {
+        return None;
+    }
+
+    fungible_store_owner_and_metadata(write_op).map(|(owner, _metadata_address)| owner)
+}
+
+/// Decodes the owner and the asset metadata address of a fungible store from the write of its
+/// object group.
+fn fungible_store_owner_and_metadata(
+    write_op: &WriteOp,
+) -> Option<(AccountAddress, AccountAddress)> {
+    let group: BTreeMap<StructTag, Vec<u8>> = bcs::from_bytes(write_op.bytes()?).ok()?;
+    let store: FungibleStoreResource =
+        bcs::from_bytes(group.get(&FungibleStoreResource::struct_tag())?).ok()?;
+    let object_core: ObjectCoreResource =
+        bcs::from_bytes(group.get(&ObjectCoreResource::struct_tag())?).ok()?;
+    Some((object_core.owner, store.metadata()))
+}
+
+/// Returns the balance changes of a transaction, together with the owner of the store and the
+/// metadata address of the asset.
+///
+/// Events only identify the store, so the owner and the asset are looked up in the store written
+/// by the same transaction. Balance changes of stores deleted by the transaction are skipped.
+fn fungible_asset_activities(
+    version: Version,
+    events: &[ContractEvent],
+    write_set: &WriteSet,
+) -> Vec<(AccountAddress, AccountAddress, FungibleAssetActivity)> {
+    events
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, event)| match event {
+            ContractEvent::V1(v1) => coin_store_activity(version, idx as u64, v1, write_set),
+            ContractEvent::V2(v2) => fungible_store_activity(version, idx as u64, v2, write_set),
+        })
+        .collect()
+}
+
+fn fungible_store_activity(
+    version: Version,
+    event_index: u64,
+    event: &ContractEventV2,
+    write_set: &WriteSet,
+) -> Option<(AccountAddress, AccountAddress, FungibleAssetActivity)> {
+    let TypeTag::Struct(tag) = event.type_tag() else {
+        return None;
+    };
+    let (activity_type, store, amount) = if **tag == DepositFAEvent::struct_tag() {
+        let deposit: DepositFAEvent = bcs::from_bytes(event.event_data()).ok()?;
+        (
+            FungibleAssetActivityType::Deposit,
+            deposit.store,
+            deposit.amount,
+        )
+    } else if **tag == WithdrawFAEvent::struct_tag() {
+        let withdraw: WithdrawFAEvent = bcs::from_bytes(event.event_data()).ok()?;
+        (
+            FungibleAssetActivityType::Withdraw,
+            withdraw.store,
+            withdraw.amount,
+        )
+    } else {
+        return None;
+    };
+
+    let write_op = write_set.get(&StateKey::resource_group(
+        &store,
+        &ObjectGroupResource::struct_tag(),
+    ))?;
+    let (owner, metadata_address) = fungible_store_owner_and_metadata(write_op)?;
+    Some((owner, metadata_address, FungibleAssetActivity {
+        version,
+        event_index,
+        activity_type,
+        store,
+        amount,
+    }))
+}
+
+fn coin_store_activity(
+    version: Version,
+    event_index: u64,
+    event: &ContractEventV1,
+    write_set: &WriteSet,
+) -> Option<(AccountAddress, AccountAddress, FungibleAssetActivity)> {
+    let (activity_type, amount) = if event.type_tag() == &*DEPOSIT_EVENT_TYPE {
+        let deposit = DepositEvent::try_from_bytes(event.event_data()).ok()?;
+        (FungibleAssetActivityType::Deposit, deposit.amount())
+    } else if event.type_tag() == &*WITHDRAW_EVENT_TYPE {
+        let withdraw = WithdrawEvent::try_from_bytes(event.event_data()).ok()?;
+        (FungibleAssetActivityType::Withdraw, withdraw.amount())
+    } else {
+        return None;
+    };
+
+    // The event doesn't carry the coin type, so find the coin store whose handle emitted it.
+    let owner = event.key().get_creator_address();
+    let coin_type = write_set.iter().find_map(|(state_key, write_op)| {
+        let StateKeyInner::AccessPath(access_path) = state_key.inner() else {
+            return None;
+        };
+        let Path::Resource(tag) = access_path.get_path() else {
+            return None;
+        };
+        if access_path.address != owner
+            || tag.address != CORE_CODE_ADDRESS
+            || tag.module.as_str() != "coin"
+            || tag.name.as_str() != "CoinStore"
+        {
+            return None;
+        }
+        let coin_store: CoinStoreResourceUntyped = bcs::from_bytes(write_op.bytes()?).ok()?;
+        let handle = match activity_type {
+            FungibleAssetActivityType::Deposit => coin_store.deposit_events(),
+            FungibleAssetActivityType::Withdraw => coin_store.withdraw_events(),
+        };
+        if handle.key() != event.key() {
+            return None;
+        }
+        tag.type_args.first().cloned()
+    })?;
+
+    Some((
+        owner,
+        paired_metadata_address(&coin_type),
+        FungibleAssetActivity {
+            version,
+            event_index,
+            activity_type,
+            store: owner,
+            amount,
+        },
+    ))
+}
+
+/// Returns the address of the fungible asset paired with a coin, as derived by
+/// `coin::create_and_return_paired_metadata_if_not_exist`.
+fn paired_metadata_address(coin_type: &TypeTag) -> AccountAddress {
+    if coin_type == &AptosCoinType::type_tag() {
+        AccountAddress::TEN
+    } else {
+        create_object_address(AccountAddress::TEN, coin_type.to_string().as_bytes())
+    }
+}
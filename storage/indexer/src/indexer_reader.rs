@@ -7,7 +7,10 @@ use aptos_types::{
     account_address::AccountAddress,
     contract_event::{ContractEventV1, ContractEventV2, EventWithVersion},
     event::EventKey,
-    indexer::indexer_db_reader::{IndexerReader, Order},
+    indexer::{
+        fungible_asset_activity::FungibleAssetActivity,
+        indexer_db_reader::{IndexerReader, Order},
+    },
     state_store::{
         state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
@@ -137,6 +140,82 @@ impl IndexerReader for IndexerReaders {
         anyhow::bail!("DB indexer reader is not available")
     }
 
+    fn get_account_participant_transactions(
+        &self,
+        address: AccountAddress,
+        start_version: Option<Version>,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> anyhow::Result<AccountTransactionsWithProof> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader
+                .indexer_db
+                .participant_transactions_enabled()
+            {
+                return Ok(db_indexer_reader.get_account_participant_transactions(
+                    address,
+                    start_version,
+                    limit,
+                    include_events,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal transaction by participant index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
+
+    fn get_fungible_asset_activities(
+        &self,
+        owner: AccountAddress,
+        metadata_address: AccountAddress,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> anyhow::Result<Vec<FungibleAssetActivity>> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader
+                .indexer_db
+                .fungible_asset_activities_enabled()
+            {
+                return Ok(db_indexer_reader.get_fungible_asset_activities(
+                    owner,
+                    metadata_address,
+                    start_version,
+                    limit,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal fungible asset activity index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
+
+    fn get_state_value_history(
+        &self,
+        state_key: &StateKey,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> anyhow::Result<Vec<(Version, Option<StateValue>)>> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader.indexer_db.state_value_history_enabled() {
+                return Ok(db_indexer_reader.get_state_value_history(
+                    state_key,
+                    start_version,
+                    limit,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal state value history index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
+
     fn get_prefixed_state_value_iterator(
         &self,
         key_prefix: &StateKeyPrefix,
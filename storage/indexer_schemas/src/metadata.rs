@@ -39,6 +39,9 @@ pub enum MetadataKey {
     StateVersion,
     TransactionVersion,
     EventV2TranslationVersion,
+    ParticipantTransactionVersion,
+    FungibleAssetActivityVersion,
+    StateValueHistoryVersion,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
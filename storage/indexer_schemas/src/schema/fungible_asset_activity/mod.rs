@@ -0,0 +1,77 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the balance changes of fungible assets held by
+//! an account, in the order they happened. Balance changes of coin stores are stored under the
+//! metadata address of the fungible asset paired with the coin.
+//!
+//! ```text
+//! |<----------------------key----------------------->|<-----------value------------>|
+//! | owner | metadata_address | txn_ver | event_index | activity_type | store | amount |
+//! ```
+
+use crate::{schema::FUNGIBLE_ASSET_ACTIVITY_CF_NAME, utils::ensure_slice_len_eq};
+use anyhow::Result;
+use aptos_schemadb::{
+    define_pub_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::{
+    account_address::AccountAddress, indexer::fungible_asset_activity::FungibleAssetActivityType,
+    transaction::Version,
+};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::{convert::TryFrom, mem::size_of};
+
+define_pub_schema!(
+    FungibleAssetActivitySchema,
+    Key,
+    Value,
+    FUNGIBLE_ASSET_ACTIVITY_CF_NAME
+);
+
+type Index = u64;
+type Key = (AccountAddress, AccountAddress, Version, Index);
+
+type Amount = u64;
+type Value = (FungibleAssetActivityType, AccountAddress, Amount);
+
+impl KeyCodec<FungibleAssetActivitySchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref owner, ref metadata_address, version, index) = *self;
+
+        let mut encoded = owner.to_vec();
+        encoded.extend_from_slice(metadata_address.as_ref());
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        const ADDRESSES_LEN: usize = 2 * AccountAddress::LENGTH;
+        const ADDRESSES_AND_VER_LEN: usize = ADDRESSES_LEN + size_of::<Version>();
+        let owner = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let metadata_address =
+            AccountAddress::try_from(&data[AccountAddress::LENGTH..ADDRESSES_LEN])?;
+        let version = (&data[ADDRESSES_LEN..]).read_u64::<BigEndian>()?;
+        let index = (&data[ADDRESSES_AND_VER_LEN..]).read_u64::<BigEndian>()?;
+
+        Ok((owner, metadata_address, version, index))
+    }
+}
+
+impl ValueCodec<FungibleAssetActivitySchema> for Value {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(self).map_err(Into::into)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        bcs::from_bytes(data).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test;
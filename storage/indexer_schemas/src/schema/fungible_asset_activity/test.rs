@@ -0,0 +1,24 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        owner in any::<AccountAddress>(),
+        metadata_address in any::<AccountAddress>(),
+        version in any::<Version>(),
+        index in any::<Index>(),
+        value in any::<Value>(),
+    ) {
+        assert_encode_decode::<FungibleAssetActivitySchema>(
+            &(owner, metadata_address, version, index),
+            &value,
+        );
+    }
+}
+
+test_no_panic_decoding!(FungibleAssetActivitySchema);
@@ -9,10 +9,13 @@
 pub mod event_by_key;
 pub mod event_by_version;
 pub mod event_sequence_number;
+pub mod fungible_asset_activity;
 pub mod indexer_metadata;
 pub mod state_keys;
+pub mod state_value_history;
 pub mod table_info;
 pub mod transaction_by_account;
+pub mod transaction_by_participant;
 pub mod translated_v1_event;
 
 use anyhow::ensure;
@@ -25,7 +28,10 @@ pub const TABLE_INFO_CF_NAME: ColumnFamilyName = "table_info";
 pub const EVENT_BY_KEY_CF_NAME: ColumnFamilyName = "event_by_key";
 pub const EVENT_BY_VERSION_CF_NAME: ColumnFamilyName = "event_by_version";
 pub const TRANSACTION_BY_ACCOUNT_CF_NAME: ColumnFamilyName = "transaction_by_account";
+pub const TRANSACTION_BY_PARTICIPANT_CF_NAME: ColumnFamilyName = "transaction_by_participant";
+pub const FUNGIBLE_ASSET_ACTIVITY_CF_NAME: ColumnFamilyName = "fungible_asset_activity";
 pub const STATE_KEYS_CF_NAME: ColumnFamilyName = "state_keys";
+pub const STATE_VALUE_HISTORY_CF_NAME: ColumnFamilyName = "state_value_history";
 pub const TRANSLATED_V1_EVENT_CF_NAME: ColumnFamilyName = "translated_v1_event";
 pub const EVENT_SEQUENCE_NUMBER_CF_NAME: ColumnFamilyName = "event_sequence_number";
 
@@ -47,6 +53,9 @@ pub fn internal_indexer_column_families() -> Vec<ColumnFamilyName> {
         STATE_KEYS_CF_NAME,
         TRANSLATED_V1_EVENT_CF_NAME,
         EVENT_SEQUENCE_NUMBER_CF_NAME,
+        TRANSACTION_BY_PARTICIPANT_CF_NAME,
+        FUNGIBLE_ASSET_ACTIVITY_CF_NAME,
+        STATE_VALUE_HISTORY_CF_NAME,
     ]
 }
 
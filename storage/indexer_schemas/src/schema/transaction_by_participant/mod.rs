@@ -0,0 +1,66 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for a transaction index via which the versions of
+//! all transactions an account participated in can be found, in the order they were committed.
+//! Unlike `TransactionByAccountSchema`, this covers transactions the account did not send itself,
+//! e.g., transactions where it is the fee payer, a secondary signer or the recipient of a
+//! transfer.
+//!
+//! ```text
+//! |<-------key------->|<-value->|
+//! | address | txn_ver |  empty  |
+//! ```
+
+use crate::{schema::TRANSACTION_BY_PARTICIPANT_CF_NAME, utils::ensure_slice_len_eq};
+use anyhow::Result;
+use aptos_schemadb::{
+    define_pub_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::{account_address::AccountAddress, transaction::Version};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::{convert::TryFrom, mem::size_of};
+
+define_pub_schema!(
+    TransactionByParticipantSchema,
+    Key,
+    (),
+    TRANSACTION_BY_PARTICIPANT_CF_NAME
+);
+
+type Key = (AccountAddress, Version);
+
+impl KeyCodec<TransactionByParticipantSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref account_address, version) = *self;
+
+        let mut encoded = account_address.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let version = (&data[AccountAddress::LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((address, version))
+    }
+}
+
+impl ValueCodec<TransactionByParticipantSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
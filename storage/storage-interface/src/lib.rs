@@ -42,10 +42,12 @@ mod ledger_summary;
 mod metrics;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod mock;
+pub mod pruner;
 pub mod state_store;
 
 use crate::{
     chunk_to_commit::ChunkToCommit,
+    pruner::{PrunerKind, PrunerStatus},
     state_store::{state::State, state_summary::StateSummary},
 };
 pub use aptos_types::block_info::BlockHeight;
@@ -447,6 +449,9 @@ pub trait DbReader: Send + Sync {
         /// Get the ledger prune window config value.
         fn get_ledger_prune_window(&self) -> Result<usize>;
 
+        /// Returns the targets and progress of all pruners.
+        fn get_pruner_statuses(&self) -> Result<Vec<PrunerStatus>>;
+
         /// Get table info from the internal indexer.
         fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo>;
 
@@ -587,6 +592,23 @@ pub trait DbWriter: Send + Sync {
     ) -> Result<()> {
         unimplemented!()
     }
+
+    /// Pauses or resumes a pruner. The state is persisted and survives restarts.
+    fn set_pruner_paused(&self, kind: PrunerKind, paused: bool) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Asks a pruner to prune everything before `target_version`, optionally overriding its batch
+    /// size, which is persisted and survives restarts. Returns without waiting for the pruning to
+    /// finish.
+    fn prune_to_version(
+        &self,
+        kind: PrunerKind,
+        target_version: Version,
+        batch_size: Option<usize>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
 }
 
 #[derive(Clone)]
@@ -0,0 +1,73 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::AptosDbError;
+use aptos_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// The background pruners of the DB, each configured by its own section of the `StorageConfig`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrunerKind {
+    Ledger,
+    StateKv,
+    StateMerkle,
+    EpochSnapshot,
+}
+
+impl PrunerKind {
+    pub const ALL: [PrunerKind; 4] = [
+        PrunerKind::Ledger,
+        PrunerKind::StateKv,
+        PrunerKind::StateMerkle,
+        PrunerKind::EpochSnapshot,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrunerKind::Ledger => "ledger",
+            PrunerKind::StateKv => "state_kv",
+            PrunerKind::StateMerkle => "state_merkle",
+            PrunerKind::EpochSnapshot => "epoch_snapshot",
+        }
+    }
+}
+
+impl fmt::Display for PrunerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for PrunerKind {
+    type Err = AptosDbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| AptosDbError::Other(format!("Unknown pruner: {}", s)))
+    }
+}
+
+/// A snapshot of the progress of a pruner.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PrunerStatus {
+    pub kind: PrunerKind,
+    /// Whether the pruner is enabled in the `StorageConfig`.
+    pub enabled: bool,
+    /// Whether pruning is paused at runtime.
+    pub paused: bool,
+    pub prune_window: Version,
+    /// The batch size in use, which can be overridden at runtime. None if the pruner is not
+    /// enabled.
+    pub batch_size: Option<usize>,
+    pub min_readable_version: Version,
+    /// The version the pruner is pruning towards.
+    pub target_version: Version,
+    /// Everything before this version has been pruned.
+    pub progress: Version,
+    /// Persisted progress of each sub-pruner (or shard), keyed by name.
+    pub sub_pruner_progress: BTreeMap<String, Version>,
+}
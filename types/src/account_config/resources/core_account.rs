@@ -50,6 +50,11 @@ impl AccountResource {
         self.sequence_number
     }
 
+    /// Set the sequence_number field for the given AccountResource
+    pub fn set_sequence_number(&mut self, sequence_number: u64) {
+        self.sequence_number = sequence_number;
+    }
+
     /// Return the authentication_key field for the given AccountResource
     pub fn authentication_key(&self) -> &[u8] {
         &self.authentication_key
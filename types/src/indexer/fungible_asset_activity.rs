@@ -0,0 +1,29 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account_address::AccountAddress, transaction::Version};
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest_derive::Arbitrary;
+use serde::{Deserialize, Serialize};
+
+/// Whether a balance change added to or removed from a store.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub enum FungibleAssetActivityType {
+    Deposit,
+    Withdraw,
+}
+
+/// A single balance change of a fungible asset held by an account, derived from a deposit or
+/// withdraw event. Changes to a coin store are reported under the fungible asset paired with the
+/// coin, with the account itself as the store.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FungibleAssetActivity {
+    pub version: Version,
+    /// Index of the event in the transaction.
+    pub event_index: u64,
+    pub activity_type: FungibleAssetActivityType,
+    /// Address of the store whose balance changed.
+    pub store: AccountAddress,
+    pub amount: u64,
+}
@@ -5,6 +5,7 @@ use crate::{
     account_address::AccountAddress,
     contract_event::{ContractEventV1, ContractEventV2, EventWithVersion},
     event::EventKey,
+    indexer::fungible_asset_activity::FungibleAssetActivity,
     state_store::{
         state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
@@ -52,6 +53,38 @@ pub trait IndexerReader: Send + Sync {
         ledger_version: Version,
     ) -> Result<AccountTransactionsWithProof>;
 
+    /// Returns transactions `address` participated in as any party, not just as the sender,
+    /// starting at `start_version` or, if not given, the latest ones.
+    fn get_account_participant_transactions(
+        &self,
+        address: AccountAddress,
+        start_version: Option<Version>,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionsWithProof>;
+
+    /// Returns the balance changes of the fungible asset with metadata at `metadata_address` held
+    /// by `owner`, starting at `start_version` or, if not given, the latest ones.
+    fn get_fungible_asset_activities(
+        &self,
+        owner: AccountAddress,
+        metadata_address: AccountAddress,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<FungibleAssetActivity>>;
+
+    /// Returns the versions at which `state_key` was written together with the written values,
+    /// `None` for deletions, starting at `start_version` or, if not given, the latest ones.
+    fn get_state_value_history(
+        &self,
+        state_key: &StateKey,
+        start_version: Option<Version>,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, Option<StateValue>)>>;
+
     fn get_prefixed_state_value_iterator(
         &self,
         key_prefix: &StateKeyPrefix,
@@ -257,6 +257,15 @@ impl ConfigurationResource {
         &self.events
     }
 
+    /// Returns the resource for the next epoch, reconfigured at `last_reconfiguration_time`.
+    pub fn bump_epoch(&self, last_reconfiguration_time: u64) -> Self {
+        Self {
+            epoch: self.epoch + 1,
+            last_reconfiguration_time,
+            events: self.events.clone(),
+        }
+    }
+
     #[cfg(feature = "fuzzing")]
     pub fn bump_epoch_for_test(&self) -> Self {
         let epoch = self.epoch + 1;
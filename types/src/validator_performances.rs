@@ -1,6 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    move_resource::{MoveResource, MoveStructType},
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -13,3 +18,10 @@ pub struct ValidatorPerformance {
 pub struct ValidatorPerformances {
     pub validators: Vec<ValidatorPerformance>,
 }
+
+impl MoveStructType for ValidatorPerformances {
+    const MODULE_NAME: &'static IdentStr = ident_str!("stake");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("ValidatorPerformance");
+}
+
+impl MoveResource for ValidatorPerformances {}